@@ -1,8 +1,8 @@
 use crate::{
     error::ContractResult,
     msg::{AuthExecuteMsg, AuthQueryMsg},
-    orderbook::set_maker_fee,
-    state::MAKER_FEE_RECIPIENT,
+    orderbook::{register_denom_alias, set_maker_fee, set_taker_fee},
+    state::{DISALLOW_SAME_TICK_OPPOSING, MAKER_FEE_RECIPIENT, PAUSED},
     sudo, ContractError,
 };
 use cosmwasm_std::{ensure, Addr, Api, Decimal256, Deps, DepsMut, MessageInfo, Response, Storage};
@@ -64,6 +64,20 @@ pub(crate) fn dispatch(
         AuthExecuteMsg::SetMakerFeeRecipient { recipient } => {
             dispatch_set_maker_fee_recipient(deps, info, recipient)
         }
+
+        // Set the taker fee and its collector for the contract
+        AuthExecuteMsg::SetTakerFee { fee, fee_collector } => {
+            dispatch_set_taker_fee(deps, info, fee, fee_collector)
+        }
+
+        // Reject limit orders placed on ticks holding opposing liquidity
+        AuthExecuteMsg::SetDisallowSameTickOpposing { disallow } => {
+            dispatch_set_disallow_same_tick_opposing(deps, info, disallow)
+        }
+        AuthExecuteMsg::SetPaused { paused } => dispatch_set_paused(deps, info, paused),
+        AuthExecuteMsg::RegisterDenomAlias { alias, canonical } => {
+            dispatch_register_denom_alias(deps, info, alias, canonical)
+        }
     }
 }
 
@@ -344,6 +358,87 @@ pub(crate) fn dispatch_set_maker_fee_recipient(
     ]))
 }
 
+/// Sets the taker fee taken from market order output and the address it is sent to.
+///
+/// Only callable by admin.
+pub(crate) fn dispatch_set_taker_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    taker_fee: Decimal256,
+    fee_collector: Addr,
+) -> ContractResult<Response> {
+    ensure_is_admin(deps.as_ref(), &info.sender)?;
+
+    let addr = deps
+        .api
+        .addr_validate(fee_collector.as_str())
+        .map_err(|_| ContractError::InvalidFeeCollector)?;
+    let taker_fee = set_taker_fee(deps.storage, taker_fee, addr.clone())?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "set_taker_fee"),
+        ("taker_fee", &taker_fee.to_string()),
+        ("fee_collector", addr.as_str()),
+    ]))
+}
+
+/// Sets whether limit orders may be placed on a tick that already holds liquidity in the opposing direction.
+///
+/// Only callable by admin.
+pub(crate) fn dispatch_set_disallow_same_tick_opposing(
+    deps: DepsMut,
+    info: MessageInfo,
+    disallow: bool,
+) -> ContractResult<Response> {
+    ensure_is_admin(deps.as_ref(), &info.sender)?;
+
+    DISALLOW_SAME_TICK_OPPOSING.save(deps.storage, &disallow)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "set_disallow_same_tick_opposing"),
+        ("disallow", &disallow.to_string()),
+    ]))
+}
+
+/// Pauses or unpauses trading on the orderbook.
+///
+/// While paused new orders and swaps are rejected, but cancels and claims remain available so
+/// users can always retrieve their funds. Only callable by the admin.
+pub(crate) fn dispatch_set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> ContractResult<Response> {
+    ensure_is_admin(deps.as_ref(), &info.sender)?;
+
+    PAUSED.save(deps.storage, &paused)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "set_paused"),
+        ("paused", &paused.to_string()),
+    ]))
+}
+
+/// Registers an alternative form of one of the orderbook's denoms.
+///
+/// Only callable by admin.
+pub(crate) fn dispatch_register_denom_alias(
+    deps: DepsMut,
+    info: MessageInfo,
+    alias: String,
+    canonical: String,
+) -> ContractResult<Response> {
+    ensure_is_admin(deps.as_ref(), &info.sender)?;
+
+    register_denom_alias(deps.storage, alias.clone(), canonical.clone())?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "register_denom_alias"),
+        ("alias", &alias),
+        ("canonical", &canonical),
+    ]))
+}
+
 // -- Ensure Methods --
 
 /// Validates that the provided address is the current contract admin.
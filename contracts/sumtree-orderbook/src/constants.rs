@@ -8,7 +8,18 @@ pub const GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS: i64 = 9_000_000;
 // The swap fee expected by this contract
 pub const EXPECTED_SWAP_FEE: Decimal = Decimal::zero();
 pub const MAX_BATCH_CLAIM: u32 = 100;
+// Maximum number of orders cancelled by a single `CancelOrdersInRange`
+pub const MAX_BATCH_CANCEL: u64 = 100;
+// Maximum number of ticks returned by a single `TicksInRange` query
+pub const MAX_TICKS_IN_RANGE: u64 = 1000;
+// Maximum number of nodes returned by a single `SumtreeNodes` query
+pub const MAX_SUMTREE_NODES: u64 = 1000;
+// Length of the rolling window market order volume is reported over, in seconds
+pub const VOLUME_WINDOW_SECONDS: u64 = 86_400;
+// Granularity at which volume is bucketed within the rolling window, in seconds
+pub const VOLUME_BUCKET_SECONDS: u64 = 3_600;
 pub const MAX_MAKER_FEE_PERCENTAGE: Decimal256 = Decimal256::percent(5);
+pub const MAX_TAKER_FEE_PERCENTAGE: Decimal256 = Decimal256::percent(5);
 
 // Address controlled by Osmosis governance
 pub const OSMOSIS_GOV_ADDR: &str = "osmo10d07y265gmmuvt4z0w9aw880jnsr700jjeq4qp";
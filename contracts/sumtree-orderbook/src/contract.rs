@@ -1,30 +1,34 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure, to_json_binary, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Reply, Response,
-    Uint128,
+    coin, ensure, to_json_binary, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Reply,
+    Response, Storage, Timestamp, Uint128,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
+use semver::Version;
 
 use crate::auth::{ADMIN, MODERATOR};
-use crate::constants::{CIRCUIT_BREAKER_SUBDAO_ADDR, OSMOSIS_GOV_ADDR};
+use crate::constants::{CIRCUIT_BREAKER_SUBDAO_ADDR, MAX_TICK, MIN_TICK, OSMOSIS_GOV_ADDR};
 use crate::error::{ContractError, ContractResult};
-use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SwapRefund};
 
-use crate::orderbook::create_orderbook;
+use crate::orderbook::{
+    create_orderbook, set_max_claim_bounty, set_max_orders_per_tick, set_max_tick_move,
+    set_min_order_size, set_tick_bounds, set_tick_spacing,
+};
 use crate::sudo;
-use crate::types::OrderDirection;
-use crate::{auth, order};
+use crate::types::{OrderDirection, TimeInForce};
+use crate::{auth, credit, order, payout};
 use crate::{query, state};
 
 // version info for migration info
-const CONTRACT_NAME: &str = "crates.io:sumtree-orderbook";
-const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub(crate) const CONTRACT_NAME: &str = "crates.io:sumtree-orderbook";
+pub(crate) const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Handling contract instantiation
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
+    mut deps: DepsMut,
     _env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
@@ -38,8 +42,41 @@ pub fn instantiate(
     let moderator = deps.api.addr_validate(CIRCUIT_BREAKER_SUBDAO_ADDR)?;
     MODERATOR.save(deps.storage, &moderator)?;
 
+    // Narrow the global tick bounds if requested
+    if msg.min_tick.is_some() || msg.max_tick.is_some() {
+        set_tick_bounds(
+            deps.storage,
+            msg.min_tick.unwrap_or(MIN_TICK),
+            msg.max_tick.unwrap_or(MAX_TICK),
+        )?;
+    }
+
     // Instantiate orderbook
-    create_orderbook(deps, msg.quote_denom.clone(), msg.base_denom.clone())?;
+    create_orderbook(
+        deps.branch(),
+        msg.quote_denom.clone(),
+        msg.base_denom.clone(),
+    )?;
+
+    if let Some(min_order_size) = msg.min_order_size {
+        set_min_order_size(deps.storage, min_order_size)?;
+    }
+
+    if let Some(max_tick_move) = msg.max_tick_move {
+        set_max_tick_move(deps.storage, max_tick_move)?;
+    }
+
+    if let Some(tick_spacing) = msg.tick_spacing {
+        set_tick_spacing(deps.storage, tick_spacing)?;
+    }
+
+    if let Some(max_orders_per_tick) = msg.max_orders_per_tick {
+        set_max_orders_per_tick(deps.storage, max_orders_per_tick)?;
+    }
+
+    if let Some(max_claim_bounty) = msg.max_claim_bounty {
+        set_max_claim_bounty(deps.storage, max_claim_bounty)?;
+    }
 
     Ok(Response::new().add_attributes(vec![
         ("method", "instantiate"),
@@ -48,13 +85,90 @@ pub fn instantiate(
     ]))
 }
 
+/// Handling contract migration
+///
+/// Runs the state migrations for the stored contract version and then records the new version.
+/// Migrating from another contract or to an older version is rejected.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    ensure!(
+        stored.contract == CONTRACT_NAME,
+        ContractError::InvalidMigration {
+            error: format!("cannot migrate from contract {}", stored.contract)
+        }
+    );
+
+    let previous_version = parse_version(&stored.version)?;
+    let new_version = parse_version(CONTRACT_VERSION)?;
+    ensure!(
+        previous_version <= new_version,
+        ContractError::InvalidMigration {
+            error: format!("cannot downgrade from {previous_version} to {new_version}")
+        }
+    );
+
+    let migration_attributes = run_migrations(deps.storage, &previous_version)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::default()
+        .add_attributes(vec![
+            ("method", "migrate".to_string()),
+            ("previous_version", previous_version.to_string()),
+            ("new_version", new_version.to_string()),
+        ])
+        .add_attributes(migration_attributes))
+}
+
+fn parse_version(version: &str) -> ContractResult<Version> {
+    version
+        .parse()
+        .map_err(|err| ContractError::InvalidMigration {
+            error: format!("invalid contract version {version}: {err}"),
+        })
+}
+
+/// Runs every state migration needed to bring storage written by `previous_version` up to date,
+/// returning attributes describing what was migrated.
+///
+/// Each storage layout change adds a step gated on the versions that predate it, for example
+/// backfilling new running totals by iterating `TICK_STATE`.
+fn run_migrations(
+    storage: &mut dyn Storage,
+    previous_version: &Version,
+) -> ContractResult<Vec<(&'static str, String)>> {
+    let mut attributes = vec![];
+
+    // Up to 0.1.0: orders partially claimed before claim totals were tracked need them inferred.
+    // Claim totals are only ever inferred once, so this is a no-op for already migrated state.
+    if *previous_version <= Version::new(0, 1, 0) {
+        let backfilled = order::backfill_claimed_totals(storage)?;
+        attributes.push(("backfilled_orders", backfilled.to_string()));
+    }
+
+    // Up to 0.1.0: market orders walk the populated tick index, which must cover existing ticks.
+    // The index is rebuilt from `TICK_STATE`, so this is a no-op for already migrated state.
+    if *previous_version <= Version::new(0, 1, 0) {
+        let indexed = state::backfill_populated_ticks(storage)?;
+        attributes.push(("indexed_ticks", indexed.to_string()));
+    }
+
+    Ok(attributes)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let response = dispatch_execute(deps.branch(), env, info, msg)?;
+    payout::track_recoverable_payouts(deps.storage, response, false)
+}
+
+fn dispatch_execute(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
@@ -73,6 +187,11 @@ pub fn execute(
             order_direction,
             quantity,
             claim_bounty,
+            post_only,
+            expiry,
+            time_in_force,
+            display_quantity,
+            use_credit,
         } => dispatch_place_limit(
             deps,
             env,
@@ -81,19 +200,122 @@ pub fn execute(
             order_direction,
             quantity,
             claim_bounty,
+            post_only,
+            expiry,
+            time_in_force,
+            display_quantity,
+            use_credit,
         ),
 
+        // Places multiple limit orders on given market
+        ExecuteMsg::PlaceLimitBatch { orders } => order::place_limit_batch(deps, env, info, orders),
+
         // Cancels limit order with given ID
-        ExecuteMsg::CancelLimit { tick_id, order_id } => {
-            order::cancel_limit(deps, env, info, tick_id, order_id)
+        ExecuteMsg::CancelLimit {
+            tick_id,
+            order_id,
+            swap_refund,
+            to_credit,
+        } => match (swap_refund, to_credit.unwrap_or_default()) {
+            (Some(_), true) => Err(ContractError::SwapRefundToCredit),
+            (Some(SwapRefund { min_output }), false) => {
+                order::cancel_limit_and_swap(deps, env, info, tick_id, order_id, min_output)
+            }
+            (None, true) => order::cancel_limit_to_credit(deps, info, tick_id, order_id),
+            (None, false) => order::cancel_limit(deps, env, info, tick_id, order_id),
+        },
+
+        // Cancels part of a limit order, keeping the remainder on the book
+        ExecuteMsg::CancelLimitPartial {
+            tick_id,
+            order_id,
+            amount,
+        } => order::cancel_limit_partial(deps, env, info, tick_id, order_id, amount),
+
+        // Cancels limit order and donates its quantity to the tick
+        ExecuteMsg::CancelLimitDonate { tick_id, order_id } => {
+            order::cancel_limit_donate(deps, info, tick_id, order_id)
         }
 
+        // Cancels the sender's orders within a tick range
+        ExecuteMsg::CancelOrdersInRange {
+            min_tick,
+            max_tick,
+            direction,
+            limit,
+        } => order::cancel_orders_in_range(deps, info, min_tick, max_tick, direction, limit),
+
+        // Changes the quantity of a limit order, keeping its queue position
+        ExecuteMsg::AmendLimit {
+            tick_id,
+            order_id,
+            new_quantity,
+        } => order::amend_limit(deps, env, info, tick_id, order_id, new_quantity),
+
+        // Reassigns a limit order to a new owner
+        ExecuteMsg::TransferOrder {
+            tick_id,
+            order_id,
+            new_owner,
+        } => order::transfer_order(deps, info, tick_id, order_id, new_owner),
+
         // Claims a limit order with given ID
-        ExecuteMsg::ClaimLimit { tick_id, order_id } => {
-            order::claim_limit(deps, env, info, tick_id, order_id)
+        ExecuteMsg::ClaimLimit {
+            tick_id,
+            order_id,
+            min_claim,
+            to_credit,
+        } => {
+            if to_credit.unwrap_or_default() {
+                order::claim_limit_to_credit(deps, env, info, tick_id, order_id, min_claim)
+            } else {
+                order::claim_limit(deps, env, info, tick_id, order_id, min_claim)
+            }
         }
 
-        ExecuteMsg::BatchClaim { orders } => order::batch_claim_limits(deps, info, env, orders),
+        // Claims a limit order and re-places the proceeds at a new tick
+        ExecuteMsg::RollOrder {
+            tick_id,
+            order_id,
+            new_tick_id,
+            new_direction,
+        } => order::roll_order(
+            deps,
+            env,
+            info,
+            tick_id,
+            order_id,
+            new_tick_id,
+            new_direction,
+        ),
+
+        // Claims all filled orders of the sender
+        ExecuteMsg::ClaimAll { start_after, limit } => {
+            order::claim_all(deps, env, info, start_after, limit)
+        }
+
+        ExecuteMsg::BatchClaim { orders, min_claim } => {
+            order::batch_claim_limits(deps, info, env, orders, min_claim)
+        }
+
+        // Merges the sender's orders on a tick into one
+        ExecuteMsg::ConsolidateOrders { tick_id, direction } => {
+            order::consolidate_orders(deps, env, info, tick_id, direction)
+        }
+
+        // Withdraws the sender's payouts that previously failed to send
+        ExecuteMsg::WithdrawFailedPayout {} => payout::withdraw_failed_payout(deps, env, info),
+
+        // Credits the sender with the attached funds
+        ExecuteMsg::Deposit {} => credit::deposit(deps, info),
+
+        // Sends part of the sender's credit back to them
+        ExecuteMsg::Withdraw { denom, amount } => credit::withdraw(deps, info, denom, amount),
+
+        // Reclaims the storage of an emptied tick direction
+        ExecuteMsg::PruneTick { tick_id, direction } => {
+            order::prune_tick(deps, info, tick_id, direction)
+        }
 
         // Handles all authorisation messages
         ExecuteMsg::Auth(auth_msg) => auth::dispatch(deps, info, auth_msg),
@@ -102,7 +324,7 @@ pub fn execute(
 
 /// Handling contract query
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> ContractResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> ContractResult<Binary> {
     match msg {
         QueryMsg::SpotPrice {
             quote_asset_denom,
@@ -118,6 +340,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> ContractResult<Binary> {
             swap_fee,
         } => Ok(to_json_binary(&query::calc_out_amount_given_in(
             deps,
+            env,
             token_in,
             token_out_denom,
             swap_fee,
@@ -140,11 +363,189 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> ContractResult<Binary> {
             start_from,
             end_at,
             limit,
+            direction,
+            only_claimable,
         } => Ok(to_json_binary(&query::orders_by_owner(
-            deps, owner, start_from, end_at, limit,
+            deps,
+            owner,
+            start_from,
+            end_at,
+            limit,
+            direction,
+            only_claimable.unwrap_or_default(),
+        )?)?),
+        QueryMsg::OwnerTicks {
+            owner,
+            direction,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&query::owner_ticks(
+            deps,
+            owner,
+            direction,
+            start_after,
+            limit,
         )?)?),
         QueryMsg::Denoms {} => Ok(to_json_binary(&query::denoms(deps)?)?),
         QueryMsg::GetMakerFee {} => Ok(to_json_binary(&state::get_maker_fee(deps.storage)?)?),
+        QueryMsg::OrderAvgPrice { tick_id, order_id } => Ok(to_json_binary(
+            &query::order_avg_price(deps, tick_id, order_id)?,
+        )?),
+        QueryMsg::OrderFillPrice { tick_id, order_id } => Ok(to_json_binary(
+            &query::order_fill_price(deps, tick_id, order_id)?,
+        )?),
+        QueryMsg::SolvencyCheck {} => Ok(to_json_binary(&query::solvency_check(deps, env)?)?),
+        QueryMsg::TickQueue {
+            tick_id,
+            direction,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&query::tick_queue(
+            deps,
+            tick_id,
+            direction,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::SimulateClaimAll {
+            owner,
+            claimer,
+            start_after,
+            max_orders,
+        } => Ok(to_json_binary(&query::simulate_claim_all(
+            deps,
+            env,
+            owner,
+            claimer,
+            start_after,
+            max_orders,
+        )?)?),
+        QueryMsg::SimulateSwapExactAmountIn {
+            token_in,
+            token_out_denom,
+            target_tick,
+            max_ticks_crossed,
+        } => Ok(to_json_binary(&query::simulate_swap_exact_amount_in(
+            deps,
+            env,
+            token_in,
+            token_out_denom,
+            target_tick,
+            max_ticks_crossed,
+        )?)?),
+        QueryMsg::SimulatePriceImpact { direction, amount } => Ok(to_json_binary(
+            &query::simulate_price_impact(deps, env, direction, amount)?,
+        )?),
+        QueryMsg::CompactionCandidates {
+            min_fragments,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&query::compaction_candidates(
+            deps,
+            min_fragments,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::TopClaimable { limit } => {
+            Ok(to_json_binary(&query::top_claimable(deps, limit)?)?)
+        }
+
+        QueryMsg::ClaimableAmount { tick_id, order_id } => Ok(to_json_binary(
+            &query::claimable_amount(deps, tick_id, order_id)?,
+        )?),
+
+        QueryMsg::TotalClaimable { owner, limit } => Ok(to_json_binary(&query::total_claimable(
+            deps, owner, limit,
+        )?)?),
+
+        QueryMsg::TicksInRange {
+            min_tick,
+            max_tick,
+            direction,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&query::ticks_in_range(
+            deps,
+            min_tick,
+            max_tick,
+            direction,
+            start_after,
+            limit,
+        )?)?),
+
+        QueryMsg::TickSwapProgress { tick_id, direction } => Ok(to_json_binary(
+            &query::tick_swap_progress(deps, tick_id, direction)?,
+        )?),
+
+        QueryMsg::Spread {} => Ok(to_json_binary(&query::spread(deps)?)?),
+        QueryMsg::CancelledPrefixSum {
+            tick_id,
+            direction,
+            target_etas,
+        } => Ok(to_json_binary(&query::cancelled_prefix_sum(
+            deps,
+            tick_id,
+            direction,
+            target_etas,
+        )?)?),
+        QueryMsg::BookLiquidity {} => Ok(to_json_binary(&query::book_liquidity(deps)?)?),
+        QueryMsg::BookImbalance {} => Ok(to_json_binary(&query::book_imbalance(deps)?)?),
+        QueryMsg::TickPrice { tick_id } => Ok(to_json_binary(&query::tick_price(tick_id)?)?),
+        QueryMsg::PriceToTick { price, round_up } => Ok(to_json_binary(&query::price_to_tick(
+            price,
+            round_up.unwrap_or_default(),
+        )?)?),
+        QueryMsg::SumtreeNodes {
+            tick_id,
+            direction,
+            max_nodes,
+        } => Ok(to_json_binary(&query::sumtree_nodes(
+            deps, tick_id, direction, max_nodes,
+        )?)?),
+        QueryMsg::SumtreeInfo { tick_id, direction } => Ok(to_json_binary(&query::sumtree_info(
+            deps, tick_id, direction,
+        )?)?),
+        QueryMsg::EstimateSwapExactAmountIn {
+            token_in,
+            token_out_denom,
+            swap_fee,
+        } => Ok(to_json_binary(&query::estimate_swap_exact_amount_in(
+            deps,
+            env,
+            token_in,
+            token_out_denom,
+            swap_fee,
+        )?)?),
+        QueryMsg::EstimateSwapExactAmountOut {
+            token_out,
+            token_in_denom,
+            swap_fee,
+        } => Ok(to_json_binary(&query::estimate_swap_exact_amount_out(
+            deps,
+            env,
+            token_out,
+            token_in_denom,
+            swap_fee,
+        )?)?),
+        QueryMsg::MarketStats {} => Ok(to_json_binary(&query::market_stats(deps, env)?)?),
+        QueryMsg::RealizedCancels { tick_id, direction } => Ok(to_json_binary(
+            &query::realized_cancels(deps, tick_id, direction)?,
+        )?),
+        QueryMsg::QueuePosition { tick_id, order_id } => Ok(to_json_binary(
+            &query::queue_position(deps, tick_id, order_id)?,
+        )?),
+        QueryMsg::TickState { tick_id, direction } => Ok(to_json_binary(&query::tick_values(
+            deps, tick_id, direction,
+        )?)?),
+        QueryMsg::OrderStatus { tick_id, order_id } => Ok(to_json_binary(&query::order_status(
+            deps, tick_id, order_id,
+        )?)?),
+        QueryMsg::FillPreview { direction, amount } => Ok(to_json_binary(&query::fill_preview(
+            deps, env, direction, amount,
+        )?)?),
+        QueryMsg::CancelRefundPreview { tick_id, order_id } => Ok(to_json_binary(
+            &query::cancel_refund_preview(deps, tick_id, order_id)?,
+        )?),
 
         // -- Auth Queries --
         QueryMsg::Auth(msg) => Ok(to_json_binary(&auth::query(deps, msg)?)?),
@@ -152,7 +553,12 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> ContractResult<Binary> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    // Failed refunds, claims and bounties are recovered instead of reverting the transaction
+    if payout::is_recoverable_payout(msg.id) {
+        return payout::handle_payout_reply(deps, env, msg);
+    }
+
     ensure!(
         msg.result.is_ok(),
         ContractError::ReplyError {
@@ -172,7 +578,27 @@ pub fn dispatch_place_limit(
     order_direction: OrderDirection,
     quantity: Uint128,
     claim_bounty: Option<Decimal256>,
+    post_only: Option<bool>,
+    expiry: Option<Timestamp>,
+    time_in_force: Option<TimeInForce>,
+    display_quantity: Option<Uint128>,
+    use_credit: Option<bool>,
 ) -> Result<Response, ContractError> {
+    // Credit stands in for the attached funds, so an order cannot be funded from both
+    let info = if use_credit.unwrap_or_default() {
+        ensure!(info.funds.is_empty(), ContractError::FundsWithCredit);
+        let denom = state::ORDERBOOK
+            .load(deps.storage)?
+            .get_expected_denom(&order_direction);
+        credit::debit_credit(deps.storage, &info.sender, &denom, quantity)?;
+        MessageInfo {
+            sender: info.sender,
+            funds: vec![coin(quantity.u128(), denom)],
+        }
+    } else {
+        info
+    };
+
     order::place_limit(
         &mut deps,
         env,
@@ -181,5 +607,10 @@ pub fn dispatch_place_limit(
         order_direction,
         quantity,
         claim_bounty,
+        post_only,
+        expiry,
+        time_in_force,
+        display_quantity,
+        use_credit.unwrap_or_default(),
     )
 }
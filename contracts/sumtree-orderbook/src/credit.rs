@@ -0,0 +1,112 @@
+use cosmwasm_std::{coin, ensure, Addr, BankMsg, DepsMut, MessageInfo, Response, Storage, Uint128};
+use cw_utils::{nonpayable, PaymentError};
+
+use crate::{
+    error::{ContractError, ContractResult},
+    state::{ensure_canonical_denom, CREDIT_BALANCES, ORDERBOOK},
+};
+
+/// Adds `amount` of the canonical `denom` to the credit of `owner`.
+pub(crate) fn add_credit(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    denom: &str,
+    amount: Uint128,
+) -> ContractResult<()> {
+    CREDIT_BALANCES.update(storage, (owner, denom), |balance| -> ContractResult<_> {
+        Ok(balance.unwrap_or_default().checked_add(amount)?)
+    })?;
+    Ok(())
+}
+
+/// Removes `amount` of the canonical `denom` from the credit of `owner`, dropping the entry once
+/// it is emptied.
+///
+/// Errors with `InsufficientCredit` if the owner holds less than `amount` of `denom`.
+pub(crate) fn debit_credit(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    denom: &str,
+    amount: Uint128,
+) -> ContractResult<()> {
+    let available = CREDIT_BALANCES
+        .may_load(storage, (owner, denom))?
+        .unwrap_or_default();
+    ensure!(
+        amount <= available,
+        ContractError::InsufficientCredit {
+            denom: denom.to_string(),
+            available,
+            required: amount,
+        }
+    );
+
+    let remaining = available.checked_sub(amount)?;
+    if remaining.is_zero() {
+        CREDIT_BALANCES.remove(storage, (owner, denom));
+    } else {
+        CREDIT_BALANCES.save(storage, (owner, denom), &remaining)?;
+    }
+    Ok(())
+}
+
+/// Credits the sender with the funds attached to the message, which must be in the orderbook's
+/// denoms.
+pub(crate) fn deposit(deps: DepsMut, info: MessageInfo) -> ContractResult<Response> {
+    ensure!(!info.funds.is_empty(), PaymentError::NoFunds {});
+
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+    for sent in &info.funds {
+        ensure_canonical_denom(deps.storage, &sent.denom)?;
+        ensure!(
+            sent.denom == orderbook.base_denom || sent.denom == orderbook.quote_denom,
+            PaymentError::ExtraDenom(sent.denom.clone())
+        );
+        add_credit(deps.storage, &info.sender, &sent.denom, sent.amount)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("method", "deposit"),
+        ("owner", info.sender.as_str()),
+        (
+            "amount",
+            &info
+                .funds
+                .iter()
+                .map(|coin| coin.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    ]))
+}
+
+/// Sends `amount` of the sender's credit in `denom` back to them.
+///
+/// Errors if `amount` is zero or exceeds the sender's credit.
+pub(crate) fn withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    amount: Uint128,
+) -> ContractResult<Response> {
+    nonpayable(&info)?;
+    ensure!(
+        !amount.is_zero(),
+        ContractError::InvalidQuantity { quantity: amount }
+    );
+
+    ensure_canonical_denom(deps.storage, &denom)?;
+    debit_credit(deps.storage, &info.sender, &denom, amount)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("method", "withdraw"),
+            ("owner", info.sender.as_str()),
+            ("denom", &denom),
+            ("amount", &amount.to_string()),
+        ])
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(amount.u128(), denom)],
+        }))
+}
@@ -1,7 +1,8 @@
-use crate::constants::MAX_MAKER_FEE_PERCENTAGE;
+use crate::constants::{MAX_MAKER_FEE_PERCENTAGE, MAX_TAKER_FEE_PERCENTAGE, MAX_TICK, MIN_TICK};
 use cosmwasm_std::{
-    CheckedFromRatioError, CheckedMultiplyRatioError, CoinsError, ConversionOverflowError,
-    Decimal256, DecimalRangeExceeded, DivideByZeroError, OverflowError, StdError, Uint128,
+    CheckedFromRatioError, CheckedMultiplyRatioError, CoinsError, ConversionOverflowError, Decimal,
+    Decimal256, DecimalRangeExceeded, DivideByZeroError, OverflowError, StdError, Timestamp,
+    Uint128, Uint256,
 };
 use cw_utils::PaymentError;
 use thiserror::Error;
@@ -97,7 +98,7 @@ pub enum ContractError {
     #[error("Orderbook ran out of liquidity during market order")]
     InsufficientLiquidity,
 
-    #[error("Claim bounty must be a value between 0 and 0.01 (1%). Received: {claim_bounty:?}")]
+    #[error("Claim bounty must be a value between 0 and 0.01 (1%) and within the orderbook's maximum claim bounty. Received: {claim_bounty:?}")]
     InvalidClaimBounty { claim_bounty: Option<Decimal256> },
 
     #[error(
@@ -122,6 +123,155 @@ pub enum ContractError {
 
     #[error("Invalid sumtree: {error}")]
     InvalidSumtree { error: String },
+
+    #[error("Tick {tick_id} already has liquidity in the opposing direction")]
+    SameTickOpposing { tick_id: i64 },
+
+    #[error("Order {order_id} on tick {tick_id} has not yet been reached by fills")]
+    NotYetReached { tick_id: i64, order_id: u64 },
+
+    #[error("Invalid tick bounds: [{min_tick}, {max_tick}] must be non-empty and within [{MIN_TICK}, {MAX_TICK}]")]
+    InvalidTickBounds { min_tick: i64, max_tick: i64 },
+
+    #[error("Claim of {amount} for order {order_id} on tick {tick_id} is below the minimum of {min_claim}")]
+    ClaimBelowMinimum {
+        tick_id: i64,
+        order_id: u64,
+        amount: Uint256,
+        min_claim: Uint128,
+    },
+
+    #[error("Cannot amend order to {new_quantity}: {filled} has already been filled")]
+    AmendFilledOrder {
+        filled: Uint128,
+        new_quantity: Uint128,
+    },
+
+    #[error(
+        "Post-only order on tick {tick_id} would cross the best opposing tick {best_opposing_tick}"
+    )]
+    WouldCross {
+        tick_id: i64,
+        best_opposing_tick: i64,
+    },
+
+    #[error("An orderbook already exists for ({quote_denom}, {base_denom})")]
+    BookAlreadyExists {
+        quote_denom: String,
+        base_denom: String,
+    },
+
+    #[error("Order expiry {expiry} must be after the current block time {block_time}")]
+    InvalidExpiry {
+        expiry: Timestamp,
+        block_time: Timestamp,
+    },
+
+    #[error("Cannot cancel {amount}: only {remaining} of the order is unfilled")]
+    CancelExceedsRemaining { amount: Uint128, remaining: Uint128 },
+
+    #[error("Invalid Taker Fee: provided fee must be less than or equal to {MAX_TAKER_FEE_PERCENTAGE:?}")]
+    InvalidTakerFee,
+
+    #[error("Invalid Fee Collector")]
+    InvalidFeeCollector,
+
+    #[error("Orderbook is paused")]
+    Paused,
+
+    #[error("Order size {size} is below the minimum of {minimum}")]
+    OrderTooSmall { size: Uint128, minimum: Uint128 },
+
+    #[error("Invalid migration: {error}")]
+    InvalidMigration { error: String },
+
+    #[error("No failed payout to withdraw for {recipient}")]
+    NoFailedPayout { recipient: String },
+
+    #[error("Cannot prune tick {tick_id}: {reason}")]
+    TickNotPrunable { tick_id: i64, reason: String },
+
+    #[error("Market order would move from tick {start_tick} to {tick_id}, beyond the maximum of {max_tick_move} ticks")]
+    ExcessivePriceImpact {
+        start_tick: i64,
+        tick_id: i64,
+        max_tick_move: i64,
+    },
+
+    #[error("Invalid max tick move {max_tick_move}: must not be negative")]
+    InvalidMaxTickMove { max_tick_move: i64 },
+
+    #[error("Cannot consolidate orders on tick {tick_id}: {reason}")]
+    CannotConsolidate { tick_id: i64, reason: String },
+
+    #[error("Insufficient liquidity for swap: requested {requested}, available {available}")]
+    InsufficientLiquidityForSwap {
+        requested: Uint128,
+        available: Uint128,
+    },
+
+    #[error("Invalid tick spacing {tick_spacing}: must be positive")]
+    InvalidTickSpacing { tick_spacing: i64 },
+
+    #[error("Invalid max orders per tick {max_orders_per_tick}: must be positive")]
+    InvalidMaxOrdersPerTick { max_orders_per_tick: u32 },
+
+    #[error("Tick {tick_id} already holds the maximum number of orders")]
+    TickFull { tick_id: i64 },
+
+    #[error("Invalid display quantity {display_quantity}: must be positive and below the order quantity {quantity}")]
+    InvalidDisplayQuantity {
+        display_quantity: Uint128,
+        quantity: Uint128,
+    },
+
+    #[error("Swap input is too small to produce any output")]
+    ZeroOutput,
+
+    #[error("Liquidity underflow on tick {tick_id}: more liquidity removed than the tick holds")]
+    LiquidityUnderflow { tick_id: i64 },
+
+    #[error("Insufficient credit in {denom}: available {available}, required {required}")]
+    InsufficientCredit {
+        denom: String,
+        available: Uint128,
+        required: Uint128,
+    },
+
+    #[error("An order placed from credit cannot also attach funds")]
+    FundsWithCredit,
+
+    #[error("A refund cannot be both swapped and kept as credit")]
+    SwapRefundToCredit,
+
+    #[error("Swap output {received}{denom} is below the minimum {min}{denom}")]
+    SwapBelowMin {
+        min: Uint256,
+        received: Uint256,
+        denom: String,
+    },
+
+    #[error("Swap input {received}{denom} exceeds the maximum {max}{denom}")]
+    SwapAboveMax {
+        max: Uint256,
+        received: Uint256,
+        denom: String,
+    },
+
+    #[error("Invalid swap denom: expected {expected} received {received}")]
+    SwapWrongDenom { expected: String, received: String },
+
+    #[error("Provided swap fee does not match: expected {expected} received {received}")]
+    SwapFeeMismatch {
+        expected: Decimal,
+        received: Decimal,
+    },
+
+    #[error("Immediate-or-cancel orders never rest on the book and cannot set {option}")]
+    InvalidImmediateOrCancel { option: String },
+
+    #[error("Denom {alias} is an alias of {canonical}, use {canonical} to move funds")]
+    AliasedDenom { alias: String, canonical: String },
 }
 
 pub type ContractResult<T> = Result<T, ContractError>;
@@ -1,10 +1,12 @@
 pub mod auth;
 pub mod constants;
 pub mod contract;
+mod credit;
 mod error;
 pub mod msg;
 mod order;
 mod orderbook;
+mod payout;
 pub mod query;
 pub mod state;
 pub mod sudo;
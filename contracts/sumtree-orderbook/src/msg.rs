@@ -1,6 +1,10 @@
-use crate::types::{OrderDirection, TickState};
+use crate::types::{
+    Coin256, MarketOrderStopReason, MsgSend256, OrderDirection, TickState, TimeInForce,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Coin, Decimal, Decimal256, Uint128, Uint256};
+use cosmwasm_std::{
+    Addr, Coin, Decimal, Decimal256, Int256, SignedDecimal256, Timestamp, Uint128, Uint256,
+};
 use osmosis_std::types::cosmos::base::v1beta1::Coin as ProtoCoin;
 
 /// Message type for `instantiate` entry_point
@@ -8,6 +12,19 @@ use osmosis_std::types::cosmos::base::v1beta1::Coin as ProtoCoin;
 pub struct InstantiateMsg {
     pub base_denom: String,
     pub quote_denom: String,
+    /// Narrower global tick bounds for limit orders, defaulting to `MIN_TICK` and `MAX_TICK`
+    pub min_tick: Option<i64>,
+    pub max_tick: Option<i64>,
+    /// Smallest limit order quantity accepted, defaulting to no minimum
+    pub min_order_size: Option<Uint128>,
+    /// Furthest a single market order may move the tick pointer, defaulting to no limit
+    pub max_tick_move: Option<i64>,
+    /// Limit orders may only be placed on multiples of this tick spacing, defaulting to every tick
+    pub tick_spacing: Option<i64>,
+    /// Most orders that may rest in one direction of a tick, defaulting to no limit
+    pub max_orders_per_tick: Option<u32>,
+    /// Highest claim bounty limit orders may offer, at most 0.01 (1%) and defaulting to 0.01
+    pub max_claim_bounty: Option<Decimal256>,
 }
 
 /// Message type for `execute` entry_point
@@ -18,39 +35,183 @@ pub enum ExecuteMsg {
         order_direction: OrderDirection,
         quantity: Uint128,
         claim_bounty: Option<Decimal256>,
+        /// Rejects the order if it would cross the best opposing tick instead of resting on the book
+        post_only: Option<bool>,
+        /// Unfilled quantity is voided and refunded once a fill reaches the order after this time
+        expiry: Option<Timestamp>,
+        /// Defaults to `GoodTillCancelled`, an `ImmediateOrCancel` order never rests on the book and
+        /// cannot set `expiry`, `display_quantity` or `claim_bounty`
+        time_in_force: Option<TimeInForce>,
+        /// Quantity shown in tick depth queries while the rest is held in reserve, below `quantity`
+        display_quantity: Option<Uint128>,
+        /// Draws `quantity` from the sender's credit instead of attached funds, which must be empty.
+        /// The unfilled remainder of an immediate-or-cancel order is credited back
+        use_credit: Option<bool>,
+    },
+    PlaceLimitBatch {
+        orders: Vec<PlaceLimitInput>,
     },
     CancelLimit {
         tick_id: i64,
         order_id: u64,
+        /// If provided, the refund is swapped into the opposite denom against this orderbook
+        swap_refund: Option<SwapRefund>,
+        /// Keeps the refund as the owner's credit instead of sending it, excludes `swap_refund`
+        to_credit: Option<bool>,
+    },
+    /// Cancels the sender's unfilled orders in one direction on ticks within the range (inclusive)
+    CancelOrdersInRange {
+        min_tick: i64,
+        max_tick: i64,
+        direction: OrderDirection,
+        /// Maximum number of orders to cancel, defaults to and is capped at `MAX_BATCH_CANCEL`
+        limit: Option<u64>,
+    },
+    CancelLimitPartial {
+        tick_id: i64,
+        order_id: u64,
+        amount: Uint128,
+    },
+    /// Cancels an unfilled order and leaves its quantity at the back of the tick, for takers to
+    /// fill without paying out to anyone
+    CancelLimitDonate {
+        tick_id: i64,
+        order_id: u64,
+    },
+    AmendLimit {
+        tick_id: i64,
+        order_id: u64,
+        new_quantity: Uint128,
     },
     ClaimLimit {
         tick_id: i64,
         order_id: u64,
+        /// Rejects the claim if it would realize less than this amount
+        min_claim: Option<Uint128>,
+        /// Keeps the claimed amount as the owner's credit instead of sending it, owner only
+        to_credit: Option<bool>,
+    },
+    /// Claims the sender's filled orders, scanning at most `limit` of them
+    ClaimAll {
+        // For indexed based pagination (tick_id, order_id), exclusive
+        start_after: Option<(i64, u64)>,
+        limit: Option<u64>,
     },
     BatchClaim {
         orders: Vec<(i64, u64)>,
+        /// Skips any claim that would realize less than this amount
+        min_claim: Option<Uint128>,
+    },
+    /// Withdraws the sender's refunds and claims whose bank send failed
+    WithdrawFailedPayout {},
+    /// Credits the sender with the attached funds, to place orders from with `use_credit`
+    Deposit {},
+    /// Sends `amount` of the sender's credit in `denom` back to them
+    Withdraw {
+        denom: String,
+        amount: Uint128,
+    },
+    /// Reassigns one of the sender's orders, along with its unclaimed fills, to `new_owner`
+    TransferOrder {
+        tick_id: i64,
+        order_id: u64,
+        new_owner: String,
+    },
+    /// Claims one of the sender's orders and places the proceeds as a new order at `new_tick_id`
+    RollOrder {
+        tick_id: i64,
+        order_id: u64,
+        new_tick_id: i64,
+        new_direction: OrderDirection,
+    },
+    /// Merges the sender's orders in one direction of a tick into a single order
+    ConsolidateOrders {
+        tick_id: i64,
+        direction: OrderDirection,
+    },
+    /// Removes the state of a tick direction that no longer holds any orders
+    PruneTick {
+        tick_id: i64,
+        direction: OrderDirection,
     },
     Auth(AuthExecuteMsg),
 }
 
+/// Set as the response data of `ClaimLimit`, and per order within `BatchClaimData`, so that
+/// calling contracts can parse the claimed funds from a `reply_on_success` submessage reply
+#[cw_serde]
+pub struct ClaimData {
+    pub tick_id: i64,
+    pub order_id: u64,
+    pub owner: Addr,
+    /// Amount sent to the order owner, after bounty and maker fee deductions
+    pub claimed: Coin256,
+}
+
+/// Set as the response data of `BatchClaim`, containing only the orders that were claimed
+#[cw_serde]
+pub struct BatchClaimData {
+    pub claims: Vec<ClaimData>,
+}
+
+/// A single limit order within a `PlaceLimitBatch`
+#[cw_serde]
+pub struct PlaceLimitInput {
+    pub tick_id: i64,
+    pub order_direction: OrderDirection,
+    pub quantity: Uint128,
+    pub claim_bounty: Option<Decimal256>,
+}
+
+/// Parameters for swapping a cancelled order's refund into the opposite denom
+#[cw_serde]
+pub struct SwapRefund {
+    /// The minimum amount of the opposite denom that must be received
+    pub min_output: Uint128,
+}
+
 #[cw_serde]
 pub enum AuthExecuteMsg {
     // -- Admin Messages --
-    TransferAdmin { new_admin: Addr },
+    TransferAdmin {
+        new_admin: Addr,
+    },
     CancelAdminTransfer {},
     RejectAdminTransfer {},
     ClaimAdmin {},
     RenounceAdminship {},
 
     // -- Moderator Messages --
-    OfferModerator { new_moderator: Addr },
+    OfferModerator {
+        new_moderator: Addr,
+    },
     RejectModeratorOffer {},
     ClaimModerator {},
 
     // -- Shared messages --
-    SetActive { active: bool },
-    SetMakerFee { fee: Decimal256 },
-    SetMakerFeeRecipient { recipient: Addr },
+    SetActive {
+        active: bool,
+    },
+    SetMakerFee {
+        fee: Decimal256,
+    },
+    SetMakerFeeRecipient {
+        recipient: Addr,
+    },
+    SetTakerFee {
+        fee: Decimal256,
+        fee_collector: Addr,
+    },
+    SetDisallowSameTickOpposing {
+        disallow: bool,
+    },
+    SetPaused {
+        paused: bool,
+    },
+    RegisterDenomAlias {
+        alias: String,
+        canonical: String,
+    },
 }
 
 /// Message type for `migrate` entry_point
@@ -115,12 +276,230 @@ pub enum QueryMsg {
         start_from: Option<(i64, u64)>,
         // For indexed based pagination (tick_id, order_id), inclusive
         end_at: Option<(i64, u64)>,
-        // Defaults to 100
+        // Defaults to and is capped at 100
+        limit: Option<u64>,
+        // Only return orders in this direction
+        direction: Option<OrderDirection>,
+        // Only return orders with a non-zero claimable amount, defaults to false
+        only_claimable: Option<bool>,
+    },
+
+    /// Distinct ticks and directions the owner has orders on, sorted by tick
+    #[returns(Vec<(i64, OrderDirection)>)]
+    OwnerTicks {
+        owner: Addr,
+        // Only return ticks with orders in this direction
+        direction: Option<OrderDirection>,
+        // For tick id based pagination, exclusive
+        start_after: Option<i64>,
+        // Maximum number of ticks to return, defaults to 100 and capped at 100
         limit: Option<u64>,
     },
 
     #[returns(DenomsResponse)]
     Denoms {},
+
+    #[returns(OrderAvgPriceResponse)]
+    OrderAvgPrice { tick_id: i64, order_id: u64 },
+
+    /// Fill progress of an order, including fills that have not been claimed yet
+    #[returns(FillPriceResponse)]
+    OrderFillPrice { tick_id: i64, order_id: u64 },
+
+    #[returns(SolvencyCheckResponse)]
+    SolvencyCheck {},
+
+    /// Resting orders in one direction of a tick, in fill priority order
+    #[returns(Vec<crate::types::LimitOrder>)]
+    TickQueue {
+        tick_id: i64,
+        direction: OrderDirection,
+        // For order id based pagination, exclusive
+        start_after: Option<u64>,
+        // Defaults to and is capped at 100
+        limit: Option<u64>,
+    },
+
+    /// Simulates claiming every claimable order of an owner without mutating state
+    #[returns(SimulateClaimAllResponse)]
+    SimulateClaimAll {
+        owner: Addr,
+        // The address receiving claim bounties, defaults to the owner
+        claimer: Option<Addr>,
+        // For indexed based pagination (tick_id, order_id), exclusive
+        start_after: Option<(i64, u64)>,
+        // Maximum number of orders to scan, defaults to and is capped at 100
+        max_orders: Option<u64>,
+    },
+
+    /// Simulates a swap, reporting why it would stop filling alongside the output
+    #[returns(SimulateSwapExactAmountInResponse)]
+    SimulateSwapExactAmountIn {
+        token_in: Coin,
+        token_out_denom: String,
+        /// Tick to stop filling at, defaults to the end of the orderbook
+        target_tick: Option<i64>,
+        /// Maximum number of ticks with liquidity to fill against
+        max_ticks_crossed: Option<u32>,
+    },
+
+    /// Simulates a market order, reporting its average and worst execution prices
+    #[returns(PriceImpactResponse)]
+    SimulatePriceImpact {
+        direction: OrderDirection,
+        amount: Uint128,
+    },
+
+    /// Ticks whose cancellation sumtrees have grown beyond `min_fragments`
+    #[returns(CompactionCandidatesResponse)]
+    CompactionCandidates {
+        /// Compared against the sumtree root's weight, which is the height of the tree
+        min_fragments: u64,
+        // For tick id based pagination, exclusive
+        start_after: Option<i64>,
+        // Maximum number of ticks to scan, defaults to 100
+        limit: Option<u64>,
+    },
+
+    #[returns(TopClaimableResponse)]
+    TopClaimable {
+        // Defaults to and is capped at `MAX_BATCH_CLAIM`
+        limit: Option<u32>,
+    },
+
+    /// The amount an order's owner would currently receive by claiming it
+    #[returns(ClaimableResponse)]
+    ClaimableAmount { tick_id: i64, order_id: u64 },
+
+    /// The amount an owner would currently receive by claiming their orders, aggregated per denom
+    #[returns(Vec<Coin>)]
+    TotalClaimable {
+        owner: Addr,
+        // Maximum number of orders to scan, defaults to and is capped at 100
+        limit: Option<u64>,
+    },
+
+    /// Resting liquidity per tick in one direction, for ticks with liquidity within the bounds
+    #[returns(Vec<(i64, Decimal256)>)]
+    TicksInRange {
+        // Inclusive
+        min_tick: i64,
+        // Inclusive
+        max_tick: i64,
+        direction: OrderDirection,
+        // For tick id based pagination, exclusive
+        start_after: Option<i64>,
+        // Defaults to 100, capped at `MAX_TICKS_IN_RANGE`
+        limit: Option<u64>,
+    },
+
+    /// How far fills have progressed through one direction of a tick
+    #[returns(TickSwapProgressResponse)]
+    TickSwapProgress {
+        tick_id: i64,
+        direction: OrderDirection,
+    },
+
+    /// Sum of the cancelled amounts in one direction of a tick starting at or below `target_etas`
+    #[returns(Decimal256)]
+    CancelledPrefixSum {
+        tick_id: i64,
+        direction: OrderDirection,
+        target_etas: Decimal256,
+    },
+
+    /// The best bid and ask with resting liquidity and the mid price between them
+    #[returns(SpreadResponse)]
+    Spread {},
+
+    /// Total resting liquidity on each side of the orderbook
+    #[returns(BookLiquidityResponse)]
+    BookLiquidity {},
+
+    /// Signed share of resting notional on the bid side, from -1 (only asks) to 1 (only bids)
+    #[returns(SignedDecimal256)]
+    BookImbalance {},
+
+    /// The price of a tick, as used when matching orders against it
+    #[returns(Decimal256)]
+    TickPrice { tick_id: i64 },
+
+    /// The highest tick priced at or below `price`, clamped to the valid tick range
+    #[returns(i64)]
+    PriceToTick {
+        price: Decimal256,
+        /// Returns the lowest tick priced at or above `price` instead, defaults to false
+        round_up: Option<bool>,
+    },
+
+    /// The nodes of a tick's cancellation sumtree in depth first order, starting at the root
+    #[returns(Vec<crate::sumtree::node::TreeNode>)]
+    SumtreeNodes {
+        tick_id: i64,
+        direction: OrderDirection,
+        // Defaults to 100, capped at `MAX_SUMTREE_NODES`
+        max_nodes: Option<u64>,
+    },
+
+    /// Size and shape of a tick's cancellation sumtree, for budgeting claim and cancel gas
+    #[returns(SumtreeInfoResponse)]
+    SumtreeInfo {
+        tick_id: i64,
+        direction: OrderDirection,
+    },
+
+    /// Estimates the outcome of a `SwapExactAmountIn` against the current orderbook state
+    #[returns(EstimateSwapExactAmountInResponse)]
+    EstimateSwapExactAmountIn {
+        token_in: Coin,
+        token_out_denom: String,
+        swap_fee: Decimal,
+    },
+
+    /// Estimates the input required to receive `token_out` against the current orderbook state
+    #[returns(EstimateSwapExactAmountOutResponse)]
+    EstimateSwapExactAmountOut {
+        token_out: Coin,
+        token_in_denom: String,
+        swap_fee: Decimal,
+    },
+
+    /// The last market order fill price and the rolling 24h traded volume
+    #[returns(MarketStatsResponse)]
+    MarketStats {},
+
+    /// Stored cumulative realized cancels of one direction of a tick against the value re-derived
+    /// from its sumtree, for detecting desyncs
+    #[returns(RealizedCancelsResponse)]
+    RealizedCancels {
+        tick_id: i64,
+        direction: OrderDirection,
+    },
+    /// Unfilled liquidity queued ahead of an order on its tick
+    #[returns(QueuePositionResponse)]
+    QueuePosition { tick_id: i64, order_id: u64 },
+
+    /// The stored values of one direction of a tick as of its last sync, defaulting for untouched ticks
+    #[returns(crate::types::TickValues)]
+    TickState {
+        tick_id: i64,
+        direction: OrderDirection,
+    },
+
+    /// Whether an order is open, partially or fully filled, or was cancelled, including after removal
+    #[returns(crate::types::OrderStatus)]
+    OrderStatus { tick_id: i64, order_id: u64 },
+
+    /// The resting orders a market order would fill against, as `(tick_id, order_id, amount_consumed)` in fill order
+    #[returns(Vec<(i64, u64, Uint128)>)]
+    FillPreview {
+        direction: OrderDirection,
+        amount: Uint128,
+    },
+
+    /// The refund cancelling an order would currently produce, its unfilled remainder in the order's denom
+    #[returns(Coin)]
+    CancelRefundPreview { tick_id: i64, order_id: u64 },
 }
 
 #[cw_serde]
@@ -173,6 +552,188 @@ pub struct AllTicksResponse {
     pub ticks: Vec<TickIdAndState>,
 }
 
+#[cw_serde]
+pub struct OrderAvgPriceResponse {
+    /// Average realized price in tick price terms, `None` if nothing has been claimed
+    pub avg_price: Option<Decimal256>,
+    pub claimed_quantity: Uint128,
+    pub claimed_output: Uint256,
+}
+
+#[cw_serde]
+pub struct FillPriceResponse {
+    /// Quantity filled so far, whether claimed or not
+    pub filled_quantity: Uint128,
+    /// Limit orders only fill at their own tick, so this is always the tick price
+    pub average_price: Decimal256,
+    /// Quantity still resting on the book
+    pub remaining_quantity: Uint128,
+}
+
+#[cw_serde]
+pub struct ClaimableResponse {
+    /// Amount owed to the order owner, after bounty and maker fee deductions
+    pub amount: Uint128,
+    pub denom: String,
+}
+
+#[cw_serde]
+pub struct TickSwapProgressResponse {
+    pub effective_total_amount_swapped: Decimal256,
+    pub cumulative_total_value: Decimal256,
+    /// Share of the tick's total value that has been swapped, `None` if nothing was ever placed
+    pub fill_ratio: Option<Decimal256>,
+}
+
+#[cw_serde]
+pub struct SpreadResponse {
+    /// Highest tick with bid liquidity, `None` if there are no bids
+    pub best_bid_tick: Option<i64>,
+    /// Lowest tick with ask liquidity, `None` if there are no asks
+    pub best_ask_tick: Option<i64>,
+    pub best_bid_price: Option<Decimal256>,
+    pub best_ask_price: Option<Decimal256>,
+    /// Average of the best bid and ask prices, only set when both sides have liquidity
+    pub mid_price: Option<Decimal256>,
+}
+
+#[cw_serde]
+pub struct BookLiquidityResponse {
+    pub total_bid_liquidity: Decimal256,
+    pub total_ask_liquidity: Decimal256,
+}
+
+#[cw_serde]
+pub struct DenomSolvency {
+    pub denom: String,
+    /// The contract's actual bank balance
+    pub balance: Uint256,
+    /// Resting order liquidity plus filled but unclaimed proceeds
+    pub liabilities: Uint256,
+    /// `balance - liabilities`, negative values indicate insolvency
+    pub delta: Int256,
+}
+
+#[cw_serde]
+pub struct SolvencyCheckResponse {
+    pub denoms: Vec<DenomSolvency>,
+}
+
+#[cw_serde]
+pub struct ClaimableOrder {
+    pub tick_id: i64,
+    pub order_id: u64,
+    pub owner: Addr,
+    /// Filled but unclaimed quantity in the order's input denom
+    pub claimable_quantity: Uint128,
+    /// Value of the claimable quantity in the output denom, prior to bounty and maker fee
+    pub claimable_output: Uint256,
+}
+
+#[cw_serde]
+pub struct TopClaimableResponse {
+    /// Orders with a non-zero claimable amount, sorted by `claimable_output` descending
+    pub orders: Vec<ClaimableOrder>,
+}
+
+#[cw_serde]
+pub struct SimulateClaimAllResponse {
+    /// Orders that would be claimed, as (tick_id, order_id)
+    pub orders: Vec<(i64, u64)>,
+    /// Claim outputs aggregated into one message per recipient
+    pub messages: Vec<MsgSend256>,
+    /// Total proceeds the owner would receive, net of bounties and maker fees
+    pub proceeds: Vec<Coin256>,
+    /// Order key to continue scanning after, `None` once all orders have been scanned
+    pub next_start_after: Option<(i64, u64)>,
+}
+
+#[cw_serde]
+pub struct SimulateSwapExactAmountInResponse {
+    pub token_out: Coin256,
+    /// Input that would be left unfilled and refunded
+    pub token_in_remaining: Uint128,
+    pub stopped_reason: MarketOrderStopReason,
+}
+
+#[cw_serde]
+pub struct PriceImpactResponse {
+    /// Average price across all fills net of the taker fee, in the same terms as `tick_to_price`.
+    /// `None` if nothing would be filled
+    pub average_price: Option<Decimal256>,
+    /// Price of the last tick filled against, `None` if nothing would be filled
+    pub worst_price: Option<Decimal256>,
+    pub output: Coin256,
+    /// Whether the orderbook holds enough liquidity to fill the whole amount
+    pub fully_filled: bool,
+}
+
+#[cw_serde]
+pub struct EstimateSwapExactAmountInResponse {
+    pub token_out: Coin256,
+    /// Last tick the swap would fill against, `None` if it fills nothing
+    pub final_tick: Option<i64>,
+}
+
+#[cw_serde]
+pub struct EstimateSwapExactAmountOutResponse {
+    /// Input required to receive `token_out`, rounded in favor of the orderbook
+    pub token_in: Coin256,
+    /// The requested output, or the most the orderbook can provide if liquidity is insufficient
+    pub token_out: Coin256,
+    pub sufficient_liquidity: bool,
+}
+
+#[cw_serde]
+pub struct SumtreeInfoResponse {
+    /// Number of internal and leaf nodes in the tree, including the root
+    pub node_count: u64,
+    /// Number of levels from the root to the deepest leaf
+    pub height: u64,
+    /// Total amount cancelled across all leaves
+    pub root_value: Decimal256,
+}
+
+#[cw_serde]
+pub struct RealizedCancelsResponse {
+    pub stored: Decimal256,
+    /// Prefix sum of the tick's sumtree at its current ETAS
+    pub recomputed: Decimal256,
+    pub in_sync: bool,
+}
+
+#[cw_serde]
+pub struct QueuePositionResponse {
+    /// Unfilled quantity of the orders on the same tick and direction that fill before the order
+    pub quantity_ahead: Decimal256,
+    /// Unfilled quantity of all orders on the same tick and direction, including the order itself
+    pub total_at_tick: Decimal256,
+}
+
+#[cw_serde]
+pub struct MarketStatsResponse {
+    /// Price of the tick the most recent market order finished on, unset before the first trade
+    pub last_price: Option<Decimal256>,
+    pub last_trade_time: Option<Timestamp>,
+    /// Traded volume over the last 24 hours, denominated in the quote asset
+    pub volume_24h: Uint256,
+}
+
+#[cw_serde]
+pub struct CompactionCandidate {
+    pub tick_id: i64,
+    pub direction: OrderDirection,
+    /// Weight of the sumtree root
+    pub weight: u64,
+}
+
+#[cw_serde]
+pub struct CompactionCandidatesResponse {
+    pub candidates: Vec<CompactionCandidate>,
+    /// Tick id to continue scanning after, `None` once all ticks have been scanned
+    pub next_start_after: Option<i64>,
+}
+
 #[cw_serde]
 pub enum SudoMsg {
     /// SwapExactAmountIn swaps an exact amount of tokens in for as many tokens out as possible.
@@ -185,6 +746,8 @@ pub enum SudoMsg {
         token_out_denom: String,
         token_out_min_amount: Uint128,
         swap_fee: Decimal,
+        /// Address credited with the swap's volume for fee sharing
+        referrer: Option<String>,
     },
     // SwapToTick functions exactly as SwapExactAmountIn, but it terminates the swap when the target tick
     // is reached.
@@ -195,6 +758,10 @@ pub enum SudoMsg {
         token_out_min_amount: Uint128,
         swap_fee: Decimal,
         target_tick: i64,
+        /// Reverts the swap instead of partially filling if the target tick is reached first
+        fill_or_kill: Option<bool>,
+        /// Cancels the sender's own resting orders on the ticks reached instead of filling against them
+        stp: Option<bool>,
     },
     /// SwapExactAmountOut swaps as many tokens in as possible for an exact amount of tokens out.
     /// The amount of tokens in is determined by the current exchange rate and the swap fee.
@@ -208,6 +775,8 @@ pub enum SudoMsg {
         token_in_max_amount: Uint128,
         token_out: Coin,
         swap_fee: Decimal,
+        /// Address credited with the swap's volume for fee sharing
+        referrer: Option<String>,
     },
     TransferAdmin {
         new_admin: Addr,
@@ -218,6 +787,27 @@ pub enum SudoMsg {
     SetActive {
         active: bool,
     },
+
+    // -- Tick Bounds --
+    /// Sets the global tick bounds for limit orders, which must lie within `MIN_TICK` and `MAX_TICK`
+    SetTickBounds {
+        min_tick: i64,
+        max_tick: i64,
+    },
+
+    // -- Diagnostics --
+    /// Disables AVL rotations in the cancellation sumtrees when enabled. Trees remain correct
+    /// but may become unbalanced. Intended only for isolating rotation bugs and must never be
+    /// left enabled in production.
+    SetSumtreeSafeMode {
+        enabled: bool,
+    },
+    /// Overwrites the cumulative realized cancels of one direction of a tick with the value
+    /// re-derived from its sumtree, repairing a desync reported by the `RealizedCancels` query.
+    RecomputeRealizedCancels {
+        tick_id: i64,
+        direction: OrderDirection,
+    },
 }
 
 #[cw_serde]
@@ -1,39 +1,420 @@
-use crate::constants::{MAX_BATCH_CLAIM, MAX_TICK, MIN_TICK};
+use std::collections::BTreeMap;
+
+use crate::constants::{MAX_BATCH_CANCEL, MAX_BATCH_CLAIM};
+use crate::credit::add_credit;
 use crate::error::{ContractError, ContractResult};
+use crate::msg::{BatchClaimData, ClaimData, PlaceLimitInput};
 use crate::state::{
-    add_directional_liquidity, get_maker_fee, new_order_id, orders, subtract_directional_liquidity,
-    MAKER_FEE_RECIPIENT, ORDERBOOK, TICK_STATE,
+    add_directional_liquidity, decrement_tick_order_count, ensure_canonical_denom,
+    get_directional_liquidity, get_maker_fee, get_market_tick_bound, get_orders_by_owner,
+    get_tick_bounds, get_tick_order_count, increment_tick_order_count, new_order_id,
+    next_populated_tick, orders, save_tick_state, subtract_directional_liquidity, CANCELLED_ORDERS,
+    DISALLOW_SAME_TICK_OPPOSING, FILLED_ORDERS, ICEBERG_ORDERS, MAKER_FEE_RECIPIENT, MARKET_STATS,
+    ORDERBOOK, ORDER_EXPIRIES, TICK_STATE,
 };
-use crate::sumtree::node::{generate_node_id, NodeType, TreeNode};
-use crate::sumtree::tree::get_or_init_root_node;
-use crate::tick::sync_tick;
-use crate::tick_math::{amount_to_value, tick_to_price, RoundingDirection};
+use crate::sudo::ensure_not_paused;
+use crate::sumtree::node::{generate_node_id, NodeType, TreeNode, NODE_ID_COUNTER};
+use crate::sumtree::tree::{get_or_init_root_node, remove_tree};
+use crate::tick::{get_synced_tick_values, subtract_liquidity, sync_tick};
+use crate::tick_math::{amount_out_rounded, amount_to_value, tick_to_price, RoundingDirection};
 use crate::types::{
-    coin_u256, Coin256, LimitOrder, MarketOrder, MsgSend256, OrderDirection, Orderbook, TickState,
-    REPLY_ID_CLAIM, REPLY_ID_CLAIM_BOUNTY, REPLY_ID_MAKER_FEE, REPLY_ID_REFUND,
+    coin_u256, Coin256, FilterOwnerOrders, LimitOrder, MarketOrder, MarketOrderStopReason,
+    MsgSend256, OrderDirection, Orderbook, TickState, TickValues, TimeInForce, REPLY_ID_CLAIM,
+    REPLY_ID_CLAIM_BOUNTY, REPLY_ID_IMMEDIATE_FILL, REPLY_ID_MAKER_FEE, REPLY_ID_REFUND,
+    REPLY_ID_TAKER_FEE,
 };
 use cosmwasm_std::{
-    coin, ensure, ensure_eq, Addr, BankMsg, Decimal256, DepsMut, Env, Event, MessageInfo, Order,
-    Response, Storage, SubMsg, Uint128, Uint256,
+    coin, ensure, ensure_eq, to_json_binary, Addr, BankMsg, Decimal256, DepsMut, Empty, Env, Event,
+    MessageInfo, Order, Response, StdResult, Storage, SubMsg, Timestamp, Uint128, Uint256,
 };
 use cw_storage_plus::Bound;
-use cw_utils::{must_pay, nonpayable};
+use cw_utils::{must_pay, nonpayable, PaymentError};
 
-#[allow(clippy::manual_range_contains, clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments)]
 pub fn place_limit(
     deps: &mut DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     tick_id: i64,
     order_direction: OrderDirection,
     quantity: Uint128,
     claim_bounty: Option<Decimal256>,
+    post_only: Option<bool>,
+    expiry: Option<Timestamp>,
+    time_in_force: Option<TimeInForce>,
+    display_quantity: Option<Uint128>,
+    refund_to_credit: bool,
 ) -> Result<Response, ContractError> {
-    let mut orderbook = ORDERBOOK.load(deps.storage)?;
+    ensure_not_paused(deps.storage)?;
+
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+
+    validate_limit_order(deps.storage, tick_id, quantity, claim_bounty)?;
+
+    // An order must be able to rest for at least one block before expiring
+    if let Some(expiry) = expiry {
+        ensure!(
+            expiry > env.block.time,
+            ContractError::InvalidExpiry {
+                expiry,
+                block_time: env.block.time
+            }
+        );
+    }
+
+    // Iceberg orders must keep part of their quantity in reserve
+    if let Some(display_quantity) = display_quantity {
+        ensure!(
+            !display_quantity.is_zero() && display_quantity < quantity,
+            ContractError::InvalidDisplayQuantity {
+                display_quantity,
+                quantity
+            }
+        );
+    }
+
+    // Post-only orders must rest on the book, so reject any order at or beyond the best opposing tick
+    if post_only.unwrap_or_default() {
+        let opposing_direction = order_direction.opposite();
+        let best_opposing_tick = match order_direction {
+            OrderDirection::Bid => orderbook.next_ask_tick,
+            OrderDirection::Ask => orderbook.next_bid_tick,
+        };
+        let would_cross = match order_direction {
+            OrderDirection::Bid => tick_id >= best_opposing_tick,
+            OrderDirection::Ask => tick_id <= best_opposing_tick,
+        };
+        // Tick pointers default to the tick bounds, so only an existing opposing side can be crossed
+        let has_opposing_liquidity =
+            !get_directional_liquidity(deps.storage, opposing_direction)?.is_zero();
+        ensure!(
+            !(would_cross && has_opposing_liquidity),
+            ContractError::WouldCross {
+                tick_id,
+                best_opposing_tick
+            }
+        );
+    }
+
+    // Determine the correct denom based on order direction
+    let expected_denom = orderbook.get_expected_denom(&order_direction);
+
+    // Verify the funds sent with the message match the `quantity` for the correct denom
+    // We reject any quantity that is not exactly equal to the amount in the limit order being placed
+    for coin in &info.funds {
+        ensure_canonical_denom(deps.storage, &coin.denom)?;
+    }
+    let received = must_pay(&info, &expected_denom)?;
+    ensure_eq!(
+        received,
+        quantity,
+        ContractError::InsufficientFunds {
+            sent: received,
+            required: quantity,
+        }
+    );
+
+    if time_in_force.unwrap_or_default() == TimeInForce::ImmediateOrCancel {
+        // Options that only apply to resting orders would otherwise be silently dropped
+        for (option, is_set) in [
+            ("expiry", expiry.is_some()),
+            ("display_quantity", display_quantity.is_some()),
+            ("claim_bounty", claim_bounty.is_some()),
+        ] {
+            ensure!(
+                !is_set,
+                ContractError::InvalidImmediateOrCancel {
+                    option: option.to_string()
+                }
+            );
+        }
+        return fill_immediate_or_cancel(
+            deps,
+            env,
+            info.sender,
+            tick_id,
+            order_direction,
+            quantity,
+            &orderbook,
+            refund_to_credit,
+        );
+    }
+
+    let liquidity_before = tick_liquidity(deps.storage, tick_id, order_direction)?;
+    let order_id = insert_limit_order(
+        deps.storage,
+        info.sender.clone(),
+        tick_id,
+        order_direction,
+        quantity,
+        claim_bounty,
+        expiry,
+        display_quantity,
+    )?;
+    let liquidity_after = tick_liquidity(deps.storage, tick_id, order_direction)?;
+
+    Ok(Response::default()
+        .add_events(tick_transition_event(
+            tick_id,
+            order_direction,
+            liquidity_before,
+            liquidity_after,
+        ))
+        .add_attribute("method", "placeLimit")
+        .add_attribute("owner", info.sender.to_string())
+        .add_attribute("tick_id", tick_id.to_string())
+        .add_attribute("order_id", order_id.to_string())
+        .add_attribute("order_direction", order_direction.to_string())
+        .add_attribute("quantity", quantity.to_string())
+        .add_attribute("order_denom", expected_denom.to_string())
+        .add_attribute(
+            "output_denom",
+            orderbook.get_opposite_denom(&order_direction).to_string(),
+        ))
+}
+
+/// Fills an immediate-or-cancel limit order against opposing liquidity up to its limit tick and
+/// refunds the unfilled remainder, so nothing is ever added to the tick or its sumtree.
+///
+/// An order on the outermost tick has no price bound, so it only fills what the book holds and
+/// refunds the rest rather than erroring like a full market order. Orders funded from credit have
+/// their remainder credited back instead of sent.
+#[allow(clippy::too_many_arguments)]
+fn fill_immediate_or_cancel(
+    deps: &mut DepsMut,
+    env: Env,
+    owner: Addr,
+    tick_id: i64,
+    order_direction: OrderDirection,
+    quantity: Uint128,
+    orderbook: &Orderbook,
+    refund_to_credit: bool,
+) -> ContractResult<Response> {
+    let order_denom = orderbook.get_expected_denom(&order_direction);
+    let output_denom = orderbook.get_opposite_denom(&order_direction);
+    let mut market_order = MarketOrder::new(quantity, order_direction, owner.clone());
+
+    // Tick pointers default to the tick bounds, so only an existing opposing side can be crossed
+    let would_cross = match order_direction {
+        OrderDirection::Bid => tick_id >= orderbook.next_ask_tick,
+        OrderDirection::Ask => tick_id <= orderbook.next_bid_tick,
+    };
+    let has_opposing_liquidity =
+        !get_directional_liquidity(deps.storage, order_direction.opposite())?.is_zero();
+
+    let mut msgs = vec![];
+    let mut fill_events = vec![];
+    let mut output = Uint256::zero();
+    let mut held_back = Uint128::zero();
+    if would_cross && has_opposing_liquidity {
+        let expiry_refund_msgs =
+            void_expired_orders(deps.storage, &market_order, tick_id, env.block.time)?;
+
+        // Hold back whatever the book cannot fill so the rest fills like a partial market order
+        let mut simulated_order = market_order.clone();
+        let simulated = simulate_market_order(
+            deps.storage,
+            &mut simulated_order,
+            tick_id,
+            None,
+            env.block.time,
+        )?;
+        if simulated.stop_reason == MarketOrderStopReason::OutOfLiquidity {
+            held_back = simulated_order.quantity;
+            market_order.quantity = market_order.quantity.checked_sub(held_back)?;
+        }
+
+        // Voiding expired orders may have left nothing to fill against
+        if !market_order.quantity.is_zero() {
+            let (filled_output, bank_msg, fee_msg, events) = run_market_order(
+                deps.storage,
+                env.contract.address,
+                env.block.time,
+                &mut market_order,
+                tick_id,
+                false,
+                // The limit tick already bounds the worst price the order can fill at
+                None,
+            )?;
+            output = filled_output;
+            fill_events = events;
+
+            if !output.is_zero() {
+                msgs.push(SubMsg::reply_on_error(bank_msg, REPLY_ID_IMMEDIATE_FILL));
+            }
+            if let Some(fee_msg) = fee_msg {
+                msgs.push(SubMsg::reply_on_error(fee_msg, REPLY_ID_TAKER_FEE));
+            }
+        }
+        msgs.extend(expiry_refund_msgs);
+    }
+
+    // Refund whatever could not be filled at or within the limit tick
+    let refund = market_order.quantity.checked_add(held_back)?;
+    if !refund.is_zero() {
+        if refund_to_credit {
+            add_credit(deps.storage, &owner, &order_denom, refund)?;
+        } else {
+            msgs.push(SubMsg::reply_on_error(
+                BankMsg::Send {
+                    to_address: owner.to_string(),
+                    amount: vec![coin(refund.u128(), order_denom.clone())],
+                },
+                REPLY_ID_REFUND,
+            ));
+        }
+    }
+
+    Ok(Response::default()
+        .add_attributes(vec![
+            ("method", "placeLimit"),
+            ("owner", owner.as_str()),
+            ("tick_id", &tick_id.to_string()),
+            ("order_direction", &order_direction.to_string()),
+            ("quantity", &quantity.to_string()),
+            ("order_denom", &order_denom),
+            ("output_denom", &output_denom),
+            ("time_in_force", "immediate_or_cancel"),
+            ("output_quantity", &output.to_string()),
+            ("refund_quantity", &refund.to_string()),
+        ])
+        .add_submessages(msgs)
+        .add_events(fill_events))
+}
+
+/// Places multiple limit orders for the sender in a single call.
+///
+/// Every order is validated and the attached funds must exactly equal the summed quantities per
+/// direction before any order is written, so a single invalid order rejects the whole batch.
+pub fn place_limit_batch(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    orders: Vec<PlaceLimitInput>,
+) -> ContractResult<Response> {
+    ensure_not_paused(deps.storage)?;
+
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+
+    ensure!(
+        !orders.is_empty(),
+        ContractError::InvalidQuantity {
+            quantity: Uint128::zero()
+        }
+    );
+
+    // Validate all orders and sum the funds required in each denom
+    let mut required_base = Uint128::zero();
+    let mut required_quote = Uint128::zero();
+    for order in &orders {
+        validate_limit_order(
+            deps.storage,
+            order.tick_id,
+            order.quantity,
+            order.claim_bounty,
+        )?;
+        match order.order_direction {
+            OrderDirection::Ask => required_base = required_base.checked_add(order.quantity)?,
+            OrderDirection::Bid => required_quote = required_quote.checked_add(order.quantity)?,
+        }
+    }
+
+    // Only the orderbook's denoms may be attached
+    for coin in &info.funds {
+        ensure_canonical_denom(deps.storage, &coin.denom)?;
+        ensure!(
+            coin.denom == orderbook.base_denom || coin.denom == orderbook.quote_denom,
+            PaymentError::ExtraDenom(coin.denom.clone())
+        );
+    }
+
+    // The attached funds must match the required amount in each denom exactly
+    for (denom, required) in [
+        (&orderbook.base_denom, required_base),
+        (&orderbook.quote_denom, required_quote),
+    ] {
+        let sent = info
+            .funds
+            .iter()
+            .filter(|coin| &coin.denom == denom)
+            .try_fold(Uint128::zero(), |total, coin| {
+                total.checked_add(coin.amount)
+            })?;
+        ensure_eq!(
+            sent,
+            required,
+            ContractError::InsufficientFunds { sent, required }
+        );
+    }
+
+    // Record the liquidity of each tick direction placed on so activations are reported once per tick
+    let mut liquidity_before: Vec<(i64, OrderDirection, Decimal256)> = vec![];
+    for order in &orders {
+        if !liquidity_before.iter().any(|(tick_id, direction, _)| {
+            *tick_id == order.tick_id && *direction == order.order_direction
+        }) {
+            liquidity_before.push((
+                order.tick_id,
+                order.order_direction,
+                tick_liquidity(deps.storage, order.tick_id, order.order_direction)?,
+            ));
+        }
+    }
+
+    let mut order_ids = vec![];
+    for order in orders {
+        order_ids.push(insert_limit_order(
+            deps.storage,
+            info.sender.clone(),
+            order.tick_id,
+            order.order_direction,
+            order.quantity,
+            order.claim_bounty,
+            None,
+            None,
+        )?);
+    }
+
+    let mut events = vec![];
+    for (tick_id, direction, before) in liquidity_before {
+        let after = tick_liquidity(deps.storage, tick_id, direction)?;
+        events.extend(tick_transition_event(tick_id, direction, before, after));
+    }
+
+    Ok(Response::default().add_events(events).add_attributes(vec![
+        ("method", "placeLimitBatch"),
+        ("owner", info.sender.as_str()),
+        ("order_count", &order_ids.len().to_string()),
+        (
+            "order_ids",
+            &order_ids
+                .iter()
+                .map(|order_id| order_id.to_string())
+                .collect::<Vec<String>>()
+                .join(","),
+        ),
+    ]))
+}
 
-    // Validate tick_id is within valid range
+/// Validates the parameters of a limit order prior to placement.
+///
+/// Errors if:
+/// 1. The tick is outside the configured tick bounds (`get_tick_bounds`)
+/// 2. The quantity is zero
+/// 3. The tick is not a multiple of the orderbook's `tick_spacing`, if one is set
+/// 4. The quantity is below the orderbook's `min_order_size`
+/// 5. The claim bounty is above 1%, or above the orderbook's `max_claim_bounty` if one is set
+#[allow(clippy::manual_range_contains)]
+fn validate_limit_order(
+    storage: &dyn Storage,
+    tick_id: i64,
+    quantity: Uint128,
+    claim_bounty: Option<Decimal256>,
+) -> ContractResult<()> {
+    // Validate tick_id is within the configured global range
+    let (min_tick, max_tick) = get_tick_bounds(storage)?;
     ensure!(
-        tick_id >= MIN_TICK && tick_id <= MAX_TICK,
+        tick_id >= min_tick && tick_id <= max_tick,
         ContractError::InvalidTickId { tick_id }
     );
 
@@ -43,6 +424,25 @@ pub fn place_limit(
         ContractError::InvalidQuantity { quantity }
     );
 
+    // Ensure the tick lies on the orderbook's tick grid
+    let orderbook = ORDERBOOK.load(storage)?;
+    if let Some(tick_spacing) = orderbook.tick_spacing {
+        ensure!(
+            tick_id % tick_spacing == 0,
+            ContractError::InvalidTickId { tick_id }
+        );
+    }
+
+    // Ensure order_quantity meets the orderbook's minimum order size
+    let minimum = orderbook.min_order_size;
+    ensure!(
+        quantity >= minimum,
+        ContractError::OrderTooSmall {
+            size: quantity,
+            minimum
+        }
+    );
+
     // If applicable, ensure claim_bounty is between 0 and 0.01.
     // We set a conservative upper bound of 1% for claim bounties as a guardrail.
     if let Some(claim_bounty_value) = claim_bounty {
@@ -53,25 +453,75 @@ pub fn place_limit(
                 claim_bounty: Some(claim_bounty_value)
             }
         );
+
+        // Ensure claim_bounty is within the orderbook's own cap, if one is configured
+        if let Some(max_claim_bounty) = orderbook.max_claim_bounty {
+            ensure!(
+                claim_bounty_value <= max_claim_bounty,
+                ContractError::InvalidClaimBounty {
+                    claim_bounty: Some(claim_bounty_value)
+                }
+            );
+        }
     }
 
-    // Determine the correct denom based on order direction
-    let expected_denom = orderbook.get_expected_denom(&order_direction);
+    Ok(())
+}
 
-    // Verify the funds sent with the message match the `quantity` for the correct denom
-    // We reject any quantity that is not exactly equal to the amount in the limit order being placed
-    let received = must_pay(&info, &expected_denom)?;
-    ensure_eq!(
-        received,
-        quantity,
-        ContractError::InsufficientFunds {
-            sent: received,
-            required: quantity,
-        }
-    );
+/// If enabled, rejects adding liquidity to a tick that already holds liquidity in the opposing
+/// direction.
+fn ensure_no_same_tick_opposing(
+    storage: &dyn Storage,
+    tick_id: i64,
+    order_direction: OrderDirection,
+) -> ContractResult<()> {
+    if DISALLOW_SAME_TICK_OPPOSING
+        .may_load(storage)?
+        .unwrap_or_default()
+    {
+        let opposing_liquidity = TICK_STATE
+            .may_load(storage, tick_id)?
+            .unwrap_or_default()
+            .get_values(order_direction.opposite())
+            .total_amount_of_liquidity;
+        ensure!(
+            opposing_liquidity.is_zero(),
+            ContractError::SameTickOpposing { tick_id }
+        );
+    }
+    Ok(())
+}
+
+/// Writes a validated limit order to the orderbook, updating tick pointers and liquidity.
+///
+/// Returns the new order's id.
+///
+/// CONTRACT: The caller must ensure the order was validated and its funds were actually supplied.
+#[allow(clippy::too_many_arguments)]
+fn insert_limit_order(
+    storage: &mut dyn Storage,
+    owner: Addr,
+    tick_id: i64,
+    order_direction: OrderDirection,
+    quantity: Uint128,
+    claim_bounty: Option<Decimal256>,
+    expiry: Option<Timestamp>,
+    display_quantity: Option<Uint128>,
+) -> ContractResult<u64> {
+    let mut orderbook = ORDERBOOK.load(storage)?;
+
+    ensure_no_same_tick_opposing(storage, tick_id, order_direction)?;
+
+    // Cap the number of resting orders per tick direction to bound the cost of crossing the tick
+    if let Some(max_orders_per_tick) = orderbook.max_orders_per_tick {
+        ensure!(
+            get_tick_order_count(storage, tick_id, order_direction)? < max_orders_per_tick,
+            ContractError::TickFull { tick_id }
+        );
+    }
 
     // Generate a new order ID
-    let order_id = new_order_id(deps.storage)?;
+    let order_id = new_order_id(storage)?;
 
     // If bid and tick_id is higher than next bid tick, update next bid tick
     // If ask and tick_id is lower than next ask tick, update next ask tick
@@ -87,33 +537,38 @@ pub fn place_limit(
             }
         }
     }
-    ORDERBOOK.save(deps.storage, &orderbook)?;
+    ORDERBOOK.save(storage, &orderbook)?;
 
     // Update ETAS from Tick State
-    let mut tick_state = TICK_STATE.load(deps.storage, tick_id).unwrap_or_default();
+    let mut tick_state = TICK_STATE.load(storage, tick_id).unwrap_or_default();
     let mut tick_values = tick_state.get_values(order_direction);
 
     // Build limit order
-    let limit_order = LimitOrder::new(
+    let mut limit_order = LimitOrder::new(
         tick_id,
         order_id,
         order_direction,
-        info.sender.clone(),
+        owner,
         quantity,
         tick_values.cumulative_total_value,
         claim_bounty,
     );
+    limit_order.expiry = expiry;
+    limit_order.display_quantity = display_quantity;
 
     let quant_dec256 = Decimal256::from_ratio(limit_order.quantity.u128(), Uint256::one());
     // Only save the order if not fully filled
     if limit_order.quantity > Uint128::zero() {
         // Save the order to the orderbook
-        orders().save(deps.storage, &(tick_id, order_id), &limit_order)?;
+        orders().save(storage, &(tick_id, order_id), &limit_order)?;
+        increment_tick_order_count(storage, tick_id, order_direction)?;
+        if let Some(expiry) = expiry {
+            ORDER_EXPIRIES.save(storage, (tick_id, expiry.nanos(), order_id), &Empty {})?;
+        }
 
         tick_values.total_amount_of_liquidity = tick_values
             .total_amount_of_liquidity
-            .checked_add(quant_dec256)
-            .unwrap();
+            .checked_add(quant_dec256)?;
     }
 
     tick_values.cumulative_total_value = tick_values
@@ -121,21 +576,15 @@ pub fn place_limit(
         .checked_add(Decimal256::from_ratio(quantity, Uint256::one()))?;
 
     tick_state.set_values(order_direction, tick_values);
-    TICK_STATE.save(deps.storage, tick_id, &tick_state)?;
-    add_directional_liquidity(deps.storage, order_direction, quant_dec256)?;
+    save_tick_state(storage, tick_id, &tick_state)?;
+    add_directional_liquidity(storage, order_direction, quant_dec256)?;
 
-    Ok(Response::default()
-        .add_attribute("method", "placeLimit")
-        .add_attribute("owner", info.sender.to_string())
-        .add_attribute("tick_id", tick_id.to_string())
-        .add_attribute("order_id", order_id.to_string())
-        .add_attribute("order_direction", order_direction.to_string())
-        .add_attribute("quantity", quantity.to_string())
-        .add_attribute("order_denom", expected_denom.to_string())
-        .add_attribute(
-            "output_denom",
-            orderbook.get_opposite_denom(&order_direction).to_string(),
-        ))
+    if display_quantity.is_some() {
+        ICEBERG_ORDERS.save(storage, (tick_id, order_id), &Empty {})?;
+        sync_hidden_liquidity(storage, tick_id, order_direction)?;
+    }
+
+    Ok(order_id)
 }
 
 pub fn cancel_limit(
@@ -146,47 +595,9 @@ pub fn cancel_limit(
     order_id: u64,
 ) -> Result<Response, ContractError> {
     nonpayable(&info)?;
-    let key = (tick_id, order_id);
-    // Check for the order, error if not found
-    let order = orders()
-        .may_load(deps.storage, &key)?
-        .ok_or(ContractError::OrderNotFound { tick_id, order_id })?;
-
-    // Ensure the sender is the order owner
-    ensure_eq!(info.sender, order.owner, ContractError::Unauthorized {});
-
-    // Ensure the order has not been filled.
-    let tick_state = TICK_STATE.load(deps.storage, tick_id).unwrap_or_default();
-    let tick_values = tick_state.get_values(order.order_direction);
-    ensure!(
-        tick_values.effective_total_amount_swapped <= order.etas,
-        ContractError::CancelFilledOrder
-    );
-
-    // Fetch the sumtree from storage, or create one if it does not exist
-    let mut tree = get_or_init_root_node(deps.storage, tick_id, order.order_direction)?;
 
-    // Generate info for new node to insert to sumtree
-    let node_id = generate_node_id(deps.storage, order.tick_id)?;
-    let mut curr_tick_state =
-        TICK_STATE
-            .load(deps.storage, order.tick_id)
-            .ok()
-            .ok_or(ContractError::InvalidTickId {
-                tick_id: order.tick_id,
-            })?;
-    let mut curr_tick_values = curr_tick_state.get_values(order.order_direction);
-    let quant_dec256 =
-        Decimal256::from_ratio(Uint256::from_uint128(order.quantity), Uint256::one());
-    let mut new_node = TreeNode::new(
-        order.tick_id,
-        order.order_direction,
-        node_id,
-        NodeType::leaf(order.etas, quant_dec256),
-    );
-
-    // Insert new node
-    tree.insert(deps.storage, &mut new_node)?;
+    let order = cancel_order(deps.storage, info.sender.clone(), tick_id, order_id)?;
+    let tick_event = cancel_transition_event(deps.storage, &order)?;
 
     // Get orderbook info for correct denomination
     let orderbook = ORDERBOOK.load(deps.storage)?;
@@ -201,17 +612,6 @@ pub fn cancel_limit(
         REPLY_ID_REFUND,
     );
 
-    orders().remove(deps.storage, &(order.tick_id, order.order_id))?;
-
-    curr_tick_values.total_amount_of_liquidity = curr_tick_values
-        .total_amount_of_liquidity
-        .checked_sub(Decimal256::from_ratio(order.quantity, Uint256::one()))?;
-    curr_tick_state.set_values(order.order_direction, curr_tick_values);
-    TICK_STATE.save(deps.storage, order.tick_id, &curr_tick_state)?;
-    subtract_directional_liquidity(deps.storage, order.order_direction, quant_dec256)?;
-
-    tree.save(deps.storage)?;
-
     Ok(Response::new()
         .add_attributes(vec![
             ("method", "cancelLimit"),
@@ -229,30 +629,953 @@ pub fn cancel_limit(
                     .to_string(),
             ),
         ])
+        .add_events(tick_event)
         .add_submessage(refund_msg))
 }
 
-pub fn claim_limit(
+/// Cancels a limit order like `cancel_limit`, but keeps the refund as the owner's credit instead of
+/// sending it.
+pub fn cancel_limit_to_credit(
     deps: DepsMut,
-    env: Env,
     info: MessageInfo,
     tick_id: i64,
     order_id: u64,
-) -> Result<Response, ContractError> {
+) -> ContractResult<Response> {
     nonpayable(&info)?;
 
-    let (amount_claimed, bank_msgs, order) = claim_order(
-        deps.storage,
-        env.contract.address,
-        info.sender.clone(),
-        tick_id,
-        order_id,
+    let order = cancel_order(deps.storage, info.sender.clone(), tick_id, order_id)?;
+    let tick_event = cancel_transition_event(deps.storage, &order)?;
+
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let expected_denom = orderbook.get_expected_denom(&order.order_direction);
+    add_credit(deps.storage, &order.owner, &expected_denom, order.quantity)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("method", "cancelLimitToCredit"),
+            ("owner", info.sender.as_str()),
+            ("tick_id", &tick_id.to_string()),
+            ("order_id", &order_id.to_string()),
+            ("quantity", &order.quantity.to_string()),
+            ("order_direction", &order.order_direction.to_string()),
+            ("order_denom", &expected_denom),
+        ])
+        .add_events(tick_event))
+}
+
+/// Cancels part of a resting limit order, refunding exactly `amount`.
+///
+/// The cancelled amount is taken from the back of the order, so the remainder keeps its place in
+/// the tick queue and any filled quantity stays claimable. If nothing of the order is left it is
+/// removed entirely.
+///
+/// Errors if `amount` is zero or exceeds the order's unfilled quantity.
+pub fn cancel_limit_partial(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    tick_id: i64,
+    order_id: u64,
+    amount: Uint128,
+) -> ContractResult<Response> {
+    nonpayable(&info)?;
+
+    let key = (tick_id, order_id);
+    let mut order = orders()
+        .may_load(deps.storage, &key)?
+        .ok_or(ContractError::OrderNotFound { tick_id, order_id })?;
+
+    // Ensure the sender is the order owner
+    ensure_eq!(info.sender, order.owner, ContractError::Unauthorized {});
+
+    ensure!(
+        !amount.is_zero(),
+        ContractError::InvalidQuantity { quantity: amount }
+    );
+    let remaining = order
+        .quantity
+        .checked_sub(get_amount_filled(deps.storage, &order)?)?;
+    ensure!(
+        amount <= remaining,
+        ContractError::CancelExceedsRemaining { amount, remaining }
+    );
+
+    // The cancelled amount is recorded in the sumtree so it is skipped by future fills
+    let kept = order.quantity.checked_sub(amount)?;
+    remove_order_tail(deps.storage, &order, kept)?;
+
+    order.quantity = kept;
+    order.placed_quantity = order.placed_quantity.checked_sub(amount)?;
+    if order.quantity.is_zero() {
+        orders().remove(deps.storage, &key)?;
+        remove_order_expiry(deps.storage, &order);
+        decrement_tick_order_count(deps.storage, order.tick_id, order.order_direction)?;
+        CANCELLED_ORDERS.save(deps.storage, key, &Empty {})?;
+    } else {
+        orders().save(deps.storage, &key, &order)?;
+    }
+    sync_hidden_liquidity(deps.storage, tick_id, order.order_direction)?;
+    let tick_event = cancel_transition_event(deps.storage, &order)?;
+
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let expected_denom = orderbook.get_expected_denom(&order.order_direction);
+    let refund_msg = SubMsg::reply_on_error(
+        BankMsg::Send {
+            to_address: order.owner.to_string(),
+            amount: vec![coin(amount.u128(), expected_denom.clone())],
+        },
+        REPLY_ID_REFUND,
+    );
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("method", "cancelLimitPartial"),
+            ("owner", info.sender.as_str()),
+            ("tick_id", &tick_id.to_string()),
+            ("order_id", &order_id.to_string()),
+            ("quantity", &amount.to_string()),
+            ("remaining_quantity", &order.quantity.to_string()),
+            ("order_direction", &order.order_direction.to_string()),
+            ("order_denom", &expected_denom),
+        ])
+        .add_events(tick_event)
+        .add_submessage(refund_msg))
+}
+
+/// Cancels a limit order and swaps the refund into the opposite denom by running a market order
+/// for the refunded quantity against the same orderbook.
+///
+/// Errors if the swap output does not meet `min_output` or the book lacks liquidity to fill it.
+/// Any dust left unswapped is refunded in the order's original denom.
+pub fn cancel_limit_and_swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tick_id: i64,
+    order_id: u64,
+    min_output: Uint128,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let order = cancel_order(deps.storage, info.sender.clone(), tick_id, order_id)?;
+    let tick_event = cancel_transition_event(deps.storage, &order)?;
+
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let order_denom = orderbook.get_expected_denom(&order.order_direction);
+    let output_denom = orderbook.get_opposite_denom(&order.order_direction);
+
+    // The refund is sold in the same direction as the cancelled order, so the cancelled
+    // order itself can never be matched against.
+    let mut market_order = MarketOrder::new(order.quantity, order.order_direction, order.owner);
+    let tick_bound = get_market_tick_bound(deps.storage, order.order_direction)?;
+    let expiry_refund_msgs =
+        void_expired_orders(deps.storage, &market_order, tick_bound, env.block.time)?;
+    let (output, bank_msg, fee_msg, fill_events) = run_market_order(
+        deps.storage,
+        env.contract.address,
+        env.block.time,
+        &mut market_order,
+        tick_bound,
+        false,
+        // Apply the usual slippage protection to the swapped refund
+        Some(min_output),
+    )?;
+
+    let mut refund_msgs = vec![SubMsg::reply_on_error(bank_msg, REPLY_ID_REFUND)];
+    if let Some(fee_msg) = fee_msg {
+        refund_msgs.push(SubMsg::reply_on_error(fee_msg, REPLY_ID_TAKER_FEE));
+    }
+
+    // Refund any dust that could not be swapped
+    if !market_order.quantity.is_zero() {
+        refund_msgs.push(SubMsg::reply_on_error(
+            BankMsg::Send {
+                to_address: market_order.owner.to_string(),
+                amount: vec![coin(market_order.quantity.u128(), order_denom.clone())],
+            },
+            REPLY_ID_REFUND,
+        ));
+    }
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("method", "cancelLimitAndSwap"),
+            ("owner", info.sender.as_str()),
+            ("tick_id", &tick_id.to_string()),
+            ("order_id", &order_id.to_string()),
+            ("quantity", &order.quantity.to_string()),
+            ("order_direction", &order.order_direction.to_string()),
+            ("initial_quantity", &order.placed_quantity.to_string()),
+            ("order_denom", &order_denom),
+            ("output_denom", &output_denom),
+            ("output_quantity", &output.to_string()),
+        ])
+        .add_submessages(refund_msgs)
+        .add_submessages(expiry_refund_msgs)
+        .add_events(tick_event)
+        .add_events(fill_events))
+}
+
+/// Cancels a limit order and donates its quantity to the tick instead of refunding the owner.
+///
+/// The cancellation is recorded in the sumtree like any other, so orders behind it keep their
+/// place. The donated quantity is then added to the tick's `cumulative_total_value` as liquidity
+/// without an owner, which takers fill after every order already resting on the tick. Their input
+/// for it stays with the contract.
+pub fn cancel_limit_donate(
+    deps: DepsMut,
+    info: MessageInfo,
+    tick_id: i64,
+    order_id: u64,
+) -> ContractResult<Response> {
+    nonpayable(&info)?;
+
+    let order = cancel_order(deps.storage, info.sender.clone(), tick_id, order_id)?;
+
+    // Append the cancelled quantity to the back of the tick's queue
+    let donation = Decimal256::from_ratio(order.quantity, Uint256::one());
+    let mut tick_state = TICK_STATE.load(deps.storage, tick_id)?;
+    let mut tick_values = tick_state.get_values(order.order_direction);
+    tick_values.cumulative_total_value =
+        tick_values.cumulative_total_value.checked_add(donation)?;
+    tick_values.total_amount_of_liquidity = tick_values
+        .total_amount_of_liquidity
+        .checked_add(donation)?;
+    tick_state.set_values(order.order_direction, tick_values);
+    save_tick_state(deps.storage, tick_id, &tick_state)?;
+    add_directional_liquidity(deps.storage, order.order_direction, donation)?;
+
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+    Ok(Response::new().add_attributes(vec![
+        ("method", "cancelLimitDonate"),
+        ("owner", info.sender.as_str()),
+        ("tick_id", &tick_id.to_string()),
+        ("order_id", &order_id.to_string()),
+        ("order_direction", &order.order_direction.to_string()),
+        ("donated_quantity", &order.quantity.to_string()),
+        (
+            "order_denom",
+            &orderbook.get_expected_denom(&order.order_direction),
+        ),
+    ]))
+}
+
+/// Cancels the sender's orders in one direction on ticks between `min_tick` and `max_tick`
+/// (inclusive), refunding them in a single bank send.
+///
+/// Orders that have started filling cannot be cancelled and are skipped. At most `limit` orders are
+/// cancelled per call, defaulting to and capped at `MAX_BATCH_CANCEL`.
+pub fn cancel_orders_in_range(
+    deps: DepsMut,
+    info: MessageInfo,
+    min_tick: i64,
+    max_tick: i64,
+    direction: OrderDirection,
+    limit: Option<u64>,
+) -> ContractResult<Response> {
+    nonpayable(&info)?;
+
+    ensure!(
+        min_tick <= max_tick,
+        ContractError::InvalidTickBounds { min_tick, max_tick }
+    );
+    let limit = limit.unwrap_or(MAX_BATCH_CANCEL).min(MAX_BATCH_CANCEL) as usize;
+
+    let mut cancellable = vec![];
+    for maybe_order in orders().idx.owner.prefix(info.sender.clone()).range(
+        deps.storage,
+        Some(Bound::inclusive((min_tick, u64::MIN))),
+        Some(Bound::inclusive((max_tick, u64::MAX))),
+        Order::Ascending,
+    ) {
+        if cancellable.len() == limit {
+            break;
+        }
+        let (_, order) = maybe_order?;
+        if order.order_direction != direction {
+            continue;
+        }
+        let tick_values = TICK_STATE
+            .load(deps.storage, order.tick_id)?
+            .get_values(direction);
+        if tick_values.effective_total_amount_swapped > order.etas {
+            continue;
+        }
+        cancellable.push((order.tick_id, order.order_id));
+    }
+
+    let mut refunded = Uint128::zero();
+    let mut events = vec![];
+    for (tick_id, order_id) in &cancellable {
+        let order = cancel_order(deps.storage, info.sender.clone(), *tick_id, *order_id)?;
+        refunded = refunded.checked_add(order.quantity)?;
+        events.extend(cancel_transition_event(deps.storage, &order)?);
+    }
+
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let expected_denom = orderbook.get_expected_denom(&direction);
+    let mut response = Response::new()
+        .add_attributes(vec![
+            ("method", "cancelOrdersInRange"),
+            ("owner", info.sender.as_str()),
+            ("order_direction", &direction.to_string()),
+            (
+                "cancelled_orders",
+                &cancellable
+                    .iter()
+                    .map(|(tick_id, order_id)| format!("{tick_id}:{order_id}"))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            ("quantity", &refunded.to_string()),
+            ("order_denom", &expected_denom),
+        ])
+        .add_events(events);
+
+    if !refunded.is_zero() {
+        response = response.add_submessage(SubMsg::reply_on_error(
+            BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![coin(refunded.u128(), expected_denom)],
+            },
+            REPLY_ID_REFUND,
+        ));
+    }
+
+    Ok(response)
+}
+
+/// Changes the quantity of a resting limit order without cancelling and replacing it.
+///
+/// Decreasing the quantity keeps the order's ETAS, so it retains its place in the tick queue. The
+/// removed tail of the order is recorded as a cancellation in the tick's sumtree and refunded.
+///
+/// Increasing the quantity requires the difference to be attached as funds. The original amount
+/// always keeps its priority: if the order is the last in the tick queue it is extended in place,
+/// otherwise the additional quantity is placed as a new order at the back of the queue. Either way
+/// the added quantity is new liquidity, so it is rejected while trading is paused and must pass the
+/// same checks as a newly placed order.
+///
+/// Errors if more of the order has been filled than `new_quantity`.
+pub fn amend_limit(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    tick_id: i64,
+    order_id: u64,
+    new_quantity: Uint128,
+) -> ContractResult<Response> {
+    let key = (tick_id, order_id);
+    let mut order = orders()
+        .may_load(deps.storage, &key)?
+        .ok_or(ContractError::OrderNotFound { tick_id, order_id })?;
+
+    // Ensure the sender is the order owner
+    ensure_eq!(info.sender, order.owner, ContractError::Unauthorized {});
+
+    // Zero quantities should be cancelled and unchanged quantities are a no-op
+    ensure!(
+        !new_quantity.is_zero() && new_quantity != order.quantity,
+        ContractError::InvalidQuantity {
+            quantity: new_quantity
+        }
+    );
+
+    let amount_filled = get_amount_filled(deps.storage, &order)?;
+    ensure!(
+        amount_filled <= new_quantity,
+        ContractError::AmendFilledOrder {
+            filled: amount_filled,
+            new_quantity,
+        }
+    );
+
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let expected_denom = orderbook.get_expected_denom(&order.order_direction);
+    let previous_quantity = order.quantity;
+
+    let mut response = Response::new().add_attributes(vec![
+        ("method", "amendLimit"),
+        ("owner", info.sender.as_str()),
+        ("tick_id", &tick_id.to_string()),
+        ("order_id", &order_id.to_string()),
+        ("order_direction", &order.order_direction.to_string()),
+        ("previous_quantity", &previous_quantity.to_string()),
+        ("order_denom", &expected_denom),
+    ]);
+
+    if new_quantity < previous_quantity {
+        nonpayable(&info)?;
+        // The removed tail of the order is recorded as a cancellation so it is skipped by future fills
+        let removed = remove_order_tail(deps.storage, &order, new_quantity)?;
+
+        order.quantity = new_quantity;
+        order.placed_quantity = order.placed_quantity.checked_sub(removed)?;
+        orders().save(deps.storage, &key, &order)?;
+
+        response = response.add_submessage(SubMsg::reply_on_error(
+            BankMsg::Send {
+                to_address: order.owner.to_string(),
+                amount: vec![coin(removed.u128(), expected_denom)],
+            },
+            REPLY_ID_REFUND,
+        ));
+    } else {
+        ensure_not_paused(deps.storage)?;
+
+        let added = new_quantity.checked_sub(previous_quantity)?;
+        validate_limit_order(deps.storage, tick_id, added, order.claim_bounty)?;
+        let received = must_pay(&info, &expected_denom)?;
+        ensure_eq!(
+            received,
+            added,
+            ContractError::InsufficientFunds {
+                sent: received,
+                required: added,
+            }
+        );
+
+        let mut tick_state = TICK_STATE.load(deps.storage, tick_id)?;
+        let mut tick_values = tick_state.get_values(order.order_direction);
+        let order_end = order
+            .etas
+            .checked_add(Decimal256::from_ratio(order.quantity, 1u128))?;
+
+        // Orders at the back of the queue can grow in place without jumping ahead of anyone. This
+        // adds no order to the tick, so only the per-tick order cap is left to `insert_limit_order`
+        if order_end == tick_values.cumulative_total_value {
+            ensure_no_same_tick_opposing(deps.storage, tick_id, order.order_direction)?;
+            let added_dec256 = Decimal256::from_ratio(added, 1u128);
+
+            order.quantity = new_quantity;
+            order.placed_quantity = order.placed_quantity.checked_add(added)?;
+            orders().save(deps.storage, &key, &order)?;
+
+            tick_values.cumulative_total_value = tick_values
+                .cumulative_total_value
+                .checked_add(added_dec256)?;
+            tick_values.total_amount_of_liquidity = tick_values
+                .total_amount_of_liquidity
+                .checked_add(added_dec256)?;
+            tick_state.set_values(order.order_direction, tick_values);
+            save_tick_state(deps.storage, tick_id, &tick_state)?;
+            add_directional_liquidity(deps.storage, order.order_direction, added_dec256)?;
+        } else {
+            let new_order_id = insert_limit_order(
+                deps.storage,
+                order.owner.clone(),
+                tick_id,
+                order.order_direction,
+                added,
+                order.claim_bounty,
+                order.expiry,
+                None,
+            )?;
+            response = response.add_attribute("new_order_id", new_order_id.to_string());
+        }
+    }
+    sync_hidden_liquidity(deps.storage, tick_id, order.order_direction)?;
+
+    Ok(response.add_attribute("quantity", new_quantity.to_string()))
+}
+
+/// Transfers a limit order to `new_owner` without affecting its place in the tick queue.
+///
+/// The order is re-saved under the new owner so the owner indexes follow it, and any fills,
+/// including those not yet claimed, are paid to the new owner when claimed.
+///
+/// Errors if the sender is not the order owner or `new_owner` is not a valid address.
+pub fn transfer_order(
+    deps: DepsMut,
+    info: MessageInfo,
+    tick_id: i64,
+    order_id: u64,
+    new_owner: String,
+) -> ContractResult<Response> {
+    nonpayable(&info)?;
+
+    let key = (tick_id, order_id);
+    let mut order = orders()
+        .may_load(deps.storage, &key)?
+        .ok_or(ContractError::OrderNotFound { tick_id, order_id })?;
+
+    // Ensure the sender is the order owner
+    ensure_eq!(info.sender, order.owner, ContractError::Unauthorized {});
+
+    order.owner = deps.api.addr_validate(&new_owner)?;
+    orders().save(deps.storage, &key, &order)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("method", "transferOrder"),
+        ("owner", info.sender.as_str()),
+        ("new_owner", order.owner.as_str()),
+        ("tick_id", &tick_id.to_string()),
+        ("order_id", &order_id.to_string()),
+    ]))
+}
+
+/// Merges the sender's resting orders in one direction of a tick into a single order.
+///
+/// Any filled portions are claimed first. The remaining quantities are then cancelled, recording
+/// them in the tick's sumtree, and placed as one new order at the back of the tick queue, so the
+/// merged order starts from the tick's current cumulative total value and cannot claim fills that
+/// were paid out before the merge. At most `MAX_BATCH_CLAIM` orders are merged per call.
+///
+/// Errors if fewer than two orders remain to be merged or if the orders have differing claim
+/// bounties or expiries.
+pub fn consolidate_orders(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<Response> {
+    nonpayable(&info)?;
+
+    let owner_orders: Vec<LimitOrder> = orders()
+        .idx
+        .tick_and_owner
+        .prefix((tick_id, info.sender.clone()))
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, order)| order))
+        .filter(|item| {
+            item.as_ref()
+                .map_or(true, |order| order.order_direction == direction)
+        })
+        .take(MAX_BATCH_CLAIM as usize)
+        .collect::<StdResult<_>>()?;
+
+    let Some(first) = owner_orders.first() else {
+        return Err(ContractError::CannotConsolidate {
+            tick_id,
+            reason: "no orders to merge".to_string(),
+        });
+    };
+    let (claim_bounty, expiry) = (first.claim_bounty, first.expiry);
+    ensure!(
+        owner_orders
+            .iter()
+            .all(|order| order.claim_bounty == claim_bounty && order.expiry == expiry),
+        ContractError::CannotConsolidate {
+            tick_id,
+            reason: "orders have differing claim bounties or expiries".to_string(),
+        }
+    );
+
+    let mut claim_msgs = vec![];
+    let mut merged_order_ids = vec![];
+    let mut merged_quantity = Uint128::zero();
+    for order in owner_orders {
+        let (filled, _) = get_unclaimed_fill(deps.storage, &order)?;
+        if !filled.is_zero() {
+            let (_, msgs, _) = claim_order(
+                deps.storage,
+                env.contract.address.clone(),
+                info.sender.clone(),
+                tick_id,
+                order.order_id,
+                None,
+            )?;
+            claim_msgs.extend(msgs);
+        }
+
+        // Fully filled orders are removed by their claim
+        if orders()
+            .may_load(deps.storage, &(tick_id, order.order_id))?
+            .is_none()
+        {
+            continue;
+        }
+        let cancelled = cancel_order(deps.storage, info.sender.clone(), tick_id, order.order_id)?;
+        merged_quantity = merged_quantity.checked_add(cancelled.quantity)?;
+        merged_order_ids.push(cancelled.order_id);
+    }
+
+    ensure!(
+        merged_order_ids.len() >= 2,
+        ContractError::CannotConsolidate {
+            tick_id,
+            reason: "fewer than two unfilled orders to merge".to_string(),
+        }
+    );
+
+    let order_id = insert_limit_order(
+        deps.storage,
+        info.sender.clone(),
+        tick_id,
+        direction,
+        merged_quantity,
+        claim_bounty,
+        expiry,
+        None,
+    )?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("method", "consolidateOrders".to_string()),
+            ("owner", info.sender.to_string()),
+            ("tick_id", tick_id.to_string()),
+            ("order_direction", direction.to_string()),
+            (
+                "merged_order_ids",
+                merged_order_ids
+                    .iter()
+                    .map(|order_id| order_id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            ("order_id", order_id.to_string()),
+            ("quantity", merged_quantity.to_string()),
+        ])
+        .add_submessages(claim_msgs))
+}
+
+/// Removes an unfilled order from the orderbook, recording the cancellation in the tick's sumtree
+/// and releasing its liquidity.
+///
+/// Returns the cancelled order. **Does not generate a refund**, this is left to the caller.
+///
+/// Errors if the order does not exist, is not owned by `sender` or has been partially or fully filled.
+pub(crate) fn cancel_order(
+    storage: &mut dyn Storage,
+    sender: Addr,
+    tick_id: i64,
+    order_id: u64,
+) -> ContractResult<LimitOrder> {
+    let key = (tick_id, order_id);
+    // Check for the order, error if not found
+    let order = orders()
+        .may_load(storage, &key)?
+        .ok_or(ContractError::OrderNotFound { tick_id, order_id })?;
+
+    // Ensure the sender is the order owner
+    ensure_eq!(sender, order.owner, ContractError::Unauthorized {});
+
+    // Ensure the order has not been filled.
+    let tick_state = TICK_STATE.load(storage, tick_id).unwrap_or_default();
+    let tick_values = tick_state.get_values(order.order_direction);
+    ensure!(
+        tick_values.effective_total_amount_swapped <= order.etas,
+        ContractError::CancelFilledOrder
+    );
+
+    // Fetch the sumtree from storage, or create one if it does not exist
+    let mut tree = get_or_init_root_node(storage, tick_id, order.order_direction)?;
+
+    // Generate info for new node to insert to sumtree
+    let node_id = generate_node_id(storage, order.tick_id)?;
+    let mut curr_tick_state =
+        TICK_STATE
+            .load(storage, order.tick_id)
+            .ok()
+            .ok_or(ContractError::InvalidTickId {
+                tick_id: order.tick_id,
+            })?;
+    let mut curr_tick_values = curr_tick_state.get_values(order.order_direction);
+    let quant_dec256 =
+        Decimal256::from_ratio(Uint256::from_uint128(order.quantity), Uint256::one());
+    let mut new_node = TreeNode::new(
+        order.tick_id,
+        order.order_direction,
+        node_id,
+        NodeType::leaf(order.etas, quant_dec256),
+    );
+
+    // Insert new node
+    tree.insert(storage, &mut new_node)?;
+
+    orders().remove(storage, &(order.tick_id, order.order_id))?;
+    remove_order_expiry(storage, &order);
+    decrement_tick_order_count(storage, order.tick_id, order.order_direction)?;
+    CANCELLED_ORDERS.save(storage, key, &Empty {})?;
+
+    subtract_liquidity(
+        &mut curr_tick_values,
+        order.tick_id,
+        Decimal256::from_ratio(order.quantity, Uint256::one()),
+    )?;
+    curr_tick_state.set_values(order.order_direction, curr_tick_values);
+    save_tick_state(storage, order.tick_id, &curr_tick_state)?;
+    subtract_directional_liquidity(storage, order.order_direction, quant_dec256)?;
+
+    tree.save(storage)?;
+    sync_hidden_liquidity(storage, order.tick_id, order.order_direction)?;
+
+    Ok(order)
+}
+
+/// Returns how much of an order is currently filled, including any realized cancellations.
+///
+/// Rounded up so that swapped quantity is never treated as refundable.
+pub(crate) fn get_amount_filled(
+    storage: &dyn Storage,
+    order: &LimitOrder,
+) -> ContractResult<Uint128> {
+    let tick_values = get_synced_tick_values(storage, order.tick_id, order.order_direction)?;
+    let amount_filled = tick_values
+        .effective_total_amount_swapped
+        .saturating_sub(order.etas)
+        .min(Decimal256::from_ratio(order.quantity, 1u128))
+        .to_uint_ceil();
+
+    Ok(Uint128::try_from(amount_filled)?)
+}
+
+/// Removes the part of an order beyond its first `kept` units from its tick, recording it as a
+/// cancellation in the tick's sumtree and releasing its liquidity.
+///
+/// Returns the removed quantity. **Does not update the order itself**, this is left to the caller.
+fn remove_order_tail(
+    storage: &mut dyn Storage,
+    order: &LimitOrder,
+    kept: Uint128,
+) -> ContractResult<Uint128> {
+    let removed = order.quantity.checked_sub(kept)?;
+    let removed_dec256 = Decimal256::from_ratio(removed, 1u128);
+
+    let mut tree = get_or_init_root_node(storage, order.tick_id, order.order_direction)?;
+    let node_id = generate_node_id(storage, order.tick_id)?;
+    let mut new_node = TreeNode::new(
+        order.tick_id,
+        order.order_direction,
+        node_id,
+        NodeType::leaf(
+            order
+                .etas
+                .checked_add(Decimal256::from_ratio(kept, 1u128))?,
+            removed_dec256,
+        ),
+    );
+    tree.insert(storage, &mut new_node)?;
+    tree.save(storage)?;
+
+    let mut tick_state = TICK_STATE.load(storage, order.tick_id)?;
+    let mut tick_values = tick_state.get_values(order.order_direction);
+    subtract_liquidity(&mut tick_values, order.tick_id, removed_dec256)?;
+    tick_state.set_values(order.order_direction, tick_values);
+    save_tick_state(storage, order.tick_id, &tick_state)?;
+    subtract_directional_liquidity(storage, order.order_direction, removed_dec256)?;
+
+    Ok(removed)
+}
+
+/// Removes an order from the expiry index, if it has an expiry.
+fn remove_order_expiry(storage: &mut dyn Storage, order: &LimitOrder) {
+    if let Some(expiry) = order.expiry {
+        ORDER_EXPIRIES.remove(storage, (order.tick_id, expiry.nanos(), order.order_id));
+    }
+}
+
+/// Clears the ticks the given market order would reach of liquidity it must not fill against,
+/// refunding the unfilled quantity of each removed order to its owner.
+///
+/// Orders that expired before `now` are voided and, if `self_trade_owner` is set, that owner's
+/// resting orders are cancelled. Removed orders are recorded in their tick's sumtree exactly as a
+/// regular cancellation would be, keeping any already filled quantity claimable.
+///
+/// Ticks are walked once in fill order. Each tick is cleared before measuring how much of the order
+/// its remaining liquidity absorbs, so removed liquidity lets the walk reach further ticks without
+/// re-simulating the order.
+pub(crate) fn clear_fill_path(
+    storage: &mut dyn Storage,
+    order: &MarketOrder,
+    tick_bound: i64,
+    now: Timestamp,
+    self_trade_owner: Option<&Addr>,
+) -> ContractResult<Vec<SubMsg>> {
+    let orderbook = ORDERBOOK.load(storage)?;
+    let fill_direction = order.order_direction.opposite();
+    let refund_denom = orderbook.get_expected_denom(&fill_direction);
+    let mut from_tick = match order.order_direction {
+        OrderDirection::Bid => orderbook.next_ask_tick,
+        OrderDirection::Ask => orderbook.next_bid_tick,
+    };
+
+    let mut refund_msgs = vec![];
+    let mut quantity = order.quantity;
+    while let Some((tick_id, _)) =
+        next_populated_tick(storage, fill_direction, from_tick, tick_bound)?
+    {
+        from_tick = match fill_direction {
+            OrderDirection::Ask => tick_id + 1,
+            OrderDirection::Bid => tick_id - 1,
+        };
+
+        let mut order_ids = vec![];
+        for (expiry_nanos, order_id) in ORDER_EXPIRIES
+            .sub_prefix(tick_id)
+            .keys(
+                storage,
+                None,
+                Some(Bound::exclusive((now.nanos(), 0))),
+                Order::Ascending,
+            )
+            .collect::<StdResult<Vec<_>>>()?
+        {
+            let is_fill_direction = orders()
+                .may_load(storage, &(tick_id, order_id))?
+                .is_some_and(|o| o.order_direction == fill_direction);
+            if is_fill_direction {
+                ORDER_EXPIRIES.remove(storage, (tick_id, expiry_nanos, order_id));
+                order_ids.push(order_id);
+            }
+        }
+        if let Some(owner) = self_trade_owner {
+            for item in orders()
+                .idx
+                .tick_and_owner
+                .prefix((tick_id, owner.clone()))
+                .range(storage, None, None, Order::Ascending)
+            {
+                let (_, o) = item?;
+                if o.order_direction == fill_direction {
+                    order_ids.push(o.order_id);
+                }
+            }
+        }
+
+        for order_id in order_ids {
+            if let Some(refund_msg) =
+                void_order_remainder(storage, tick_id, order_id, &refund_denom)?
+            {
+                refund_msgs.push(refund_msg);
+            }
+        }
+
+        // Stop once what is left on this tick absorbs the rest of the order
+        let liquidity = TICK_STATE
+            .load(storage, tick_id)?
+            .get_values(fill_direction)
+            .total_amount_of_liquidity;
+        let Some((_, input_filled)) = fill_at_tick(
+            quantity,
+            order.order_direction,
+            tick_to_price(tick_id)?,
+            liquidity,
+        )?
+        else {
+            break;
+        };
+        quantity = quantity.checked_sub(input_filled)?;
+    }
+
+    Ok(refund_msgs)
+}
+
+/// Removes the unfilled remainder of a resting order from its tick and refunds it to the owner.
+///
+/// Any filled quantity stays on the order so it can still be claimed. Returns `None` if the order
+/// no longer exists or has nothing left unfilled.
+fn void_order_remainder(
+    storage: &mut dyn Storage,
+    tick_id: i64,
+    order_id: u64,
+    refund_denom: &str,
+) -> ContractResult<Option<SubMsg>> {
+    let key = (tick_id, order_id);
+    let Some(mut order) = orders().may_load(storage, &key)? else {
+        return Ok(None);
+    };
+
+    let amount_filled = get_amount_filled(storage, &order)?;
+    if amount_filled == order.quantity {
+        return Ok(None);
+    }
+
+    let remaining = remove_order_tail(storage, &order, amount_filled)?;
+    if amount_filled.is_zero() {
+        orders().remove(storage, &key)?;
+        remove_order_expiry(storage, &order);
+        decrement_tick_order_count(storage, order.tick_id, order.order_direction)?;
+        CANCELLED_ORDERS.save(storage, key, &Empty {})?;
+    } else {
+        order.quantity = amount_filled;
+        order.placed_quantity = order.placed_quantity.checked_sub(remaining)?;
+        orders().save(storage, &key, &order)?;
+    }
+    sync_hidden_liquidity(storage, tick_id, order.order_direction)?;
+
+    Ok(Some(SubMsg::reply_on_error(
+        BankMsg::Send {
+            to_address: order.owner.to_string(),
+            amount: vec![coin(remaining.u128(), refund_denom)],
+        },
+        REPLY_ID_REFUND,
+    )))
+}
+
+/// Voids every order that expired before `now` on the ticks the given market order would reach,
+/// refunding the unfilled quantity of each to its owner. Any filled quantity stays claimable.
+///
+/// Returns the refund messages for the voided orders.
+pub(crate) fn void_expired_orders(
+    storage: &mut dyn Storage,
+    order: &MarketOrder,
+    tick_bound: i64,
+    now: Timestamp,
+) -> ContractResult<Vec<SubMsg>> {
+    clear_fill_path(storage, order, tick_bound, now, None)
+}
+
+/// Returns the unfilled quantity of the orders in `direction` on a tick that expired before `now`.
+///
+/// A fill voids these orders before reaching them, so they never provide it any liquidity.
+pub(crate) fn expired_liquidity(
+    storage: &dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+    now: Timestamp,
+) -> ContractResult<Decimal256> {
+    let mut expired = Uint128::zero();
+    for maybe_key in ORDER_EXPIRIES.sub_prefix(tick_id).keys(
+        storage,
+        None,
+        Some(Bound::exclusive((now.nanos(), 0))),
+        Order::Ascending,
+    ) {
+        let (_, order_id) = maybe_key?;
+        let Some(order) = orders().may_load(storage, &(tick_id, order_id))? else {
+            continue;
+        };
+        if order.order_direction != direction {
+            continue;
+        }
+        let amount_filled = get_amount_filled(storage, &order)?;
+        expired = expired.checked_add(order.quantity.saturating_sub(amount_filled))?;
+    }
+
+    Ok(Decimal256::from_ratio(expired, 1u128))
+}
+
+pub fn claim_limit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tick_id: i64,
+    order_id: u64,
+    min_claim: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    nonpayable(&info)?;
+
+    let (amount_claimed, bank_msgs, order) = claim_order(
+        deps.storage,
+        env.contract.address,
+        info.sender.clone(),
+        tick_id,
+        order_id,
+        min_claim,
     )?;
 
     let orderbook = ORDERBOOK.load(deps.storage)?;
     let order_denom = orderbook.get_expected_denom(&order.order_direction);
     let output_denom = orderbook.get_opposite_denom(&order.order_direction);
 
+    let data = ClaimData {
+        tick_id,
+        order_id,
+        owner: order.owner.clone(),
+        claimed: coin_u256(amount_claimed, &output_denom),
+    };
     let event = generate_claimed_order_event(
         info.sender,
         order,
@@ -264,15 +1587,187 @@ pub fn claim_limit(
     Ok(Response::new()
         .add_attribute("method", "claimLimit")
         .add_event(event)
-        .add_submessages(bank_msgs))
+        .add_submessages(bank_msgs)
+        .set_data(to_json_binary(&data)?))
+}
+
+/// Claims one of the sender's orders like `claim_limit`, but keeps the claimed amount as their
+/// credit instead of sending it. The maker fee is still paid out.
+///
+/// Errors if the sender is not the order owner.
+pub fn claim_limit_to_credit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tick_id: i64,
+    order_id: u64,
+    min_claim: Option<Uint128>,
+) -> ContractResult<Response> {
+    nonpayable(&info)?;
+
+    let order = orders()
+        .may_load(deps.storage, &(tick_id, order_id))?
+        .ok_or(ContractError::OrderNotFound { tick_id, order_id })?;
+
+    // Ensure the sender is the order owner
+    ensure_eq!(info.sender, order.owner, ContractError::Unauthorized {});
+
+    let (amount_claimed, mut bank_msgs, order) = claim_order(
+        deps.storage,
+        env.contract.address,
+        info.sender.clone(),
+        tick_id,
+        order_id,
+        min_claim,
+    )?;
+
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let order_denom = orderbook.get_expected_denom(&order.order_direction);
+    let output_denom = orderbook.get_opposite_denom(&order.order_direction);
+
+    // The owner claimed, so the first message pays out the claimed amount and bounty together
+    bank_msgs.remove(0);
+    add_credit(
+        deps.storage,
+        &order.owner,
+        &output_denom,
+        Uint128::try_from(amount_claimed)?,
+    )?;
+
+    let data = ClaimData {
+        tick_id,
+        order_id,
+        owner: order.owner.clone(),
+        claimed: coin_u256(amount_claimed, &output_denom),
+    };
+    let event = generate_claimed_order_event(
+        info.sender,
+        order,
+        amount_claimed,
+        order_denom,
+        output_denom,
+    );
+
+    Ok(Response::new()
+        .add_attribute("method", "claimLimitToCredit")
+        .add_event(event)
+        .add_submessages(bank_msgs)
+        .set_data(to_json_binary(&data)?))
+}
+
+/// Claims the filled portion of one of the sender's orders and places the proceeds as a new limit
+/// order at `new_tick_id`, without the funds leaving the contract.
+///
+/// The sender is the owner, so any claim bounty is rolled into the new order along with the claimed
+/// amount, while the maker fee is still paid out. The new order keeps the original claim bounty,
+/// and any unfilled remainder of the original order stays on the book.
+///
+/// Errors if the claimed output denom cannot fund an order in `new_direction`.
+pub fn roll_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    tick_id: i64,
+    order_id: u64,
+    new_tick_id: i64,
+    new_direction: OrderDirection,
+) -> ContractResult<Response> {
+    nonpayable(&info)?;
+    ensure_not_paused(deps.storage)?;
+
+    let order = orders()
+        .may_load(deps.storage, &(tick_id, order_id))?
+        .ok_or(ContractError::OrderNotFound { tick_id, order_id })?;
+
+    // Ensure the sender is the order owner
+    ensure_eq!(info.sender, order.owner, ContractError::Unauthorized {});
+
+    // The claimed output must be the denom the new order is placed in
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let order_denom = orderbook.get_expected_denom(&order.order_direction);
+    let output_denom = orderbook.get_opposite_denom(&order.order_direction);
+    let expected_denom = orderbook.get_expected_denom(&new_direction);
+    ensure!(
+        output_denom == expected_denom,
+        PaymentError::MissingDenom(expected_denom)
+    );
+
+    let (
+        ClaimPayout {
+            amount,
+            bounty,
+            maker_fee_amount,
+        },
+        order,
+    ) = settle_claim(deps.storage, tick_id, order_id, None)?;
+
+    let mut response = Response::new();
+    if !maker_fee_amount.is_zero() {
+        // Maker fee recipient is controlled by contract admin/moderator
+        let maker_fee_recipient = MAKER_FEE_RECIPIENT
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoMakerFeeRecipient)?;
+        let maker_fee_msg = MsgSend256 {
+            from_address: env.contract.address.to_string(),
+            to_address: maker_fee_recipient.to_string(),
+            amount: vec![coin_u256(maker_fee_amount, &output_denom)],
+        };
+        response =
+            response.add_submessage(SubMsg::reply_on_error(maker_fee_msg, REPLY_ID_MAKER_FEE));
+    }
+
+    let quantity = Uint128::try_from(amount.checked_add(bounty)?)?;
+    validate_limit_order(deps.storage, new_tick_id, quantity, order.claim_bounty)?;
+
+    let liquidity_before = tick_liquidity(deps.storage, new_tick_id, new_direction)?;
+    let new_order_id = insert_limit_order(
+        deps.storage,
+        info.sender.clone(),
+        new_tick_id,
+        new_direction,
+        quantity,
+        order.claim_bounty,
+        None,
+        None,
+    )?;
+    let liquidity_after = tick_liquidity(deps.storage, new_tick_id, new_direction)?;
+
+    let event = generate_claimed_order_event(
+        info.sender.clone(),
+        order,
+        amount,
+        order_denom,
+        output_denom,
+    );
+
+    Ok(response
+        .add_event(event)
+        .add_events(tick_transition_event(
+            new_tick_id,
+            new_direction,
+            liquidity_before,
+            liquidity_after,
+        ))
+        .add_attributes(vec![
+            ("method", "rollOrder".to_string()),
+            ("owner", info.sender.to_string()),
+            ("tick_id", tick_id.to_string()),
+            ("order_id", order_id.to_string()),
+            ("new_tick_id", new_tick_id.to_string()),
+            ("new_order_id", new_order_id.to_string()),
+            ("order_direction", new_direction.to_string()),
+            ("quantity", quantity.to_string()),
+        ]))
 }
 
 // batch_claim_limits allows for multiple limit orders to be claimed in a single transaction.
+// Orders whose claim would fall below `min_claim` are skipped along with any other failed claims.
 pub fn batch_claim_limits(
     deps: DepsMut,
     info: MessageInfo,
     env: Env,
     orders: Vec<(i64, u64)>,
+    min_claim: Option<Uint128>,
 ) -> Result<Response, ContractError> {
     nonpayable(&info)?;
 
@@ -285,6 +1780,7 @@ pub fn batch_claim_limits(
 
     let mut responses: Vec<SubMsg> = Vec::new();
     let mut events: Vec<Event> = Vec::new();
+    let mut claims: Vec<ClaimData> = Vec::new();
     let orderbook = ORDERBOOK.load(deps.storage)?;
 
     for (tick_id, order_id) in orders {
@@ -295,10 +1791,17 @@ pub fn batch_claim_limits(
             info.sender.clone(),
             tick_id,
             order_id,
+            min_claim,
         ) {
             Ok((amount_claimed, mut bank_msgs, order)) => {
                 let order_denom = orderbook.get_expected_denom(&order.order_direction);
                 let output_denom = orderbook.get_opposite_denom(&order.order_direction);
+                claims.push(ClaimData {
+                    tick_id,
+                    order_id,
+                    owner: order.owner.clone(),
+                    claimed: coin_u256(amount_claimed, &output_denom),
+                });
                 let event = generate_claimed_order_event(
                     info.sender.clone(),
                     order,
@@ -321,7 +1824,217 @@ pub fn batch_claim_limits(
         .add_attribute("method", "batchClaimLimit")
         .add_attribute("sender", info.sender)
         .add_events(events)
-        .add_submessages(responses))
+        .add_submessages(responses)
+        .set_data(to_json_binary(&BatchClaimData { claims })?))
+}
+
+/// Claims every filled order of the sender in a single transaction, merging the resulting sends
+/// into one message per recipient. Maker fees are sent separately so a failing fee transfer can be
+/// told apart from a failing claim.
+///
+/// Orders with nothing to claim are skipped. At most `limit` of the sender's orders after
+/// `start_after` are scanned, defaulting to and capped at `MAX_BATCH_CLAIM`. If the scan stopped
+/// before the sender's last order, the `next_start_after` attribute holds the cursor to resume from.
+pub fn claim_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start_after: Option<(i64, u64)>,
+    limit: Option<u64>,
+) -> ContractResult<Response> {
+    nonpayable(&info)?;
+
+    let limit = limit
+        .unwrap_or(MAX_BATCH_CLAIM as u64)
+        .min(MAX_BATCH_CLAIM as u64);
+    let owner_orders = get_orders_by_owner(
+        deps.storage,
+        FilterOwnerOrders::all(info.sender.clone()),
+        start_after,
+        None,
+        Some(limit),
+    )?;
+    let next_start_after = if owner_orders.len() as u64 == limit {
+        owner_orders
+            .last()
+            .map(|order| (order.tick_id, order.order_id))
+    } else {
+        None
+    };
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+
+    // Amounts owed keyed by (recipient, denom)
+    let mut payouts: BTreeMap<(String, String), Uint256> = BTreeMap::new();
+    let mut add_payout = |recipient: &Addr, denom: &str, amount: Uint256| -> ContractResult<()> {
+        if !amount.is_zero() {
+            let total = payouts
+                .entry((recipient.to_string(), denom.to_string()))
+                .or_default();
+            *total = total.checked_add(amount)?;
+        }
+        Ok(())
+    };
+    let mut maker_fees: BTreeMap<String, Uint256> = BTreeMap::new();
+
+    let mut events: Vec<Event> = Vec::new();
+    let mut claims: Vec<ClaimData> = Vec::new();
+    for order in owner_orders {
+        let (_, raw_amount) = get_unclaimed_fill(deps.storage, &order)?;
+        if raw_amount.is_zero() {
+            continue;
+        }
+
+        let (
+            ClaimPayout {
+                amount,
+                bounty,
+                maker_fee_amount,
+            },
+            order,
+        ) = settle_claim(deps.storage, order.tick_id, order.order_id, None)?;
+        let order_denom = orderbook.get_expected_denom(&order.order_direction);
+        let output_denom = orderbook.get_opposite_denom(&order.order_direction);
+
+        add_payout(&order.owner, &output_denom, amount)?;
+        // The sender is the owner, so any bounty is paid back to them
+        add_payout(&info.sender, &output_denom, bounty)?;
+        if !maker_fee_amount.is_zero() {
+            let total = maker_fees.entry(output_denom.clone()).or_default();
+            *total = total.checked_add(maker_fee_amount)?;
+        }
+
+        claims.push(ClaimData {
+            tick_id: order.tick_id,
+            order_id: order.order_id,
+            owner: order.owner.clone(),
+            claimed: coin_u256(amount, &output_denom),
+        });
+        events.push(generate_claimed_order_event(
+            info.sender.clone(),
+            order,
+            amount,
+            order_denom,
+            output_denom,
+        ));
+    }
+
+    // Group payouts into one message per recipient
+    let mut messages: Vec<MsgSend256> = vec![];
+    for ((recipient, denom), amount) in payouts {
+        let coin = coin_u256(amount, &denom);
+        match messages.last_mut() {
+            Some(msg) if msg.to_address == recipient => msg.amount.push(coin),
+            _ => messages.push(MsgSend256 {
+                from_address: env.contract.address.to_string(),
+                to_address: recipient,
+                amount: vec![coin],
+            }),
+        }
+    }
+
+    let mut submessages: Vec<SubMsg> = messages
+        .into_iter()
+        .map(|msg| SubMsg::reply_on_error(msg, REPLY_ID_CLAIM))
+        .collect();
+    if !maker_fees.is_empty() {
+        // Maker fee recipient is controlled by contract admin/moderator
+        let maker_fee_recipient = MAKER_FEE_RECIPIENT
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoMakerFeeRecipient)?;
+        let maker_fee_msg = MsgSend256 {
+            from_address: env.contract.address.to_string(),
+            to_address: maker_fee_recipient.to_string(),
+            amount: maker_fees
+                .into_iter()
+                .map(|(denom, amount)| coin_u256(amount, &denom))
+                .collect(),
+        };
+        submessages.push(SubMsg::reply_on_error(maker_fee_msg, REPLY_ID_MAKER_FEE));
+    }
+
+    let claimed_orders = claims
+        .iter()
+        .map(|claim| format!("{}:{}", claim.tick_id, claim.order_id))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let mut response = Response::new()
+        .add_attribute("method", "claimAll")
+        .add_attribute("owner", info.sender)
+        .add_attribute("claimed_orders", claimed_orders);
+    if let Some((tick_id, order_id)) = next_start_after {
+        response = response.add_attribute("next_start_after", format!("{tick_id}:{order_id}"));
+    }
+
+    Ok(response
+        .add_events(events)
+        .add_submessages(submessages)
+        .set_data(to_json_binary(&BatchClaimData { claims })?))
+}
+
+/// Removes the state of one direction of a tick that no longer holds any orders, reclaiming its
+/// storage. This can be called by anyone.
+///
+/// The direction's tick values are reset and its sumtree is deleted. Once both directions of the
+/// tick are empty, the tick state and node id counter are removed entirely.
+///
+/// Errors if the direction still has liquidity or any resting order, as pruning it would lose
+/// track of funds owed to the order's owner.
+pub fn prune_tick(
+    deps: DepsMut,
+    info: MessageInfo,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<Response> {
+    nonpayable(&info)?;
+
+    let mut tick_state = TICK_STATE
+        .may_load(deps.storage, tick_id)?
+        .ok_or(ContractError::InvalidTickId { tick_id })?;
+    ensure!(
+        tick_state
+            .get_values(direction)
+            .total_amount_of_liquidity
+            .is_zero(),
+        ContractError::TickNotPrunable {
+            tick_id,
+            reason: "tick has remaining liquidity".to_string(),
+        }
+    );
+
+    // Fully filled orders have no liquidity left but may still be unclaimed
+    for maybe_order in orders()
+        .prefix(tick_id)
+        .range(deps.storage, None, None, Order::Ascending)
+    {
+        let (order_id, order) = maybe_order?;
+        ensure!(
+            order.order_direction != direction,
+            ContractError::TickNotPrunable {
+                tick_id,
+                reason: format!("order {order_id} is still resting on the tick"),
+            }
+        );
+    }
+
+    let nodes_removed = remove_tree(deps.storage, tick_id, direction)?;
+
+    tick_state.set_values(direction, TickValues::default());
+    let tick_removed = tick_state == TickState::default();
+    if tick_removed {
+        TICK_STATE.remove(deps.storage, tick_id);
+        NODE_ID_COUNTER.remove(deps.storage, &tick_id);
+    } else {
+        save_tick_state(deps.storage, tick_id, &tick_state)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("method", "pruneTick".to_string()),
+        ("tick_id", tick_id.to_string()),
+        ("direction", direction.to_string()),
+        ("nodes_removed", nodes_removed.to_string()),
+        ("tick_removed", tick_removed.to_string()),
+    ]))
 }
 
 /// Generates an event when an order is claimed to help with indexing
@@ -353,44 +2066,141 @@ fn generate_claimed_order_event(
 /// Note that this mutates the `order` object
 ///
 /// Returns:
-/// * The output after the order has been processed
+/// * The output after the order has been processed, net of the taker fee
 /// * Bank send message to process the balance transfer
+/// * Bank send message for the taker fee to the fee collector, if a fee was taken
+/// * An `order_filled` event per resting order filled, in fill order
+///
+/// Within a tick, resting orders fill in ascending ETAS order. An order's ETAS is the tick's
+/// cumulative total value when it was placed and order ids are assigned in placement order, so two
+/// orders on a tick never share an ETAS and ties always resolve to the lower `order_id` first.
+///
+/// Also records the final fill price and the traded quote volume at `now` in `MARKET_STATS`.
 ///
 /// Returns error if:
 /// * Provided order has zero quantity
 /// * Tick to price conversion fails for any tick
 /// * Order is not fully filled
+/// * `fill_or_kill` is set and the order cannot be fully filled before reaching `tick_bound`
+/// * The output across all filled ticks is below `min_output`
 ///
 /// CONTRACT: The caller must ensure that the necessary input funds were actually supplied.
+/// CONTRACT: The caller must void expired orders on the reached ticks first, see `void_expired_orders`.
 #[allow(clippy::manual_range_contains)]
 pub fn run_market_order(
     storage: &mut dyn Storage,
     contract_address: Addr,
+    now: Timestamp,
     order: &mut MarketOrder,
     tick_bound: i64,
-) -> Result<(Uint256, MsgSend256), ContractError> {
+    fill_or_kill: bool,
+    min_output: Option<Uint128>,
+) -> Result<(Uint256, MsgSend256, Option<MsgSend256>, Vec<Event>), ContractError> {
+    ensure_not_paused(storage)?;
+
+    let initial_quantity = order.quantity;
     let PostMarketOrderState {
         output,
         tick_updates,
         updated_orderbook,
-    } = run_market_order_internal(storage, order, tick_bound)?;
+        stop_reason,
+    } = run_market_order_internal(storage, order, tick_bound, now)?;
+
+    // Deduct the taker fee from the output, the consumed liquidity is still the gross output
+    let gross_output = output.amount;
+    let taker_fee = updated_orderbook.get_taker_fee(gross_output)?;
+    let output = coin_u256(gross_output.checked_sub(taker_fee)?, &output.denom);
+
+    // The fill walk above has no side effects, so a killed order leaves no state changes behind
+    ensure!(
+        !fill_or_kill || stop_reason == MarketOrderStopReason::Filled,
+        ContractError::InsufficientLiquidity
+    );
+
+    // Revert if the realized output falls short of the caller's slippage bound
+    if let Some(min_output) = min_output {
+        ensure!(
+            output.amount >= Uint256::from_uint128(min_output),
+            ContractError::SwapBelowMin {
+                min: Uint256::from_uint128(min_output),
+                received: output.amount,
+                denom: output.denom.clone(),
+            }
+        );
+    }
+
+    // Record how far each tick was filled before this order to attribute the fills to orders, and
+    // its liquidity to report ticks that were emptied
+    let fill_direction = order.order_direction.opposite();
+    let mut etas_before = Vec::with_capacity(tick_updates.len());
+    let mut liquidity_before = Vec::with_capacity(tick_updates.len());
+    for (tick_id, _) in &tick_updates {
+        let tick_values = get_synced_tick_values(storage, *tick_id, fill_direction)?;
+        etas_before.push(tick_values.effective_total_amount_swapped);
+        liquidity_before.push(tick_values.total_amount_of_liquidity);
+    }
 
     // After the core tick iteration loop, write all tick updates to state.
     // We cannot do this during the loop due to the borrow checker.
-    for (tick_id, tick_state) in tick_updates {
-        TICK_STATE.save(storage, tick_id, &tick_state)?;
+    for (tick_id, tick_state) in &tick_updates {
+        save_tick_state(storage, *tick_id, tick_state)?;
+    }
+
+    let mut fill_events = vec![];
+    for (((tick_id, _), etas_before), liquidity_before) in
+        tick_updates.iter().zip(etas_before).zip(liquidity_before)
+    {
+        let tick_values = get_synced_tick_values(storage, *tick_id, fill_direction)?;
+        fill_events.extend(order_fill_events(
+            storage,
+            *tick_id,
+            fill_direction,
+            etas_before,
+            tick_values.effective_total_amount_swapped,
+        )?);
+        fill_events.extend(tick_transition_event(
+            *tick_id,
+            fill_direction,
+            liquidity_before,
+            tick_values.total_amount_of_liquidity,
+        ));
+
+        // Filling an iceberg order reveals the next slice of its reserve
+        sync_hidden_liquidity(storage, *tick_id, fill_direction)?;
     }
 
     // Reduce the amount of liquidity in the opposite direction of the order by the output amount
     subtract_directional_liquidity(
         storage,
         order.order_direction.opposite(),
-        Decimal256::from_ratio(output.amount, Uint256::one()),
+        Decimal256::from_ratio(gross_output, Uint256::one()),
     )?;
 
+    let fee_msg = match &updated_orderbook.fee_collector {
+        Some(fee_collector) if !taker_fee.is_zero() => Some(MsgSend256 {
+            from_address: contract_address.to_string(),
+            to_address: fee_collector.to_string(),
+            amount: vec![coin_u256(taker_fee, &output.denom)],
+        }),
+        _ => None,
+    };
+
     // Update tick pointers in orderbook
     ORDERBOOK.save(storage, &updated_orderbook)?;
 
+    // Record the final fill price and the traded volume, denominated in the quote asset
+    if let Some((last_tick_id, _)) = tick_updates.last() {
+        let volume = match order.order_direction {
+            OrderDirection::Bid => {
+                Uint256::from_uint128(initial_quantity.checked_sub(order.quantity)?)
+            }
+            OrderDirection::Ask => gross_output,
+        };
+        let mut market_stats = MARKET_STATS.may_load(storage)?.unwrap_or_default();
+        market_stats.record_trade(now, tick_to_price(*last_tick_id)?, volume)?;
+        MARKET_STATS.save(storage, &market_stats)?;
+    }
+
     Ok((
         output.amount,
         MsgSend256 {
@@ -398,14 +2208,164 @@ pub fn run_market_order(
             to_address: order.owner.to_string(),
             amount: vec![output],
         },
+        fee_msg,
+        fill_events,
+    ))
+}
+
+/// Returns the resting liquidity in one direction of a tick, zero if the tick has no state.
+fn tick_liquidity(
+    storage: &dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<Decimal256> {
+    Ok(TICK_STATE
+        .may_load(storage, tick_id)?
+        .unwrap_or_default()
+        .get_values(direction)
+        .total_amount_of_liquidity)
+}
+
+/// Recomputes the hidden reserve of one direction of a tick from its iceberg orders.
+///
+/// Each iceberg order shows up to its display quantity of its unfilled remainder and hides the rest,
+/// so as fills reach an order its shown slice is replenished from the reserve until it runs out.
+/// Markers of orders that no longer exist are removed.
+fn sync_hidden_liquidity(
+    storage: &mut dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<()> {
+    let iceberg_order_ids = ICEBERG_ORDERS
+        .prefix(tick_id)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<u64>>>()?;
+    if iceberg_order_ids.is_empty() {
+        return Ok(());
+    }
+
+    let etas = get_synced_tick_values(storage, tick_id, direction)?.effective_total_amount_swapped;
+    let mut hidden_liquidity = Decimal256::zero();
+    for order_id in iceberg_order_ids {
+        let Some(order) = orders().may_load(storage, &(tick_id, order_id))? else {
+            ICEBERG_ORDERS.remove(storage, (tick_id, order_id));
+            continue;
+        };
+        let Some(display_quantity) = order.display_quantity else {
+            continue;
+        };
+        if order.order_direction != direction {
+            continue;
+        }
+
+        let order_end = order
+            .etas
+            .checked_add(Decimal256::from_ratio(order.quantity, 1u128))?;
+        let remaining = order_end.saturating_sub(order.etas.max(etas));
+        hidden_liquidity = hidden_liquidity.checked_add(
+            remaining.saturating_sub(Decimal256::from_ratio(display_quantity, 1u128)),
+        )?;
+    }
+
+    let mut tick_state = TICK_STATE.load(storage, tick_id)?;
+    let mut tick_values = tick_state.get_values(direction);
+    tick_values.hidden_liquidity = hidden_liquidity;
+    tick_state.set_values(direction, tick_values);
+    save_tick_state(storage, tick_id, &tick_state)?;
+
+    Ok(())
+}
+
+/// Builds a `tick_activated` event if one direction of a tick went from no liquidity to some, or a
+/// `tick_deactivated` event if it was emptied. Changes that keep the tick non-empty build no event.
+fn tick_transition_event(
+    tick_id: i64,
+    direction: OrderDirection,
+    liquidity_before: Decimal256,
+    liquidity_after: Decimal256,
+) -> Option<Event> {
+    let event_type = match (liquidity_before.is_zero(), liquidity_after.is_zero()) {
+        (true, false) => "tick_activated",
+        (false, true) => "tick_deactivated",
+        _ => return None,
+    };
+
+    Some(Event::new(event_type).add_attributes(vec![
+        ("tick_id", tick_id.to_string()),
+        ("direction", direction.to_string()),
+    ]))
+}
+
+/// Builds a `tick_deactivated` event if cancelling from `order` emptied its tick direction.
+///
+/// A cancellation only removes liquidity, so the tick held some before it.
+fn cancel_transition_event(
+    storage: &dyn Storage,
+    order: &LimitOrder,
+) -> ContractResult<Option<Event>> {
+    let liquidity_after = tick_liquidity(storage, order.tick_id, order.order_direction)?;
+    Ok(tick_transition_event(
+        order.tick_id,
+        order.order_direction,
+        Decimal256::one(),
+        liquidity_after,
     ))
 }
 
+/// Builds an `order_filled` event for every resting order on a tick that was filled as the tick's
+/// synced ETAS moved from `etas_before` to `etas_after`, in fill order.
+///
+/// Cancelled ranges within the tick have no remaining orders, so they never produce events.
+fn order_fill_events(
+    storage: &dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+    etas_before: Decimal256,
+    etas_after: Decimal256,
+) -> ContractResult<Vec<Event>> {
+    let mut events = vec![];
+    for maybe_order in orders()
+        .prefix(tick_id)
+        .range(storage, None, None, Order::Ascending)
+    {
+        let (_, order) = maybe_order?;
+        if order.order_direction != direction {
+            continue;
+        }
+        // Orders are placed in ascending ETAS order, so no later order was reached
+        if order.etas >= etas_after {
+            break;
+        }
+
+        let order_end = order
+            .etas
+            .checked_add(Decimal256::from_ratio(order.quantity, 1u128))?;
+        let amount_filled = order_end
+            .min(etas_after)
+            .saturating_sub(order.etas.max(etas_before))
+            .to_uint_floor();
+        if amount_filled.is_zero() {
+            continue;
+        }
+
+        events.push(Event::new("order_filled").add_attributes(vec![
+            ("tick_id", tick_id.to_string()),
+            ("order_id", order.order_id.to_string()),
+            ("owner", order.owner.to_string()),
+            ("amount_filled", amount_filled.to_string()),
+            ("direction", direction.to_string()),
+        ]));
+    }
+
+    Ok(events)
+}
+
 /// Defines the state changes resulting from a market order.
 pub(crate) struct PostMarketOrderState {
     pub output: Coin256,
     pub tick_updates: Vec<(i64, TickState)>,
     pub updated_orderbook: Orderbook,
+    pub stop_reason: MarketOrderStopReason,
 }
 
 /// Attempts to fill a market order against the orderbook. Due to the sumtree-based orderbook design,
@@ -422,13 +2382,51 @@ pub(crate) struct PostMarketOrderState {
 /// * Provided order has zero quantity
 /// * Tick to price conversion fails for any tick
 /// * Order is not fully filled
+/// * Order input is consumed without producing any output
 ///
 /// CONTRACT: The caller must ensure that the necessary input funds were actually supplied.
-#[allow(clippy::manual_range_contains)]
 pub(crate) fn run_market_order_internal(
     storage: &dyn Storage,
     order: &mut MarketOrder,
     tick_bound: i64,
+    now: Timestamp,
+) -> ContractResult<PostMarketOrderState> {
+    let post_market_order_state = simulate_market_order(storage, order, tick_bound, None, now)?;
+
+    // If, after iterating through all remaining ticks, the order quantity is still not filled (excluding dust),
+    // we error out as the orderbook has insufficient liquidity to fill the order.
+    ensure!(
+        post_market_order_state.stop_reason != MarketOrderStopReason::OutOfLiquidity,
+        ContractError::InsufficientLiquidity
+    );
+
+    // Near the tick bounds an input can be too small to buy a single unit. Iteration then halts at
+    // the first tick, so rather than succeeding with no output the order is rejected
+    ensure!(
+        !(post_market_order_state.output.amount.is_zero()
+            && post_market_order_state.stop_reason == MarketOrderStopReason::Filled
+            && !order.quantity.is_zero()),
+        ContractError::ZeroOutput
+    );
+
+    Ok(post_market_order_state)
+}
+
+/// Walks the orderbook to fill a market order, stopping once the order is filled, the book runs out of
+/// liquidity, `tick_bound` is reached or `max_ticks_crossed` ticks have been filled against.
+///
+/// Unlike `run_market_order_internal`, running out of liquidity is not an error and is instead reported
+/// through the returned `stop_reason`. Orders that expired before `now` are skipped, as a real fill
+/// voids them first.
+///
+/// Note that this mutates the `order` object and **does not perform any state mutations**
+#[allow(clippy::manual_range_contains)]
+pub(crate) fn simulate_market_order(
+    storage: &dyn Storage,
+    order: &mut MarketOrder,
+    tick_bound: i64,
+    max_ticks_crossed: Option<u32>,
+    now: Timestamp,
 ) -> ContractResult<PostMarketOrderState> {
     // Ensure order is non-empty
     ensure!(
@@ -441,18 +2439,20 @@ pub(crate) fn run_market_order_internal(
     let mut orderbook = ORDERBOOK.load(storage)?;
     let output_denom = orderbook.get_opposite_denom(&order.order_direction);
 
-    // Ensure the given tick bound is within global limits
+    // Ensure the given tick bound is within the configured global limits
+    let (min_tick, max_tick) = get_tick_bounds(storage)?;
     ensure!(
-        tick_bound <= MAX_TICK && tick_bound >= MIN_TICK,
+        tick_bound <= max_tick && tick_bound >= min_tick,
         ContractError::InvalidTickId {
             tick_id: tick_bound
         }
     );
 
-    // Derive appropriate bounds for tick iterator based on order direction:
-    // * If the order is an Ask, we iterate from [next_bid_tick, tick_bound] in descending order.
-    // * If the order is a Bid, we iterate from [tick_bound, next_ask_tick] in ascending order.
-    let (min_tick, max_tick, ordering) = match order.order_direction {
+    // Ensure the tick bound does not lie before the first tick that can be filled against:
+    // * If the order is an Ask, we walk bids from next_bid_tick down to tick_bound.
+    // * If the order is a Bid, we walk asks from next_ask_tick up to tick_bound.
+    let fill_direction = order.order_direction.opposite();
+    let start_tick = match order.order_direction {
         OrderDirection::Ask => {
             ensure!(
                 tick_bound <= orderbook.next_bid_tick,
@@ -460,7 +2460,7 @@ pub(crate) fn run_market_order_internal(
                     tick_id: tick_bound
                 }
             );
-            (tick_bound, orderbook.next_bid_tick, Order::Descending)
+            orderbook.next_bid_tick
         }
         OrderDirection::Bid => {
             ensure!(
@@ -469,45 +2469,74 @@ pub(crate) fn run_market_order_internal(
                     tick_id: tick_bound
                 }
             );
-            (orderbook.next_ask_tick, tick_bound, Order::Ascending)
+            orderbook.next_ask_tick
         }
     };
 
-    // Create tick iterator between first tick and requested tick
-    let ticks = TICK_STATE.keys(
-        storage,
-        Some(Bound::inclusive(min_tick)),
-        Some(Bound::inclusive(max_tick)),
-        ordering,
-    );
-
-    // Iterate through ticks and fill the market order as appropriate.
+    // Walk the populated ticks and fill the market order as appropriate.
     // Due to our sumtree-based design, this process carries only O(1) overhead per tick.
     let mut total_output: Uint256 = Uint256::zero();
     let mut tick_updates: Vec<(i64, TickState)> = Vec::new();
 
     // The price of the last tick iterated on, if no ticks are iterated price is constant
     let mut last_tick_price = Decimal256::one();
-    for maybe_current_tick in ticks {
-        let current_tick_id = maybe_current_tick?;
-        let mut current_tick = TICK_STATE.load(storage, current_tick_id)?;
-        let mut current_tick_values = current_tick.get_values(order.order_direction.opposite());
+    let mut ticks_crossed: u32 = 0;
+    let mut max_ticks_reached = false;
+    let mut from_tick = start_tick;
+    while let Some((current_tick_id, mut current_tick)) =
+        next_populated_tick(storage, fill_direction, from_tick, tick_bound)?
+    {
+        // Continue past the current tick on the next iteration, ticks are bounded well within i64
+        from_tick = match fill_direction {
+            OrderDirection::Ask => current_tick_id + 1,
+            OrderDirection::Bid => current_tick_id - 1,
+        };
+        let mut current_tick_values = current_tick.get_values(fill_direction);
+
+        // Expired orders are voided before a fill reaches them, so their liquidity is not available
+        let available_liquidity = current_tick_values
+            .total_amount_of_liquidity
+            .saturating_sub(expired_liquidity(
+                storage,
+                current_tick_id,
+                fill_direction,
+                now,
+            )?);
+        if available_liquidity.is_zero() {
+            continue;
+        }
+
+        if max_ticks_crossed.is_some_and(|max_ticks| ticks_crossed >= max_ticks) {
+            max_ticks_reached = true;
+            break;
+        }
+        ticks_crossed += 1;
+
         let tick_price = tick_to_price(current_tick_id)?;
         last_tick_price = tick_price;
 
-        let output_quantity = amount_to_value(
-            order.order_direction,
+        // If the remaining input cannot generate any output, we terminate tick iteration and leave it
+        // (which is rounding error dust) on the order to be refunded rather than consuming it for nothing.
+        let Some((fill_amount_dec, input_filled)) = fill_at_tick(
             order.quantity,
+            order.order_direction,
             tick_price,
-            RoundingDirection::Down,
-        )?;
-
-        // If the output quantity is zero, the remaining input amount cannot generate any output.
-        // When this is the case, we consume the remaining input (which is either zero or rounding error dust)
-        // and terminate tick iteration.
-        if output_quantity.is_zero() {
-            order.quantity = Uint128::zero();
+            available_liquidity,
+        )?
+        else {
             break;
+        };
+
+        // Halt before filling against a tick too far from where the order started
+        if let Some(max_tick_move) = orderbook.max_tick_move {
+            ensure!(
+                current_tick_id.abs_diff(start_tick) <= max_tick_move.unsigned_abs(),
+                ContractError::ExcessivePriceImpact {
+                    start_tick,
+                    tick_id: current_tick_id,
+                    max_tick_move,
+                }
+            );
         }
 
         // Update current tick pointer as we visit ticks that contribute to filling the order
@@ -516,21 +2545,8 @@ pub(crate) fn run_market_order_internal(
             OrderDirection::Bid => orderbook.next_bid_tick = current_tick_id,
         }
 
-        let output_quantity_dec = Decimal256::from_ratio(output_quantity, Uint256::one());
-
-        // If order quantity is less than the current tick's liquidity, fill the whole order.
-        // Otherwise, fill the whole tick.
-        let fill_amount_dec = if output_quantity_dec < current_tick_values.total_amount_of_liquidity
-        {
-            output_quantity_dec
-        } else {
-            current_tick_values.total_amount_of_liquidity
-        };
-
         // Update tick and order state to process the fill
-        current_tick_values.total_amount_of_liquidity = current_tick_values
-            .total_amount_of_liquidity
-            .checked_sub(fill_amount_dec)?;
+        subtract_liquidity(&mut current_tick_values, current_tick_id, fill_amount_dec)?;
 
         current_tick_values.effective_total_amount_swapped = current_tick_values
             .effective_total_amount_swapped
@@ -540,16 +2556,10 @@ pub(crate) fn run_market_order_internal(
         // By the time we get here, this should not be possible.
         let fill_amount = Uint128::try_from(fill_amount_dec.to_uint_floor())?;
 
-        let input_filled = amount_to_value(
-            order.order_direction.opposite(),
-            fill_amount,
-            tick_price,
-            RoundingDirection::Up,
-        )?;
         order.quantity = order
             .quantity
             // Safe conversions as amount filled should never be larger than order quantity which is upper bounded by Uint128::MAX
-            .checked_sub(Uint128::try_from(input_filled)?)?;
+            .checked_sub(input_filled)?;
 
         current_tick.set_values(order.order_direction.opposite(), current_tick_values);
         // Add the updated tick state to the vector
@@ -560,47 +2570,145 @@ pub(crate) fn run_market_order_internal(
 
     // Determine if filling remaining amount on the last possible tick produced any value
     // This will be 0 if the remaining balance is dust
-    let remaining_balance = amount_to_value(
-        order.order_direction,
-        order.quantity,
-        last_tick_price,
-        RoundingDirection::Down,
-    )?;
+    let remaining_balance =
+        amount_out_rounded(order.quantity, last_tick_price, order.order_direction)?;
 
-    // Since full market orders must have their bound set at MIN_TICK or MAX_TICK,
+    // Since full market orders must have their bound set at the configured min or max tick,
     // we identify partial market orders efficiently by checking if the order diverges
     // from this pattern.
     let partial_market_order = match order.order_direction {
-        OrderDirection::Ask => tick_bound > MIN_TICK,
-        OrderDirection::Bid => tick_bound < MAX_TICK,
+        OrderDirection::Ask => tick_bound > min_tick,
+        OrderDirection::Bid => tick_bound < max_tick,
     };
 
-    // If, after iterating through all remaining ticks, the order quantity is still not filled (excluding dust),
-    // we error out as the orderbook has insufficient liquidity to fill the order.
-    //
-    // We bypass this check if the order is a partial market order, which is allowed to have remaining input after
-    // completion.
-    ensure!(
-        remaining_balance.is_zero() || partial_market_order,
-        ContractError::InsufficientLiquidity
-    );
+    // If the order quantity is still not filled (excluding dust), determine why iteration stopped.
+    // Partial market orders are allowed to have remaining input once their tick bound is reached.
+    let stop_reason = if remaining_balance.is_zero() {
+        MarketOrderStopReason::Filled
+    } else if max_ticks_reached {
+        MarketOrderStopReason::MaxTicksReached
+    } else if partial_market_order {
+        MarketOrderStopReason::PriceBound
+    } else {
+        MarketOrderStopReason::OutOfLiquidity
+    };
 
     Ok(PostMarketOrderState {
         output: coin_u256(total_output, &output_denom),
         tick_updates,
         updated_orderbook: orderbook,
+        stop_reason,
     })
 }
 
-// Note: This can be called by anyone
+/// Returns how much of a tick's `liquidity` a market order with `quantity` input left fills at
+/// `tick_price`, along with the input consumed doing so.
+///
+/// If the order's output is less than the tick's liquidity the whole order is filled, otherwise
+/// the whole tick is. Returns `None` if the input is too small to buy anything at this price.
+fn fill_at_tick(
+    quantity: Uint128,
+    order_direction: OrderDirection,
+    tick_price: Decimal256,
+    liquidity: Decimal256,
+) -> ContractResult<Option<(Decimal256, Uint128)>> {
+    let output_quantity = amount_out_rounded(quantity, tick_price, order_direction)?;
+    if output_quantity.is_zero() {
+        return Ok(None);
+    }
+
+    let fill_amount_dec = Decimal256::from_ratio(output_quantity, Uint256::one()).min(liquidity);
+    // Note: this conversion errors if fill_amount_dec does not fit into Uint128
+    // By the time we get here, this should not be possible.
+    let fill_amount = Uint128::try_from(fill_amount_dec.to_uint_floor())?;
+    let input_filled = amount_to_value(
+        order_direction.opposite(),
+        fill_amount,
+        tick_price,
+        RoundingDirection::Up,
+    )?;
+
+    // Safe conversion as the input filled should never be larger than the order quantity, which is
+    // upper bounded by Uint128::MAX
+    Ok(Some((fill_amount_dec, Uint128::try_from(input_filled)?)))
+}
+
+// Note: This can be called by anyone. The claimed amount is always sent to the order owner, while
+// any claim bounty is sent to `sender`. If `sender` is the owner, the bounty is merged into the
+// owner's send, and the returned amount includes it.
 pub(crate) fn claim_order(
     storage: &mut dyn Storage,
     contract_address: Addr,
     sender: Addr,
     tick_id: i64,
     order_id: u64,
+    min_claim: Option<Uint128>,
 ) -> ContractResult<(Uint256, Vec<SubMsg>, LimitOrder)> {
     let orderbook = ORDERBOOK.load(storage)?;
+    let (
+        ClaimPayout {
+            amount,
+            bounty,
+            maker_fee_amount,
+        },
+        order,
+    ) = settle_claim(storage, tick_id, order_id, min_claim)?;
+
+    let denom = orderbook.get_opposite_denom(&order.order_direction);
+
+    // Owners claiming their own order receive the bounty alongside the claimed amount
+    let owner_is_sender = sender == order.owner;
+    let owner_amount = if owner_is_sender {
+        amount.checked_add(bounty)?
+    } else {
+        amount
+    };
+
+    // Claimed amount always goes to the order owner
+    let bank_msg = MsgSend256 {
+        from_address: contract_address.to_string(),
+        to_address: order.owner.to_string(),
+        amount: vec![coin_u256(owner_amount, &denom)],
+    };
+    let mut bank_msg_vec = vec![SubMsg::reply_on_error(bank_msg, REPLY_ID_CLAIM)];
+
+    if !bounty.is_zero() && !owner_is_sender {
+        // Bounty always goes to the sender
+        let bounty_msg = MsgSend256 {
+            from_address: contract_address.to_string(),
+            to_address: sender.to_string(),
+            amount: vec![coin_u256(bounty, &denom)],
+        };
+        bank_msg_vec.push(SubMsg::reply_on_error(bounty_msg, REPLY_ID_CLAIM_BOUNTY));
+    }
+
+    if !maker_fee_amount.is_zero() {
+        // Maker fee recipient is controlled by contract admin/moderator
+        let maker_fee_recipient = MAKER_FEE_RECIPIENT
+            .may_load(storage)?
+            .ok_or(ContractError::NoMakerFeeRecipient)?;
+        let maker_fee_msg = MsgSend256 {
+            from_address: contract_address.to_string(),
+            to_address: maker_fee_recipient.to_string(),
+            amount: vec![coin_u256(maker_fee_amount, &denom)],
+        };
+        bank_msg_vec.push(SubMsg::reply_on_error(maker_fee_msg, REPLY_ID_MAKER_FEE));
+    }
+
+    Ok((owner_amount, bank_msg_vec, order))
+}
+
+/// Realizes the currently filled portion of an order in state and splits its output between the
+/// order owner, claimer and maker fee recipient.
+///
+/// Returns the payout and the updated order. **Does not generate any bank messages**, this is left
+/// to the caller.
+fn settle_claim(
+    storage: &mut dyn Storage,
+    tick_id: i64,
+    order_id: u64,
+    min_claim: Option<Uint128>,
+) -> ContractResult<(ClaimPayout, LimitOrder)> {
     // Fetch tick values for current order direction
     let tick_state = TICK_STATE
         .may_load(storage, tick_id)?
@@ -628,10 +2736,11 @@ pub(crate) fn claim_order(
         .ok_or(ContractError::InvalidTickId { tick_id })?;
     let tick_values = tick_state.get_values(order.order_direction);
 
-    // Early exit if nothing has been filled
+    // Early exit if fills have not yet reached past the order's position in the queue, which
+    // includes an order at the head of the queue that nothing has been swapped against
     ensure!(
         tick_values.effective_total_amount_swapped > order.etas,
-        ContractError::ZeroClaim
+        ContractError::NotYetReached { tick_id, order_id }
     );
 
     // Calculate amount of order that is currently filled (may be partial).
@@ -650,29 +2759,62 @@ pub(crate) fn claim_order(
     order.quantity = order.quantity.checked_sub(amount_filled)?;
     order.etas = order.etas.checked_add(amount_filled_dec)?;
 
+    // Calculate amount to be sent to order owner
+    let tick_price = tick_to_price(tick_id)?;
+    // Immutable amount to prevent bounty/maker fee calculations affecting each other
+    let raw_amount = amount_out_rounded(amount_filled, tick_price, order.order_direction)?;
+
+    // Cannot send a zero amount, may be zero'd out by rounding
+    ensure!(!raw_amount.is_zero(), ContractError::ZeroClaim);
+
+    // Skip dust claims that are not worth realizing for the caller
+    if let Some(min_claim) = min_claim {
+        ensure!(
+            raw_amount >= Uint256::from_uint128(min_claim),
+            ContractError::ClaimBelowMinimum {
+                tick_id,
+                order_id,
+                amount: raw_amount,
+                min_claim,
+            }
+        );
+    }
+
+    // Accumulate realized fills so the order's average fill price can be derived
+    order.claimed_quantity = order.claimed_quantity.checked_add(amount_filled)?;
+    order.claimed_output = order.claimed_output.checked_add(raw_amount)?;
+
     // If order fully filled then remove
     if order.quantity.is_zero() {
         orders().remove(storage, &key)?;
+        remove_order_expiry(storage, &order);
+        decrement_tick_order_count(storage, order.tick_id, order.order_direction)?;
+        FILLED_ORDERS.save(storage, key, &Empty {})?;
     // Else update in state
     } else {
         orders().save(storage, &key, &order)?;
     }
 
-    // Calculate amount to be sent to order owner
-    let tick_price = tick_to_price(tick_id)?;
-    let mut amount = amount_to_value(
-        order.order_direction,
-        amount_filled,
-        tick_price,
-        RoundingDirection::Down,
-    )?;
-    // Immutable amount to prevent bounty/maker fee calculations affecting each other
-    let raw_amount = amount;
+    let payout = calculate_claim_payout(storage, &order, raw_amount)?;
 
-    // Cannot send a zero amount, may be zero'd out by rounding
-    ensure!(!amount.is_zero(), ContractError::ZeroClaim);
+    Ok((payout, order))
+}
 
-    let denom = orderbook.get_opposite_denom(&order.order_direction);
+/// The split of a claim's output between the order owner, claimer and maker fee recipient.
+pub(crate) struct ClaimPayout {
+    pub amount: Uint256,
+    pub bounty: Uint256,
+    pub maker_fee_amount: Uint256,
+}
+
+/// Splits the raw output of a claim into the claim bounty owed to the claimer, the maker fee and
+/// the remaining amount owed to the order owner.
+pub(crate) fn calculate_claim_payout(
+    storage: &dyn Storage,
+    order: &LimitOrder,
+    raw_amount: Uint256,
+) -> ContractResult<ClaimPayout> {
+    let mut amount = raw_amount;
 
     // Send claim bounty to sender if applicable
     let mut bounty = Uint256::zero();
@@ -696,36 +2838,77 @@ pub(crate) fn claim_order(
         amount = amount.checked_sub(maker_fee_amount)?;
     }
 
-    // Claimed amount always goes to the order owner
-    let bank_msg = MsgSend256 {
-        from_address: contract_address.to_string(),
-        to_address: order.owner.to_string(),
-        amount: vec![coin_u256(amount, &denom)],
-    };
-    let mut bank_msg_vec = vec![SubMsg::reply_on_error(bank_msg, REPLY_ID_CLAIM)];
+    Ok(ClaimPayout {
+        amount,
+        bounty,
+        maker_fee_amount,
+    })
+}
 
-    if !bounty.is_zero() {
-        // Bounty always goes to the sender
-        let bounty_msg = MsgSend256 {
-            from_address: contract_address.to_string(),
-            to_address: sender.to_string(),
-            amount: vec![coin_u256(bounty, &denom)],
-        };
-        bank_msg_vec.push(SubMsg::reply_on_error(bounty_msg, REPLY_ID_CLAIM_BOUNTY));
+/// Infers claim totals for orders that were partially claimed before they were tracked.
+///
+/// Claims decrement an order's quantity, so the claimed quantity is the difference from the placed
+/// quantity. The claimed output is valued at the order's tick price, rounding down as claims do,
+/// and may therefore differ by rounding dust from what was actually paid out.
+///
+/// Returns the number of orders updated.
+pub(crate) fn backfill_claimed_totals(storage: &mut dyn Storage) -> ContractResult<u64> {
+    let tick_ids = TICK_STATE
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<Result<Vec<i64>, _>>()?;
+
+    let mut backfilled = 0;
+    for tick_id in tick_ids {
+        let tick_orders = orders()
+            .prefix(tick_id)
+            .range(storage, None, None, Order::Ascending)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (_, mut order) in tick_orders {
+            if !order.claimed_quantity.is_zero() || order.placed_quantity <= order.quantity {
+                continue;
+            }
+
+            order.claimed_quantity = order.placed_quantity.checked_sub(order.quantity)?;
+            order.claimed_output = amount_out_rounded(
+                order.claimed_quantity,
+                tick_to_price(order.tick_id)?,
+                order.order_direction,
+            )?;
+            orders().save(storage, &(order.tick_id, order.order_id), &order)?;
+            backfilled += 1;
+        }
     }
 
-    if !maker_fee_amount.is_zero() {
-        // Maker fee recipient is controlled by contract admin/moderator
-        let maker_fee_recipient = MAKER_FEE_RECIPIENT
-            .may_load(storage)?
-            .ok_or(ContractError::NoMakerFeeRecipient)?;
-        let maker_fee_msg = MsgSend256 {
-            from_address: contract_address.to_string(),
-            to_address: maker_fee_recipient.to_string(),
-            amount: vec![coin_u256(maker_fee_amount, &denom)],
-        };
-        bank_msg_vec.push(SubMsg::reply_on_error(maker_fee_msg, REPLY_ID_MAKER_FEE));
+    Ok(backfilled)
+}
+
+/// Calculates how much of an order is currently filled but unclaimed, without mutating state.
+///
+/// Returns the filled quantity (in the order's input denom) and its raw output value prior to
+/// any bounty or maker fee deductions.
+pub(crate) fn get_unclaimed_fill(
+    storage: &dyn Storage,
+    order: &LimitOrder,
+) -> ContractResult<(Uint128, Uint256)> {
+    let tick_values = get_synced_tick_values(storage, order.tick_id, order.order_direction)?;
+
+    // Nothing has been filled past this order's starting ETAS
+    if tick_values.effective_total_amount_swapped <= order.etas {
+        return Ok((Uint128::zero(), Uint256::zero()));
     }
 
-    Ok((amount, bank_msg_vec, order))
+    let amount_filled_dec = tick_values
+        .effective_total_amount_swapped
+        .checked_sub(order.etas)?
+        .min(Decimal256::from_ratio(order.quantity, 1u128));
+    let amount_filled = Uint128::try_from(amount_filled_dec.to_uint_floor())?;
+
+    let output = amount_out_rounded(
+        amount_filled,
+        tick_to_price(order.tick_id)?,
+        order.order_direction,
+    )?;
+
+    Ok((amount_filled, output))
 }
@@ -1,11 +1,14 @@
 use crate::constants::{
-    DEFAULT_MAKER_FEE, DEFAULT_MAKER_FEE_RECIPIENT, MAX_MAKER_FEE_PERCENTAGE, MAX_TICK, MIN_TICK,
+    DEFAULT_MAKER_FEE, DEFAULT_MAKER_FEE_RECIPIENT, MAX_MAKER_FEE_PERCENTAGE,
+    MAX_TAKER_FEE_PERCENTAGE, MAX_TICK, MIN_TICK,
 };
 use crate::error::ContractResult;
-use crate::state::{MAKER_FEE, MAKER_FEE_RECIPIENT, ORDERBOOK};
+use crate::state::{
+    get_tick_bounds, DENOM_ALIASES, MAKER_FEE, MAKER_FEE_RECIPIENT, ORDERBOOK, TICK_BOUNDS,
+};
 use crate::types::Orderbook;
 use crate::ContractError;
-use cosmwasm_std::{ensure, Decimal256, DepsMut, Storage};
+use cosmwasm_std::{ensure, Addr, Decimal256, DepsMut, Storage, Uint128};
 
 pub fn create_orderbook(
     deps: DepsMut,
@@ -14,8 +17,26 @@ pub fn create_orderbook(
 ) -> ContractResult<()> {
     let denoms = [quote_denom.clone(), base_denom.clone()];
 
+    // A malformed denom can never be sent to the contract, so the book would be unusable
+    ensure!(
+        denoms.iter().all(|denom| is_valid_denom(denom)),
+        ContractError::InvalidPair {
+            token_in_denom: quote_denom,
+            token_out_denom: base_denom,
+        }
+    );
+
     ensure!(quote_denom != base_denom, ContractError::DuplicateDenoms {});
 
+    // Swap direction is derived from the denoms, so only one book may exist per pair in
+    // either ordering. As this contract holds a single book any existing book is rejected.
+    if let Some(existing) = ORDERBOOK.may_load(deps.storage)? {
+        return Err(ContractError::BookAlreadyExists {
+            quote_denom: existing.quote_denom,
+            base_denom: existing.base_denom,
+        });
+    }
+
     for denom in denoms {
         let maybe_supply = deps.querier.query_supply(denom.clone());
 
@@ -26,8 +47,9 @@ pub fn create_orderbook(
         );
     }
 
-    // Instantiate orderbook and write to state
-    let book = Orderbook::new(quote_denom, base_denom, 0, MIN_TICK, MAX_TICK);
+    // Instantiate orderbook and write to state, with the empty sides' tick pointers at the tick bounds
+    let (min_tick, max_tick) = get_tick_bounds(deps.storage)?;
+    let book = Orderbook::new(quote_denom, base_denom, 0, min_tick, max_tick);
     ORDERBOOK.save(deps.storage, &book)?;
 
     // Set maker fee
@@ -39,6 +61,15 @@ pub fn create_orderbook(
     Ok(())
 }
 
+/// Checks a denom against the Cosmos SDK format: 3 to 128 characters, starting with a letter and
+/// followed by alphanumerics or any of `/:._-`.
+fn is_valid_denom(denom: &str) -> bool {
+    let mut chars = denom.chars();
+    (3..=128).contains(&denom.len())
+        && chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c))
+}
+
 /// Sets the maker fee amount for the orderbook.
 pub fn set_maker_fee(
     storage: &mut dyn Storage,
@@ -53,6 +84,169 @@ pub fn set_maker_fee(
     Ok(maker_fee)
 }
 
+/// Sets the taker fee taken from market order output and the address it is sent to.
+pub fn set_taker_fee(
+    storage: &mut dyn Storage,
+    taker_fee: Decimal256,
+    fee_collector: Addr,
+) -> ContractResult<Decimal256> {
+    ensure!(
+        taker_fee <= MAX_TAKER_FEE_PERCENTAGE,
+        ContractError::InvalidTakerFee {}
+    );
+    let mut orderbook = ORDERBOOK.load(storage)?;
+    orderbook.taker_fee = taker_fee;
+    orderbook.fee_collector = Some(fee_collector);
+    ORDERBOOK.save(storage, &orderbook)?;
+
+    Ok(taker_fee)
+}
+
+/// Sets the global tick bounds that limit orders may be placed within.
+///
+/// The orderbook's tick pointers are clamped to the new bounds, as market orders may only walk the
+/// book from within them.
+///
+/// Errors if the bounds are empty or exceed the compile-time `MIN_TICK`/`MAX_TICK` limits.
+pub fn set_tick_bounds(
+    storage: &mut dyn Storage,
+    min_tick: i64,
+    max_tick: i64,
+) -> ContractResult<()> {
+    ensure!(
+        min_tick >= MIN_TICK && max_tick <= MAX_TICK && min_tick <= max_tick,
+        ContractError::InvalidTickBounds { min_tick, max_tick }
+    );
+    TICK_BOUNDS.save(storage, &(min_tick, max_tick))?;
+
+    // The orderbook does not exist yet when the bounds are set on instantiation
+    if let Some(mut book) = ORDERBOOK.may_load(storage)? {
+        book.next_bid_tick = book.next_bid_tick.clamp(min_tick, max_tick);
+        book.next_ask_tick = book.next_ask_tick.clamp(min_tick, max_tick);
+        ORDERBOOK.save(storage, &book)?;
+    }
+
+    Ok(())
+}
+
+/// Sets the smallest limit order quantity the orderbook accepts.
+///
+/// A zero minimum disables the check.
+pub fn set_min_order_size(
+    storage: &mut dyn Storage,
+    min_order_size: Uint128,
+) -> ContractResult<()> {
+    ORDERBOOK.update(storage, |mut book| -> ContractResult<_> {
+        book.min_order_size = min_order_size;
+        Ok(book)
+    })?;
+
+    Ok(())
+}
+
+/// Sets the furthest a single market order may move the tick pointer from where it started.
+///
+/// Errors if the maximum is negative.
+pub fn set_max_tick_move(storage: &mut dyn Storage, max_tick_move: i64) -> ContractResult<()> {
+    ensure!(
+        max_tick_move >= 0,
+        ContractError::InvalidMaxTickMove { max_tick_move }
+    );
+    ORDERBOOK.update(storage, |mut book| -> ContractResult<_> {
+        book.max_tick_move = Some(max_tick_move);
+        Ok(book)
+    })?;
+
+    Ok(())
+}
+
+/// Restricts limit orders to ticks that are a multiple of `tick_spacing`. A spacing of 1 allows
+/// every tick.
+///
+/// Errors if the spacing is not positive.
+pub fn set_tick_spacing(storage: &mut dyn Storage, tick_spacing: i64) -> ContractResult<()> {
+    ensure!(
+        tick_spacing > 0,
+        ContractError::InvalidTickSpacing { tick_spacing }
+    );
+    ORDERBOOK.update(storage, |mut book| -> ContractResult<_> {
+        book.tick_spacing = Some(tick_spacing);
+        Ok(book)
+    })?;
+
+    Ok(())
+}
+
+/// Caps the number of orders that may rest in one direction of a tick at once, bounding the cost of
+/// crossing or claiming a tick.
+///
+/// Errors if the maximum is zero.
+pub fn set_max_orders_per_tick(
+    storage: &mut dyn Storage,
+    max_orders_per_tick: u32,
+) -> ContractResult<()> {
+    ensure!(
+        max_orders_per_tick > 0,
+        ContractError::InvalidMaxOrdersPerTick {
+            max_orders_per_tick
+        }
+    );
+    ORDERBOOK.update(storage, |mut book| -> ContractResult<_> {
+        book.max_orders_per_tick = Some(max_orders_per_tick);
+        Ok(book)
+    })?;
+
+    Ok(())
+}
+
+/// Caps the claim bounty limit orders may offer, so makers cannot pay away most of their fills.
+///
+/// Errors if the cap exceeds the global 1% claim bounty guardrail.
+pub fn set_max_claim_bounty(
+    storage: &mut dyn Storage,
+    max_claim_bounty: Decimal256,
+) -> ContractResult<()> {
+    ensure!(
+        max_claim_bounty <= Decimal256::percent(1),
+        ContractError::InvalidClaimBounty {
+            claim_bounty: Some(max_claim_bounty)
+        }
+    );
+    ORDERBOOK.update(storage, |mut book| -> ContractResult<_> {
+        book.max_claim_bounty = Some(max_claim_bounty);
+        Ok(book)
+    })?;
+
+    Ok(())
+}
+
+/// Registers `alias` as an alternative form of one of the orderbook's denoms.
+///
+/// Aliases are only resolved when looking up the pair in queries. Funds must still be sent and paid
+/// out in the canonical denom, as balances are tracked per denom. Errors if `canonical` is not one
+/// of the orderbook's denoms or if `alias` is itself one of them.
+pub fn register_denom_alias(
+    storage: &mut dyn Storage,
+    alias: String,
+    canonical: String,
+) -> ContractResult<()> {
+    let orderbook = ORDERBOOK.load(storage)?;
+    let book_denoms = [orderbook.quote_denom, orderbook.base_denom];
+
+    ensure!(
+        book_denoms.contains(&canonical),
+        ContractError::InvalidDenom { denom: canonical }
+    );
+    ensure!(
+        !book_denoms.contains(&alias),
+        ContractError::InvalidDenom { denom: alias }
+    );
+
+    DENOM_ALIASES.save(storage, &alias, &canonical)?;
+
+    Ok(())
+}
+
 /// Sets the recipient address for the maker fee for the orderbook.
 pub fn set_maker_fee_recipient(deps: DepsMut, maker_fee_recipient: &str) -> ContractResult<()> {
     let addr = deps
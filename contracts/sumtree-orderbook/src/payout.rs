@@ -0,0 +1,205 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{
+    BankMsg, CosmosMsg, DepsMut, Env, Event, MessageInfo, Reply, ReplyOn, Response, Storage,
+    SubMsg, SubMsgResult, Uint256,
+};
+use osmosis_std::types::cosmos::bank::v1beta1::MsgSend;
+
+use crate::{
+    error::{ContractError, ContractResult},
+    state::{FAILED_PAYOUTS, PENDING_PAYOUTS},
+    types::{
+        coin_u256, Coin256, MsgSend256, PendingPayout, REPLY_ID_CLAIM, REPLY_ID_CLAIM_BOUNTY,
+        REPLY_ID_IMMEDIATE_FILL, REPLY_ID_REFUND,
+    },
+};
+
+/// Whether a failed payout with the given reply id is recovered rather than reverting the transaction.
+pub(crate) fn is_recoverable_payout(reply_id: u64) -> bool {
+    matches!(
+        reply_id,
+        REPLY_ID_REFUND | REPLY_ID_CLAIM | REPLY_ID_CLAIM_BOUNTY | REPLY_ID_IMMEDIATE_FILL
+    )
+}
+
+/// Switches the refund, claim, bounty and immediate fill payouts of a response to always reply and records them as
+/// pending in execution order, so that `reply` can identify the payout each reply belongs to.
+///
+/// A bounty is always preceded by the claim paying out its order's owner, which is recorded as the
+/// order owner of the bounty.
+///
+/// Messages from a reply execute before any remaining messages of the original response, so
+/// `from_reply` queues them ahead of the payouts still pending.
+pub(crate) fn track_recoverable_payouts(
+    storage: &mut dyn Storage,
+    mut response: Response,
+    from_reply: bool,
+) -> ContractResult<Response> {
+    let mut tracked = vec![];
+    let mut last_claim_recipient = None;
+    for sub_msg in response.messages.iter_mut() {
+        if !is_recoverable_payout(sub_msg.id) {
+            continue;
+        }
+        let Some((recipient, amount)) = parse_send(&sub_msg.msg)? else {
+            continue;
+        };
+
+        let order_owner = match sub_msg.id {
+            REPLY_ID_CLAIM => {
+                last_claim_recipient = Some(recipient.clone());
+                None
+            }
+            REPLY_ID_CLAIM_BOUNTY => last_claim_recipient.clone(),
+            _ => None,
+        };
+
+        sub_msg.reply_on = ReplyOn::Always;
+        tracked.push(PendingPayout {
+            reply_id: sub_msg.id,
+            recipient,
+            amount,
+            order_owner,
+        });
+    }
+
+    if tracked.is_empty() {
+        return Ok(response);
+    }
+
+    let mut pending = PENDING_PAYOUTS.may_load(storage)?.unwrap_or_default();
+    if from_reply {
+        tracked.extend(pending);
+        pending = tracked;
+    } else {
+        pending.extend(tracked);
+    }
+    PENDING_PAYOUTS.save(storage, &pending)?;
+
+    Ok(response)
+}
+
+/// Extracts the recipient and amount of a bank send, in either its `BankMsg` or `MsgSend256` form.
+fn parse_send(msg: &CosmosMsg) -> ContractResult<Option<(cosmwasm_std::Addr, Vec<Coin256>)>> {
+    match msg {
+        CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => Ok(Some((
+            cosmwasm_std::Addr::unchecked(to_address),
+            amount
+                .iter()
+                .map(|coin| coin_u256(coin.amount, &coin.denom))
+                .collect(),
+        ))),
+        CosmosMsg::Stargate { type_url, value } if type_url == MsgSend::TYPE_URL => {
+            let msg_send = MsgSend::try_from(value.clone())?;
+            let mut amount = vec![];
+            for coin in msg_send.amount {
+                amount.push(coin_u256(Uint256::from_str(&coin.amount)?, &coin.denom));
+            }
+            Ok(Some((
+                cosmwasm_std::Addr::unchecked(msg_send.to_address),
+                amount,
+            )))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Resolves the reply of the oldest pending payout.
+///
+/// A failed bounty is paid to the order owner instead, since the claimer could not receive it. Any
+/// other failed payout is credited to its recipient in `FAILED_PAYOUTS` for later withdrawal.
+pub(crate) fn handle_payout_reply(deps: DepsMut, env: Env, msg: Reply) -> ContractResult<Response> {
+    let mut pending = PENDING_PAYOUTS.may_load(deps.storage)?.unwrap_or_default();
+    let payout = match pending.first() {
+        Some(payout) if payout.reply_id == msg.id => pending.remove(0),
+        _ => {
+            return Err(ContractError::ReplyError {
+                id: msg.id,
+                error: "Reply does not match the next pending payout".to_string(),
+            })
+        }
+    };
+    if pending.is_empty() {
+        PENDING_PAYOUTS.remove(deps.storage);
+    } else {
+        PENDING_PAYOUTS.save(deps.storage, &pending)?;
+    }
+
+    let SubMsgResult::Err(error) = msg.result else {
+        return Ok(Response::default());
+    };
+
+    if let (REPLY_ID_CLAIM_BOUNTY, Some(order_owner)) = (payout.reply_id, &payout.order_owner) {
+        let recredit_msg = MsgSend256 {
+            from_address: env.contract.address.to_string(),
+            to_address: order_owner.to_string(),
+            amount: payout.amount.clone(),
+        };
+        let response = Response::default()
+            .add_submessage(SubMsg::reply_on_error(recredit_msg, REPLY_ID_CLAIM))
+            .add_event(
+                payout_failed_event(&payout, &error).add_attribute("recredited_to", order_owner),
+            );
+        return track_recoverable_payouts(deps.storage, response, true);
+    }
+
+    let mut failed = FAILED_PAYOUTS
+        .may_load(deps.storage, &payout.recipient)?
+        .unwrap_or_default();
+    for coin in &payout.amount {
+        match failed.iter_mut().find(|owed| owed.denom == coin.denom) {
+            Some(owed) => owed.amount = owed.amount.checked_add(coin.amount)?,
+            None => failed.push(coin.clone()),
+        }
+    }
+    FAILED_PAYOUTS.save(deps.storage, &payout.recipient, &failed)?;
+
+    Ok(Response::default().add_event(payout_failed_event(&payout, &error)))
+}
+
+fn payout_failed_event(payout: &PendingPayout, error: &str) -> Event {
+    Event::new("payout_failed").add_attributes(vec![
+        ("reply_id", payout.reply_id.to_string()),
+        ("recipient", payout.recipient.to_string()),
+        (
+            "amount",
+            payout
+                .amount
+                .iter()
+                .map(|coin| format!("{}{}", coin.amount, coin.denom))
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        ("error", error.to_string()),
+    ])
+}
+
+/// Sends the sender everything credited to them from failed payouts.
+///
+/// The send is not recoverable, so if it fails again the credit is kept.
+pub(crate) fn withdraw_failed_payout(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> ContractResult<Response> {
+    cw_utils::nonpayable(&info)?;
+
+    let amount = FAILED_PAYOUTS.may_load(deps.storage, &info.sender)?.ok_or(
+        ContractError::NoFailedPayout {
+            recipient: info.sender.to_string(),
+        },
+    )?;
+    FAILED_PAYOUTS.remove(deps.storage, &info.sender);
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            ("method", "withdrawFailedPayout"),
+            ("recipient", info.sender.as_str()),
+        ])
+        .add_message(MsgSend256 {
+            from_address: env.contract.address.to_string(),
+            to_address: info.sender.to_string(),
+            amount,
+        }))
+}
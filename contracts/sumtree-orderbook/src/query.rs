@@ -1,20 +1,42 @@
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 
-use cosmwasm_std::{coin, ensure, Addr, Coin, Decimal, Deps, Order, Uint128};
+use cosmwasm_std::{
+    coin, ensure, Addr, Coin, Decimal, Decimal256, Deps, Env, Int256, Order, SignedDecimal256,
+    Uint128, Uint256,
+};
 use cw_storage_plus::Bound;
 
 use crate::{
-    constants::{MAX_TICK, MIN_TICK},
+    constants::{MAX_BATCH_CLAIM, MAX_SUMTREE_NODES, MAX_TICK, MAX_TICKS_IN_RANGE, MIN_TICK},
     error::ContractResult,
     msg::{
-        AllTicksResponse, CalcOutAmtGivenInResponse, DenomsResponse, GetSwapFeeResponse,
-        GetTotalPoolLiquidityResponse, SpotPriceResponse, TickIdAndState,
+        AllTicksResponse, BookLiquidityResponse, CalcOutAmtGivenInResponse, ClaimableOrder,
+        ClaimableResponse, CompactionCandidate, CompactionCandidatesResponse, DenomSolvency,
+        DenomsResponse, EstimateSwapExactAmountInResponse, EstimateSwapExactAmountOutResponse,
+        FillPriceResponse, GetSwapFeeResponse, GetTotalPoolLiquidityResponse, MarketStatsResponse,
+        OrderAvgPriceResponse, PriceImpactResponse, QueuePositionResponse, RealizedCancelsResponse,
+        SimulateClaimAllResponse, SimulateSwapExactAmountInResponse, SolvencyCheckResponse,
+        SpotPriceResponse, SpreadResponse, SumtreeInfoResponse, TickIdAndState,
+        TickSwapProgressResponse, TopClaimableResponse,
     },
     order,
-    state::{get_directional_liquidity, get_orders_by_owner, IS_ACTIVE, ORDERBOOK, TICK_STATE},
+    state::{
+        get_directional_liquidity, get_market_tick_bound, get_orders_by_owner, get_orders_by_tick,
+        next_populated_tick, orders, resolve_denom, CANCELLED_ORDERS, CREDIT_BALANCES,
+        DEFAULT_PAGE_SIZE, FAILED_PAYOUTS, FILLED_ORDERS, IS_ACTIVE, MAKER_FEE_RECIPIENT,
+        MARKET_STATS, MAX_PAGE_SIZE, ORDERBOOK, TICK_STATE,
+    },
     sudo::ensure_swap_fee,
-    tick_math::tick_to_price,
-    types::{FilterOwnerOrders, LimitOrder, MarketOrder, OrderDirection},
+    sumtree::{
+        node::{TreeNode, NODES},
+        tree::{get_prefix_sum, get_root_node, get_root_node_or_default, TREE},
+    },
+    tick::{get_recomputed_realized_cancels, get_synced_tick_values},
+    tick_math::{self, amount_to_value, tick_to_price, RoundingDirection},
+    types::{
+        coin_u256, FilterOwnerOrders, LimitOrder, MarketOrder, MarketOrderStopReason, MsgSend256,
+        OrderDirection, OrderStatus, TickValues,
+    },
     ContractError,
 };
 
@@ -64,12 +86,14 @@ pub(crate) fn spot_price(
 ///
 /// Output is calculated by generating a mock market order, the direction of which is dependent on the order of the input/output denoms versus what the orderbook expects.
 /// The mock order is then filled against the current orderbook state, and the output amount is the result of the fill.
+/// The output amount is reported net of any taker fee.
 ///
 /// Errors if:
 /// 1. The provided swap fee does not match the orderbook's expected swap fee, which is set to zero.
 /// 2. The provided denom pair is not supported by the orderbook
 pub(crate) fn calc_out_amount_given_in(
     deps: Deps,
+    env: Env,
     token_in: Coin,
     token_out_denom: String,
     swap_fee: Decimal,
@@ -82,23 +106,345 @@ pub(crate) fn calc_out_amount_given_in(
     // Determine order direction
     let direction = orderbook.direction_from_pair(token_in.denom, token_out_denom)?;
 
-    let tick_bound = match direction {
-        OrderDirection::Bid => MAX_TICK,
-        OrderDirection::Ask => MIN_TICK,
-    };
+    let tick_bound = get_market_tick_bound(deps.storage, direction)?;
 
     // Generate mock order for query
     let mut mock_order = MarketOrder::new(token_in.amount, direction, Addr::unchecked("querier"));
 
     // Generate output coin given the input order by simulating a fill against current orderbook state
-    let order::PostMarketOrderState { output, .. } =
-        order::run_market_order_internal(deps.storage, &mut mock_order, tick_bound)?;
+    let order::PostMarketOrderState { mut output, .. } = order::run_market_order_internal(
+        deps.storage,
+        &mut mock_order,
+        tick_bound,
+        env.block.time,
+    )?;
+    output.amount = output
+        .amount
+        .checked_sub(orderbook.get_taker_fee(output.amount)?)?;
 
     Ok(CalcOutAmtGivenInResponse {
         token_out: output.into(),
     })
 }
 
+/// Simulates a swap against the current orderbook state, reporting why the swap would stop filling.
+///
+/// Unlike `calc_out_amount_given_in`, running out of liquidity is not an error, allowing routers to decide
+/// whether to split the swap.
+///
+/// Errors if:
+/// 1. The provided denom pair is not supported by the orderbook
+/// 2. The target tick is invalid for the swap direction
+pub(crate) fn simulate_swap_exact_amount_in(
+    deps: Deps,
+    env: Env,
+    token_in: Coin,
+    token_out_denom: String,
+    target_tick: Option<i64>,
+    max_ticks_crossed: Option<u32>,
+) -> ContractResult<SimulateSwapExactAmountInResponse> {
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let direction = orderbook.direction_from_pair(token_in.denom, token_out_denom)?;
+
+    let tick_bound = match target_tick {
+        Some(target_tick) => target_tick,
+        None => get_market_tick_bound(deps.storage, direction)?,
+    };
+
+    let mut mock_order = MarketOrder::new(token_in.amount, direction, Addr::unchecked("querier"));
+    let order::PostMarketOrderState {
+        mut output,
+        stop_reason,
+        ..
+    } = order::simulate_market_order(
+        deps.storage,
+        &mut mock_order,
+        tick_bound,
+        max_ticks_crossed,
+        env.block.time,
+    )?;
+    output.amount = output
+        .amount
+        .checked_sub(orderbook.get_taker_fee(output.amount)?)?;
+
+    Ok(SimulateSwapExactAmountInResponse {
+        token_out: output,
+        token_in_remaining: mock_order.quantity,
+        stopped_reason: stop_reason,
+    })
+}
+
+/// Simulates a market order against the current orderbook state, reporting the prices it would
+/// fill at.
+///
+/// The average price is taken over the input consumed and the output net of the taker fee, so it
+/// reflects the effective price received. Prices are in the same terms as `tick_to_price`.
+/// Running out of liquidity is not an error and is instead reported through `fully_filled`.
+pub(crate) fn simulate_price_impact(
+    deps: Deps,
+    env: Env,
+    direction: OrderDirection,
+    amount: Uint128,
+) -> ContractResult<PriceImpactResponse> {
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let tick_bound = get_market_tick_bound(deps.storage, direction)?;
+
+    let mut mock_order = MarketOrder::new(amount, direction, Addr::unchecked("querier"));
+    let order::PostMarketOrderState {
+        mut output,
+        tick_updates,
+        stop_reason,
+        ..
+    } = order::simulate_market_order(
+        deps.storage,
+        &mut mock_order,
+        tick_bound,
+        None,
+        env.block.time,
+    )?;
+    output.amount = output
+        .amount
+        .checked_sub(orderbook.get_taker_fee(output.amount)?)?;
+
+    let input = Uint256::from_uint128(amount.checked_sub(mock_order.quantity)?);
+    let average_price = if input.is_zero() || output.amount.is_zero() {
+        None
+    } else {
+        Some(match direction {
+            // Asks swap base for quote, so the price is input over output
+            OrderDirection::Ask => Decimal256::checked_from_ratio(input, output.amount)?,
+            // Bids swap quote for base, so the price is output over input
+            OrderDirection::Bid => Decimal256::checked_from_ratio(output.amount, input)?,
+        })
+    };
+    let worst_price = tick_updates
+        .last()
+        .map(|(tick_id, _)| tick_to_price(*tick_id))
+        .transpose()?;
+
+    Ok(PriceImpactResponse {
+        average_price,
+        worst_price,
+        output,
+        fully_filled: stop_reason == MarketOrderStopReason::Filled,
+    })
+}
+
+/// Previews the resting orders a market order of `amount` would fill against, as
+/// `(tick_id, order_id, amount_consumed)` in fill order.
+///
+/// The walk reuses `simulate_market_order`, so it stops where a market order would: once `amount`
+/// is exhausted or the book runs out of liquidity. Each tick's fill is spread over its orders in
+/// ascending ETAS order. Amounts consumed are in the resting orders' denom, before the taker fee.
+pub(crate) fn fill_preview(
+    deps: Deps,
+    env: Env,
+    direction: OrderDirection,
+    amount: Uint128,
+) -> ContractResult<Vec<(i64, u64, Uint128)>> {
+    let tick_bound = get_market_tick_bound(deps.storage, direction)?;
+    let fill_direction = direction.opposite();
+
+    let mut mock_order = MarketOrder::new(amount, direction, Addr::unchecked("querier"));
+    let order::PostMarketOrderState { tick_updates, .. } = order::simulate_market_order(
+        deps.storage,
+        &mut mock_order,
+        tick_bound,
+        None,
+        env.block.time,
+    )?;
+
+    let mut preview = vec![];
+    for (tick_id, updated_tick) in tick_updates {
+        let liquidity_before = TICK_STATE
+            .load(deps.storage, tick_id)?
+            .get_values(fill_direction)
+            .total_amount_of_liquidity;
+        let tick_fill = liquidity_before.checked_sub(
+            updated_tick
+                .get_values(fill_direction)
+                .total_amount_of_liquidity,
+        )?;
+        let mut remaining = Uint128::try_from(tick_fill.to_uint_floor())?;
+
+        let mut resting_orders = vec![];
+        for maybe_order in
+            orders()
+                .prefix(tick_id)
+                .range(deps.storage, None, None, Order::Ascending)
+        {
+            let (_, resting_order) = maybe_order?;
+            // Expired orders are voided before the fill reaches them
+            let is_expired = resting_order
+                .expiry
+                .is_some_and(|expiry| expiry < env.block.time);
+            if resting_order.order_direction == fill_direction && !is_expired {
+                resting_orders.push(resting_order);
+            }
+        }
+        resting_orders.sort_by_key(|resting_order| resting_order.etas);
+
+        for resting_order in resting_orders {
+            if remaining.is_zero() {
+                break;
+            }
+            let (unclaimed_quantity, _) = order::get_unclaimed_fill(deps.storage, &resting_order)?;
+            let unfilled = resting_order.quantity.checked_sub(unclaimed_quantity)?;
+            let consumed = unfilled.min(remaining);
+            if consumed.is_zero() {
+                continue;
+            }
+            remaining = remaining.checked_sub(consumed)?;
+            preview.push((tick_id, resting_order.order_id, consumed));
+        }
+    }
+
+    Ok(preview)
+}
+
+/// Estimates the result of a `SwapExactAmountIn` sudo message without executing it.
+///
+/// The swap is matched against the current orderbook state exactly as the sudo handler would, including the taker
+/// fee, but no state is written. Aliased denoms are resolved to their canonical form, in which the swap itself must
+/// be sent. Orders that have expired but not yet been voided are skipped, as a real swap would void them first.
+///
+/// Errors if:
+/// 1. The provided swap fee does not match the orderbook's expected swap fee
+/// 2. The provided denom pair is not supported by the orderbook
+/// 3. The orderbook has insufficient liquidity to fill the swap
+pub(crate) fn estimate_swap_exact_amount_in(
+    deps: Deps,
+    env: Env,
+    token_in: Coin,
+    token_out_denom: String,
+    swap_fee: Decimal,
+) -> ContractResult<EstimateSwapExactAmountInResponse> {
+    ensure_swap_fee(swap_fee)?;
+
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let direction = orderbook.direction_from_pair(
+        resolve_denom(deps.storage, &token_in.denom)?,
+        resolve_denom(deps.storage, &token_out_denom)?,
+    )?;
+
+    let tick_bound = get_market_tick_bound(deps.storage, direction)?;
+
+    let mut mock_order = MarketOrder::new(token_in.amount, direction, Addr::unchecked("querier"));
+    let order::PostMarketOrderState {
+        mut output,
+        tick_updates,
+        ..
+    } = order::run_market_order_internal(
+        deps.storage,
+        &mut mock_order,
+        tick_bound,
+        env.block.time,
+    )?;
+    output.amount = output
+        .amount
+        .checked_sub(orderbook.get_taker_fee(output.amount)?)?;
+
+    Ok(EstimateSwapExactAmountInResponse {
+        token_out: output,
+        final_tick: tick_updates.last().map(|(tick_id, _)| *tick_id),
+    })
+}
+
+/// Estimates the input a swap must provide to receive exactly `token_out`, without executing it.
+///
+/// Ticks are filled in the same order as a market order, and the input for each fill is rounded up so the estimate
+/// is a safe upper bound: swapping the estimated input in yields at least the requested output. Enough is filled to
+/// also cover the taker fee. If the orderbook cannot provide the requested output, the most it can provide is
+/// returned with `sufficient_liquidity` unset rather than erroring.
+///
+/// Errors if:
+/// 1. The provided swap fee does not match the orderbook's expected swap fee
+/// 2. The provided denom pair is not supported by the orderbook
+pub(crate) fn estimate_swap_exact_amount_out(
+    deps: Deps,
+    env: Env,
+    token_out: Coin,
+    token_in_denom: String,
+    swap_fee: Decimal,
+) -> ContractResult<EstimateSwapExactAmountOutResponse> {
+    ensure_swap_fee(swap_fee)?;
+
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let direction = orderbook.direction_from_pair(
+        resolve_denom(deps.storage, &token_in_denom)?,
+        resolve_denom(deps.storage, &token_out.denom)?,
+    )?;
+    let fill_direction = direction.opposite();
+
+    // The taker fee is deducted from the output, so enough must be filled to cover it
+    let requested_output = Uint256::from_uint128(token_out.amount);
+    let mut required_output = requested_output;
+    if !orderbook.get_taker_fee(required_output)?.is_zero() {
+        required_output = Decimal256::from_ratio(required_output, Uint256::one())
+            .checked_div(Decimal256::one().checked_sub(orderbook.taker_fee)?)?
+            .to_uint_ceil();
+        while required_output.checked_sub(orderbook.get_taker_fee(required_output)?)?
+            < requested_output
+        {
+            required_output = required_output.checked_add(Uint256::one())?;
+        }
+    }
+
+    let tick_bound = get_market_tick_bound(deps.storage, direction)?;
+    let mut from_tick = match direction {
+        OrderDirection::Bid => orderbook.next_ask_tick,
+        OrderDirection::Ask => orderbook.next_bid_tick,
+    };
+
+    let mut filled = Uint256::zero();
+    let mut required_input = Uint256::zero();
+    while filled < required_output {
+        let Some((tick_id, tick_state)) =
+            next_populated_tick(deps.storage, fill_direction, from_tick, tick_bound)?
+        else {
+            break;
+        };
+        from_tick = match fill_direction {
+            OrderDirection::Ask => tick_id + 1,
+            OrderDirection::Bid => tick_id - 1,
+        };
+
+        // Expired orders are voided before a swap reaches them, so their liquidity is not available
+        let liquidity = tick_state
+            .get_values(fill_direction)
+            .total_amount_of_liquidity
+            .saturating_sub(order::expired_liquidity(
+                deps.storage,
+                tick_id,
+                fill_direction,
+                env.block.time,
+            )?)
+            .to_uint_floor();
+        let fill_amount = liquidity.min(required_output.checked_sub(filled)?);
+
+        required_input = required_input.checked_add(amount_to_value(
+            fill_direction,
+            Uint128::try_from(fill_amount)?,
+            tick_to_price(tick_id)?,
+            RoundingDirection::Up,
+        )?)?;
+        filled = filled.checked_add(fill_amount)?;
+    }
+
+    let sufficient_liquidity = filled >= required_output;
+    let output = if sufficient_liquidity {
+        requested_output
+    } else {
+        filled.checked_sub(orderbook.get_taker_fee(filled)?)?
+    };
+
+    Ok(EstimateSwapExactAmountOutResponse {
+        token_in: coin_u256(required_input, &orderbook.get_expected_denom(&direction)),
+        token_out: coin_u256(output, &orderbook.get_opposite_denom(&direction)),
+        sufficient_liquidity,
+    })
+}
+
 /// Calculates the total pool liquidity for the current orderbook state.
 ///
 /// Total pool liquidity is calculated by summing the total amount of liquidity in each active tick.
@@ -131,7 +477,7 @@ pub(crate) fn total_pool_liquidity(deps: Deps) -> ContractResult<GetTotalPoolLiq
     })
 }
 
-/// Returns all active ticks in the orderbook.
+/// Returns all active ticks in the orderbook, with hidden reserves left out of their liquidity.
 pub(crate) fn all_ticks(
     deps: Deps,
     start_from: Option<i64>,
@@ -155,7 +501,7 @@ pub(crate) fn all_ticks(
                 let (tick_id, tick_state) = maybe_tick.unwrap();
                 TickIdAndState {
                     tick_id,
-                    tick_state,
+                    tick_state: tick_state.displayed(),
                 }
             })
             .collect()
@@ -165,7 +511,7 @@ pub(crate) fn all_ticks(
                 let (tick_id, tick_state) = maybe_tick.unwrap();
                 TickIdAndState {
                     tick_id,
-                    tick_state,
+                    tick_state: tick_state.displayed(),
                 }
             })
             .collect()
@@ -176,6 +522,106 @@ pub(crate) fn all_ticks(
     })
 }
 
+/// Simulates claiming every claimable order of `owner`, as a batch claim sent by `claimer` would.
+///
+/// At most `max_orders` of the owner's orders are scanned per call, capped at `MAX_PAGE_SIZE`. Orders with nothing to claim are
+/// skipped. If the scan stopped before the owner's last order, `next_start_after` holds the cursor to
+/// resume from.
+pub(crate) fn simulate_claim_all(
+    deps: Deps,
+    env: Env,
+    owner: Addr,
+    claimer: Option<Addr>,
+    start_after: Option<(i64, u64)>,
+    max_orders: Option<u64>,
+) -> ContractResult<SimulateClaimAllResponse> {
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let claimer = claimer.unwrap_or(owner.clone());
+    let page_size = max_orders.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+
+    let owner_orders = get_orders_by_owner(
+        deps.storage,
+        FilterOwnerOrders::all(owner.clone()),
+        start_after,
+        None,
+        Some(page_size),
+    )?;
+
+    // Amounts owed keyed by (recipient, denom)
+    let mut payouts: BTreeMap<(String, String), Uint256> = BTreeMap::new();
+    let mut add_payout = |recipient: &Addr, denom: &str, amount: Uint256| -> ContractResult<()> {
+        if !amount.is_zero() {
+            let total = payouts
+                .entry((recipient.to_string(), denom.to_string()))
+                .or_default();
+            *total = total.checked_add(amount)?;
+        }
+        Ok(())
+    };
+
+    let mut claimed_orders = vec![];
+    for order in &owner_orders {
+        let (_, raw_amount) = order::get_unclaimed_fill(deps.storage, order)?;
+        if raw_amount.is_zero() {
+            continue;
+        }
+
+        let order::ClaimPayout {
+            amount,
+            bounty,
+            maker_fee_amount,
+        } = order::calculate_claim_payout(deps.storage, order, raw_amount)?;
+        let denom = orderbook.get_opposite_denom(&order.order_direction);
+
+        add_payout(&order.owner, &denom, amount)?;
+        add_payout(&claimer, &denom, bounty)?;
+        if !maker_fee_amount.is_zero() {
+            let maker_fee_recipient = MAKER_FEE_RECIPIENT
+                .may_load(deps.storage)?
+                .ok_or(ContractError::NoMakerFeeRecipient)?;
+            add_payout(&maker_fee_recipient, &denom, maker_fee_amount)?;
+        }
+
+        claimed_orders.push((order.tick_id, order.order_id));
+    }
+
+    // Group payouts into one message per recipient
+    let mut messages: Vec<MsgSend256> = vec![];
+    for ((recipient, denom), amount) in payouts {
+        let coin = coin_u256(amount, &denom);
+        match messages.last_mut() {
+            Some(msg) if msg.to_address == recipient => msg.amount.push(coin),
+            _ => messages.push(MsgSend256 {
+                from_address: env.contract.address.to_string(),
+                to_address: recipient,
+                amount: vec![coin],
+            }),
+        }
+    }
+
+    // When the owner is also the claimer their proceeds include any bounties
+    let proceeds = messages
+        .iter()
+        .find(|msg| msg.to_address == owner.as_str())
+        .map(|msg| msg.amount.clone())
+        .unwrap_or_default();
+
+    let next_start_after = if owner_orders.len() as u64 == page_size {
+        owner_orders
+            .last()
+            .map(|order| (order.tick_id, order.order_id))
+    } else {
+        None
+    };
+
+    Ok(SimulateClaimAllResponse {
+        orders: claimed_orders,
+        messages,
+        proceeds,
+        next_start_after,
+    })
+}
+
 /// Returns the current active status of the orderbook
 pub(crate) fn is_active(deps: Deps) -> ContractResult<bool> {
     let is_active = IS_ACTIVE.may_load(deps.storage)?;
@@ -190,21 +636,488 @@ pub(crate) fn get_swap_fee() -> ContractResult<GetSwapFeeResponse> {
 }
 
 /// Returns all active orders for a given address
+/// Returns an owner's orders in `(tick_id, order_id)` order, optionally restricted to one direction
+/// and to orders with a non-zero claimable amount.
+///
+/// Filtered out orders do not count towards `limit`, so the last returned order is always a stable
+/// cursor for the next page.
 pub(crate) fn orders_by_owner(
     deps: Deps,
     owner: Addr,
     start_from: Option<(i64, u64)>,
     end_at: Option<(i64, u64)>,
     limit: Option<u64>,
+    direction: Option<OrderDirection>,
+    only_claimable: bool,
 ) -> ContractResult<Vec<LimitOrder>> {
-    let orders = get_orders_by_owner(
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE) as usize;
+
+    let mut owner_orders = vec![];
+    for maybe_order in orders().idx.owner.prefix(owner).range(
+        deps.storage,
+        start_from.map(Bound::exclusive),
+        end_at.map(Bound::inclusive),
+        Order::Ascending,
+    ) {
+        if owner_orders.len() == limit {
+            break;
+        }
+
+        let (_, order) = maybe_order?;
+        if direction.is_some_and(|direction| direction != order.order_direction) {
+            continue;
+        }
+        if only_claimable && order::get_unclaimed_fill(deps.storage, &order)?.0.is_zero() {
+            continue;
+        }
+        owner_orders.push(order);
+    }
+
+    Ok(owner_orders)
+}
+
+/// Returns the distinct ticks the owner has orders on, with the directions of those orders.
+///
+/// Entries are sorted by tick, with bids before asks on the same tick. Both directions of a tick
+/// are always returned on the same page, so `limit` counts ticks rather than entries.
+pub(crate) fn owner_ticks(
+    deps: Deps,
+    owner: Addr,
+    direction: Option<OrderDirection>,
+    start_after: Option<i64>,
+    limit: Option<u64>,
+) -> ContractResult<Vec<(i64, OrderDirection)>> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE) as usize;
+    // Skip every order on the cursor tick
+    let min = start_after.map(|tick_id| Bound::exclusive((tick_id, u64::MAX)));
+
+    // Orders are keyed by (tick_id, order_id), so the owner's orders are iterated in tick order.
+    // Each entry holds (tick_id, has_bids, has_asks)
+    let mut ticks: Vec<(i64, bool, bool)> = vec![];
+    for maybe_order in
+        orders()
+            .idx
+            .owner
+            .prefix(owner)
+            .range(deps.storage, min, None, Order::Ascending)
+    {
+        let (_, order) = maybe_order?;
+        if direction.is_some_and(|direction| direction != order.order_direction) {
+            continue;
+        }
+
+        if ticks.last().map(|(tick_id, ..)| *tick_id) != Some(order.tick_id) {
+            if ticks.len() == limit {
+                break;
+            }
+            ticks.push((order.tick_id, false, false));
+        }
+        if let Some((_, has_bids, has_asks)) = ticks.last_mut() {
+            match order.order_direction {
+                OrderDirection::Bid => *has_bids = true,
+                OrderDirection::Ask => *has_asks = true,
+            }
+        }
+    }
+
+    Ok(ticks
+        .into_iter()
+        .flat_map(|(tick_id, has_bids, has_asks)| {
+            [
+                has_bids.then_some((tick_id, OrderDirection::Bid)),
+                has_asks.then_some((tick_id, OrderDirection::Ask)),
+            ]
+        })
+        .flatten()
+        .collect())
+}
+
+/// Returns the resting orders in one direction of a tick, ordered by fill priority
+pub(crate) fn tick_queue(
+    deps: Deps,
+    tick_id: i64,
+    direction: OrderDirection,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+) -> ContractResult<Vec<LimitOrder>> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    let orders = get_orders_by_tick(deps.storage, tick_id, direction, start_after, Some(limit))?;
+    Ok(orders)
+}
+
+/// Returns the amount `claim_order` would currently pay the owner of an order, without mutating state.
+///
+/// Unlike claiming, orders with nothing to claim return zero rather than erroring.
+pub(crate) fn claimable_amount(
+    deps: Deps,
+    tick_id: i64,
+    order_id: u64,
+) -> ContractResult<ClaimableResponse> {
+    let order = orders()
+        .may_load(deps.storage, &(tick_id, order_id))?
+        .ok_or(ContractError::OrderNotFound { tick_id, order_id })?;
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let denom = orderbook.get_opposite_denom(&order.order_direction);
+
+    let (_, raw_amount) = order::get_unclaimed_fill(deps.storage, &order)?;
+    if raw_amount.is_zero() {
+        return Ok(ClaimableResponse {
+            amount: Uint128::zero(),
+            denom,
+        });
+    }
+
+    let payout = order::calculate_claim_payout(deps.storage, &order, raw_amount)?;
+    Ok(ClaimableResponse {
+        amount: Uint128::try_from(payout.amount)?,
+        denom,
+    })
+}
+
+/// Returns the refund `cancel_limit` would currently produce for an order, in base for asks and
+/// quote for bids.
+///
+/// Claiming an order's fills reduces it to its unfilled remainder, which is what a cancel refunds.
+///
+/// Errors if:
+/// 1. The order does not exist, with `OrderNotFound`
+/// 2. Fills have reached the order without being claimed, with `CancelFilledOrder` as the cancel
+///    itself would
+pub(crate) fn cancel_refund_preview(
+    deps: Deps,
+    tick_id: i64,
+    order_id: u64,
+) -> ContractResult<Coin> {
+    let order = orders()
+        .may_load(deps.storage, &(tick_id, order_id))?
+        .ok_or(ContractError::OrderNotFound { tick_id, order_id })?;
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+
+    // Mirror the check `cancel_order` applies before refunding
+    let tick_values = TICK_STATE
+        .may_load(deps.storage, tick_id)?
+        .unwrap_or_default()
+        .get_values(order.order_direction);
+    ensure!(
+        tick_values.effective_total_amount_swapped <= order.etas,
+        ContractError::CancelFilledOrder
+    );
+
+    Ok(coin(
+        order.quantity.u128(),
+        orderbook.get_expected_denom(&order.order_direction),
+    ))
+}
+
+/// Returns the amounts `claim_order` would currently pay `owner` across their orders, aggregated per
+/// denom in ascending denom order.
+///
+/// At most `limit` of the owner's orders are scanned, defaulting to and capped at `MAX_PAGE_SIZE`.
+pub(crate) fn total_claimable(
+    deps: Deps,
+    owner: Addr,
+    limit: Option<u64>,
+) -> ContractResult<Vec<Coin>> {
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+
+    let owner_orders = get_orders_by_owner(
         deps.storage,
         FilterOwnerOrders::all(owner),
-        start_from,
-        end_at,
-        limit,
+        None,
+        None,
+        Some(limit),
     )?;
-    Ok(orders)
+
+    let mut totals: BTreeMap<String, Uint256> = BTreeMap::new();
+    for order in &owner_orders {
+        let (_, raw_amount) = order::get_unclaimed_fill(deps.storage, order)?;
+        if raw_amount.is_zero() {
+            continue;
+        }
+
+        let payout = order::calculate_claim_payout(deps.storage, order, raw_amount)?;
+        let total = totals
+            .entry(orderbook.get_opposite_denom(&order.order_direction))
+            .or_default();
+        *total = total.checked_add(payout.amount)?;
+    }
+
+    totals
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|(denom, amount)| Ok(coin(Uint128::try_from(amount)?.u128(), denom)))
+        .collect()
+}
+
+/// Returns `(tick_id, liquidity)` in ascending tick order for every tick between `min_tick` and
+/// `max_tick` (inclusive) displaying liquidity in the given direction, excluding hidden reserves.
+///
+/// Ticks without liquidity in the direction are skipped and do not count towards `limit`.
+pub(crate) fn ticks_in_range(
+    deps: Deps,
+    min_tick: i64,
+    max_tick: i64,
+    direction: OrderDirection,
+    start_after: Option<i64>,
+    limit: Option<u64>,
+) -> ContractResult<Vec<(i64, Decimal256)>> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_TICKS_IN_RANGE) as usize;
+
+    // Resume after the pagination cursor if it lies within the range
+    let min_bound = match start_after {
+        Some(start_after) if start_after >= min_tick => Bound::exclusive(start_after),
+        _ => Bound::inclusive(min_tick),
+    };
+
+    let mut ticks = vec![];
+    for maybe_tick in TICK_STATE.range(
+        deps.storage,
+        Some(min_bound),
+        Some(Bound::inclusive(max_tick)),
+        Order::Ascending,
+    ) {
+        let (tick_id, tick_state) = maybe_tick?;
+        let liquidity = tick_state.get_values(direction).displayed_liquidity();
+        if liquidity.is_zero() {
+            continue;
+        }
+
+        ticks.push((tick_id, liquidity));
+        if ticks.len() >= limit {
+            break;
+        }
+    }
+
+    Ok(ticks)
+}
+
+/// Returns the synced ETAS and CTT of one direction of a tick alongside the implied fill ratio.
+///
+/// Ticks that have never held an order report zero values and no ratio.
+pub(crate) fn tick_swap_progress(
+    deps: Deps,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<TickSwapProgressResponse> {
+    let tick_values = if TICK_STATE.has(deps.storage, tick_id) {
+        get_synced_tick_values(deps.storage, tick_id, direction)?
+    } else {
+        TickValues::default()
+    };
+
+    let fill_ratio = if tick_values.cumulative_total_value.is_zero() {
+        None
+    } else {
+        Some(
+            tick_values
+                .effective_total_amount_swapped
+                .checked_div(tick_values.cumulative_total_value)?,
+        )
+    };
+
+    Ok(TickSwapProgressResponse {
+        effective_total_amount_swapped: tick_values.effective_total_amount_swapped,
+        cumulative_total_value: tick_values.cumulative_total_value,
+        fill_ratio,
+    })
+}
+
+/// Returns the sum of cancelled amounts in one direction of a tick whose range starts at or below
+/// `target_etas`, i.e. the cancellations a tick sync at that ETAS would realize.
+///
+/// Ticks without a sumtree have no cancellations and return zero.
+pub(crate) fn cancelled_prefix_sum(
+    deps: Deps,
+    tick_id: i64,
+    direction: OrderDirection,
+    target_etas: Decimal256,
+) -> ContractResult<Decimal256> {
+    let tree = get_root_node_or_default(deps.storage, tick_id, direction)?;
+    get_prefix_sum(deps.storage, tree, target_etas)
+}
+
+/// Returns the total resting liquidity on each side of the orderbook.
+///
+/// The totals are maintained incrementally as orders are placed, cancelled and filled, so this
+/// does not iterate over ticks.
+pub(crate) fn book_liquidity(deps: Deps) -> ContractResult<BookLiquidityResponse> {
+    Ok(BookLiquidityResponse {
+        total_bid_liquidity: get_directional_liquidity(deps.storage, OrderDirection::Bid)?,
+        total_ask_liquidity: get_directional_liquidity(deps.storage, OrderDirection::Ask)?,
+    })
+}
+
+/// Returns how one-sided the orderbook is as `(bid_notional - ask_notional) / (bid_notional + ask_notional)`,
+/// ranging from -1 (only asks) to 1 (only bids).
+///
+/// Notional is measured in the quote denom: bid liquidity is already quote, while each tick's ask
+/// liquidity is converted at that tick's price. An empty orderbook returns zero.
+pub(crate) fn book_imbalance(deps: Deps) -> ContractResult<SignedDecimal256> {
+    let bid_notional = get_directional_liquidity(deps.storage, OrderDirection::Bid)?;
+    let ask_liquidity = get_directional_liquidity(deps.storage, OrderDirection::Ask)?;
+    if ask_liquidity.is_zero() && bid_notional.is_zero() {
+        return Ok(SignedDecimal256::zero());
+    }
+
+    let mut ask_notional = Decimal256::zero();
+    if !ask_liquidity.is_zero() {
+        for maybe_tick in TICK_STATE.range(deps.storage, None, None, Order::Ascending) {
+            let (tick_id, tick_state) = maybe_tick?;
+            let liquidity = tick_state
+                .get_values(OrderDirection::Ask)
+                .total_amount_of_liquidity;
+            if liquidity.is_zero() {
+                continue;
+            }
+            ask_notional =
+                ask_notional.checked_add(liquidity.checked_div(tick_to_price(tick_id)?)?)?;
+        }
+    }
+
+    let total = bid_notional.checked_add(ask_notional)?;
+    if total.is_zero() {
+        return Ok(SignedDecimal256::zero());
+    }
+
+    // The magnitude is at most one, so the sign is applied after dividing unsigned values
+    let (difference, negative) = if bid_notional >= ask_notional {
+        (bid_notional.checked_sub(ask_notional)?, false)
+    } else {
+        (ask_notional.checked_sub(bid_notional)?, true)
+    };
+    let ratio = SignedDecimal256::new(Int256::try_from(difference.checked_div(total)?.atomics())?);
+
+    Ok(if negative { -ratio } else { ratio })
+}
+
+/// Returns the price of the given tick.
+///
+/// Errors if the tick is outside of `[MIN_TICK, MAX_TICK]`.
+#[allow(clippy::manual_range_contains)]
+pub(crate) fn tick_price(tick_id: i64) -> ContractResult<Decimal256> {
+    ensure!(
+        tick_id >= MIN_TICK && tick_id <= MAX_TICK,
+        ContractError::InvalidTickId { tick_id }
+    );
+
+    tick_to_price(tick_id)
+}
+
+/// Returns the tick nearest to the given price, rounding down unless `round_up` is set.
+///
+/// Prices outside of the valid tick range are clamped to `MIN_TICK` or `MAX_TICK`.
+pub(crate) fn price_to_tick(price: Decimal256, round_up: bool) -> ContractResult<i64> {
+    let rounding_direction = if round_up {
+        RoundingDirection::Up
+    } else {
+        RoundingDirection::Down
+    };
+
+    tick_math::price_to_tick(price, rounding_direction)
+}
+
+/// Returns the nodes of a tick's cancellation sumtree in depth first order, starting at the root.
+///
+/// At most `max_nodes` nodes are returned. Ticks without a sumtree return no nodes.
+pub(crate) fn sumtree_nodes(
+    deps: Deps,
+    tick_id: i64,
+    direction: OrderDirection,
+    max_nodes: Option<u64>,
+) -> ContractResult<Vec<TreeNode>> {
+    let max_nodes = max_nodes
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .min(MAX_SUMTREE_NODES);
+    let Ok(root) = get_root_node(deps.storage, tick_id, direction) else {
+        return Ok(vec![]);
+    };
+
+    root.traverse_limited(deps.storage, max_nodes as usize)
+}
+
+/// Returns the node count, height and total cancelled value of a tick's cancellation sumtree.
+///
+/// Ticks without a sumtree report zeros.
+pub(crate) fn sumtree_info(
+    deps: Deps,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<SumtreeInfoResponse> {
+    let Ok(root) = get_root_node(deps.storage, tick_id, direction) else {
+        return Ok(SumtreeInfoResponse {
+            node_count: 0,
+            height: 0,
+            root_value: Decimal256::zero(),
+        });
+    };
+
+    Ok(SumtreeInfoResponse {
+        node_count: root.traverse_limited(deps.storage, usize::MAX)?.len() as u64,
+        height: root.get_height(deps.storage)?,
+        root_value: root.get_value(),
+    })
+}
+
+/// Returns the top of book for both sides of the orderbook.
+///
+/// The next tick pointers are not moved when their liquidity is cancelled, so each side is
+/// resolved to the first tick from its pointer that actually holds liquidity.
+pub(crate) fn spread(deps: Deps) -> ContractResult<SpreadResponse> {
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+
+    let best_bid_tick =
+        best_tick_with_liquidity(deps, OrderDirection::Bid, orderbook.next_bid_tick)?;
+    let best_ask_tick =
+        best_tick_with_liquidity(deps, OrderDirection::Ask, orderbook.next_ask_tick)?;
+    let best_bid_price = best_bid_tick.map(tick_to_price).transpose()?;
+    let best_ask_price = best_ask_tick.map(tick_to_price).transpose()?;
+
+    let mid_price = match (best_bid_price, best_ask_price) {
+        (Some(bid_price), Some(ask_price)) => Some(
+            bid_price
+                .checked_add(ask_price)?
+                .checked_div(Decimal256::from_ratio(2u128, 1u128))?,
+        ),
+        _ => None,
+    };
+
+    Ok(SpreadResponse {
+        best_bid_tick,
+        best_ask_tick,
+        best_bid_price,
+        best_ask_price,
+        mid_price,
+    })
+}
+
+/// Finds the best tick holding liquidity in the given direction, starting from `next_tick` and
+/// moving towards worse prices. Returns `None` if the direction holds no liquidity.
+fn best_tick_with_liquidity(
+    deps: Deps,
+    direction: OrderDirection,
+    next_tick: i64,
+) -> ContractResult<Option<i64>> {
+    if get_directional_liquidity(deps.storage, direction)?.is_zero() {
+        return Ok(None);
+    }
+
+    let (min_bound, max_bound, ordering) = match direction {
+        OrderDirection::Bid => (None, Some(Bound::inclusive(next_tick)), Order::Descending),
+        OrderDirection::Ask => (Some(Bound::inclusive(next_tick)), None, Order::Ascending),
+    };
+    for maybe_tick in TICK_STATE.range(deps.storage, min_bound, max_bound, ordering) {
+        let (tick_id, tick_state) = maybe_tick?;
+        if !tick_state
+            .get_values(direction)
+            .total_amount_of_liquidity
+            .is_zero()
+        {
+            return Ok(Some(tick_id));
+        }
+    }
+
+    Ok(None)
 }
 
 pub(crate) fn denoms(deps: Deps) -> ContractResult<DenomsResponse> {
@@ -214,3 +1127,358 @@ pub(crate) fn denoms(deps: Deps) -> ContractResult<DenomsResponse> {
         base_denom: orderbook.base_denom,
     })
 }
+
+/// Returns the average fill price realized by claims on a given order.
+///
+/// Errors if the order does not exist. Note that fully claimed orders are removed from state,
+/// so this is only available while some quantity remains on the order.
+pub(crate) fn order_avg_price(
+    deps: Deps,
+    tick_id: i64,
+    order_id: u64,
+) -> ContractResult<OrderAvgPriceResponse> {
+    let order = orders()
+        .may_load(deps.storage, &(tick_id, order_id))?
+        .ok_or(ContractError::OrderNotFound { tick_id, order_id })?;
+
+    Ok(OrderAvgPriceResponse {
+        avg_price: order.average_fill_price()?,
+        claimed_quantity: order.claimed_quantity,
+        claimed_output: order.claimed_output,
+    })
+}
+
+/// Reports how much of an order has been filled at its tick price and how much is still resting.
+///
+/// The filled quantity covers both claimed fills and fills that are yet to be claimed.
+pub(crate) fn order_fill_price(
+    deps: Deps,
+    tick_id: i64,
+    order_id: u64,
+) -> ContractResult<FillPriceResponse> {
+    let order = orders()
+        .may_load(deps.storage, &(tick_id, order_id))?
+        .ok_or(ContractError::OrderNotFound { tick_id, order_id })?;
+
+    let (unclaimed_quantity, _) = order::get_unclaimed_fill(deps.storage, &order)?;
+
+    Ok(FillPriceResponse {
+        filled_quantity: order.claimed_quantity.checked_add(unclaimed_quantity)?,
+        average_price: tick_to_price(tick_id)?,
+        remaining_quantity: order.quantity.checked_sub(unclaimed_quantity)?,
+    })
+}
+
+/// Compares the contract's bank balances against its tracked liabilities for each orderbook denom.
+///
+/// Liabilities are the resting liquidity of unfilled orders, the proceeds of filled orders that
+/// have not yet been claimed, failed payouts and deposited credit. A negative delta signals
+/// insolvency, while a positive delta is dust accumulated through rounding in the contract's favour.
+pub(crate) fn solvency_check(deps: Deps, env: Env) -> ContractResult<SolvencyCheckResponse> {
+    let orderbook = ORDERBOOK.load(deps.storage)?;
+
+    // Resting liquidity is held in the denom each direction deposits
+    let mut ask_liabilities =
+        get_directional_liquidity(deps.storage, OrderDirection::Ask)?.to_uint_ceil();
+    let mut bid_liabilities =
+        get_directional_liquidity(deps.storage, OrderDirection::Bid)?.to_uint_ceil();
+
+    // Unclaimed proceeds are owed in the denom opposite to each order's direction
+    for maybe_tick_id in TICK_STATE.keys(deps.storage, None, None, Order::Ascending) {
+        let tick_id = maybe_tick_id?;
+        for maybe_order in
+            orders()
+                .prefix(tick_id)
+                .range(deps.storage, None, None, Order::Ascending)
+        {
+            let (_, order) = maybe_order?;
+            let (_, unclaimed_output) = order::get_unclaimed_fill(deps.storage, &order)?;
+            match order.order_direction {
+                OrderDirection::Ask => {
+                    bid_liabilities = bid_liabilities.checked_add(unclaimed_output)?
+                }
+                OrderDirection::Bid => {
+                    ask_liabilities = ask_liabilities.checked_add(unclaimed_output)?
+                }
+            }
+        }
+    }
+
+    // Payouts that failed to send are held until withdrawn by their recipient
+    for maybe_failed in FAILED_PAYOUTS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, failed) = maybe_failed?;
+        for coin in failed {
+            if coin.denom == orderbook.base_denom {
+                ask_liabilities = ask_liabilities.checked_add(coin.amount)?;
+            } else if coin.denom == orderbook.quote_denom {
+                bid_liabilities = bid_liabilities.checked_add(coin.amount)?;
+            }
+        }
+    }
+
+    // Credit is held until it funds an order or is withdrawn
+    for maybe_credit in CREDIT_BALANCES.range(deps.storage, None, None, Order::Ascending) {
+        let ((_, denom), amount) = maybe_credit?;
+        let amount = Uint256::from_uint128(amount);
+        if denom == orderbook.base_denom {
+            ask_liabilities = ask_liabilities.checked_add(amount)?;
+        } else if denom == orderbook.quote_denom {
+            bid_liabilities = bid_liabilities.checked_add(amount)?;
+        }
+    }
+
+    let mut denoms = vec![];
+    for (direction, liabilities) in [
+        (OrderDirection::Ask, ask_liabilities),
+        (OrderDirection::Bid, bid_liabilities),
+    ] {
+        let denom = orderbook.get_expected_denom(&direction);
+        let balance = Uint256::from_uint128(
+            deps.querier
+                .query_balance(env.contract.address.clone(), denom.clone())?
+                .amount,
+        );
+        let delta = Int256::try_from(balance)?.checked_sub(Int256::try_from(liabilities)?)?;
+
+        denoms.push(DenomSolvency {
+            denom,
+            balance,
+            liabilities,
+            delta,
+        });
+    }
+
+    Ok(SolvencyCheckResponse { denoms })
+}
+
+/// Returns the orders with the largest claimable amounts across the orderbook, sorted descending
+/// by claimable output. Orders with equal claimable output are returned in (tick id, order id) order.
+///
+/// Every resting order is scanned, so this is intended for off-chain keepers selecting which
+/// orders to include in a batch claim.
+pub(crate) fn top_claimable(
+    deps: Deps,
+    limit: Option<u32>,
+) -> ContractResult<TopClaimableResponse> {
+    let limit = limit.unwrap_or(MAX_BATCH_CLAIM).min(MAX_BATCH_CLAIM) as usize;
+
+    let mut claimable = vec![];
+    for maybe_tick_id in TICK_STATE.keys(deps.storage, None, None, Order::Ascending) {
+        let tick_id = maybe_tick_id?;
+        for maybe_order in
+            orders()
+                .prefix(tick_id)
+                .range(deps.storage, None, None, Order::Ascending)
+        {
+            let (_, order) = maybe_order?;
+            let (claimable_quantity, claimable_output) =
+                order::get_unclaimed_fill(deps.storage, &order)?;
+            if claimable_quantity.is_zero() {
+                continue;
+            }
+
+            claimable.push(ClaimableOrder {
+                tick_id: order.tick_id,
+                order_id: order.order_id,
+                owner: order.owner,
+                claimable_quantity,
+                claimable_output,
+            });
+        }
+    }
+
+    // Only the top `limit` entries need to be ordered, so partition around the cutoff first
+    if claimable.len() > limit {
+        if limit == 0 {
+            claimable.clear();
+        } else {
+            claimable.select_nth_unstable_by(limit - 1, compare_claimable);
+            claimable.truncate(limit);
+        }
+    }
+    claimable.sort_unstable_by(compare_claimable);
+
+    Ok(TopClaimableResponse { orders: claimable })
+}
+
+/// Orders claimable entries by output descending, breaking ties by (tick id, order id) ascending.
+fn compare_claimable(a: &ClaimableOrder, b: &ClaimableOrder) -> std::cmp::Ordering {
+    b.claimable_output
+        .cmp(&a.claimable_output)
+        .then_with(|| (a.tick_id, a.order_id).cmp(&(b.tick_id, b.order_id)))
+}
+
+/// Returns ticks whose cancellation sumtrees have a root weight above `min_fragments`, so keepers
+/// can discover which ticks would benefit from compaction.
+///
+/// At most `limit` ticks are scanned per call, each costing a constant number of reads. If the scan
+/// stopped before the last tick, `next_start_after` holds the cursor to resume from.
+pub(crate) fn compaction_candidates(
+    deps: Deps,
+    min_fragments: u64,
+    start_after: Option<i64>,
+    limit: Option<u64>,
+) -> ContractResult<CompactionCandidatesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE) as usize;
+
+    let tick_ids = TICK_STATE
+        .keys(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<Result<Vec<i64>, _>>()?;
+
+    let mut candidates = vec![];
+    for &tick_id in &tick_ids {
+        for direction in [OrderDirection::Bid, OrderDirection::Ask] {
+            // Ticks without cancellations in this direction have no sumtree
+            let Some(root_id) = TREE.may_load(deps.storage, &(tick_id, &direction.to_string()))?
+            else {
+                continue;
+            };
+            let weight = NODES.load(deps.storage, &(tick_id, root_id))?.get_weight();
+            if weight > min_fragments {
+                candidates.push(CompactionCandidate {
+                    tick_id,
+                    direction,
+                    weight,
+                });
+            }
+        }
+    }
+
+    let next_start_after = if tick_ids.len() == limit {
+        tick_ids.last().copied()
+    } else {
+        None
+    };
+
+    Ok(CompactionCandidatesResponse {
+        candidates,
+        next_start_after,
+    })
+}
+
+/// Returns the last market order fill price and the traded volume over the rolling window ending at
+/// the current block time.
+pub(crate) fn market_stats(deps: Deps, env: Env) -> ContractResult<MarketStatsResponse> {
+    let Some(market_stats) = MARKET_STATS.may_load(deps.storage)? else {
+        return Ok(MarketStatsResponse {
+            last_price: None,
+            last_trade_time: None,
+            volume_24h: Uint256::zero(),
+        });
+    };
+
+    Ok(MarketStatsResponse {
+        last_price: Some(market_stats.last_price),
+        last_trade_time: Some(market_stats.last_trade_time),
+        volume_24h: market_stats.volume(env.block.time)?,
+    })
+}
+
+/// Compares the stored cumulative realized cancels of one direction of a tick against the value
+/// re-derived from its sumtree.
+///
+/// Errors if the tick has no state.
+pub(crate) fn realized_cancels(
+    deps: Deps,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<RealizedCancelsResponse> {
+    let recomputed = get_recomputed_realized_cancels(deps.storage, tick_id, direction)?;
+    let stored = TICK_STATE
+        .load(deps.storage, tick_id)?
+        .get_values(direction)
+        .cumulative_realized_cancels;
+
+    Ok(RealizedCancelsResponse {
+        stored,
+        recomputed,
+        in_sync: stored == recomputed,
+    })
+}
+
+/// Returns the unfilled liquidity ahead of an order in its tick's fill queue, alongside the total
+/// unfilled liquidity on the tick in the order's direction.
+///
+/// Errors with `OrderNotFound` if the order does not exist.
+pub(crate) fn queue_position(
+    deps: Deps,
+    tick_id: i64,
+    order_id: u64,
+) -> ContractResult<QueuePositionResponse> {
+    let order = orders()
+        .may_load(deps.storage, &(tick_id, order_id))?
+        .ok_or(ContractError::OrderNotFound { tick_id, order_id })?;
+
+    // Orders fill in ascending ETAS order, so only earlier orders can be ahead
+    let mut quantity_ahead = Decimal256::zero();
+    for maybe_order in orders()
+        .prefix(tick_id)
+        .range(deps.storage, None, None, Order::Ascending)
+    {
+        let (_, other) = maybe_order?;
+        if other.order_direction != order.order_direction || other.etas >= order.etas {
+            continue;
+        }
+        let (unclaimed_quantity, _) = order::get_unclaimed_fill(deps.storage, &other)?;
+        let unfilled = other.quantity.checked_sub(unclaimed_quantity)?;
+        quantity_ahead = quantity_ahead.checked_add(Decimal256::from_ratio(unfilled, 1u128))?;
+    }
+
+    let total_at_tick = TICK_STATE
+        .load(deps.storage, tick_id)?
+        .get_values(order.order_direction)
+        .total_amount_of_liquidity;
+
+    Ok(QueuePositionResponse {
+        quantity_ahead,
+        total_at_tick,
+    })
+}
+
+/// Returns the stored values of one direction of a tick without syncing it, so cancellations the
+/// next sync would realize are not yet reflected.
+///
+/// Ticks that were never written return default values.
+pub(crate) fn tick_values(
+    deps: Deps,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<TickValues> {
+    Ok(TICK_STATE
+        .may_load(deps.storage, tick_id)?
+        .unwrap_or_default()
+        .get_values(direction))
+}
+
+/// Returns the lifecycle status of an order.
+///
+/// An order that rests on its tick is fully filled once the tick has swapped past all of it, even
+/// if it has not been claimed. Removed orders are reported from the markers left when they were
+/// fully claimed or cancelled.
+pub(crate) fn order_status(deps: Deps, tick_id: i64, order_id: u64) -> ContractResult<OrderStatus> {
+    let key = (tick_id, order_id);
+    let Some(order) = orders().may_load(deps.storage, &key)? else {
+        if FILLED_ORDERS.has(deps.storage, key) {
+            return Ok(OrderStatus::FullyFilled);
+        }
+        if CANCELLED_ORDERS.has(deps.storage, key) {
+            return Ok(OrderStatus::Cancelled);
+        }
+        return Ok(OrderStatus::NotFound);
+    };
+
+    let amount_filled = order::get_amount_filled(deps.storage, &order)?;
+    Ok(if amount_filled == order.quantity {
+        OrderStatus::FullyFilled
+    } else if amount_filled.is_zero() && order.claimed_quantity.is_zero() {
+        OrderStatus::Open
+    } else {
+        OrderStatus::PartiallyFilled
+    })
+}
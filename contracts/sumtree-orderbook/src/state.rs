@@ -1,23 +1,64 @@
+use crate::constants::{MAX_TICK, MIN_TICK};
 use crate::error::ContractResult;
-use crate::types::{FilterOwnerOrders, LimitOrder, OrderDirection, Orderbook, TickState};
+use crate::types::{
+    Coin256, FilterOwnerOrders, LimitOrder, MarketStats, OrderDirection, Orderbook, PendingPayout,
+    TickState,
+};
 use crate::ContractError;
-use cosmwasm_std::{Addr, Decimal256, Order, StdResult, Storage};
+use cosmwasm_std::{Addr, Decimal256, Empty, Order, StdResult, Storage, Uint128, Uint256};
 use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
 // Counters for ID tracking
 pub const ORDER_ID: Item<u64> = Item::new("order_id");
 
 // Pagination constants for queries
-const DEFAULT_PAGE_SIZE: u64 = 100;
+pub(crate) const DEFAULT_PAGE_SIZE: u64 = 100;
+pub(crate) const MAX_PAGE_SIZE: u64 = 100;
 
 pub const ORDERBOOK: Item<Orderbook> = Item::new("orderbook");
 pub const TICK_STATE: Map<i64, TickState> = Map::new("tick_state");
+// Ticks holding liquidity in a direction, kept in sync by `save_tick_state`; Key: (direction, tick_id)
+pub const POPULATED_TICKS: Map<(&str, i64), Empty> = Map::new("populated_ticks");
 pub const DIRECTION_TOTAL_LIQUIDITY: Map<&str, Decimal256> = Map::new("direction_liquidity");
+// Configured (min, max) tick bounds, defaults to (MIN_TICK, MAX_TICK)
+pub const TICK_BOUNDS: Item<(i64, i64)> = Item::new("tick_bounds");
+// Orders with an expiry; Key: (tick_id, expiry in nanoseconds, order_id)
+pub const ORDER_EXPIRIES: Map<(i64, u64, u64), Empty> = Map::new("order_expiries");
+// Orders removed after being fully filled and claimed; Key: (tick_id, order_id)
+pub const FILLED_ORDERS: Map<(i64, u64), Empty> = Map::new("filled_orders");
+// Orders removed by a cancellation or expiry before being fully filled; Key: (tick_id, order_id)
+pub const CANCELLED_ORDERS: Map<(i64, u64), Empty> = Map::new("cancelled_orders");
+// Orders with a display quantity; Key: (tick_id, order_id)
+pub const ICEBERG_ORDERS: Map<(i64, u64), Empty> = Map::new("iceberg_orders");
+// Last trade and rolling volume of market orders against the orderbook
+pub const MARKET_STATS: Item<MarketStats> = Item::new("market_stats");
+// Refunds and claims whose bank send failed, withdrawable by the recipient; Key: recipient
+pub const FAILED_PAYOUTS: Map<&Addr, Vec<Coin256>> = Map::new("failed_payouts");
+// Funds deposited to place orders from, withdrawable by their owner; Key: (owner, denom)
+pub const CREDIT_BALANCES: Map<(&Addr, &str), Uint128> = Map::new("credit_balances");
+// Recoverable payouts awaiting their reply, in execution order. Empty between transactions.
+pub const PENDING_PAYOUTS: Item<Vec<PendingPayout>> = Item::new("pending_payouts");
+
+// Number of orders resting in each direction of a tick; Key: (tick_id, direction)
+pub const TICK_ORDER_COUNT: Map<(i64, &str), u32> = Map::new("tick_order_count");
+
+// Cumulative quote volume of sudo swaps attributed to each referrer; Key: referrer
+pub const REFERRER_VOLUME: Map<&Addr, Uint256> = Map::new("referrer_volume");
+
+// Alternative denoms accepted in place of the orderbook's denoms; Key: alias, Value: canonical denom
+pub const DENOM_ALIASES: Map<&str, String> = Map::new("denom_aliases");
 
 // Admin State
 pub const IS_ACTIVE: Item<bool> = Item::new("is_active");
 pub const MAKER_FEE: Item<Decimal256> = Item::new("maker_fee");
 pub const MAKER_FEE_RECIPIENT: Item<Addr> = Item::new("maker_fee_recipient");
+pub const DISALLOW_SAME_TICK_OPPOSING: Item<bool> = Item::new("disallow_same_tick_opposing");
+// When set, new orders and swaps are rejected while cancels and claims remain available
+pub const PAUSED: Item<bool> = Item::new("paused");
+
+// Diagnostic State
+// When set, sumtree insertions skip AVL rotations. Must never be enabled in production.
+pub const SUMTREE_SAFE_MODE: Item<bool> = Item::new("sumtree_safe_mode");
 
 pub struct OrderIndexes {
     // Index by owner; Generic types: MultiIndex<Index Key: owner, Input Data: LimitOrder, Map Key: (tick_id, order_id)>
@@ -52,6 +93,10 @@ pub fn orders() -> IndexedMap<'static, &'static (i64, u64), LimitOrder, OrderInd
     )
 }
 
+/// Allocates the next order ID from a single contract-wide counter.
+///
+/// IDs are strictly increasing and never recycled, including after cancellation or tick
+/// pruning, so a `(tick_id, order_id)` key always refers to at most one order.
 pub fn new_order_id(storage: &mut dyn Storage) -> Result<u64, ContractError> {
     let id = ORDER_ID.load(storage).unwrap_or_default();
     ORDER_ID.save(storage, &(id + 1))?;
@@ -62,15 +107,17 @@ pub fn new_order_id(storage: &mut dyn Storage) -> Result<u64, ContractError> {
 ///
 /// This function allows for filtering orders based on the owner's address, optionally further
 /// filtering by tick ID. It supports pagination through `min`, `max`, and `page_size` parameters.
+/// Orders are returned in ascending (tick_id, order_id) order, so passing the key of the last order
+/// of a page as `min` returns the next page without skipping or repeating any order.
 ///
 /// ## Arguments
 ///
 /// * `storage` - CosmWasm Storage struct
 /// * `filter` - Specifies how to filter orders based on the owner. Can be by all orders of the owner,
-/// by a specific book, or by a specific tick within a book.
+///   or by a specific tick.
 /// * `min` - An optional minimum bound (exclusive) for the order key (tick, order_id) to start the query.
-/// * `max` - An optional maximum bound (exclusive) for the order key to end the query.
-/// * `page_size` - An optional maximum number of orders to return. Limited by `MAX_PAGE_SIZE = 100` defaults to `DEFAULT_PAGE_SIZE = 50`.
+/// * `max` - An optional maximum bound (inclusive) for the order key to end the query.
+/// * `page_size` - An optional maximum number of orders to return. Defaults to `DEFAULT_PAGE_SIZE = 100` and capped at `MAX_PAGE_SIZE = 100`.
 ///
 /// ## Returns
 ///
@@ -82,7 +129,7 @@ pub fn get_orders_by_owner(
     max: Option<(i64, u64)>,
     page_size: Option<u64>,
 ) -> StdResult<Vec<LimitOrder>> {
-    let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE) as usize;
+    let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE) as usize;
     let min = min.map(Bound::exclusive);
     let max = max.map(Bound::inclusive);
 
@@ -95,14 +142,145 @@ pub fn get_orders_by_owner(
     };
 
     // Get orders based on pagination
-    let orders: Vec<LimitOrder> = iter
-        .range(storage, min, max, Order::Ascending)
+    iter.range(storage, min, max, Order::Ascending)
+        .take(page_size)
+        .map(|item| item.map(|(_, order)| order))
+        .collect()
+}
+
+/// Retrieves the resting orders in one direction of a tick in fill priority (ascending ETAS) order.
+///
+/// Order ids are assigned in placement order and each placement increases the tick's cumulative
+/// value, so iterating a tick's orders by id yields them in ascending ETAS order.
+///
+/// ## Arguments
+///
+/// * `storage` - CosmWasm Storage struct
+/// * `tick_id` - The tick to retrieve orders for
+/// * `direction` - Only orders in this direction are returned
+/// * `min` - An optional minimum bound (exclusive) for the order id to start the query.
+/// * `page_size` - An optional maximum number of orders to return. Defaults to `DEFAULT_PAGE_SIZE = 100`.
+pub fn get_orders_by_tick(
+    storage: &dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+    min: Option<u64>,
+    page_size: Option<u64>,
+) -> StdResult<Vec<LimitOrder>> {
+    let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE) as usize;
+    let min = min.map(Bound::exclusive);
+
+    orders()
+        .prefix(tick_id)
+        .range(storage, min, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref()
+                .map_or(true, |(_, order)| order.order_direction == direction)
+        })
         .take(page_size)
-        .filter_map(|item| item.ok())
-        .map(|(_, order)| order)
-        .collect();
+        .map(|item| item.map(|(_, order)| order))
+        .collect()
+}
+
+/// Finds the next tick holding liquidity in `direction`, walking from `from_tick` towards
+/// `tick_bound` (both inclusive).
+///
+/// Bids are walked in descending and asks in ascending tick order, i.e. from the best price to
+/// worse prices as a market order fills them. Only ticks indexed in `POPULATED_TICKS` are visited,
+/// so the walk never steps through empty or drained ticks.
+pub fn next_populated_tick(
+    storage: &dyn Storage,
+    direction: OrderDirection,
+    from_tick: i64,
+    tick_bound: i64,
+) -> ContractResult<Option<(i64, TickState)>> {
+    let (min_tick, max_tick, ordering) = match direction {
+        OrderDirection::Bid => (tick_bound, from_tick, Order::Descending),
+        OrderDirection::Ask => (from_tick, tick_bound, Order::Ascending),
+    };
+    if min_tick > max_tick {
+        return Ok(None);
+    }
+
+    let next_tick = POPULATED_TICKS
+        .prefix(&direction.to_string())
+        .keys(
+            storage,
+            Some(Bound::inclusive(min_tick)),
+            Some(Bound::inclusive(max_tick)),
+            ordering,
+        )
+        .next()
+        .transpose()?;
+
+    match next_tick {
+        Some(tick_id) => Ok(Some((tick_id, TICK_STATE.load(storage, tick_id)?))),
+        None => Ok(None),
+    }
+}
 
-    Ok(orders)
+/// Saves the state of a tick, indexing it in `POPULATED_TICKS` for each direction it holds
+/// liquidity in.
+pub fn save_tick_state(
+    storage: &mut dyn Storage,
+    tick_id: i64,
+    tick_state: &TickState,
+) -> ContractResult<()> {
+    TICK_STATE.save(storage, tick_id, tick_state)?;
+    for direction in [OrderDirection::Bid, OrderDirection::Ask] {
+        let direction_key = &direction.to_string();
+        if tick_state
+            .get_values(direction)
+            .total_amount_of_liquidity
+            .is_zero()
+        {
+            POPULATED_TICKS.remove(storage, (direction_key, tick_id));
+        } else {
+            POPULATED_TICKS.save(storage, (direction_key, tick_id), &Empty {})?;
+        }
+    }
+    Ok(())
+}
+
+/// Rebuilds `POPULATED_TICKS` from every stored tick, for state written before the index existed.
+///
+/// Returns the number of ticks indexed in either direction.
+pub(crate) fn backfill_populated_ticks(storage: &mut dyn Storage) -> ContractResult<u64> {
+    let ticks = TICK_STATE
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut indexed = 0;
+    for (tick_id, tick_state) in ticks {
+        save_tick_state(storage, tick_id, &tick_state)?;
+        if !tick_state.bid_values.total_amount_of_liquidity.is_zero()
+            || !tick_state.ask_values.total_amount_of_liquidity.is_zero()
+        {
+            indexed += 1;
+        }
+    }
+    Ok(indexed)
+}
+
+/// Resolves a denom to its canonical form, returning it unchanged if it is not a registered alias.
+pub fn resolve_denom(storage: &dyn Storage, denom: &str) -> ContractResult<String> {
+    Ok(DENOM_ALIASES
+        .may_load(storage, denom)?
+        .unwrap_or_else(|| denom.to_string()))
+}
+
+/// Ensures a denom that funds are sent or paid out in is not a registered alias.
+///
+/// Bank balances are tracked per denom, so funds received in an alias could never be paid back out
+/// in the canonical denom they are booked as. Aliases are only resolved for lookups.
+pub fn ensure_canonical_denom(storage: &dyn Storage, denom: &str) -> ContractResult<()> {
+    if let Some(canonical) = DENOM_ALIASES.may_load(storage, denom)? {
+        return Err(ContractError::AliasedDenom {
+            alias: denom.to_string(),
+            canonical,
+        });
+    }
+    Ok(())
 }
 
 /// Gets the currently stored total liquidity for the specified `OrderDirection`.
@@ -159,6 +337,81 @@ pub fn subtract_directional_liquidity(
     Ok(())
 }
 
+/// Returns the number of orders resting in the specified direction of a tick.
+pub fn get_tick_order_count(
+    storage: &dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<u32> {
+    Ok(TICK_ORDER_COUNT
+        .may_load(storage, (tick_id, &direction.to_string()))?
+        .unwrap_or_default())
+}
+
+/// Records a new order resting in the specified direction of a tick.
+pub fn increment_tick_order_count(
+    storage: &mut dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<()> {
+    let count = get_tick_order_count(storage, tick_id, direction)?;
+    TICK_ORDER_COUNT.save(storage, (tick_id, &direction.to_string()), &(count + 1))?;
+    Ok(())
+}
+
+/// Records the removal of an order from the specified direction of a tick.
+///
+/// Saturates at zero, as orders placed before counts were tracked were never recorded.
+pub fn decrement_tick_order_count(
+    storage: &mut dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<()> {
+    let direction_key = &direction.to_string();
+    let count = get_tick_order_count(storage, tick_id, direction)?.saturating_sub(1);
+    if count == 0 {
+        TICK_ORDER_COUNT.remove(storage, (tick_id, direction_key));
+    } else {
+        TICK_ORDER_COUNT.save(storage, (tick_id, direction_key), &count)?;
+    }
+    Ok(())
+}
+
+/// Adds `volume` to the cumulative volume attributed to `referrer`, returning the new total.
+pub fn add_referrer_volume(
+    storage: &mut dyn Storage,
+    referrer: &Addr,
+    volume: Uint256,
+) -> ContractResult<Uint256> {
+    let total = REFERRER_VOLUME
+        .may_load(storage, referrer)?
+        .unwrap_or_default()
+        .checked_add(volume)?;
+    REFERRER_VOLUME.save(storage, referrer, &total)?;
+    Ok(total)
+}
+
+/// Returns the configured global (min, max) tick bounds
+///
+/// If none are set defaults to `(MIN_TICK, MAX_TICK)`
+pub fn get_tick_bounds(storage: &dyn Storage) -> ContractResult<(i64, i64)> {
+    let bounds = TICK_BOUNDS.may_load(storage)?;
+    Ok(bounds.unwrap_or((MIN_TICK, MAX_TICK)))
+}
+
+/// Returns the furthest tick a market order in `direction` can fill against under the configured
+/// tick bounds: the max tick for bids and the min tick for asks.
+pub fn get_market_tick_bound(
+    storage: &dyn Storage,
+    direction: OrderDirection,
+) -> ContractResult<i64> {
+    let (min_tick, max_tick) = get_tick_bounds(storage)?;
+    Ok(match direction {
+        OrderDirection::Bid => max_tick,
+        OrderDirection::Ask => min_tick,
+    })
+}
+
 /// Returns the current maker fee
 ///
 /// If none is set defaults to `Decimal256::zero()`
@@ -1,24 +1,35 @@
 use cosmwasm_std::{
     coin, ensure, entry_point, to_json_binary, BankMsg, Coin, Decimal, Deps, DepsMut, Env,
-    Response, SubMsg, Uint128, Uint256,
+    Response, Storage, SubMsg, Uint128, Uint256,
 };
 
 use crate::{
     auth,
-    constants::{EXPECTED_SWAP_FEE, MAX_TICK, MIN_TICK},
+    constants::EXPECTED_SWAP_FEE,
     error::ContractResult,
     msg::{SudoMsg, SwapExactAmountInResponseData},
-    order::run_market_order,
-    state::{IS_ACTIVE, ORDERBOOK},
+    order::{clear_fill_path, run_market_order, simulate_market_order, PostMarketOrderState},
+    orderbook::set_tick_bounds,
+    payout,
+    state::{
+        add_referrer_volume, ensure_canonical_denom, get_market_tick_bound, get_tick_bounds,
+        IS_ACTIVE, ORDERBOOK, PAUSED, SUMTREE_SAFE_MODE,
+    },
+    tick::recompute_realized_cancels,
     types::{
-        coin_u256, Coin256, MarketOrder, MsgSend256, OrderDirection, REPLY_ID_REFUND,
-        REPLY_ID_SUDO_SWAP_EXACT_IN,
+        coin_u256, Coin256, MarketOrder, MarketOrderStopReason, MsgSend256, OrderDirection,
+        REPLY_ID_REFUND, REPLY_ID_SUDO_SWAP_EXACT_IN, REPLY_ID_TAKER_FEE,
     },
     ContractError,
 };
 
-#[cfg_attr(not(feature = "imported"), entry_point)]
-pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(mut deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
+    let response = dispatch_sudo(deps.branch(), env, msg)?;
+    payout::track_recoverable_payouts(deps.storage, response, false)
+}
+
+fn dispatch_sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
     // Ensure orderbook is active
     ensure_is_active(deps.as_ref())?;
 
@@ -29,6 +40,7 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
             token_out_denom,
             token_out_min_amount,
             swap_fee,
+            referrer,
         } => dispatch_swap_exact_amount_in(
             deps,
             env,
@@ -38,6 +50,9 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
             token_out_min_amount,
             swap_fee,
             None,
+            false,
+            false,
+            referrer,
         ),
         SudoMsg::SwapToTick {
             sender,
@@ -46,6 +61,8 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
             token_out_min_amount,
             swap_fee,
             target_tick,
+            fill_or_kill,
+            stp,
         } => dispatch_swap_exact_amount_in(
             deps,
             env,
@@ -55,6 +72,9 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
             token_out_min_amount,
             swap_fee,
             Some(target_tick),
+            fill_or_kill.unwrap_or_default(),
+            stp.unwrap_or_default(),
+            None,
         ),
         SudoMsg::SwapExactAmountOut {
             sender,
@@ -62,6 +82,7 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
             token_in_max_amount,
             token_out,
             swap_fee,
+            referrer,
         } => dispatch_swap_exact_amount_out(
             deps,
             sender,
@@ -69,6 +90,7 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
             token_in_max_amount,
             token_out,
             swap_fee,
+            referrer,
         ),
         // -- Sudo admin actions --
 
@@ -89,6 +111,31 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
 
         // -- Active Switch --
         SudoMsg::SetActive { active } => set_active(deps, active),
+
+        // -- Tick Bounds --
+        SudoMsg::SetTickBounds { min_tick, max_tick } => {
+            set_tick_bounds(deps.storage, min_tick, max_tick)?;
+            Ok(Response::default().add_attributes(vec![
+                ("method", "sudo_set_tick_bounds"),
+                ("min_tick", &min_tick.to_string()),
+                ("max_tick", &max_tick.to_string()),
+            ]))
+        }
+
+        // -- Diagnostics --
+        SudoMsg::SetSumtreeSafeMode { enabled } => set_sumtree_safe_mode(deps, enabled),
+        SudoMsg::RecomputeRealizedCancels { tick_id, direction } => {
+            let tick_values = recompute_realized_cancels(deps.storage, tick_id, direction)?;
+            Ok(Response::default().add_attributes(vec![
+                ("method", "sudo_recompute_realized_cancels"),
+                ("tick_id", &tick_id.to_string()),
+                ("direction", &direction.to_string()),
+                (
+                    "cumulative_realized_cancels",
+                    &tick_values.cumulative_realized_cancels.to_string(),
+                ),
+            ]))
+        }
     }
 }
 
@@ -97,6 +144,9 @@ pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult<Response> {
 /// Order direction is automatically determined by the token in/token out pairing.
 ///
 /// Errors if the amount provided by the swap does not meet the `token_out_min_amount` or if there is no orderbook for the provided pair.
+/// Without a `target_tick`, also errors if the orderbook cannot swap the full `token_in`.
+///
+/// If a `referrer` is provided the swap's quote volume is added to its `REFERRER_VOLUME`.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn dispatch_swap_exact_amount_in(
     deps: DepsMut,
@@ -107,23 +157,45 @@ pub(crate) fn dispatch_swap_exact_amount_in(
     token_out_min_amount: Uint128,
     swap_fee: Decimal,
     target_tick: Option<i64>,
+    fill_or_kill: bool,
+    stp: bool,
+    referrer: Option<String>,
 ) -> ContractResult<Response> {
+    ensure_not_paused(deps.storage)?;
+
     // Ensure the provided swap fee matches what is expected
     ensure_swap_fee(swap_fee)?;
 
-    let token_in_denom = token_in.denom.clone();
+    let referrer = referrer
+        .map(|referrer| deps.api.addr_validate(&referrer))
+        .transpose()?;
 
-    // Ensure in and out denoms are not equal
-    ensure!(
-        token_in_denom != token_out_denom,
-        ContractError::InvalidSwap {
-            error: "Input and output denoms cannot be the same".to_string()
-        }
-    );
+    // Funds move in both denoms, so neither may be a registered alias
+    ensure_canonical_denom(deps.storage, &token_in.denom)?;
+    ensure_canonical_denom(deps.storage, &token_out_denom)?;
+    let token_in_denom = token_in.denom.clone();
 
     // Load the orderbook for the provided pair
     let orderbook = ORDERBOOK.load(deps.storage)?;
 
+    // A swap from an orderbook denom into itself must instead output the opposite orderbook denom.
+    // Denoms outside the orderbook fall through to the pair check below
+    if token_in_denom == token_out_denom {
+        let expected = if token_in_denom == orderbook.base_denom {
+            Some(&orderbook.quote_denom)
+        } else if token_in_denom == orderbook.quote_denom {
+            Some(&orderbook.base_denom)
+        } else {
+            None
+        };
+        if let Some(expected) = expected {
+            return Err(ContractError::SwapWrongDenom {
+                expected: expected.clone(),
+                received: token_out_denom,
+            });
+        }
+    }
+
     // Determine order direction based on token in/out denoms
     let order_direction = orderbook.direction_from_pair(token_in_denom, token_out_denom.clone())?;
 
@@ -134,15 +206,63 @@ pub(crate) fn dispatch_swap_exact_amount_in(
         deps.api.addr_validate(&sender)?,
     );
 
+    // A target tick must lie within the configured global bounds
+    if let Some(target_tick) = target_tick {
+        let (min_tick, max_tick) = get_tick_bounds(deps.storage)?;
+        ensure!(
+            target_tick >= min_tick && target_tick <= max_tick,
+            ContractError::InvalidTickId {
+                tick_id: target_tick
+            }
+        );
+    }
+
     // Market orders always run until either the input is filled or the orderbook is exhausted.
-    let tick_bound = target_tick.unwrap_or(match order_direction {
-        OrderDirection::Bid => MAX_TICK,
-        OrderDirection::Ask => MIN_TICK,
-    });
+    let tick_bound = match target_tick {
+        Some(target_tick) => target_tick,
+        None => get_market_tick_bound(deps.storage, order_direction)?,
+    };
+
+    // Expired orders must not be filled, so clear them from the ticks this order reaches. With
+    // self-trade prevention the sender's own resting orders are cancelled rather than filled
+    let voided_refund_msgs = clear_fill_path(
+        deps.storage,
+        &order,
+        tick_bound,
+        env.block.time,
+        stp.then_some(&order.owner),
+    )?;
+
+    // Without a target tick the CW pool interface expects the full input to be swapped, so a swap
+    // that would stop short of filling is rejected rather than partially filled and refunded
+    if target_tick.is_none() {
+        let mut simulated_order = order.clone();
+        let PostMarketOrderState { stop_reason, .. } = simulate_market_order(
+            deps.storage,
+            &mut simulated_order,
+            tick_bound,
+            None,
+            env.block.time,
+        )?;
+        ensure!(
+            stop_reason == MarketOrderStopReason::Filled,
+            ContractError::InsufficientLiquidityForSwap {
+                requested: token_in.amount,
+                available: token_in.amount.checked_sub(simulated_order.quantity)?,
+            }
+        );
+    }
 
     // Run market order against orderbook
-    let (output, bank_msg) =
-        run_market_order(deps.storage, env.contract.address, &mut order, tick_bound)?;
+    let (output, bank_msg, fee_msg, fill_events) = run_market_order(
+        deps.storage,
+        env.contract.address,
+        env.block.time,
+        &mut order,
+        tick_bound,
+        fill_or_kill,
+        Some(token_out_min_amount),
+    )?;
 
     // Validate the output message against the order
     let MsgSend256 { amount, .. } = bank_msg.clone();
@@ -156,10 +276,37 @@ pub(crate) fn dispatch_swap_exact_amount_in(
         output_amt,
     )?;
 
+    // Attribute the swap's volume to the referrer, denominated in the quote asset as in market stats
+    let mut referrer_attrs = vec![];
+    if let Some(referrer) = referrer {
+        let volume = match order_direction {
+            OrderDirection::Bid => {
+                Uint256::from_uint128(token_in.amount.checked_sub(order.quantity)?)
+            }
+            OrderDirection::Ask => match &fee_msg {
+                Some(fee_msg) => output.checked_add(
+                    fee_msg
+                        .amount
+                        .first()
+                        .map(|fee| fee.amount)
+                        .unwrap_or_default(),
+                )?,
+                None => output,
+            },
+        };
+        let total_volume = add_referrer_volume(deps.storage, &referrer, volume)?;
+        referrer_attrs.push(("referrer", referrer.to_string()));
+        referrer_attrs.push(("referred_volume", volume.to_string()));
+        referrer_attrs.push(("referrer_total_volume", total_volume.to_string()));
+    }
+
     let mut bank_msgs = vec![SubMsg::reply_on_error(
         bank_msg,
         REPLY_ID_SUDO_SWAP_EXACT_IN,
     )];
+    if let Some(fee_msg) = fee_msg {
+        bank_msgs.push(SubMsg::reply_on_error(fee_msg, REPLY_ID_TAKER_FEE));
+    }
 
     if !order.quantity.is_zero() {
         bank_msgs.push(SubMsg::reply_on_error(
@@ -170,6 +317,7 @@ pub(crate) fn dispatch_swap_exact_amount_in(
             REPLY_ID_REFUND,
         ));
     }
+    bank_msgs.extend(voided_refund_msgs);
 
     Ok(Response::default()
         .add_submessages(bank_msgs)
@@ -181,6 +329,8 @@ pub(crate) fn dispatch_swap_exact_amount_in(
             ("token_out_min_amount", &token_out_min_amount.to_string()),
             ("output_quantity", &output.to_string()),
         ])
+        .add_attributes(referrer_attrs)
+        .add_events(fill_events)
         .set_data(to_json_binary(&SwapExactAmountInResponseData {
             token_out_amount: output,
         })?))
@@ -194,6 +344,7 @@ pub(crate) fn dispatch_swap_exact_amount_out(
     _token_in_max_amount: Uint128,
     _token_out: Coin,
     _swap_fee: Decimal,
+    _referrer: Option<String>,
 ) -> ContractResult<Response> {
     unimplemented!();
 }
@@ -211,21 +362,19 @@ pub(crate) fn validate_output_amount(
     // Generated amount must be less than or equal to the maximum allowed amount
     ensure!(
         input.amount <= max_in_amount,
-        ContractError::InvalidSwap {
-            error: format!(
-                "Exceeded max swap amount: expected {max_in_amount} received {}",
-                input.amount
-            )
+        ContractError::SwapAboveMax {
+            max: max_in_amount,
+            received: input.amount,
+            denom: input.denom.clone(),
         }
     );
     // Generated amount must be more than or equal to the minimum allowed amount
     ensure!(
         output.amount >= min_out_amount,
-        ContractError::InvalidSwap {
-            error: format!(
-                "Did not meet minimum swap amount: expected {min_out_amount} received {}",
-                output.amount
-            )
+        ContractError::SwapBelowMin {
+            min: min_out_amount,
+            received: output.amount,
+            denom: output.denom.clone(),
         }
     );
 
@@ -243,10 +392,9 @@ pub(crate) fn validate_output_amount(
 pub(crate) fn ensure_swap_fee(fee: Decimal) -> ContractResult<()> {
     ensure!(
         fee == EXPECTED_SWAP_FEE,
-        ContractError::InvalidSwap {
-            error: format!(
-                "Provided swap fee does not match: expected {EXPECTED_SWAP_FEE} received {fee}"
-            )
+        ContractError::SwapFeeMismatch {
+            expected: EXPECTED_SWAP_FEE,
+            received: fee,
         }
     );
     Ok(())
@@ -264,6 +412,34 @@ pub(crate) fn set_active(deps: DepsMut, active: bool) -> ContractResult<Response
     ]))
 }
 
+/// Toggles sumtree safe mode, in which AVL rotations are skipped on insertion.
+///
+/// This is a diagnostic tool for isolating whether a state bug originates in rotation logic
+/// or in accounting. It must never be left enabled in production.
+pub(crate) fn set_sumtree_safe_mode(deps: DepsMut, enabled: bool) -> ContractResult<Response> {
+    SUMTREE_SAFE_MODE.save(deps.storage, &enabled)?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("method", "sudo_set_sumtree_safe_mode"),
+        ("enabled", &enabled.to_string()),
+        (
+            "warning",
+            "diagnostic only: sumtree rotations disabled, do not use in production",
+        ),
+    ]))
+}
+
+/// Asserts that trading on the orderbook is not paused.
+///
+/// Errors if the `PAUSED` switch is true. If `PAUSED` is empty then it defaults to false.
+pub(crate) fn ensure_not_paused(storage: &dyn Storage) -> ContractResult<()> {
+    let paused = PAUSED.may_load(storage)?.unwrap_or_default();
+
+    ensure!(!paused, ContractError::Paused);
+
+    Ok(())
+}
+
 /// Asserts that the orderbook is currently active.
 ///
 /// Errors if the `IS_ACTIVE` switch is false.
@@ -11,7 +11,10 @@ use cosmwasm_std::Uint256;
 use cosmwasm_std::{ensure, Decimal256, Storage};
 use cw_storage_plus::Map;
 
-use crate::{error::ContractResult, sumtree::tree::TREE, types::OrderDirection, ContractError};
+use crate::{
+    error::ContractResult, state::SUMTREE_SAFE_MODE, sumtree::tree::TREE, types::OrderDirection,
+    ContractError,
+};
 
 pub const NODES: Map<&(i64, u64), TreeNode> = Map::new("nodes");
 pub const NODE_ID_COUNTER: Map<&i64, u64> = Map::new("node_id");
@@ -172,10 +175,12 @@ impl TreeNode {
     ///
     /// For `Internal` nodes, this is the maximum value of the associated range.
     /// For `Leaf` nodes, this is the sum of the `value` and `etas` fields.
-    pub fn get_max_range(&self) -> Decimal256 {
+    ///
+    /// Errors if a leaf's range end overflows.
+    pub fn get_max_range(&self) -> ContractResult<Decimal256> {
         match self.node_type {
-            NodeType::Internal { range, .. } => range.1,
-            NodeType::Leaf { value, etas } => value.checked_add(etas).unwrap(),
+            NodeType::Internal { range, .. } => Ok(range.1),
+            NodeType::Leaf { value, etas } => Ok(value.checked_add(etas)?),
         }
     }
 
@@ -211,8 +216,8 @@ impl TreeNode {
     }
 
     /// Determines if the node's minimum range is less than the maximum range of the given left node.
-    pub fn in_or_below_range(&self, node: TreeNode) -> bool {
-        self.get_min_range() < node.get_max_range()
+    pub fn in_or_below_range(&self, node: TreeNode) -> ContractResult<bool> {
+        Ok(self.get_min_range() < node.get_max_range()?)
     }
 
     /// Determines if the node's minimum range is greater than or equal to the minimum range of the given right node.
@@ -221,23 +226,23 @@ impl TreeNode {
     }
 
     /// Determines if the current node's max is less than the min of the provided node
-    pub fn below_range(&self, node: TreeNode) -> bool {
+    pub fn below_range(&self, node: TreeNode) -> ContractResult<bool> {
         // This comparison is inclusive because the left node in an adjacent pair is considered strictly below
         // the right node.
         //
         // Since range bounds can overlap, this comparison is inclusive for both below and above range
         // checks.
-        self.get_max_range() <= node.get_min_range()
+        Ok(self.get_max_range()? <= node.get_min_range())
     }
 
     /// Determines if the current node's min is greater than or equal to the max of the provided node
-    pub fn above_range(&self, node: TreeNode) -> bool {
+    pub fn above_range(&self, node: TreeNode) -> ContractResult<bool> {
         // This comparison is inclusive because the right node in an adjacent pair is considered strictly above
         // the left node.
         //
         // Since range bounds can overlap, this comparison is inclusive for both below and above range
         // checks.
-        self.get_min_range() >= node.get_max_range()
+        Ok(self.get_min_range() >= node.get_max_range()?)
     }
 
     pub fn set_value(&mut self, value: Decimal256) -> ContractResult<()> {
@@ -313,17 +318,17 @@ impl TreeNode {
         // Calculate new range
         let (min, max) = if left_exists && !right_exists {
             let left = maybe_left.clone().unwrap();
-            (left.get_min_range(), left.get_max_range())
+            (left.get_min_range(), left.get_max_range()?)
         } else if right_exists && !left_exists {
             let right = maybe_right.clone().unwrap();
-            (right.get_min_range(), right.get_max_range())
+            (right.get_min_range(), right.get_max_range()?)
         } else {
             let left = maybe_left.clone().unwrap();
             let right = maybe_right.clone().unwrap();
 
             (
                 left.get_min_range().min(right.get_min_range()),
-                left.get_max_range().max(right.get_max_range()),
+                left.get_max_range()?.max(right.get_max_range()?),
             )
         };
         self.set_min_range(min)?;
@@ -360,15 +365,19 @@ impl TreeNode {
     /// If the node is internal or the current node is a leaf an error is returned.
     ///
     /// If the node is a leaf it will be inserted by the following priority:
+    ///
     /// Internal conditions:
     /// 1. New node fits in left internal range, insert left
     /// 2. New node fits in right internal range, insert right
     /// 3. Both left and right are internal, node does not fit in either, insert left
+    ///
     /// Splitting conditions:
     /// 4. New node does not fit in right range (or is less than right.min if right is a leaf) and left node is a leaf, split left
     /// 5. New node does not fit in left range (or is greater than or equal to left.max when left is a leaf) and right node is a leaf, split right
+    ///
     /// Reordering conditions:
     /// 6. Right node is empty, new node is lower than left node, move left node to right and insert left
+    ///
     /// Empty conditions:
     /// 7. Left node is empty, insert left
     /// 8. Right is empty, insert right
@@ -399,27 +408,29 @@ impl TreeNode {
         );
 
         // Either node can be internal
-        let is_left_internal = maybe_left.clone().map_or(false, |l| l.is_internal());
-        let is_right_internal = maybe_right.clone().map_or(false, |r| r.is_internal());
+        let is_left_internal = maybe_left.clone().is_some_and(|l| l.is_internal());
+        let is_right_internal = maybe_right.clone().is_some_and(|r| r.is_internal());
 
         // Either node can be a leaf
         let left_is_leaf = maybe_left.is_some() && !is_left_internal;
         let right_is_leaf = maybe_right.is_some() && !is_right_internal;
 
         // Check if new node is lower than the left node's max, false if node does not exist
-        let is_in_left_range = maybe_left
-            .clone()
-            .map_or(false, |left| new_node.in_or_below_range(left));
+        let is_in_left_range = match maybe_left.clone() {
+            Some(left) => new_node.in_or_below_range(left)?,
+            None => false,
+        };
         // Check if new node is higher than the right node's min, false if node does not exist
         let is_in_right_range = maybe_right
             .clone()
-            .map_or(false, |right| new_node.in_or_above_range(right));
+            .is_some_and(|right| new_node.in_or_above_range(right));
 
         // Check if new node's max is strictly less than left node's min
-        let is_less_than_left = maybe_left
-            .clone()
-            // As node ranges may overlap on equality comparisons (i.e. left_node.max == right_node.min) we check strictly here
-            .map_or(false, |left| new_node.below_range(left));
+        // As node ranges may overlap on equality comparisons (i.e. left_node.max == right_node.min) we check strictly here
+        let is_less_than_left = match maybe_left.clone() {
+            Some(left) => new_node.below_range(left)?,
+            None => false,
+        };
 
         // Internal conditions
         // One node is internal and the new node fits in its range, or both are internal and the new node does not fit in either range
@@ -548,10 +559,10 @@ impl TreeNode {
         let accumulator = self.get_value().checked_add(new_node.get_value())?;
 
         // Determine which node goes to which side, maintaining order by ETAS
-        let (new_left, new_right) = if self.below_range(new_node.clone()) {
+        let (new_left, new_right) = if self.below_range(new_node.clone())? {
             // Current node is below new node
             (self.key, new_node.key)
-        } else if self.above_range(new_node.clone()) {
+        } else if self.above_range(new_node.clone())? {
             // Current node is above new node
             (new_node.key, self.key)
         } else {
@@ -562,7 +573,7 @@ impl TreeNode {
         // Determine the new range for the generated parent
         let (new_min, new_max) = (
             new_node.get_min_range().min(self.get_min_range()),
-            new_node.get_max_range().max(self.get_max_range()),
+            new_node.get_max_range()?.max(self.get_max_range()?),
         );
 
         // Create new parent to be saved
@@ -598,6 +609,8 @@ impl TreeNode {
     /// Deletes a given node from the tree and propagates value changes up through its parent nodes.
     ///
     /// If the parent node has no children after removal it is also deleted recursively, to prune empty branches.
+    /// A non-root parent left with a single child is replaced by that child. The remaining ancestors are then
+    /// rebalanced, as they are after an insertion.
     pub fn delete(&self, storage: &mut dyn Storage) -> ContractResult<()> {
         let maybe_parent = self.get_parent(storage)?;
         if let Some(mut parent) = maybe_parent {
@@ -611,9 +624,29 @@ impl TreeNode {
             if !parent.has_child() {
                 // Remove no-children parents
                 parent.delete(storage)?;
+            } else if let Some(mut grandparent) = parent.get_parent(storage)? {
+                // Splice the remaining child into the parent's place. Keeping a single-child parent would
+                // shorten its subtree by two levels, which one rotation cannot rebalance.
+                let mut child = parent
+                    .get_left(storage)?
+                    .or(parent.get_right(storage)?)
+                    .ok_or(ContractError::ChildlessInternalNode)?;
+                if grandparent.left == Some(parent.key) {
+                    grandparent.left = Some(child.key);
+                } else {
+                    grandparent.right = Some(child.key);
+                }
+                child.parent = Some(grandparent.key);
+                child.save(storage)?;
+                grandparent.save(storage)?;
+                NODES.remove(storage, &(parent.tick_id, parent.key));
+
+                grandparent.rebalance_up(storage)?;
             } else {
-                // Update parents values after removing node
-                parent.sync_range_and_value_up(storage)?;
+                // The root always remains, even with a single child.
+                // Rebalancing reloads nodes from storage, so the removed reference must be saved first.
+                parent.save(storage)?;
+                parent.rebalance_up(storage)?;
             }
         }
 
@@ -636,6 +669,8 @@ impl TreeNode {
     /// This method ensures that the AVL tree properties are maintained after insertions or deletions
     /// have been performed. It checks the balance factor of the current node and performs rotations
     /// as necessary to bring the tree back into balance.
+    ///
+    /// If sumtree safe mode is enabled, rotations are skipped and only the node's range and value are synced.
     pub fn rebalance(&mut self, storage: &mut dyn Storage) -> ContractResult<()> {
         // Synchronize the current node's state with storage before rebalancing.
         self.sync(storage)?;
@@ -645,8 +680,9 @@ impl TreeNode {
 
         // Calculate the balance factor to determine if rebalancing is needed.
         let balance_factor = self.get_balance_factor(storage)?;
-        // Early return if the tree is already balanced.
-        if balance_factor.abs() <= 1 {
+        let safe_mode = SUMTREE_SAFE_MODE.may_load(storage)?.unwrap_or(false);
+        // Early return if the tree is already balanced or rotations are disabled.
+        if balance_factor.abs() <= 1 || safe_mode {
             self.sync_range_and_value(storage)?;
             return Ok(());
         }
@@ -692,6 +728,20 @@ impl TreeNode {
         Ok(())
     }
 
+    /// Rebalances the current node and then each of its ancestors up to the root.
+    ///
+    /// A rotation may move the current node below one of its children, so the parent is looked up
+    /// before rebalancing. Its child pointer is updated by the rotation to the subtree's new root.
+    pub fn rebalance_up(&mut self, storage: &mut dyn Storage) -> ContractResult<()> {
+        self.sync(storage)?;
+        let maybe_parent = self.get_parent(storage)?;
+        self.rebalance(storage)?;
+        if let Some(mut parent) = maybe_parent {
+            parent.rebalance_up(storage)?;
+        }
+        Ok(())
+    }
+
     /// Performs a right rotation on the current node. **Called by the root of the subtree to be rotated.**
     ///
     /// This operation is used to rebalance the tree when the left subtree
@@ -703,10 +753,10 @@ impl TreeNode {
         // Determine if the current node is a left or right child of its parent.
         let is_left_child = maybe_parent
             .clone()
-            .map_or(false, |p| p.left == Some(self.key));
+            .is_some_and(|p| p.left == Some(self.key));
         let is_right_child = maybe_parent
             .clone()
-            .map_or(false, |p| p.right == Some(self.key));
+            .is_some_and(|p| p.right == Some(self.key));
 
         // Ensure the current node has a left child to rotate.
         let maybe_left = self.get_left(storage)?;
@@ -770,10 +820,10 @@ impl TreeNode {
         // Determine if the current node is a left or right child of its parent.
         let is_left_child = maybe_parent
             .clone()
-            .map_or(false, |p| p.left == Some(self.key));
+            .is_some_and(|p| p.left == Some(self.key));
         let is_right_child = maybe_parent
             .clone()
-            .map_or(false, |p| p.right == Some(self.key));
+            .is_some_and(|p| p.right == Some(self.key));
 
         // Ensure the current node has a right child to rotate.
         let maybe_right = self.get_right(storage)?;
@@ -791,13 +841,13 @@ impl TreeNode {
             new_right.save(storage)?;
         }
 
-        // Complete the rotation by setting the right child of the left node to the current node.
+        // Complete the rotation by setting the left child of the right node to the current node.
         right.left = Some(self.key);
         // Save the changes to both nodes.
         right.save(storage)?;
         self.save(storage)?;
 
-        // If the left node has no parent, it becomes the new root.
+        // If the right node has no parent, it becomes the new root.
         if right.parent.is_none() {
             TREE.save(
                 storage,
@@ -845,21 +895,37 @@ impl TreeNode {
     #[cfg(test)]
     /// Depth first search traversal of tree
     pub fn traverse(&self, storage: &dyn Storage) -> ContractResult<Vec<TreeNode>> {
+        self.traverse_limited(storage, usize::MAX)
+    }
+
+    /// Depth first search traversal of tree, stopping once `max_nodes` nodes have been visited.
+    ///
+    /// Nodes are returned in pre-order: each node is followed by its left then right subtree.
+    pub fn traverse_limited(
+        &self,
+        storage: &dyn Storage,
+        max_nodes: usize,
+    ) -> ContractResult<Vec<TreeNode>> {
         let mut nodes = vec![];
-        nodes.push(self.clone());
-        if !self.is_internal() {
-            return Ok(nodes);
-        }
-        if let Some(left) = self.get_left(storage)? {
-            nodes.append(&mut left.traverse(storage)?);
-        }
-        if let Some(right) = self.get_right(storage)? {
-            nodes.append(&mut right.traverse(storage)?);
+        let mut stack = vec![self.clone()];
+        while let Some(node) = stack.pop() {
+            if nodes.len() >= max_nodes {
+                break;
+            }
+            if node.is_internal() {
+                if let Some(right) = node.get_right(storage)? {
+                    stack.push(right);
+                }
+                if let Some(left) = node.get_left(storage)? {
+                    stack.push(left);
+                }
+            }
+            nodes.push(node);
         }
         Ok(nodes)
     }
 
-    #[cfg(test)]
+    /// Number of levels in the subtree rooted at this node, counting this node
     pub fn get_height(&self, storage: &dyn Storage) -> ContractResult<u64> {
         let mut height = 0;
         if let Some(left) = self.get_left(storage)? {
@@ -5,6 +5,7 @@ use crate::{
         node::{generate_node_id, NodeType, TreeNode, NODES},
         tree::{get_prefix_sum, get_root_node, TREE},
     },
+    state::SUMTREE_SAFE_MODE,
     types::OrderDirection,
     ContractError,
 };
@@ -58,14 +59,14 @@ pub fn assert_internal_values(
             );
         let max = left_node
             .clone()
-            .map_or(Decimal256::MIN, |n| n.get_max_range())
+            .map_or(Decimal256::MIN, |n| n.get_max_range().unwrap())
             .max(
                 right_node
                     .clone()
-                    .map_or(Decimal256::MIN, |n| n.get_max_range()),
+                    .map_or(Decimal256::MIN, |n| n.get_max_range().unwrap()),
             );
         assert_eq!(internal_node.get_min_range(), min);
-        assert_eq!(internal_node.get_max_range(), max);
+        assert_eq!(internal_node.get_max_range().unwrap(), max);
 
         let balance_factor = right_node
             .clone()
@@ -127,12 +128,66 @@ pub fn assert_internal_values(
         }
 
         // Ensure there is no overlap in child nodes
-        let left_max = left_node.map_or(Decimal256::MIN, |n| n.get_max_range());
+        let left_max = left_node.map_or(Decimal256::MIN, |n| n.get_max_range().unwrap());
         let right_min = right_node.map_or(Decimal256::MAX, |n| n.get_min_range());
         assert!(left_max <= right_min, "{}: Left max is higher than right min", test_name);
     }
 }
 
+// Asserts every node's parent pointer matches its position in the tree, starting from the stored root
+pub fn assert_parent_pointers(
+    test_name: &str,
+    storage: &dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) {
+    let root = get_root_node(storage, tick_id, direction).unwrap();
+    assert_eq!(
+        root.parent, None,
+        "{}: Root {} has a parent",
+        test_name, root.key
+    );
+
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        for child in [
+            node.get_left(storage).unwrap(),
+            node.get_right(storage).unwrap(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            assert_eq!(
+                child.parent,
+                Some(node.key),
+                "{}: Child {} is positioned under {} but points to parent {:?}",
+                test_name,
+                child.key,
+                node.key,
+                child.parent
+            );
+            stack.push(child);
+        }
+    }
+
+    // Every stored node with a parent must be one of that parent's children
+    for item in NODES
+        .prefix(tick_id)
+        .range(storage, None, None, cosmwasm_std::Order::Ascending)
+    {
+        let (_, node) = item.unwrap();
+        if let Some(parent) = node.get_parent(storage).unwrap() {
+            assert!(
+                parent.left == Some(node.key) || parent.right == Some(node.key),
+                "{}: Node {} points to parent {} which does not reference it",
+                test_name,
+                node.key,
+                parent.key
+            );
+        }
+    }
+}
+
 #[test]
 fn test_node_insert_cases() {
     let tick_id = 1;
@@ -294,6 +349,52 @@ fn test_node_insert_cases() {
             expected: vec![1, 3, 2],
             print: true,
         },
+        // Pre
+        // ---
+        //          1: 20 1-22
+        //     ┌────────────────┐
+        // 3: 1 10         2: 12 10
+        //
+        // Post
+        // ----
+        //                          1: 25 1-35
+        //             ┌────────────────────────────────┐
+        //         3: 1 10                       5: 15 12-35
+        //                                   ┌────────────────┐
+        //                               2: 12 10        ->4: 30 5
+        TestNodeInsertCase {
+            name: "Case 6 reordered leaves keep ascending order when a higher node splits the right",
+            nodes: vec![
+                NodeType::leaf_uint256(12u32, 10u32),
+                NodeType::leaf_uint256(1u32, 10u32),
+                NodeType::leaf_uint256(30u32, 5u32),
+            ],
+            expected: vec![1, 3, 5, 2, 4],
+            print: true,
+        },
+        // Pre
+        // ---
+        //          1: 20 1-22
+        //     ┌────────────────┐
+        // 3: 1 10         2: 12 10
+        //
+        // Post
+        // ----
+        //                          1: 21 1-22
+        //             ┌────────────────────────────────┐
+        //        5: 11 1-12                       2: 12 10
+        //     ┌────────────────┐
+        // 3: 1 10         ->4: 11 1
+        TestNodeInsertCase {
+            name: "Case 6 reordered leaves keep ascending order when a node between them splits the left",
+            nodes: vec![
+                NodeType::leaf_uint256(12u32, 10u32),
+                NodeType::leaf_uint256(1u32, 10u32),
+                NodeType::leaf_uint256(11u32, 1u32),
+            ],
+            expected: vec![1, 5, 3, 4, 2],
+            print: true,
+        },
         
         // Pre
         // ---
@@ -519,11 +620,9 @@ fn test_node_deletion_valid() {
         //
         // Post
         // ----
-        //                   1: 15 11-26
-        //         ┌────────────────────────────────┐
-        //   5: 10 11-21                       3: 21 5
-        //         ────────┐
-        //             4: 11 10
+        //          1: 15 11-26
+        //     ┌────────────────┐
+        // 4: 11 10         3: 21 5
         NodeDeletionTestCase {
             name: "Remove nested node",
             nodes: vec![
@@ -532,7 +631,7 @@ fn test_node_deletion_valid() {
                 NodeType::leaf_uint256(11u32, 10u32),
             ],
             delete: vec![2],
-            expected: vec![1, 5, 4, 3],
+            expected: vec![1, 4, 3],
             print: true,
         },
         // Pre
@@ -583,6 +682,32 @@ fn test_node_deletion_valid() {
             expected: vec![1, 3],
             print: true,
         },
+        // Pre
+        // ---
+        //                      1: 60 5-65
+        //         ┌────────────────────────────────┐
+        //    ->2: 5 10                       5: 50 15-65
+        //                                 ┌────────────────┐
+        //                             3: 15 20        4: 35 30
+        //
+        // Post
+        // ----
+        //                      5: 50 15-65
+        //         ┌────────────────────────────────┐
+        //    1: 20 15-35                      4: 35 30
+        //         ────────┐
+        //             3: 15 20
+        NodeDeletionTestCase {
+            name: "Remove node unbalancing the root, rotating a new root in",
+            nodes: vec![
+                NodeType::leaf_uint256(5u128, 10u128),
+                NodeType::leaf_uint256(15u128, 20u128),
+                NodeType::leaf_uint256(35u128, 30u128),
+            ],
+            delete: vec![2],
+            expected: vec![5, 1, 3, 4],
+            print: true,
+        },
     ];
 
     for test in test_cases {
@@ -593,6 +718,7 @@ fn test_node_deletion_valid() {
             generate_node_id(deps.as_mut().storage,  tick_id).unwrap(),
             NodeType::internal_uint256(Uint256::zero(), (u32::MAX, u32::MIN)),
         );
+        TREE.save(deps.as_mut().storage, &(tick_id, &direction.to_string()), &tree.key).unwrap();
 
         for node in test.nodes {
             let mut tree_node = TreeNode::new(
@@ -628,9 +754,8 @@ fn test_node_deletion_valid() {
             continue;
         }
 
-        let tree = NODES
-            .load(deps.as_ref().storage, &( tick_id, tree.key))
-            .unwrap();
+        // Rebalancing may rotate a new root in, so the root is refetched
+        let tree = get_root_node(deps.as_ref().storage, tick_id, direction).unwrap();
 
         if test.print {
             print_tree("Post-Deletion Tree", test.name, &tree, &deps.as_ref());
@@ -660,6 +785,94 @@ fn test_node_deletion_valid() {
     }
 }
 
+#[test]
+fn test_node_deletion_stays_balanced() {
+    let tick_id = 1;
+    let direction = OrderDirection::Bid;
+
+    // Without rotations the same deletions skew the tree, showing the order is adversarial
+    for rotations_enabled in [true, false] {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let root = TreeNode::new(
+            tick_id,
+            direction,
+            generate_node_id(deps.as_mut().storage, tick_id).unwrap(),
+            NodeType::internal_uint256(Uint256::zero(), (u32::MAX, u32::MIN)),
+        );
+        root.save(deps.as_mut().storage).unwrap();
+        TREE.save(
+            deps.as_mut().storage,
+            &(tick_id, &direction.to_string()),
+            &root.key,
+        )
+        .unwrap();
+
+        let mut leaves = vec![];
+        for etas in (0..32u128).map(|i| i * 10) {
+            let mut leaf = TreeNode::new(
+                tick_id,
+                direction,
+                generate_node_id(deps.as_mut().storage, tick_id).unwrap(),
+                NodeType::leaf_uint256(etas, 10u128),
+            );
+            let mut root = get_root_node(deps.as_ref().storage, tick_id, direction).unwrap();
+            root.insert(deps.as_mut().storage, &mut leaf).unwrap();
+            leaves.push(leaf.key);
+        }
+        SUMTREE_SAFE_MODE
+            .save(deps.as_mut().storage, &!rotations_enabled)
+            .unwrap();
+
+        // -- System under test --
+        // Deleting the lowest leaves first strips the left side of every subtree
+        let mut max_balance_factor = 0;
+        for key in &leaves[..24] {
+            let node = NODES.load(deps.as_ref().storage, &(tick_id, *key)).unwrap();
+            node.delete(deps.as_mut().storage).unwrap();
+
+            let root = get_root_node(deps.as_ref().storage, tick_id, direction).unwrap();
+            let nodes = root.traverse(deps.as_ref().storage).unwrap();
+            let internals: Vec<&TreeNode> = nodes.iter().filter(|x| x.is_internal()).collect();
+            for internal in &internals {
+                max_balance_factor = max_balance_factor.max(
+                    internal
+                        .get_balance_factor(deps.as_ref().storage)
+                        .unwrap()
+                        .abs(),
+                );
+            }
+            assert_internal_values(
+                &format!("delete {key}, rotations enabled: {rotations_enabled}"),
+                deps.as_ref(),
+                internals,
+                rotations_enabled,
+            );
+        }
+
+        // -- Post Test Assertions --
+        if rotations_enabled {
+            assert!(max_balance_factor <= 1);
+        } else {
+            assert!(max_balance_factor > 1);
+        }
+
+        // The remaining leaves keep their ascending ETAS order and total value
+        let root = get_root_node(deps.as_ref().storage, tick_id, direction).unwrap();
+        let remaining_etas: Vec<Decimal256> = root
+            .traverse(deps.as_ref().storage)
+            .unwrap()
+            .iter()
+            .filter(|x| !x.is_internal())
+            .map(|x| x.get_min_range())
+            .collect();
+        let expected_etas: Vec<Decimal256> = (24..32u128)
+            .map(|i| Decimal256::from_ratio(i * 10, 1u128))
+            .collect();
+        assert_eq!(remaining_etas, expected_etas);
+        assert_eq!(root.get_value(), Decimal256::from_ratio(80u128, 1u128));
+    }
+}
 struct RotateRightTestCase {
     name: &'static str,
     nodes: Vec<TreeNode>,
@@ -1343,6 +1556,7 @@ fn test_rotate_right() {
 
         let internals = nodes.iter().filter(|n| n.is_internal()).collect();
         assert_internal_values(test.name, deps.as_ref(), internals, false);
+        assert_parent_pointers(test.name, deps.as_ref().storage, tick_id, direction);
     }
 }
 
@@ -1932,9 +2146,102 @@ fn test_rotate_left() {
 
         let internals = nodes.iter().filter(|n| n.is_internal()).collect();
         assert_internal_values(test.name, deps.as_ref(), internals, false);
+        assert_parent_pointers(test.name, deps.as_ref().storage, tick_id, direction);
     }
 }
 
+#[test]
+fn test_rotate_left_reparents_transferred_grandchild() {
+    let tick_id = 1;
+    let direction = OrderDirection::Bid;
+    let mut deps = mock_dependencies();
+
+    // Pre-rotation, rotating node 2
+    // --------------------------
+    //                 1
+    //          ┌──────────┐
+    //          2          3
+    //       ┌─────┐
+    //       4     5
+    //          ┌─────┐
+    //          6     7
+    //
+    // Post-rotation
+    // --------------------------
+    //                 1
+    //          ┌──────────┐
+    //          5          3
+    //       ┌─────┐
+    //       2     7
+    //    ┌─────┐
+    //    4     6
+    let nodes = vec![
+        TreeNode::new(
+            tick_id,
+            direction,
+            1,
+            NodeType::internal_uint256(0u32, (0u32, 0u32)),
+        )
+        .with_children(Some(2), Some(3)),
+        TreeNode::new(
+            tick_id,
+            direction,
+            2,
+            NodeType::internal_uint256(0u32, (0u32, 0u32)),
+        )
+        .with_children(Some(4), Some(5))
+        .with_parent(1),
+        TreeNode::new(tick_id, direction, 3, NodeType::leaf_uint256(10u32, 1u32)).with_parent(1),
+        TreeNode::new(tick_id, direction, 4, NodeType::leaf_uint256(1u32, 1u32)).with_parent(2),
+        TreeNode::new(
+            tick_id,
+            direction,
+            5,
+            NodeType::internal_uint256(0u32, (0u32, 0u32)),
+        )
+        .with_children(Some(6), Some(7))
+        .with_parent(2),
+        TreeNode::new(tick_id, direction, 6, NodeType::leaf_uint256(2u32, 1u32)).with_parent(5),
+        TreeNode::new(tick_id, direction, 7, NodeType::leaf_uint256(3u32, 1u32)).with_parent(5),
+    ];
+    TREE.save(
+        deps.as_mut().storage,
+        &(tick_id, &direction.to_string()),
+        &1,
+    )
+    .unwrap();
+    for node in &nodes {
+        NODES
+            .save(deps.as_mut().storage, &(tick_id, node.key), node)
+            .unwrap();
+    }
+    // Sync internal values bottom up
+    for key in [5, 2, 1] {
+        let mut node = NODES.load(deps.as_ref().storage, &(tick_id, key)).unwrap();
+        node.sync_range_and_value(deps.as_mut().storage).unwrap();
+    }
+    assert_parent_pointers("pre-rotation", deps.as_ref().storage, tick_id, direction);
+
+    let mut node = NODES.load(deps.as_ref().storage, &(tick_id, 2)).unwrap();
+    node.rotate_left(deps.as_mut().storage).unwrap();
+
+    let tree = get_root_node(deps.as_ref().storage, tick_id, direction).unwrap();
+    let nodes = tree.traverse(deps.as_ref().storage).unwrap();
+    let keys: Vec<u64> = nodes.iter().map(|n| n.key).collect();
+    assert_eq!(keys, vec![1, 5, 2, 4, 6, 7, 3]);
+
+    // The right child's former left child is now the rotated node's right child
+    let transferred = NODES.load(deps.as_ref().storage, &(tick_id, 6)).unwrap();
+    assert_eq!(transferred.parent, Some(2));
+    let rotated = NODES.load(deps.as_ref().storage, &(tick_id, 2)).unwrap();
+    assert_eq!(rotated.right, Some(6));
+    assert_eq!(rotated.parent, Some(5));
+
+    assert_parent_pointers("post-rotation", deps.as_ref().storage, tick_id, direction);
+    let internals = nodes.iter().filter(|n| n.is_internal()).collect();
+    assert_internal_values("post-rotation", deps.as_ref(), internals, false);
+}
+
 struct RebalanceTestCase {
     name: &'static str,
     nodes: Vec<TreeNode>,
@@ -2670,9 +2977,51 @@ fn test_rebalance() {
 
         let internals = nodes.iter().filter(|n| n.is_internal()).collect();
         assert_internal_values(test.name, deps.as_ref(), internals, true);
+        assert_parent_pointers(test.name, deps.as_ref().storage, tick_id, direction);
     }
 }
 
+#[test]
+fn test_node_insert_max_range_overflow() {
+    let tick_id = 1;
+    let direction = OrderDirection::Bid;
+    let mut deps = mock_dependencies();
+
+    let mut tree = TreeNode::new(
+        tick_id,
+        direction,
+        generate_node_id(deps.as_mut().storage, tick_id).unwrap(),
+        NodeType::default(),
+    );
+    TREE.save(
+        deps.as_mut().storage,
+        &(tick_id, &direction.to_string()),
+        &tree.key,
+    )
+    .unwrap();
+
+    // A leaf whose ETAS plus value exceeds the maximum representable range end
+    let mut node = TreeNode::new(
+        tick_id,
+        direction,
+        generate_node_id(deps.as_mut().storage, tick_id).unwrap(),
+        NodeType::leaf(
+            Decimal256::MAX - Decimal256::one(),
+            Decimal256::from_ratio(10u128, 1u128),
+        ),
+    );
+    assert!(matches!(
+        node.get_max_range(),
+        Err(ContractError::Overflow(_))
+    ));
+
+    // Inserting the leaf errors gracefully when the parent's range is synced
+    let res = tree.insert(deps.as_mut().storage, &mut node);
+    assert!(
+        matches!(res, Err(ContractError::Overflow(_))),
+        "expected overflow error, got {res:?}"
+    );
+}
 fn generate_nodes(
     storage: &mut dyn Storage,
     tick_id: i64,
@@ -1,9 +1,15 @@
+use std::cell::Cell;
+
+use crate::state::SUMTREE_SAFE_MODE;
 use crate::sumtree::node::{generate_node_id, NodeType, TreeNode, NODES};
 use crate::sumtree::test::test_node::assert_internal_values;
-use crate::sumtree::tree::{get_or_init_root_node, get_prefix_sum, get_root_node, TREE};
+use crate::sumtree::tree::{
+    get_or_init_root_node, get_prefix_sum, get_root_node, verify_tree_integrity, TREE,
+};
 use crate::types::OrderDirection;
-use cosmwasm_std::Storage;
+use crate::ContractError;
 use cosmwasm_std::{testing::mock_dependencies, Decimal256};
+use cosmwasm_std::{Order, Record, Storage};
 
 struct TestPrefixSumCase {
     name: &'static str,
@@ -165,6 +171,41 @@ fn test_get_prefix_sum_valid() {
             // 5 + 19 + 4 + 10 + 9 + 20 + 50 + 40 + 29 = 186
             expected_sum: Decimal256::from_ratio(186u128, 1u128),
         },
+        TestPrefixSumCase {
+            name: "Fractional ETAS and values, target ETAS between nodes",
+            nodes: vec![
+                // 0.5-2.75
+                NodeType::leaf(
+                    Decimal256::from_ratio(1u128, 2u128),
+                    Decimal256::from_ratio(9u128, 4u128),
+                ),
+                // 2.75-4.25 adjacent to the first
+                NodeType::leaf(
+                    Decimal256::from_ratio(11u128, 4u128),
+                    Decimal256::from_ratio(3u128, 2u128),
+                ),
+            ],
+            target_etas: Decimal256::from_ratio(27u128, 10u128),
+
+            expected_sum: Decimal256::from_ratio(9u128, 4u128),
+        },
+        TestPrefixSumCase {
+            name: "Fractional ETAS and values, target ETAS at fractional lower bound",
+            nodes: vec![
+                NodeType::leaf(
+                    Decimal256::from_ratio(11u128, 4u128),
+                    Decimal256::from_ratio(3u128, 2u128),
+                ),
+                NodeType::leaf(
+                    Decimal256::from_ratio(1u128, 2u128),
+                    Decimal256::from_ratio(9u128, 4u128),
+                ),
+            ],
+            target_etas: Decimal256::from_ratio(11u128, 4u128),
+
+            // No rounding at the sumtree boundary: 2.25 + 1.5
+            expected_sum: Decimal256::from_ratio(15u128, 4u128),
+        },
     ];
 
     for test in test_cases {
@@ -223,3 +264,243 @@ pub fn insert_and_refetch(
         .unwrap();
     NODES.load(storage, &(tick_id, root_id)).unwrap()
 }
+
+struct VerifyTreeIntegrityTestCase {
+    name: &'static str,
+    // Corrupts the stored tree, returning the key of the violating node and the expected violation
+    corrupt: fn(&mut dyn Storage, TreeNode) -> (u64, String),
+}
+
+#[test]
+fn test_verify_tree_integrity() {
+    let tick_id = 1;
+    let direction = OrderDirection::Bid;
+    let test_cases: Vec<VerifyTreeIntegrityTestCase> = vec![
+        VerifyTreeIntegrityTestCase {
+            name: "accumulator does not match children",
+            corrupt: |storage, mut root| {
+                let NodeType::Internal { accumulator, .. } = &mut root.node_type else {
+                    unreachable!()
+                };
+                let sum = *accumulator;
+                *accumulator += Decimal256::one();
+                root.save(storage).unwrap();
+                (
+                    root.key,
+                    format!(
+                        "has accumulator {} but its children sum to {sum}",
+                        root.get_value()
+                    ),
+                )
+            },
+        },
+        VerifyTreeIntegrityTestCase {
+            name: "range does not match children",
+            corrupt: |storage, mut root| {
+                let (min, max) = (root.get_min_range(), root.get_max_range().unwrap());
+                root.set_max_range(max + Decimal256::one()).unwrap();
+                root.save(storage).unwrap();
+                (
+                    root.key,
+                    format!(
+                        "has range {min}-{} but its children span {min}-{max}",
+                        max + Decimal256::one()
+                    ),
+                )
+            },
+        },
+        VerifyTreeIntegrityTestCase {
+            name: "weight does not match height",
+            corrupt: |storage, mut root| {
+                let height = root.get_weight();
+                root.set_weight(height + 1).unwrap();
+                root.save(storage).unwrap();
+                (
+                    root.key,
+                    format!("has weight {} but a height of {height}", height + 1),
+                )
+            },
+        },
+        VerifyTreeIntegrityTestCase {
+            name: "unbalanced",
+            corrupt: |storage, root| {
+                // Deleting every leaf on the right leaves the root with only its deeper left subtree.
+                // Deletion rebalances, so rotations are disabled to keep the skewed shape.
+                SUMTREE_SAFE_MODE.save(storage, &true).unwrap();
+                let right = root.get_right(storage).unwrap().unwrap();
+                for leaf in right.traverse(storage).unwrap() {
+                    if !leaf.is_internal() {
+                        // Deletion may reparent the remaining leaves, so each is refetched
+                        let leaf = NODES.load(storage, &(leaf.tick_id, leaf.key)).unwrap();
+                        leaf.delete(storage).unwrap();
+                    }
+                }
+                let root = get_root_node(storage, root.tick_id, root.direction).unwrap();
+                let left_weight = root.get_left(storage).unwrap().unwrap().get_weight();
+                (root.key, format!("has balance factor -{left_weight}"))
+            },
+        },
+        VerifyTreeIntegrityTestCase {
+            name: "child points to the wrong parent",
+            corrupt: |storage, root| {
+                let mut left = root.get_left(storage).unwrap().unwrap();
+                left.parent = root.right;
+                left.save(storage).unwrap();
+                (
+                    left.key,
+                    format!("is a child of {} but has parent {:?}", root.key, root.right),
+                )
+            },
+        },
+        VerifyTreeIntegrityTestCase {
+            name: "dangling child pointer",
+            corrupt: |storage, root| {
+                let left = root.left.unwrap();
+                NODES.remove(storage, &(root.tick_id, left));
+                (root.key, format!("references missing child {left}"))
+            },
+        },
+        VerifyTreeIntegrityTestCase {
+            name: "root has a parent",
+            corrupt: |storage, mut root| {
+                root.parent = root.left;
+                root.save(storage).unwrap();
+                (root.key, "is the root but has a parent".to_string())
+            },
+        },
+        VerifyTreeIntegrityTestCase {
+            name: "orphaned node",
+            corrupt: |storage, root| {
+                let key = generate_node_id(storage, root.tick_id).unwrap();
+                TreeNode::new(
+                    root.tick_id,
+                    root.direction,
+                    key,
+                    NodeType::leaf_uint256(100u128, 5u128),
+                )
+                .save(storage)
+                .unwrap();
+                (key, "is not reachable from the root".to_string())
+            },
+        },
+    ];
+
+    for test in test_cases {
+        let mut deps = mock_dependencies();
+
+        // Ticks without a sumtree are valid
+        verify_tree_integrity(deps.as_ref().storage, tick_id, direction).unwrap();
+
+        let mut tree = get_or_init_root_node(deps.as_mut().storage, tick_id, direction).unwrap();
+        for etas in [10u128, 20, 30, 40, 50, 60] {
+            tree = insert_and_refetch(
+                deps.as_mut().storage,
+                tick_id,
+                direction,
+                &NodeType::leaf_uint256(etas, 5u128),
+            );
+        }
+
+        // Trees built through insertion are valid
+        verify_tree_integrity(deps.as_ref().storage, tick_id, direction).unwrap();
+
+        // -- System under test --
+        let (key, error) = (test.corrupt)(deps.as_mut().storage, tree);
+        let res = verify_tree_integrity(deps.as_ref().storage, tick_id, direction);
+
+        assert_eq!(
+            res,
+            Err(ContractError::InvalidSumtree {
+                error: format!("node {key} {error}")
+            }),
+            "{}",
+            test.name
+        );
+    }
+}
+
+/// Read-only storage wrapper counting reads, to measure how many nodes a walk loads
+struct CountingStorage<'a> {
+    inner: &'a dyn Storage,
+    reads: Cell<u64>,
+}
+
+impl Storage for CountingStorage<'_> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.reads.set(self.reads.get() + 1);
+        self.inner.get(key)
+    }
+
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'b> {
+        self.inner.range(start, end, order)
+    }
+
+    fn set(&mut self, _key: &[u8], _value: &[u8]) {
+        unimplemented!("read-only storage")
+    }
+
+    fn remove(&mut self, _key: &[u8]) {
+        unimplemented!("read-only storage")
+    }
+}
+
+#[test]
+fn test_prefix_sum_walk_reads() {
+    let tick_id = 1;
+    let direction = OrderDirection::Bid;
+    let mut deps = mock_dependencies();
+
+    // Leaves of value 1 at every other ETAS, leaving gaps between them
+    let leaf_count = 600u128;
+    let mut tree = get_or_init_root_node(deps.as_mut().storage, tick_id, direction).unwrap();
+    for i in 1..=leaf_count {
+        tree = insert_and_refetch(
+            deps.as_mut().storage,
+            tick_id,
+            direction,
+            &NodeType::leaf_uint256(2 * i, 1u128),
+        );
+    }
+    let height = tree.get_weight();
+    assert_eq!(height, 11);
+
+    let storage = CountingStorage {
+        inner: deps.as_ref().storage,
+        reads: Cell::new(0),
+    };
+    for target in 0..=2 * leaf_count + 2 {
+        storage.reads.set(0);
+
+        // -- System under test --
+        let prefix_sum = get_prefix_sum(
+            &storage,
+            tree.clone(),
+            Decimal256::from_ratio(target, 1u128),
+        )
+        .unwrap();
+
+        // -- Post test assertions --
+        // Leaves starting at or below the target are included
+        let expected_sum = (target / 2).min(leaf_count);
+        assert_eq!(prefix_sum, Decimal256::from_ratio(expected_sum, 1u128));
+
+        // At most both children are loaded on each level below the root
+        assert!(storage.reads.get() <= 2 * (height - 1));
+    }
+
+    // Walking right to the last leaf loads a single node per level, where loading both children
+    // of every node on the path would take twice as many reads
+    storage.reads.set(0);
+    get_prefix_sum(
+        &storage,
+        tree,
+        Decimal256::from_ratio(2 * leaf_count, 1u128),
+    )
+    .unwrap();
+    assert!(storage.reads.get() < height);
+}
@@ -1,6 +1,8 @@
+use std::collections::BTreeSet;
+
 use super::node::{generate_node_id, NodeType, TreeNode, NODES};
-use crate::{error::ContractResult, types::OrderDirection};
-use cosmwasm_std::{Decimal256, Storage};
+use crate::{error::ContractResult, types::OrderDirection, ContractError};
+use cosmwasm_std::{Decimal256, Order, Storage};
 use cw_storage_plus::Map;
 
 // Key: (tick_id, direction as str)
@@ -17,6 +19,24 @@ pub fn get_root_node(
     Ok(NODES.load(storage, &(tick_id, root_id))?)
 }
 
+/// Retrieves the root node of a specific tick and direction from storage, or an empty root if the
+/// tick has no sumtree or its root node was never written.
+///
+/// A tick that has never had a cancellation has no sumtree, so the empty root, which has a prefix
+/// sum of zero, is equivalent. The empty root is not saved to storage.
+pub fn get_root_node_or_default(
+    storage: &dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<TreeNode> {
+    let root = match TREE.may_load(storage, &(tick_id, &direction.to_string()))? {
+        Some(root_id) => NODES.may_load(storage, &(tick_id, root_id))?,
+        None => None,
+    };
+
+    Ok(root.unwrap_or_else(|| TreeNode::new(tick_id, direction, 0, NodeType::default())))
+}
+
 #[allow(dead_code)]
 /// Retrieves the root node of a specific book and tick from storage.
 /// If it is not available, initializes a sumtree and returns the root.
@@ -40,6 +60,162 @@ pub fn get_or_init_root_node(
     Ok(tree)
 }
 
+#[allow(dead_code)]
+/// Walks the sumtree of a tick and direction, checking that:
+/// * Every internal node's accumulator is the sum of its children's values
+/// * Every internal node's range spans exactly the ranges of its children
+/// * Every internal node's weight is its height
+/// * No internal node has a balance factor outside of ±1
+/// * Every child points back to its parent and every referenced node exists
+/// * No stored node for the tick and direction is unreachable from the root
+///
+/// Returns an `InvalidSumtree` error describing the first violating node. Ticks without a
+/// sumtree are trivially valid.
+pub fn verify_tree_integrity(
+    storage: &dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<()> {
+    let Some(root_id) = TREE.may_load(storage, &(tick_id, &direction.to_string()))? else {
+        return Ok(());
+    };
+    let invalid = |key: u64, error: &str| ContractError::InvalidSumtree {
+        error: format!("node {key} {error}"),
+    };
+
+    let root = NODES
+        .may_load(storage, &(tick_id, root_id))?
+        .ok_or_else(|| invalid(root_id, "is the root but does not exist"))?;
+    if root.parent.is_some() {
+        return Err(invalid(root.key, "is the root but has a parent"));
+    }
+
+    let mut reachable = BTreeSet::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        reachable.insert(node.key);
+
+        let mut children = vec![];
+        for child_id in [node.left, node.right].into_iter().flatten() {
+            let child = NODES
+                .may_load(storage, &(tick_id, child_id))?
+                .ok_or_else(|| {
+                    invalid(node.key, &format!("references missing child {child_id}"))
+                })?;
+            if child.parent != Some(node.key) {
+                return Err(invalid(
+                    child.key,
+                    &format!(
+                        "is a child of {} but has parent {:?}",
+                        node.key, child.parent
+                    ),
+                ));
+            }
+            children.push(child);
+        }
+
+        if let NodeType::Internal {
+            accumulator,
+            range,
+            weight,
+        } = node.node_type
+        {
+            // Only an empty root may have no children
+            if children.is_empty() {
+                if node.parent.is_some() {
+                    return Err(invalid(node.key, "is an internal node without children"));
+                }
+                continue;
+            }
+
+            let mut sum = Decimal256::zero();
+            let mut min = Decimal256::MAX;
+            let mut max = Decimal256::MIN;
+            let mut max_child_weight = 0;
+            for child in &children {
+                sum = sum.checked_add(child.get_value())?;
+                min = min.min(child.get_min_range());
+                max = max.max(child.get_max_range()?);
+                max_child_weight = max_child_weight.max(child.get_weight());
+            }
+
+            if accumulator != sum {
+                return Err(invalid(
+                    node.key,
+                    &format!("has accumulator {accumulator} but its children sum to {sum}"),
+                ));
+            }
+            if range != (min, max) {
+                return Err(invalid(
+                    node.key,
+                    &format!(
+                        "has range {}-{} but its children span {min}-{max}",
+                        range.0, range.1
+                    ),
+                ));
+            }
+            if weight != max_child_weight + 1 {
+                return Err(invalid(
+                    node.key,
+                    &format!(
+                        "has weight {weight} but a height of {}",
+                        max_child_weight + 1
+                    ),
+                ));
+            }
+            let balance_factor = node.get_balance_factor(storage)?;
+            if balance_factor.abs() > 1 {
+                return Err(invalid(
+                    node.key,
+                    &format!("has balance factor {balance_factor}"),
+                ));
+            }
+        }
+
+        stack.extend(children);
+    }
+
+    for item in NODES
+        .prefix(tick_id)
+        .range(storage, None, None, Order::Ascending)
+    {
+        let (key, node) = item?;
+        if node.direction == direction && !reachable.contains(&key) {
+            return Err(invalid(key, "is not reachable from the root"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Deletes the sumtree of a tick and direction, including any nodes no longer reachable from its
+/// root. The node id counter is shared between directions and is left to the caller.
+///
+/// Returns the number of nodes removed.
+pub fn remove_tree(
+    storage: &mut dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<u64> {
+    TREE.remove(storage, &(tick_id, &direction.to_string()));
+
+    let mut node_ids = vec![];
+    for item in NODES
+        .prefix(tick_id)
+        .range(storage, None, None, Order::Ascending)
+    {
+        let (key, node) = item?;
+        if node.direction == direction {
+            node_ids.push(key);
+        }
+    }
+    for node_id in &node_ids {
+        NODES.remove(storage, &(tick_id, *node_id));
+    }
+
+    Ok(node_ids.len() as u64)
+}
+
 #[allow(dead_code)]
 /// Calculates the prefix sum of values in the sumtree up to a target ETAS.
 pub fn get_prefix_sum(
@@ -73,7 +249,7 @@ fn prefix_sum_walk(
     if target_etas < node.get_min_range() {
         // If the target ETAS is below the root node's range, we can return zero early.
         return Ok(Decimal256::zero());
-    } else if target_etas >= node.get_max_range() {
+    } else if target_etas >= node.get_max_range()? {
         // If the target ETAS is above the root node's range, we can return the full sum early.
         return Ok(current_sum);
     }
@@ -88,72 +264,28 @@ fn prefix_sum_walk(
         return Ok(current_sum);
     }
 
-    // --- Attempt walk left ---
-
-    // We fetch both children here since we need to access both regardless of
-    // whether we walk left or right.
-    let left_child = node.get_left(storage)?;
-    let right_child = node.get_right(storage)?;
-
-    // If the left child exists, we run the following logic:
-    // * If target ETAS < left child's lower bound, exit early with zero
-    // * Else if target ETAS <= upper bound, subtract right child sum from prefix sum and walk left
-    //
-    // If neither of the above conditions are met, we continue to logic around walking right.
-    if let Some(left_child) = left_child {
-        if target_etas < left_child.get_min_range() {
-            // If the target ETAS is below the left child's range, nothing in the
-            // entire tree should be included in the prefix sum, so we return zero.
-            //
-            // TODO: This should not be possible now that the check above is added.
-            // Consider removing or erroring here.
-            return Ok(Decimal256::zero());
+    // The right child alone decides where to walk. Leaves are ordered by ETAS, so if the target ETAS
+    // is at or above the right child's lower bound it lies in or beyond the right subtree, and
+    // otherwise only the left subtree can start below it. The child walked into is passed down
+    // rather than reloaded, and the left child is only loaded when the walk descends into it, so
+    // a walk loads at most two nodes per level and a single node per level when walking right.
+    if let Some(right_child) = node.get_right(storage)? {
+        if target_etas >= right_child.get_min_range() {
+            // We do not need to update the prefix sum here because we do not know how much
+            // to subtract from it yet. The right walk handles this update, returning the sum
+            // as is if the target ETAS is above the right child's range.
+            return prefix_sum_walk(storage, &right_child, current_sum, target_etas);
         }
 
-        if target_etas < left_child.get_max_range() {
-            // Since the target ETAS is within the left child's range, we can safely conclude
-            // that everything below the right child should not be in our prefix sum.
-            let right_sum = right_child.map_or(Decimal256::zero(), |r| r.get_value());
-
-            current_sum = current_sum.checked_sub(right_sum)?;
-
-            // Walk left recursively
-            current_sum = prefix_sum_walk(storage, &left_child, current_sum, target_etas)?;
-
-            return Ok(current_sum);
-        }
-    }
-
-    // --- Attempt walk right ---
-
-    // If right child either doesn't exist, the current prefix sum is simply the sum of the left child,
-    // which is fully below the target ETAS, so we return the prefix sum as is.
-    if right_child.is_none() {
-        return Ok(current_sum);
-    }
-
-    // In the case where right child exists and the target ETAS is above the left child, we run the following logic:
-    // * If target ETAS < right child's lower bound: subtract right child's sum from prefix sum and return
-    // * If target ETAS <= right child's upper bound: walk right
-    // * If target ETAS > right child's upper bound: return full sum
-    let right_child = right_child.unwrap();
-    if target_etas < right_child.get_min_range() {
-        // If the ETAS is below the right child's range, we know that anything below the right child
-        // should not be included in the prefix sum. We subtract the right child's sum from the prefix sum.
+        // Everything below the right child starts above the target ETAS, so it is not included
+        // in the prefix sum.
         current_sum = current_sum.checked_sub(right_child.get_value())?;
+    }
 
-        Ok(current_sum)
-    } else if target_etas <= right_child.get_max_range() {
-        // If the target ETAS falls in the right child's range, we need to walk right.
-        // We do not need to update the prefix sum here because we do not know how much
-        // to subtract from it yet. The right walk handles this update.
-
-        // Walk right recursively
-        current_sum = prefix_sum_walk(storage, &right_child, current_sum, target_etas)?;
-
-        Ok(current_sum)
-    } else {
-        // If we reach here, everything in the tree is below the target ETAS, so we simply return the full sum.
-        Ok(current_sum)
+    // Walk left recursively. If the target ETAS is above the left child's range, the walk returns
+    // the prefix sum as is, and without a left child nothing else is left to subtract.
+    match node.get_left(storage)? {
+        Some(left_child) => prefix_sum_walk(storage, &left_child, current_sum, target_etas),
+        None => Ok(current_sum),
     }
 }
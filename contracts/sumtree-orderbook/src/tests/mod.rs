@@ -1,9 +1,12 @@
 mod mock_querier;
 pub mod test_auth;
+pub mod test_credit;
 mod test_constants;
 pub mod test_instantiate;
+pub mod test_migrate;
 pub mod test_order;
 pub mod test_orderbook;
+pub mod test_payout;
 pub mod test_query;
 pub mod test_state;
 pub mod test_sudo;
@@ -11,14 +11,18 @@ use crate::{
         dispatch_renounce_adminship, dispatch_transfer_admin, ADMIN, ADMIN_OFFER, MODERATOR,
         MODERATOR_OFFER,
     },
-    constants::MAX_MAKER_FEE_PERCENTAGE,
+    constants::{MAX_MAKER_FEE_PERCENTAGE, MAX_TAKER_FEE_PERCENTAGE, MAX_TICK, MIN_TICK},
     contract::{execute, query},
     msg::{AuthExecuteMsg, AuthQueryMsg, ExecuteMsg, QueryMsg},
-    state::{get_maker_fee, IS_ACTIVE, MAKER_FEE, MAKER_FEE_RECIPIENT},
+    state::{
+        get_maker_fee, resolve_denom, DENOM_ALIASES, IS_ACTIVE, MAKER_FEE, MAKER_FEE_RECIPIENT,
+        ORDERBOOK, PAUSED,
+    },
+    types::Orderbook,
     ContractError,
 };
 
-use super::test_constants::DEFAULT_SENDER;
+use super::test_constants::{BASE_DENOM, DEFAULT_SENDER, QUOTE_DENOM};
 
 struct TransferAdminTestCase {
     name: &'static str,
@@ -775,6 +779,195 @@ fn test_set_active() {
     }
 }
 
+struct SetPausedTestCase {
+    name: &'static str,
+    sender: &'static str,
+    new_paused_state: bool,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_paused() {
+    let current_admin = "current_admin";
+    let current_moderator = "current_moderator";
+    let test_cases = vec![
+        SetPausedTestCase {
+            name: "valid admin pause",
+            sender: current_admin,
+            new_paused_state: true,
+            expected_error: None,
+        },
+        SetPausedTestCase {
+            name: "valid admin unpause",
+            sender: current_admin,
+            new_paused_state: false,
+            expected_error: None,
+        },
+        SetPausedTestCase {
+            name: "moderator cannot pause",
+            sender: current_moderator,
+            new_paused_state: true,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetPausedTestCase {
+            name: "unauthorized",
+            sender: "notadminormoderator",
+            new_paused_state: true,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let info = mock_info(test.sender, &[]);
+        let env = mock_env();
+
+        // Setup state variables
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+            .unwrap();
+        MODERATOR
+            .save(deps.as_mut().storage, &Addr::unchecked(current_moderator))
+            .unwrap();
+
+        let msg = AuthExecuteMsg::SetPaused {
+            paused: test.new_paused_state,
+        };
+
+        // -- System under test --
+        let res = execute(deps.as_mut(), env, info, ExecuteMsg::Auth(msg));
+
+        // -- Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            assert!(PAUSED.may_load(deps.as_ref().storage).unwrap().is_none());
+            continue;
+        }
+
+        res.unwrap();
+
+        let paused = PAUSED.load(deps.as_ref().storage).unwrap();
+        assert_eq!(
+            paused, test.new_paused_state,
+            "{}: paused state did not update correctly",
+            test.name
+        );
+    }
+}
+
+struct RegisterDenomAliasTestCase {
+    name: &'static str,
+    sender: &'static str,
+    alias: &'static str,
+    canonical: &'static str,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_register_denom_alias() {
+    let current_admin = "current_admin";
+    let test_cases = vec![
+        RegisterDenomAliasTestCase {
+            name: "valid alias for quote denom",
+            sender: current_admin,
+            alias: "ibc/quote",
+            canonical: QUOTE_DENOM,
+            expected_error: None,
+        },
+        RegisterDenomAliasTestCase {
+            name: "valid alias for base denom",
+            sender: current_admin,
+            alias: "ibc/base",
+            canonical: BASE_DENOM,
+            expected_error: None,
+        },
+        RegisterDenomAliasTestCase {
+            name: "canonical denom not in orderbook",
+            sender: current_admin,
+            alias: "ibc/other",
+            canonical: "other",
+            expected_error: Some(ContractError::InvalidDenom {
+                denom: "other".to_string(),
+            }),
+        },
+        RegisterDenomAliasTestCase {
+            name: "alias is an orderbook denom",
+            sender: current_admin,
+            alias: BASE_DENOM,
+            canonical: QUOTE_DENOM,
+            expected_error: Some(ContractError::InvalidDenom {
+                denom: BASE_DENOM.to_string(),
+            }),
+        },
+        RegisterDenomAliasTestCase {
+            name: "unauthorized",
+            sender: "notadmin",
+            alias: "ibc/quote",
+            canonical: QUOTE_DENOM,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(test.sender, &[]);
+
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+            .unwrap();
+        ORDERBOOK
+            .save(
+                deps.as_mut().storage,
+                &Orderbook::new(
+                    QUOTE_DENOM.to_string(),
+                    BASE_DENOM.to_string(),
+                    0,
+                    MIN_TICK,
+                    MAX_TICK,
+                ),
+            )
+            .unwrap();
+        let msg = ExecuteMsg::Auth(AuthExecuteMsg::RegisterDenomAlias {
+            alias: test.alias.to_string(),
+            canonical: test.canonical.to_string(),
+        });
+
+        // -- System under test --
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        // -- Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            assert!(DENOM_ALIASES
+                .may_load(deps.as_ref().storage, test.alias)
+                .unwrap()
+                .is_none());
+            continue;
+        }
+
+        res.unwrap();
+        assert_eq!(
+            resolve_denom(deps.as_ref().storage, test.alias).unwrap(),
+            test.canonical,
+            "{}: alias did not resolve to canonical denom",
+            test.name
+        );
+    }
+}
+
 #[test]
 fn test_get_admin() {
     let admin = "admin";
@@ -1043,3 +1236,113 @@ fn test_set_maker_fee_recipient() {
         );
     }
 }
+struct SetTakerFeeTestCase {
+    name: &'static str,
+    sender: &'static str,
+    fee: Decimal256,
+    fee_collector: &'static str,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_set_taker_fee() {
+    let current_admin = "admin";
+    let current_moderator = "moderator";
+
+    let test_cases = vec![
+        SetTakerFeeTestCase {
+            name: "valid fee set by admin",
+            sender: current_admin,
+            fee: Decimal256::permille(1),
+            fee_collector: "collector",
+            expected_error: None,
+        },
+        SetTakerFeeTestCase {
+            name: "valid fee set by moderator",
+            sender: current_moderator,
+            fee: Decimal256::permille(1),
+            fee_collector: "collector",
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+        SetTakerFeeTestCase {
+            name: "fee above maximum",
+            sender: current_admin,
+            fee: MAX_TAKER_FEE_PERCENTAGE
+                .checked_add(Decimal256::percent(1))
+                .unwrap(),
+            fee_collector: "collector",
+            expected_error: Some(ContractError::InvalidTakerFee),
+        },
+        SetTakerFeeTestCase {
+            name: "invalid fee collector",
+            sender: current_admin,
+            fee: Decimal256::permille(1),
+            fee_collector: "",
+            expected_error: Some(ContractError::InvalidFeeCollector),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info(test.sender, &[]);
+
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(current_admin))
+            .unwrap();
+        MODERATOR
+            .save(deps.as_mut().storage, &Addr::unchecked(current_moderator))
+            .unwrap();
+        ORDERBOOK
+            .save(
+                deps.as_mut().storage,
+                &Orderbook::new(
+                    QUOTE_DENOM.to_string(),
+                    BASE_DENOM.to_string(),
+                    0,
+                    MIN_TICK,
+                    MAX_TICK,
+                ),
+            )
+            .unwrap();
+        let msg = ExecuteMsg::Auth(AuthExecuteMsg::SetTakerFee {
+            fee: test.fee,
+            fee_collector: Addr::unchecked(test.fee_collector),
+        });
+
+        // -- System under test --
+        let res = execute(deps.as_mut(), env, info, msg);
+
+        // -- Test Assertions --
+        let orderbook = ORDERBOOK.load(deps.as_ref().storage).unwrap();
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            assert!(
+                orderbook.taker_fee.is_zero() && orderbook.fee_collector.is_none(),
+                "{}: fee was incorrectly set",
+                test.name
+            );
+            continue;
+        }
+
+        res.unwrap();
+
+        assert_eq!(
+            orderbook.taker_fee, test.fee,
+            "{}: fee did not update correctly",
+            test.name
+        );
+        assert_eq!(
+            orderbook.fee_collector,
+            Some(Addr::unchecked(test.fee_collector)),
+            "{}: fee collector did not update correctly",
+            test.name
+        );
+    }
+}
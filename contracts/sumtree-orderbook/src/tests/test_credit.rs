@@ -0,0 +1,344 @@
+use cosmwasm_std::{
+    coin,
+    testing::{mock_env, mock_info},
+    Addr, BankMsg, CosmosMsg, Decimal256, Storage, Uint128,
+};
+
+use super::{
+    mock_querier::mock_dependencies_custom,
+    test_constants::{BASE_DENOM, DEFAULT_SENDER, QUOTE_DENOM},
+    test_utils::OrderOperation,
+};
+use crate::{
+    contract::execute,
+    msg::{ExecuteMsg, SwapRefund},
+    orderbook::create_orderbook,
+    state::{orders, CREDIT_BALANCES},
+    types::{LimitOrder, MarketOrder, OrderDirection, TimeInForce, REPLY_ID_REFUND},
+    ContractError,
+};
+
+fn place_ask_msg(quantity: u128, use_credit: Option<bool>) -> ExecuteMsg {
+    ExecuteMsg::PlaceLimit {
+        tick_id: 0,
+        order_direction: OrderDirection::Ask,
+        quantity: Uint128::from(quantity),
+        claim_bounty: None,
+        post_only: None,
+        expiry: None,
+        time_in_force: None,
+        display_quantity: None,
+        use_credit,
+    }
+}
+
+fn credit_of(storage: &dyn Storage, owner: &str, denom: &str) -> Option<Uint128> {
+    CREDIT_BALANCES
+        .may_load(storage, (&Addr::unchecked(owner), denom))
+        .unwrap()
+}
+
+#[test]
+fn test_place_limit_from_credit() {
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(DEFAULT_SENDER, &[coin(100, BASE_DENOM)]),
+        ExecuteMsg::Deposit {},
+    )
+    .unwrap();
+    assert_eq!(
+        credit_of(&deps.storage, DEFAULT_SENDER, BASE_DENOM),
+        Some(Uint128::from(100u128))
+    );
+
+    // -- System under test --
+    // No funds are attached, the order is funded entirely from credit
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(DEFAULT_SENDER, &[]),
+        place_ask_msg(60, Some(true)),
+    )
+    .unwrap();
+
+    // -- Post Test Assertions --
+    assert!(res.messages.is_empty());
+    let order = orders().load(&deps.storage, &(0, 0)).unwrap();
+    assert_eq!(order.owner, Addr::unchecked(DEFAULT_SENDER));
+    assert_eq!(order.quantity, Uint128::from(60u128));
+    assert_eq!(
+        credit_of(&deps.storage, DEFAULT_SENDER, BASE_DENOM),
+        Some(Uint128::from(40u128))
+    );
+
+    // Credit cannot be overdrawn
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(DEFAULT_SENDER, &[]),
+        place_ask_msg(50, Some(true)),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientCredit {
+            denom: BASE_DENOM.to_string(),
+            available: Uint128::from(40u128),
+            required: Uint128::from(50u128),
+        }
+    );
+
+    // Funds and credit cannot be mixed in one order
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(DEFAULT_SENDER, &[coin(10, BASE_DENOM)]),
+        place_ask_msg(50, Some(true)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::FundsWithCredit);
+
+    // Draining the credit removes the entry
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(DEFAULT_SENDER, &[]),
+        place_ask_msg(40, Some(true)),
+    )
+    .unwrap();
+    assert!(credit_of(&deps.storage, DEFAULT_SENDER, BASE_DENOM).is_none());
+}
+
+#[test]
+fn test_refund_to_credit_and_withdraw() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // Asks of 100 and 50, with the first filled by a market order
+    let operations = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            1,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(100u128),
+            OrderDirection::Bid,
+            Addr::unchecked("buyer"),
+        )),
+    ];
+    for operation in operations {
+        operation
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+
+    // -- System under test --
+    // Only the owner may keep a claim as credit
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::ClaimLimit {
+            tick_id: 0,
+            order_id: 0,
+            min_claim: None,
+            to_credit: Some(true),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let claim_res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::ClaimLimit {
+            tick_id: 0,
+            order_id: 0,
+            min_claim: None,
+            to_credit: Some(true),
+        },
+    )
+    .unwrap();
+
+    // A refund cannot be both swapped and kept as credit
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::CancelLimit {
+            tick_id: 0,
+            order_id: 1,
+            swap_refund: Some(SwapRefund {
+                min_output: Uint128::zero(),
+            }),
+            to_credit: Some(true),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::SwapRefundToCredit);
+
+    let cancel_res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::CancelLimit {
+            tick_id: 0,
+            order_id: 1,
+            swap_refund: None,
+            to_credit: Some(true),
+        },
+    )
+    .unwrap();
+
+    // -- Post Test Assertions --
+    // Nothing is sent, the claim and refund are held as credit
+    assert!(claim_res.messages.is_empty());
+    assert!(cancel_res.messages.is_empty());
+    assert_eq!(
+        credit_of(&deps.storage, DEFAULT_SENDER, QUOTE_DENOM),
+        Some(Uint128::from(100u128))
+    );
+    assert_eq!(
+        credit_of(&deps.storage, DEFAULT_SENDER, BASE_DENOM),
+        Some(Uint128::from(50u128))
+    );
+
+    // Withdrawing sends the credit back and debits it
+    let withdraw_res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::Withdraw {
+            denom: QUOTE_DENOM.to_string(),
+            amount: Uint128::from(30u128),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        withdraw_res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: sender.to_string(),
+            amount: vec![coin(30, QUOTE_DENOM)],
+        })
+    );
+    assert_eq!(
+        credit_of(&deps.storage, DEFAULT_SENDER, QUOTE_DENOM),
+        Some(Uint128::from(70u128))
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::Withdraw {
+            denom: QUOTE_DENOM.to_string(),
+            amount: Uint128::from(71u128),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientCredit {
+            denom: QUOTE_DENOM.to_string(),
+            available: Uint128::from(70u128),
+            required: Uint128::from(71u128),
+        }
+    );
+}
+
+#[test]
+fn test_immediate_or_cancel_from_credit() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // A resting ask of 40 for the bid to partially fill against
+    OrderOperation::PlaceLimit(LimitOrder::new(
+        0,
+        0,
+        OrderDirection::Ask,
+        Addr::unchecked("maker"),
+        Uint128::from(40u128),
+        Decimal256::zero(),
+        None,
+    ))
+    .run(deps.as_mut(), env.clone(), mock_info("maker", &[]))
+    .unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(sender.as_str(), &[coin(100, QUOTE_DENOM)]),
+        ExecuteMsg::Deposit {},
+    )
+    .unwrap();
+
+    // -- System under test --
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(sender.as_str(), &[]),
+        ExecuteMsg::PlaceLimit {
+            tick_id: 0,
+            order_direction: OrderDirection::Bid,
+            quantity: Uint128::from(100u128),
+            claim_bounty: None,
+            post_only: None,
+            expiry: None,
+            time_in_force: Some(TimeInForce::ImmediateOrCancel),
+            display_quantity: None,
+            use_credit: Some(true),
+        },
+    )
+    .unwrap();
+
+    // -- Post Test Assertions --
+    // Only the fill output is sent, the unfilled 60 goes back to credit
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "refund_quantity" && attr.value == "60"));
+    assert!(res.messages.iter().all(|msg| msg.id != REPLY_ID_REFUND));
+    assert_eq!(
+        credit_of(&deps.storage, DEFAULT_SENDER, QUOTE_DENOM),
+        Some(Uint128::from(60u128))
+    );
+}
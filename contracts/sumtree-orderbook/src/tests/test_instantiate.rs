@@ -1,13 +1,20 @@
 use cosmwasm_std::{
     coin,
     testing::{mock_env, mock_info},
+    Decimal256, Uint128,
 };
 
 use super::{
     mock_querier::mock_dependencies_custom,
     test_constants::{BASE_DENOM, DEFAULT_SENDER, QUOTE_DENOM},
 };
-use crate::{contract::instantiate, msg::InstantiateMsg, ContractError};
+use crate::{
+    constants::{MAX_TICK, MIN_TICK},
+    contract::instantiate,
+    msg::InstantiateMsg,
+    state::{get_tick_bounds, ORDERBOOK},
+    ContractError,
+};
 
 struct InstantiateTestCase {
     name: &'static str,
@@ -23,15 +30,219 @@ fn test_instantiate() {
             msg: InstantiateMsg {
                 quote_denom: QUOTE_DENOM.to_string(),
                 base_denom: BASE_DENOM.to_string(),
+                min_tick: None,
+                max_tick: None,
+                min_order_size: None,
+                max_tick_move: None,
+                tick_spacing: None,
+                max_orders_per_tick: None,
+                max_claim_bounty: None,
+            },
+            expected_error: None,
+        },
+        InstantiateTestCase {
+            name: "valid instantiate with narrowed tick bounds",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                min_tick: Some(-1000),
+                max_tick: Some(1000),
+                min_order_size: None,
+                max_tick_move: None,
+                tick_spacing: None,
+                max_orders_per_tick: None,
+                max_claim_bounty: None,
+            },
+            expected_error: None,
+        },
+        InstantiateTestCase {
+            name: "valid instantiate with minimum order size",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                min_tick: None,
+                max_tick: None,
+                min_order_size: Some(Uint128::from(100u128)),
+                max_tick_move: None,
+                tick_spacing: None,
+                max_orders_per_tick: None,
+                max_claim_bounty: None,
+            },
+            expected_error: None,
+        },
+        InstantiateTestCase {
+            name: "valid instantiate with max tick move",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                min_tick: None,
+                max_tick: None,
+                min_order_size: None,
+                max_tick_move: Some(1000),
+                tick_spacing: None,
+                max_orders_per_tick: None,
+                max_claim_bounty: None,
             },
             expected_error: None,
         },
+        InstantiateTestCase {
+            name: "negative max tick move",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                min_tick: None,
+                max_tick: None,
+                min_order_size: None,
+                max_tick_move: Some(-1),
+                tick_spacing: None,
+                max_orders_per_tick: None,
+                max_claim_bounty: None,
+            },
+            expected_error: Some(ContractError::InvalidMaxTickMove { max_tick_move: -1 }),
+        },
+        InstantiateTestCase {
+            name: "valid instantiate with tick spacing",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                min_tick: None,
+                max_tick: None,
+                min_order_size: None,
+                max_tick_move: None,
+                tick_spacing: Some(100),
+                max_orders_per_tick: None,
+                max_claim_bounty: None,
+            },
+            expected_error: None,
+        },
+        InstantiateTestCase {
+            name: "zero tick spacing",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                min_tick: None,
+                max_tick: None,
+                min_order_size: None,
+                max_tick_move: None,
+                tick_spacing: Some(0),
+                max_orders_per_tick: None,
+                max_claim_bounty: None,
+            },
+            expected_error: Some(ContractError::InvalidTickSpacing { tick_spacing: 0 }),
+        },
+        InstantiateTestCase {
+            name: "valid instantiate with max orders per tick",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                min_tick: None,
+                max_tick: None,
+                min_order_size: None,
+                max_tick_move: None,
+                tick_spacing: None,
+                max_orders_per_tick: Some(50),
+                max_claim_bounty: None,
+            },
+            expected_error: None,
+        },
+        InstantiateTestCase {
+            name: "zero max orders per tick",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                min_tick: None,
+                max_tick: None,
+                min_order_size: None,
+                max_tick_move: None,
+                tick_spacing: None,
+                max_orders_per_tick: Some(0),
+                max_claim_bounty: None,
+            },
+            expected_error: Some(ContractError::InvalidMaxOrdersPerTick {
+                max_orders_per_tick: 0,
+            }),
+        },
+        InstantiateTestCase {
+            name: "valid instantiate with max claim bounty",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                min_tick: None,
+                max_tick: None,
+                min_order_size: None,
+                max_tick_move: None,
+                tick_spacing: None,
+                max_orders_per_tick: None,
+                max_claim_bounty: Some(Decimal256::permille(5)),
+            },
+            expected_error: None,
+        },
+        InstantiateTestCase {
+            name: "max claim bounty above guardrail",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                min_tick: None,
+                max_tick: None,
+                min_order_size: None,
+                max_tick_move: None,
+                tick_spacing: None,
+                max_orders_per_tick: None,
+                max_claim_bounty: Some(Decimal256::percent(2)),
+            },
+            expected_error: Some(ContractError::InvalidClaimBounty {
+                claim_bounty: Some(Decimal256::percent(2)),
+            }),
+        },
+        InstantiateTestCase {
+            name: "tick bounds beyond compile-time limits",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                min_tick: Some(MIN_TICK - 1),
+                max_tick: None,
+                min_order_size: None,
+                max_tick_move: None,
+                tick_spacing: None,
+                max_orders_per_tick: None,
+                max_claim_bounty: None,
+            },
+            expected_error: Some(ContractError::InvalidTickBounds {
+                min_tick: MIN_TICK - 1,
+                max_tick: MAX_TICK,
+            }),
+        },
+        InstantiateTestCase {
+            name: "empty tick bounds",
+            msg: InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                min_tick: Some(10),
+                max_tick: Some(-10),
+                min_order_size: None,
+                max_tick_move: None,
+                tick_spacing: None,
+                max_orders_per_tick: None,
+                max_claim_bounty: None,
+            },
+            expected_error: Some(ContractError::InvalidTickBounds {
+                min_tick: 10,
+                max_tick: -10,
+            }),
+        },
         InstantiateTestCase {
             name: "invalid instantiate",
             msg: InstantiateMsg {
                 // Same denom for both quote and base
                 quote_denom: QUOTE_DENOM.to_string(),
                 base_denom: QUOTE_DENOM.to_string(),
+                min_tick: None,
+                max_tick: None,
+                min_order_size: None,
+                max_tick_move: None,
+                tick_spacing: None,
+                max_orders_per_tick: None,
+                max_claim_bounty: None,
             },
             expected_error: Some(ContractError::DuplicateDenoms {}),
         },
@@ -43,6 +254,16 @@ fn test_instantiate() {
         let env = mock_env();
         let info = mock_info(DEFAULT_SENDER, &[coin(100u128, BASE_DENOM)]);
 
+        let expected_tick_bounds = (
+            test.msg.min_tick.unwrap_or(MIN_TICK),
+            test.msg.max_tick.unwrap_or(MAX_TICK),
+        );
+        let expected_min_order_size = test.msg.min_order_size.unwrap_or_default();
+        let expected_max_tick_move = test.msg.max_tick_move;
+        let expected_tick_spacing = test.msg.tick_spacing;
+        let expected_max_orders_per_tick = test.msg.max_orders_per_tick;
+        let expected_max_claim_bounty = test.msg.max_claim_bounty;
+
         // -- System under test --
         let res = instantiate(deps.as_mut(), env, info, test.msg);
 
@@ -63,5 +284,50 @@ fn test_instantiate() {
             test.name,
             res.unwrap_err()
         );
+        assert_eq!(
+            get_tick_bounds(deps.as_ref().storage).unwrap(),
+            expected_tick_bounds,
+            "{}: tick bounds incorrectly set",
+            test.name
+        );
+        assert_eq!(
+            ORDERBOOK
+                .load(deps.as_ref().storage)
+                .unwrap()
+                .min_order_size,
+            expected_min_order_size,
+            "{}: minimum order size incorrectly set",
+            test.name
+        );
+        assert_eq!(
+            ORDERBOOK.load(deps.as_ref().storage).unwrap().max_tick_move,
+            expected_max_tick_move,
+            "{}: max tick move incorrectly set",
+            test.name
+        );
+        assert_eq!(
+            ORDERBOOK.load(deps.as_ref().storage).unwrap().tick_spacing,
+            expected_tick_spacing,
+            "{}: tick spacing incorrectly set",
+            test.name
+        );
+        assert_eq!(
+            ORDERBOOK
+                .load(deps.as_ref().storage)
+                .unwrap()
+                .max_orders_per_tick,
+            expected_max_orders_per_tick,
+            "{}: max orders per tick incorrectly set",
+            test.name
+        );
+        assert_eq!(
+            ORDERBOOK
+                .load(deps.as_ref().storage)
+                .unwrap()
+                .max_claim_bounty,
+            expected_max_claim_bounty,
+            "{}: max claim bounty incorrectly set",
+            test.name
+        );
     }
 }
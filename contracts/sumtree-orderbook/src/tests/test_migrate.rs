@@ -0,0 +1,163 @@
+use cosmwasm_std::{
+    testing::{mock_env, mock_info},
+    Addr, Decimal256, Uint128,
+};
+use cw2::{get_contract_version, set_contract_version};
+
+use super::{
+    mock_querier::mock_dependencies_custom,
+    test_constants::{BASE_DENOM, DEFAULT_SENDER, QUOTE_DENOM},
+    test_utils::{format_test_name, OrderOperation},
+};
+use crate::{
+    constants::MIN_TICK,
+    contract::{instantiate, migrate, CONTRACT_NAME, CONTRACT_VERSION},
+    msg::{InstantiateMsg, MigrateMsg},
+    state::{next_populated_tick, orders, POPULATED_TICKS},
+    types::{LimitOrder, OrderDirection},
+    ContractError,
+};
+
+struct MigrateTestCase {
+    name: &'static str,
+    stored_contract: &'static str,
+    stored_version: &'static str,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_migrate() {
+    let test_cases = vec![
+        MigrateTestCase {
+            name: "migrate from an older version",
+            stored_contract: CONTRACT_NAME,
+            stored_version: "0.0.1",
+            expected_error: None,
+        },
+        MigrateTestCase {
+            name: "migrate from the current version",
+            stored_contract: CONTRACT_NAME,
+            stored_version: CONTRACT_VERSION,
+            expected_error: None,
+        },
+        MigrateTestCase {
+            name: "downgrade",
+            stored_contract: CONTRACT_NAME,
+            stored_version: "99.0.0",
+            expected_error: Some(ContractError::InvalidMigration {
+                error: format!("cannot downgrade from 99.0.0 to {CONTRACT_VERSION}"),
+            }),
+        },
+        MigrateTestCase {
+            name: "different contract",
+            stored_contract: "crates.io:other-contract",
+            stored_version: "0.0.1",
+            expected_error: Some(ContractError::InvalidMigration {
+                error: "cannot migrate from contract crates.io:other-contract".to_string(),
+            }),
+        },
+        MigrateTestCase {
+            name: "invalid stored version",
+            stored_contract: CONTRACT_NAME,
+            stored_version: "not-a-version",
+            expected_error: Some(ContractError::InvalidMigration {
+                error: "invalid contract version not-a-version: unexpected character 'n' while parsing major version number".to_string(),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            InstantiateMsg {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+                min_tick: None,
+                max_tick: None,
+                min_order_size: None,
+                max_tick_move: None,
+                tick_spacing: None,
+                max_orders_per_tick: None,
+                max_claim_bounty: None,
+            },
+        )
+        .unwrap();
+
+        let order = LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Bid,
+            Addr::unchecked(DEFAULT_SENDER),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        );
+        OrderOperation::PlaceLimit(order.clone())
+            .run(deps.as_mut(), env.clone(), info)
+            .unwrap();
+        // State written before ticks were indexed by liquidity
+        POPULATED_TICKS.remove(deps.as_mut().storage, ("bid", 0));
+
+        set_contract_version(
+            deps.as_mut().storage,
+            test.stored_contract,
+            test.stored_version,
+        )
+        .unwrap();
+
+        // -- System under test --
+        let res = migrate(deps.as_mut(), env, MigrateMsg {});
+
+        // -- Post Test Assertions --
+        let stored = get_contract_version(deps.as_ref().storage).unwrap();
+        if let Some(err) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                err,
+                "{}: did not receive expected error",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let res = res.unwrap();
+        assert_eq!(
+            (stored.contract.as_str(), stored.version.as_str()),
+            (CONTRACT_NAME, CONTRACT_VERSION),
+            "{}: contract version not updated",
+            format_test_name(test.name)
+        );
+        assert!(
+            res.attributes
+                .iter()
+                .any(|attr| attr.key == "previous_version" && attr.value == test.stored_version),
+            "{}: previous version not reported",
+            format_test_name(test.name)
+        );
+
+        // Existing orders remain readable after migrating
+        let stored_order = orders().load(deps.as_ref().storage, &(0, 0)).unwrap();
+        assert_eq!(
+            stored_order,
+            order,
+            "{}: order changed by migration",
+            format_test_name(test.name)
+        );
+
+        // Existing ticks are indexed for market orders to walk
+        assert_eq!(
+            next_populated_tick(deps.as_ref().storage, OrderDirection::Bid, 0, MIN_TICK)
+                .unwrap()
+                .map(|(tick_id, _)| tick_id),
+            Some(0),
+            "{}: tick not indexed by migration",
+            format_test_name(test.name)
+        );
+    }
+}
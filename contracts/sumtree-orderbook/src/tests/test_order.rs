@@ -1,17 +1,20 @@
 use std::str::FromStr;
 
 use crate::{
-    constants::{MAX_TICK, MIN_TICK}, error::ContractError, order::*, orderbook::*, state::*, sumtree::{
-        node::{NodeType, TreeNode},
-        tree::get_root_node,
+    constants::{DEFAULT_MAKER_FEE_RECIPIENT, MAX_TICK, MIN_TICK}, error::ContractError, msg::{BatchClaimData, ClaimData, PlaceLimitInput}, order::*, orderbook::*, state::*, sumtree::{
+        node::{NodeType, TreeNode, NODES},
+        tree::{get_root_node, TREE},
     },
+    query::{claimable_amount, ticks_in_range},
+    tick::get_synced_tick_values,
+    tick_math::tick_to_price,
     tests::{mock_querier::mock_dependencies_custom, test_utils::{decimal256_from_u128, place_multiple_limit_orders}},
     types::{
-        coin_u256, FilterOwnerOrders, LimitOrder, MarketOrder, MsgSend256, OrderDirection, Orderbook, TickState, TickValues, REPLY_ID_CLAIM, REPLY_ID_CLAIM_BOUNTY, REPLY_ID_MAKER_FEE, REPLY_ID_REFUND
+        coin_u256, Coin256, FilterOwnerOrders, LimitOrder, MarketOrder, MsgSend256, OrderDirection, Orderbook, TickState, TickValues, REPLY_ID_CLAIM, REPLY_ID_CLAIM_BOUNTY, REPLY_ID_IMMEDIATE_FILL, REPLY_ID_MAKER_FEE, REPLY_ID_REFUND, TimeInForce
     },
 };
 use cosmwasm_std::{
-    coin, Addr, BankMsg, Coin, Empty, SubMsg, Uint128, Uint256
+    coin, from_json, Addr, BankMsg, Coin, DepsMut, Empty, Event, Order, Storage, SubMsg, Uint128, Uint256
 };
 use cosmwasm_std::{
     testing::{mock_env, mock_info},
@@ -207,6 +210,11 @@ fn test_place_limit() {
             test.order_direction,
             test.quantity,
             test.claim_bounty,
+            None,
+            None,
+            None,
+            None,
+            false,
         );
 
         // --- Assertions ---
@@ -332,6 +340,111 @@ fn test_place_limit() {
     }
 }
 
+struct SameTickOpposingTestCase {
+    name: &'static str,
+    disallow_same_tick_opposing: bool,
+    // Tick of the resting ask placed before the bid under test
+    ask_tick_id: i64,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_place_limit_same_tick_opposing() {
+    let bid_tick_id = 0;
+    let quantity = Uint128::from(100u128);
+    let test_cases = vec![
+        SameTickOpposingTestCase {
+            name: "flag disabled allows opposing order on same tick",
+            disallow_same_tick_opposing: false,
+            ask_tick_id: bid_tick_id,
+            expected_error: None,
+        },
+        SameTickOpposingTestCase {
+            name: "flag enabled rejects opposing order on same tick",
+            disallow_same_tick_opposing: true,
+            ask_tick_id: bid_tick_id,
+            expected_error: Some(ContractError::SameTickOpposing {
+                tick_id: bid_tick_id,
+            }),
+        },
+        SameTickOpposingTestCase {
+            name: "flag enabled allows opposing order on different tick",
+            disallow_same_tick_opposing: true,
+            ask_tick_id: 1,
+            expected_error: None,
+        },
+    ];
+
+    for test in test_cases {
+        // --- Setup ---
+
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(deps.as_mut(), QUOTE_DENOM.to_string(), BASE_DENOM.to_string()).unwrap();
+        DISALLOW_SAME_TICK_OPPOSING
+            .save(deps.as_mut().storage, &test.disallow_same_tick_opposing)
+            .unwrap();
+
+        place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info(DEFAULT_SENDER, &[coin(quantity.u128(), BASE_DENOM)]),
+            test.ask_tick_id,
+            OrderDirection::Ask,
+            quantity,
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+
+        // --- System under test ---
+
+        let res = place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info(DEFAULT_SENDER, &[coin(quantity.u128(), QUOTE_DENOM)]),
+            bid_tick_id,
+            OrderDirection::Bid,
+            quantity,
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+        );
+
+        // --- Assertions ---
+
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        res.unwrap();
+        let bid_liquidity = TICK_STATE
+            .load(deps.as_ref().storage, bid_tick_id)
+            .unwrap()
+            .get_values(OrderDirection::Bid)
+            .total_amount_of_liquidity;
+        assert_eq!(
+            bid_liquidity,
+            Decimal256::from_ratio(quantity, 1u128),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
 struct CancelLimitTestCase {
     name: &'static str,
     tick_id: i64,
@@ -426,6 +539,11 @@ fn test_cancel_limit() {
                 test.order_direction,
                 test.quantity,
                 None,
+                None,
+                None,
+                None,
+                None,
+                false,
             )
             .unwrap();
         }
@@ -611,6 +729,152 @@ fn test_cancel_limit() {
     }
 }
 
+struct CancelLimitAndSwapTestCase {
+    name: &'static str,
+    // Quantity of quote placed as a bid by another user to swap the refund against
+    counterparty_quantity: Uint128,
+    min_output: Uint128,
+    expected_output: Uint256,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_cancel_limit_and_swap() {
+    let tick_id = 1;
+    let quantity = Uint128::from(100u128);
+    let test_cases = vec![
+        CancelLimitAndSwapTestCase {
+            name: "valid cancel and swap",
+            counterparty_quantity: Uint128::from(100u128),
+            min_output: Uint128::from(100u128),
+            expected_output: Uint256::from(100u128),
+            expected_error: None,
+        },
+        CancelLimitAndSwapTestCase {
+            name: "output below min output",
+            counterparty_quantity: Uint128::from(100u128),
+            min_output: Uint128::from(101u128),
+            expected_output: Uint256::zero(),
+            expected_error: Some(ContractError::SwapBelowMin {
+                min: Uint256::from(101u128),
+                received: Uint256::from(100u128),
+                denom: QUOTE_DENOM.to_string(),
+            }),
+        },
+        CancelLimitAndSwapTestCase {
+            name: "insufficient liquidity to swap refund",
+            counterparty_quantity: Uint128::from(50u128),
+            min_output: Uint128::zero(),
+            expected_output: Uint256::zero(),
+            expected_error: Some(ContractError::InsufficientLiquidity),
+        },
+    ];
+
+    for test in test_cases {
+        // --- Setup ---
+
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_OWNER, &[]);
+
+        create_orderbook(deps.as_mut(), QUOTE_DENOM.to_string(), BASE_DENOM.to_string()).unwrap();
+
+        // Place the counterparty bid at tick 0 (price 1)
+        place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info(
+                DEFAULT_SENDER,
+                &[coin(test.counterparty_quantity.u128(), QUOTE_DENOM)],
+            ),
+            0,
+            OrderDirection::Bid,
+            test.counterparty_quantity,
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+
+        // Place the ask to be cancelled
+        place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info(DEFAULT_OWNER, &[coin(quantity.u128(), BASE_DENOM)]),
+            tick_id,
+            OrderDirection::Ask,
+            quantity,
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+
+        // --- System under test ---
+
+        let response = cancel_limit_and_swap(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            tick_id,
+            1,
+            test.min_output,
+        );
+
+        // --- Assertions ---
+
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                response.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let response = response.unwrap();
+        assert_eq!(
+            response.messages,
+            vec![SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: env.contract.address.to_string(),
+                    to_address: DEFAULT_OWNER.to_string(),
+                    amount: vec![coin_u256(test.expected_output, QUOTE_DENOM)],
+                },
+                REPLY_ID_REFUND,
+            )],
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // The cancelled order is removed and its liquidity released
+        assert!(
+            orders()
+                .may_load(&deps.storage, &(tick_id, 1))
+                .unwrap()
+                .is_none(),
+            "{}",
+            format_test_name(test.name)
+        );
+        // The counterparty bid is fully consumed by the swap
+        for direction in [OrderDirection::Ask, OrderDirection::Bid] {
+            assert_eq!(
+                get_directional_liquidity(deps.as_ref().storage, direction).unwrap(),
+                Decimal256::zero(),
+                "{}",
+                format_test_name(test.name)
+            );
+        }
+    }
+}
+
 struct RunMarketOrderTestCase {
     name: &'static str,
     placed_order: MarketOrder,
@@ -903,7 +1167,7 @@ fn test_run_market_order() {
         // --- System under test ---
 
         let mut market_order = test.placed_order.clone();
-        let response = run_market_order(deps.as_mut().storage, env.contract.address.clone(), &mut market_order, test.tick_bound);
+        let response = run_market_order(deps.as_mut().storage, env.contract.address.clone(), env.block.time, &mut market_order, test.tick_bound, false, None);
 
         // --- Assertions ---
 
@@ -1062,6 +1326,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: Decimal256::zero(),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
                 (
@@ -1073,6 +1338,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: decimal256_from_u128(5u128),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
                 (
@@ -1084,6 +1350,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: decimal256_from_u128(10u128),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
             ],
@@ -1139,6 +1406,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: decimal256_from_u128(5u128),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
                 (
@@ -1150,6 +1418,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: decimal256_from_u128(10u128),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
                 (
@@ -1161,6 +1430,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: decimal256_from_u128(10u128),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
             ],
@@ -1216,6 +1486,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: decimal256_from_u128(5u128),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
                 (
@@ -1227,6 +1498,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: decimal256_from_u128(10u128),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
                 (
@@ -1238,6 +1510,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: decimal256_from_u128(10u128),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
             ],
@@ -1303,6 +1576,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: Decimal256::zero(),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
                 (
@@ -1314,6 +1588,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: decimal256_from_u128(5u128),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
                 (
@@ -1325,6 +1600,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: decimal256_from_u128(10u128),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
             ],
@@ -1400,6 +1676,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: decimal256_from_u128(12u128),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
                 (
@@ -1411,6 +1688,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: decimal256_from_u128(5u128),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
                 (
@@ -1422,6 +1700,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: Decimal256::zero(),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
             ],
@@ -1495,6 +1774,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: decimal256_from_u128(12u128),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
                 (
@@ -1506,6 +1786,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: decimal256_from_u128(5u128),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
                 (
@@ -1517,6 +1798,7 @@ fn test_run_market_order_moving_tick() {
                         total_amount_of_liquidity: Decimal256::zero(),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 ),
             ],
@@ -1687,7 +1969,7 @@ fn test_claim_order() {
                 Uint128::from(5u128),
                 decimal256_from_u128(5u128),
                 None,
-            ).with_placed_quantity(10u128)),  // Added placed quantity to expected order state
+            ).with_placed_quantity(10u128).with_claimed(5u128, 5u128)),  // Added placed quantity to expected order state
             expected_error: None,
         },
         ClaimOrderTestCase {
@@ -1894,7 +2176,7 @@ fn test_claim_order() {
                 Uint128::from(6u128),
                 decimal256_from_u128(4u128),
                 None,
-            ).with_placed_quantity(10u128)),
+            ).with_placed_quantity(10u128).with_claimed(4u128, 2u128)),
             expected_error: None,
         },
         ClaimOrderTestCase {
@@ -2013,7 +2295,7 @@ fn test_claim_order() {
                 Uint128::from(50u128),
                 decimal256_from_u128(50u128),
                 None,
-            ).with_placed_quantity(100u128)),
+            ).with_placed_quantity(100u128).with_claimed(50u128, 100u128)),
             expected_error: None,
         },
         ClaimOrderTestCase {
@@ -2143,7 +2425,7 @@ fn test_claim_order() {
                 Uint128::from(7u128),
                 decimal256_from_u128(3u128),
                 None,
-            ).with_placed_quantity(10u128)),
+            ).with_placed_quantity(10u128).with_claimed(3u128, 3_000_000_000_000u128)),
             expected_error: None,
         },
         // A tick id of 0 operates on a tick price of 1
@@ -2220,7 +2502,7 @@ fn test_claim_order() {
                 Uint128::from(5u128),
                 decimal256_from_u128(5u128),
                 None,
-            ).with_placed_quantity(10u128)),
+            ).with_placed_quantity(10u128).with_claimed(5u128, 5u128)),
             expected_error: None,
         },
         ClaimOrderTestCase {
@@ -2338,7 +2620,7 @@ fn test_claim_order() {
                 Uint128::from(5u128),
                 decimal256_from_u128(5u128),
                 None,
-            ).with_placed_quantity(10u128)),
+            ).with_placed_quantity(10u128).with_claimed(5u128, 10u128)),
             expected_error: None,
         },
         ClaimOrderTestCase {
@@ -2456,7 +2738,7 @@ fn test_claim_order() {
                 Uint128::from(50u128),
                 decimal256_from_u128(50u128),
                 None,
-            ).with_placed_quantity(100u128)),
+            ).with_placed_quantity(100u128).with_claimed(50u128, 25u128)),
             expected_error: None,
         },
         ClaimOrderTestCase {
@@ -2649,7 +2931,7 @@ fn test_claim_order() {
             }),
         },
         ClaimOrderTestCase {
-            name: "zero claim amount",
+            name: "not yet reached (head of queue, nothing swapped)",
             sender: sender.clone(),
             operations: vec![OrderOperation::PlaceLimit(LimitOrder::new(
                 valid_tick_id,
@@ -2673,7 +2955,10 @@ fn test_claim_order() {
             ),
             expected_bounty_msg: None,
             expected_order_state: None,
-            expected_error: Some(ContractError::ZeroClaim),
+            expected_error: Some(ContractError::NotYetReached {
+                tick_id: valid_tick_id,
+                order_id: 0,
+            }),
         },
         ClaimOrderTestCase {
             name: "zero claim amount (tick etas < order etas)",
@@ -2711,10 +2996,47 @@ fn test_claim_order() {
             ),
             expected_bounty_msg: None,
             expected_order_state: None,
-            expected_error: Some(ContractError::ZeroClaim),
+            expected_error: Some(ContractError::NotYetReached {
+                tick_id: valid_tick_id,
+                order_id: 1,
+            }),
+        },
+        ClaimOrderTestCase {
+            name: "not yet reached (deeply queued behind partial fill)",
+            sender: sender.clone(),
+            operations: vec![
+                OrderOperation::PlaceLimitMulti((
+                    vec![valid_tick_id],
+                    5,
+                    Uint128::from(10u128),
+                    OrderDirection::Ask,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(15u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            order_id: 4,
+
+            tick_id: valid_tick_id,
+            expected_bank_msg: SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: "cosmos2contract".to_string(),
+                    to_address: sender.to_string(),
+                    amount: vec![coin_u256(Uint256::from(5u128), QUOTE_DENOM)],
+                },
+                REPLY_ID_CLAIM,
+            ),
+            expected_bounty_msg: None,
+            expected_order_state: None,
+            expected_error: Some(ContractError::NotYetReached {
+                tick_id: valid_tick_id,
+                order_id: 4,
+            }),
         },
         ClaimOrderTestCase {
-            name: "zero claim amount (cancelled order larger etas than order)",
+            name: "not yet reached (cancelled order larger etas than order)",
             sender: sender.clone(),
             operations: vec![
                 OrderOperation::PlaceLimit(LimitOrder::new(
@@ -2750,56 +3072,173 @@ fn test_claim_order() {
             ),
             expected_bounty_msg: None,
             expected_order_state: None,
-            expected_error: Some(ContractError::ZeroClaim),
+            expected_error: Some(ContractError::NotYetReached {
+                tick_id: valid_tick_id,
+                order_id: 0,
+            }),
         },
-    ];
-
-    for test in test_cases {
-        // Test Setup
-        let mut deps = mock_dependencies_custom();
-        let env = mock_env();
-        let info = mock_info(test.sender.as_str(), &[]);
-        create_orderbook(
-            deps.as_mut(),
-            QUOTE_DENOM.to_string(),
-            BASE_DENOM.to_string(),
-        )
-        .unwrap();
-
-        // Run setup operations
-        for operation in test.operations {
-            operation
-                .run(deps.as_mut(), env.clone(), info.clone())
-                .unwrap();
-        }
-
-        // Claim designated order
-        let res = claim_limit(
-            deps.as_mut(),
-            env,
-            info,
-            test.tick_id,
-            test.order_id,
-        );
-
-        if let Some(err) = test.expected_error {
-            assert_eq!(res, Err(err), "{}", format_test_name(test.name));
-            continue;
-        }
-
-        let res = res.unwrap();
-
-        // Assert that the generated bank and bounty messages are as expected
-        assert_eq!(
-            res.messages[0],
-            test.expected_bank_msg,
-            "{}",
-            format_test_name(test.name)
-        );
+        ClaimOrderTestCase {
+            name: "zero claim amount (fill rounds to zero output)",
+            sender: sender.clone(),
+            operations: vec![
+                // A bid of 1 at a price of 0.5 is worth half a unit of base once filled
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    LARGE_NEGATIVE_TICK,
+                    0,
+                    OrderDirection::Bid,
+                    sender.clone(),
+                    Uint128::one(),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::one(),
+                    OrderDirection::Ask,
+                    sender.clone(),
+                )),
+            ],
+            order_id: 0,
 
-        if let Some(expected_bounty_msg) = test.expected_bounty_msg {
-            // Bounty message expected
-            assert_eq!((res.messages).len(), 2, "{}", format_test_name(test.name));
+            tick_id: LARGE_NEGATIVE_TICK,
+            expected_bank_msg: SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: "cosmos2contract".to_string(),
+                    to_address: sender.to_string(),
+                    amount: vec![coin_u256(Uint256::zero(), BASE_DENOM)],
+                },
+                REPLY_ID_CLAIM,
+            ),
+            expected_bounty_msg: None,
+            expected_order_state: None,
+            expected_error: Some(ContractError::ZeroClaim),
+        },
+        // Claims are permissionless: the principal goes to the order owner and the bounty to the claimer
+        ClaimOrderTestCase {
+            name: "ASK: keeper claims a stranger's fully filled order with max claim bounty",
+            sender: Addr::unchecked("keeper"),
+            operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    valid_tick_id,
+                    0,
+                    OrderDirection::Ask,
+                    Addr::unchecked("stranger"),
+                    Uint128::from(1000u128),
+                    Decimal256::zero(),
+                    Some(Decimal256::percent(1)),
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(1000u128),
+                    OrderDirection::Bid,
+                    Addr::unchecked("buyer"),
+                )),
+            ],
+            order_id: 0,
+            tick_id: valid_tick_id,
+            expected_bank_msg: SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: "cosmos2contract".to_string(),
+                    to_address: "stranger".to_string(),
+                    amount: vec![coin_u256(Uint256::from(990u128), QUOTE_DENOM)],
+                },
+                REPLY_ID_CLAIM,
+            ),
+            expected_bounty_msg: Some(SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: "cosmos2contract".to_string(),
+                    to_address: "keeper".to_string(),
+                    amount: vec![coin_u256(Uint256::from(10u128), QUOTE_DENOM)],
+                },
+                REPLY_ID_CLAIM_BOUNTY,
+            )),
+            expected_order_state: None,
+            expected_error: None,
+        },
+        ClaimOrderTestCase {
+            name: "ASK: keeper claims an already claimed order",
+            sender: Addr::unchecked("keeper"),
+            operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    valid_tick_id,
+                    0,
+                    OrderDirection::Ask,
+                    Addr::unchecked("stranger"),
+                    Uint128::from(1000u128),
+                    Decimal256::zero(),
+                    Some(Decimal256::percent(1)),
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(500u128),
+                    OrderDirection::Bid,
+                    Addr::unchecked("buyer"),
+                )),
+                OrderOperation::Claim((valid_tick_id, 0)),
+            ],
+            order_id: 0,
+            tick_id: valid_tick_id,
+            expected_bank_msg: SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: "cosmos2contract".to_string(),
+                    to_address: "stranger".to_string(),
+                    amount: vec![coin_u256(Uint256::from(495u128), QUOTE_DENOM)],
+                },
+                REPLY_ID_CLAIM,
+            ),
+            expected_bounty_msg: None,
+            expected_order_state: None,
+            expected_error: Some(ContractError::NotYetReached {
+                tick_id: valid_tick_id,
+                order_id: 0,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // Test Setup
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(test.sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        // Run setup operations
+        for operation in test.operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // Claim designated order
+        let res = claim_limit(
+            deps.as_mut(),
+            env,
+            info,
+            test.tick_id,
+            test.order_id,
+            None,
+        );
+
+        if let Some(err) = test.expected_error {
+            assert_eq!(res, Err(err), "{}", format_test_name(test.name));
+            continue;
+        }
+
+        let res = res.unwrap();
+
+        // Assert that the generated bank and bounty messages are as expected
+        assert_eq!(
+            res.messages[0],
+            test.expected_bank_msg,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        if let Some(expected_bounty_msg) = test.expected_bounty_msg {
+            // Bounty message expected
+            assert_eq!((res.messages).len(), 2, "{}", format_test_name(test.name));
             assert_eq!(
                 res.messages[1],
                 expected_bounty_msg,
@@ -2947,7 +3386,7 @@ fn test_claim_order_moving_tick() {
                 Uint128::from(25u128),
                 decimal256_from_u128(25u128),
                 None,
-            ).with_placed_quantity(50u128)),
+            ).with_placed_quantity(50u128).with_claimed(25u128, 25u128)),
             expected_error: None,
         },
         MovingClaimOrderTestCase {
@@ -3019,7 +3458,7 @@ fn test_claim_order_moving_tick() {
                 Uint128::from(25u128),
                 decimal256_from_u128(50u128),
                 None,
-            ).with_placed_quantity(50u128)),
+            ).with_placed_quantity(50u128).with_claimed(25u128, 25u128)),
             expected_error: None,
         },
         MovingClaimOrderTestCase {
@@ -3074,7 +3513,7 @@ fn test_claim_order_moving_tick() {
                 Uint128::from(25u128),
                 decimal256_from_u128(25u128),
                 None,
-            ).with_placed_quantity(50u128)),
+            ).with_placed_quantity(50u128).with_claimed(25u128, 25u128)),
             expected_error: None,
         },
         MovingClaimOrderTestCase {
@@ -3254,7 +3693,7 @@ fn test_claim_order_moving_tick() {
                 Uint128::from(25u128),
                 decimal256_from_u128(25u128),
                 None,
-            ).with_placed_quantity(50u128)),
+            ).with_placed_quantity(50u128).with_claimed(25u128, 25u128)),
             expected_error: None,
         },
         MovingClaimOrderTestCase {
@@ -3326,7 +3765,7 @@ fn test_claim_order_moving_tick() {
                 Uint128::from(25u128),
                 decimal256_from_u128(50u128),
                 None,
-            ).with_placed_quantity(50u128)),
+            ).with_placed_quantity(50u128).with_claimed(25u128, 25u128)),
             expected_error: None,
         },
         MovingClaimOrderTestCase {
@@ -3382,7 +3821,7 @@ fn test_claim_order_moving_tick() {
                 Uint128::from(25u128),
                 decimal256_from_u128(25u128),
                 None,
-            ).with_placed_quantity(50u128)),
+            ).with_placed_quantity(50u128).with_claimed(25u128, 25u128)),
             expected_error: None,
         },
         MovingClaimOrderTestCase {
@@ -3480,6 +3919,7 @@ fn test_claim_order_moving_tick() {
             info,
             test.tick_id,
             test.order_id,
+            None,
         );
 
         if let Some(err) = test.expected_error {
@@ -3511,6 +3951,96 @@ fn test_claim_order_moving_tick() {
     }
 }
 
+#[test]
+fn test_claim_order_owner_bounty_merged() {
+    let owner = Addr::unchecked(DEFAULT_OWNER);
+    let env = mock_env();
+    let info = mock_info(DEFAULT_SENDER, &[]);
+
+    for (claimer, expected_msgs) in [
+        // The owner receives the principal and bounty in a single send
+        (
+            owner.clone(),
+            vec![SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: env.contract.address.to_string(),
+                    to_address: owner.to_string(),
+                    amount: vec![coin_u256(100u128, QUOTE_DENOM)],
+                },
+                REPLY_ID_CLAIM,
+            )],
+        ),
+        // Other claimers are paid the bounty separately
+        (
+            Addr::unchecked("keeper"),
+            vec![
+                SubMsg::reply_on_error(
+                    MsgSend256 {
+                        from_address: env.contract.address.to_string(),
+                        to_address: owner.to_string(),
+                        amount: vec![coin_u256(99u128, QUOTE_DENOM)],
+                    },
+                    REPLY_ID_CLAIM,
+                ),
+                SubMsg::reply_on_error(
+                    MsgSend256 {
+                        from_address: env.contract.address.to_string(),
+                        to_address: "keeper".to_string(),
+                        amount: vec![coin_u256(1u128, QUOTE_DENOM)],
+                    },
+                    REPLY_ID_CLAIM_BOUNTY,
+                ),
+            ],
+        ),
+    ] {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        let operations = vec![
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Ask,
+                owner.clone(),
+                Uint128::from(100u128),
+                Decimal256::zero(),
+                Some(Decimal256::percent(1)),
+            )),
+            OrderOperation::RunMarket(MarketOrder::new(
+                Uint128::from(100u128),
+                OrderDirection::Bid,
+                Addr::unchecked("buyer"),
+            )),
+        ];
+        for operation in operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let (amount, msgs, _) = claim_order(
+            deps.as_mut().storage,
+            env.contract.address.clone(),
+            claimer.clone(),
+            0,
+            0,
+            None,
+        )
+        .unwrap();
+
+        // -- Post test assertions --
+        assert_eq!(msgs, expected_msgs, "claimer {claimer}");
+        let expected_amount = if claimer == owner { 100u128 } else { 99u128 };
+        assert_eq!(amount, Uint256::from(expected_amount), "claimer {claimer}");
+    }
+}
+
 struct BatchClaimOrderTestCase {
     name: &'static str,
     operations: Vec<OrderOperation>,
@@ -3693,7 +4223,7 @@ fn test_batch_claim_order() {
         let info = mock_info(sender.as_str(), &[]);
 
         // Batch claim orders
-        let res = batch_claim_limits(deps.as_mut(), info.clone(), env, test.orders.clone());
+        let res = batch_claim_limits(deps.as_mut(), info.clone(), env, test.orders.clone(), None);
 
         if let Some(err) = test.expected_error {
             assert_eq!(res, Err(err), "{}", format_test_name(test.name));
@@ -4007,12 +4537,13 @@ fn test_maker_fee() {
         },
         MakerFeeTestCase {
             name: "Basic Maker Fee Test w/ bounty",
-            placed_order: LimitOrder::new(0, 0, OrderDirection::Bid, sender.clone(), Uint128::from(100u128), Decimal256::zero(), Some(Decimal256::percent(1))),
+            // Owned by another address so the bounty is paid separately to the claimer
+            placed_order: LimitOrder::new(0, 0, OrderDirection::Bid, Addr::unchecked(DEFAULT_OWNER), Uint128::from(100u128), Decimal256::zero(), Some(Decimal256::percent(1))),
             maker_fee: Some(Decimal256::percent(2)), // 2% maker fee
             maker_fee_recipient: Some(maker_fee_recipient.clone()),
             expected_claimer_msg: MsgSend256 {
                 from_address: env.contract.address.to_string(),
-                to_address: sender.to_string(),
+                to_address: DEFAULT_OWNER.to_string(),
                 amount: vec![coin_u256(97u32, BASE_DENOM)], // 100 - 2% maker fee - 1% claim bounty
             },
             expected_maker_fee_msg: Some(MsgSend256 {
@@ -4112,6 +4643,7 @@ fn test_maker_fee() {
             effective_total_amount_swapped: decimal256_from_u128(test.placed_order.quantity),
             cumulative_realized_cancels: decimal256_from_u128(0u128),
             last_tick_sync_etas: decimal256_from_u128(test.placed_order.quantity),
+            hidden_liquidity: Decimal256::zero(),
         };
 
         tick_state.set_values(test.placed_order.order_direction, tick_values);
@@ -4124,6 +4656,7 @@ fn test_maker_fee() {
             sender.clone(),
             test.placed_order.tick_id,
             test.placed_order.order_id,
+            None,
         );
 
         // -- Post test assertions --
@@ -4159,3 +4692,4892 @@ fn test_maker_fee() {
 
 }
 
+#[test]
+fn test_order_lifecycle_quantities() {
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(DEFAULT_OWNER, &[]);
+    let tick_id = 0;
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // Asserts (remaining quantity, placed quantity, claimed quantity, claimed output)
+    let assert_order = |storage: &dyn Storage, expected: (u128, u128, u128, u128), stage: &str| {
+        let order = orders().load(storage, &(tick_id, 0)).unwrap();
+        assert_eq!(
+            (
+                order.quantity,
+                order.placed_quantity,
+                order.claimed_quantity,
+                order.claimed_output
+            ),
+            (
+                Uint128::from(expected.0),
+                Uint128::from(expected.1),
+                Uint128::from(expected.2),
+                Uint256::from(expected.3)
+            ),
+            "{}",
+            stage
+        );
+    };
+
+    // Place
+    OrderOperation::PlaceLimit(LimitOrder::new(
+        tick_id,
+        0,
+        OrderDirection::Ask,
+        Addr::unchecked(DEFAULT_OWNER),
+        Uint128::from(100u128),
+        Decimal256::zero(),
+        None,
+    ))
+    .run(deps.as_mut(), env.clone(), info.clone())
+    .unwrap();
+    assert_order(&deps.storage, (100, 100, 0, 0), "place");
+
+    // Fill, the order is unchanged until claimed
+    OrderOperation::RunMarket(MarketOrder::new(
+        Uint128::from(60u128),
+        OrderDirection::Bid,
+        Addr::unchecked(DEFAULT_SENDER),
+    ))
+    .run(deps.as_mut(), env.clone(), info.clone())
+    .unwrap();
+    assert_order(&deps.storage, (100, 100, 0, 0), "fill");
+
+    // Claim
+    OrderOperation::Claim((tick_id, 0))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+    assert_order(&deps.storage, (40, 100, 60, 60), "claim");
+
+    // Orders stored before claims were tracked load with zeroed claim totals
+    let legacy_order: LimitOrder = cosmwasm_std::from_json(
+        r#"{"tick_id":0,"order_id":0,"order_direction":"ask","owner":"owner","quantity":"40","etas":"60","claim_bounty":null,"placed_quantity":"100"}"#,
+    )
+    .unwrap();
+    assert_eq!(legacy_order.claimed_quantity, Uint128::zero());
+    assert_eq!(legacy_order.claimed_output, Uint256::zero());
+
+    // Migration infers the claim totals from the placed and remaining quantity
+    let claimed_order = orders().load(&deps.storage, &(tick_id, 0)).unwrap();
+    let untracked_order = LimitOrder {
+        claimed_quantity: Uint128::zero(),
+        claimed_output: Uint256::zero(),
+        ..claimed_order
+    };
+    orders()
+        .save(&mut deps.storage, &(tick_id, 0), &untracked_order)
+        .unwrap();
+    let backfilled = backfill_claimed_totals(&mut deps.storage).unwrap();
+    assert_eq!(backfilled, 1);
+    assert_order(&deps.storage, (40, 100, 60, 60), "backfill");
+
+    // Orders with tracked claims are left untouched
+    let backfilled = backfill_claimed_totals(&mut deps.storage).unwrap();
+    assert_eq!(backfilled, 0);
+}
+
+#[test]
+fn test_place_limit_tick_bounds() {
+    let (min_tick, max_tick) = (-1000, 1000);
+    let quantity = Uint128::from(100u128);
+    // (tick id, expected to be accepted)
+    let placements = vec![
+        (min_tick, true),
+        (max_tick, true),
+        (min_tick - 1, false),
+        (max_tick + 1, false),
+        // Within the compile-time limits but outside the configured bounds
+        (MIN_TICK, false),
+        (MAX_TICK, false),
+    ];
+
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    create_orderbook(deps.as_mut(), QUOTE_DENOM.to_string(), BASE_DENOM.to_string()).unwrap();
+    set_tick_bounds(deps.as_mut().storage, min_tick, max_tick).unwrap();
+
+    // Configured bounds may never exceed the compile-time limits
+    assert_eq!(
+        set_tick_bounds(deps.as_mut().storage, MIN_TICK - 1, max_tick).unwrap_err(),
+        ContractError::InvalidTickBounds {
+            min_tick: MIN_TICK - 1,
+            max_tick
+        }
+    );
+    assert_eq!(
+        get_tick_bounds(deps.as_ref().storage).unwrap(),
+        (min_tick, max_tick)
+    );
+
+    for (tick_id, accepted) in placements {
+        let res = place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info(DEFAULT_OWNER, &[coin(quantity.u128(), BASE_DENOM)]),
+            tick_id,
+            OrderDirection::Ask,
+            quantity,
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+        );
+
+        if accepted {
+            assert!(res.is_ok(), "tick {tick_id} should be accepted");
+        } else {
+            assert_eq!(
+                res.unwrap_err(),
+                ContractError::InvalidTickId { tick_id },
+                "tick {tick_id} should be rejected"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_market_order_tick_bounds() {
+    let (min_tick, max_tick) = (-1000, 1000);
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+    set_tick_bounds(deps.as_mut().storage, min_tick, max_tick).unwrap();
+    OrderOperation::PlaceLimit(LimitOrder::new(
+        0,
+        0,
+        OrderDirection::Ask,
+        sender.clone(),
+        Uint128::from(100u128),
+        Decimal256::zero(),
+        None,
+    ))
+    .run(deps.as_mut(), env.clone(), info.clone())
+    .unwrap();
+
+    // Market orders default to the configured bounds rather than the compile-time limits
+    assert_eq!(
+        get_market_tick_bound(deps.as_ref().storage, OrderDirection::Bid).unwrap(),
+        max_tick
+    );
+    assert_eq!(
+        get_market_tick_bound(deps.as_ref().storage, OrderDirection::Ask).unwrap(),
+        min_tick
+    );
+
+    // A bound outside the configured range is rejected even within the compile-time limits
+    let mut market_order =
+        MarketOrder::new(Uint128::from(50u128), OrderDirection::Bid, sender.clone());
+    let res = simulate_market_order(
+        deps.as_ref().storage,
+        &mut market_order,
+        MAX_TICK,
+        None,
+        env.block.time,
+    );
+    assert!(matches!(
+        res,
+        Err(ContractError::InvalidTickId { tick_id: MAX_TICK })
+    ));
+
+    let (output, ..) = run_market_order(
+        deps.as_mut().storage,
+        env.contract.address.clone(),
+        env.block.time,
+        &mut market_order,
+        max_tick,
+        false,
+        None,
+    )
+    .unwrap();
+    assert_eq!(output, Uint256::from(50u128));
+
+    // The empty bid side's pointer sits at the configured bounds, so market orders against it
+    // run out of liquidity rather than rejecting the default bound
+    let orderbook = ORDERBOOK.load(deps.as_ref().storage).unwrap();
+    assert_eq!(orderbook.next_bid_tick, min_tick);
+    let mut market_order =
+        MarketOrder::new(Uint128::from(50u128), OrderDirection::Ask, sender.clone());
+    let res = run_market_order(
+        deps.as_mut().storage,
+        env.contract.address.clone(),
+        env.block.time,
+        &mut market_order,
+        min_tick,
+        false,
+        None,
+    );
+    assert_eq!(res.unwrap_err(), ContractError::InsufficientLiquidity);
+
+    // Bounds set before the orderbook is created also place the pointers at the bounds
+    let mut deps = mock_dependencies_custom();
+    set_tick_bounds(deps.as_mut().storage, min_tick, max_tick).unwrap();
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+    let orderbook = ORDERBOOK.load(deps.as_ref().storage).unwrap();
+    assert_eq!(
+        (orderbook.next_bid_tick, orderbook.next_ask_tick),
+        (min_tick, max_tick)
+    );
+}
+
+struct PlaceLimitBatchTestCase {
+    name: &'static str,
+    orders: Vec<PlaceLimitInput>,
+    sent: Vec<Coin>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_place_limit_batch() {
+    let quantity = Uint128::from(10u128);
+    // Five asks above and five bids below tick 0
+    let valid_orders: Vec<PlaceLimitInput> = (1..=5)
+        .map(|tick_id| PlaceLimitInput {
+            tick_id,
+            order_direction: OrderDirection::Ask,
+            quantity,
+            claim_bounty: None,
+        })
+        .chain((1..=5).map(|tick_id| PlaceLimitInput {
+            tick_id: -tick_id,
+            order_direction: OrderDirection::Bid,
+            quantity,
+            claim_bounty: None,
+        }))
+        .collect();
+    let valid_funds = vec![coin(50, BASE_DENOM), coin(50, QUOTE_DENOM)];
+    let with_invalid_order = |invalid_order: PlaceLimitInput| {
+        let mut orders = valid_orders.clone();
+        orders[5] = invalid_order;
+        orders
+    };
+
+    let test_cases = vec![
+        PlaceLimitBatchTestCase {
+            name: "valid mixed batch",
+            orders: valid_orders.clone(),
+            sent: valid_funds.clone(),
+            expected_error: None,
+        },
+        PlaceLimitBatchTestCase {
+            name: "zero quantity order in the middle",
+            orders: with_invalid_order(PlaceLimitInput {
+                tick_id: -1,
+                order_direction: OrderDirection::Bid,
+                quantity: Uint128::zero(),
+                claim_bounty: None,
+            }),
+            sent: vec![coin(50, BASE_DENOM), coin(40, QUOTE_DENOM)],
+            expected_error: Some(ContractError::InvalidQuantity {
+                quantity: Uint128::zero(),
+            }),
+        },
+        PlaceLimitBatchTestCase {
+            name: "invalid tick in the middle",
+            orders: with_invalid_order(PlaceLimitInput {
+                tick_id: MIN_TICK - 1,
+                order_direction: OrderDirection::Bid,
+                quantity,
+                claim_bounty: None,
+            }),
+            sent: valid_funds.clone(),
+            expected_error: Some(ContractError::InvalidTickId {
+                tick_id: MIN_TICK - 1,
+            }),
+        },
+        PlaceLimitBatchTestCase {
+            name: "insufficient quote funds",
+            orders: valid_orders.clone(),
+            sent: vec![coin(50, BASE_DENOM), coin(49, QUOTE_DENOM)],
+            expected_error: Some(ContractError::InsufficientFunds {
+                sent: Uint128::from(49u128),
+                required: Uint128::from(50u128),
+            }),
+        },
+        PlaceLimitBatchTestCase {
+            name: "missing base funds",
+            orders: valid_orders.clone(),
+            sent: vec![coin(50, QUOTE_DENOM)],
+            expected_error: Some(ContractError::InsufficientFunds {
+                sent: Uint128::zero(),
+                required: Uint128::from(50u128),
+            }),
+        },
+        PlaceLimitBatchTestCase {
+            name: "unrelated denom attached",
+            orders: valid_orders.clone(),
+            sent: vec![
+                coin(50, BASE_DENOM),
+                coin(50, QUOTE_DENOM),
+                coin(1, "uother"),
+            ],
+            expected_error: Some(ContractError::PaymentError(PaymentError::ExtraDenom(
+                "uother".to_string(),
+            ))),
+        },
+        PlaceLimitBatchTestCase {
+            name: "empty batch",
+            orders: vec![],
+            sent: vec![],
+            expected_error: Some(ContractError::InvalidQuantity {
+                quantity: Uint128::zero(),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // --- Setup ---
+
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_OWNER, &test.sent);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        // --- System under test ---
+
+        let res = place_limit_batch(deps.as_mut(), env, info, test.orders.clone());
+
+        // --- Assertions ---
+
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+
+            // Ensure no part of the batch was written to state
+            assert_eq!(
+                ORDER_ID.may_load(deps.as_ref().storage).unwrap(),
+                None,
+                "{}",
+                format_test_name(test.name)
+            );
+            assert_eq!(
+                TICK_STATE
+                    .keys(
+                        deps.as_ref().storage,
+                        None,
+                        None,
+                        cosmwasm_std::Order::Ascending
+                    )
+                    .count(),
+                0,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let res = res.unwrap();
+        let expected_ids: Vec<String> = (0..test.orders.len()).map(|id| id.to_string()).collect();
+        assert_eq!(
+            res.attributes[3],
+            ("order_ids", expected_ids.join(",")),
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Each order is saved on its tick with the expected liquidity
+        for (order_id, input) in test.orders.iter().enumerate() {
+            let order = orders()
+                .load(deps.as_ref().storage, &(input.tick_id, order_id as u64))
+                .unwrap();
+            assert_eq!(
+                order.order_direction,
+                input.order_direction,
+                "{}",
+                format_test_name(test.name)
+            );
+            assert_eq!(
+                order.quantity,
+                input.quantity,
+                "{}",
+                format_test_name(test.name)
+            );
+        }
+        for direction in [OrderDirection::Ask, OrderDirection::Bid] {
+            assert_eq!(
+                get_directional_liquidity(deps.as_ref().storage, direction).unwrap(),
+                decimal256_from_u128(50u128),
+                "{}",
+                format_test_name(test.name)
+            );
+        }
+        let orderbook = ORDERBOOK.load(deps.as_ref().storage).unwrap();
+        assert_eq!(
+            orderbook.next_ask_tick,
+            1,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            orderbook.next_bid_tick,
+            -1,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+struct ClaimMinimumTestCase {
+    name: &'static str,
+    orders: Vec<(i64, u64)>,
+    min_claim: Option<Uint128>,
+    // Orders expected to remain in state after the batch claim
+    expected_remaining: Vec<(i64, u64)>,
+}
+
+#[test]
+fn test_batch_claim_min_claim() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    // Three asks of mixed size on tick 0, all fully filled
+    let operations = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            1,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            2,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(160u128),
+            OrderDirection::Bid,
+            sender.clone(),
+        )),
+    ];
+    let test_cases = vec![
+        ClaimMinimumTestCase {
+            name: "no minimum claims all",
+            orders: vec![(0, 0), (0, 1), (0, 2)],
+            min_claim: None,
+            expected_remaining: vec![],
+        },
+        ClaimMinimumTestCase {
+            name: "minimum skips dust claim",
+            orders: vec![(0, 0), (0, 1), (0, 2)],
+            min_claim: Some(Uint128::from(50u128)),
+            expected_remaining: vec![(0, 1)],
+        },
+        ClaimMinimumTestCase {
+            name: "minimum above all claims",
+            orders: vec![(0, 0), (0, 1), (0, 2)],
+            min_claim: Some(Uint128::from(101u128)),
+            expected_remaining: vec![(0, 0), (0, 1), (0, 2)],
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for operation in operations.clone() {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = batch_claim_limits(
+            deps.as_mut(),
+            info.clone(),
+            env.clone(),
+            test.orders.clone(),
+            test.min_claim,
+        )
+        .unwrap();
+
+        // -- Post test assertions --
+        let claimed_count = test.orders.len() - test.expected_remaining.len();
+        assert_eq!(
+            res.events.len(),
+            claimed_count,
+            "{}",
+            format_test_name(test.name)
+        );
+        for key in &test.orders {
+            let exists = orders().has(deps.as_ref().storage, key);
+            assert_eq!(
+                exists,
+                test.expected_remaining.contains(key),
+                "{}: order {:?}",
+                format_test_name(test.name),
+                key
+            );
+        }
+
+        // Single claims below the minimum are rejected rather than skipped
+        for (tick_id, order_id) in test.expected_remaining {
+            let order = orders()
+                .load(deps.as_ref().storage, &(tick_id, order_id))
+                .unwrap();
+            let res = claim_limit(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                tick_id,
+                order_id,
+                test.min_claim,
+            );
+            assert_eq!(
+                res,
+                Err(ContractError::ClaimBelowMinimum {
+                    tick_id,
+                    order_id,
+                    amount: Uint256::from_uint128(order.quantity),
+                    min_claim: test.min_claim.unwrap(),
+                }),
+                "{}",
+                format_test_name(test.name)
+            );
+        }
+    }
+}
+struct AmendLimitTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    sender: &'static str,
+    order_id: u64,
+    new_quantity: Uint128,
+    sent: Vec<Coin>,
+    expected_refund: Option<Uint128>,
+    // Expected (order_id, quantity, etas) of orders on the tick after amending
+    expected_orders: Vec<(u64, u128, Decimal256)>,
+    expected_liquidity: u128,
+    // Market bid run after amending and the expected claim for each order, zero if unfilled
+    post_fill: u128,
+    expected_claims: Vec<(u64, u128)>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_amend_limit() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let place_orders = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            1,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        )),
+    ];
+    let fill = |quantity: u128| {
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(quantity),
+            OrderDirection::Bid,
+            sender.clone(),
+        ))
+    };
+    let test_cases = vec![
+        AmendLimitTestCase {
+            name: "decrease keeps queue priority",
+            pre_operations: place_orders.clone(),
+            sender: DEFAULT_SENDER,
+            order_id: 0,
+            new_quantity: Uint128::from(40u128),
+            sent: vec![],
+            expected_refund: Some(Uint128::from(60u128)),
+            expected_orders: vec![
+                (0, 40, decimal256_from_u128(0u128)),
+                (1, 50, decimal256_from_u128(100u128)),
+            ],
+            expected_liquidity: 90,
+            post_fill: 60,
+            expected_claims: vec![(0, 40), (1, 20)],
+            expected_error: None,
+        },
+        AmendLimitTestCase {
+            name: "decrease partially filled order",
+            pre_operations: [place_orders.clone(), vec![fill(70)]].concat(),
+            sender: DEFAULT_SENDER,
+            order_id: 0,
+            new_quantity: Uint128::from(80u128),
+            sent: vec![],
+            expected_refund: Some(Uint128::from(20u128)),
+            expected_orders: vec![
+                (0, 80, decimal256_from_u128(0u128)),
+                (1, 50, decimal256_from_u128(100u128)),
+            ],
+            expected_liquidity: 60,
+            post_fill: 30,
+            expected_claims: vec![(0, 80), (1, 20)],
+            expected_error: None,
+        },
+        AmendLimitTestCase {
+            name: "increase last order in place",
+            pre_operations: place_orders.clone(),
+            sender: DEFAULT_SENDER,
+            order_id: 1,
+            new_quantity: Uint128::from(80u128),
+            sent: vec![coin(30, BASE_DENOM)],
+            expected_refund: None,
+            expected_orders: vec![
+                (0, 100, decimal256_from_u128(0u128)),
+                (1, 80, decimal256_from_u128(100u128)),
+            ],
+            expected_liquidity: 180,
+            post_fill: 180,
+            expected_claims: vec![(0, 100), (1, 80)],
+            expected_error: None,
+        },
+        AmendLimitTestCase {
+            name: "increase queued order appends new order",
+            pre_operations: place_orders.clone(),
+            sender: DEFAULT_SENDER,
+            order_id: 0,
+            new_quantity: Uint128::from(120u128),
+            sent: vec![coin(20, BASE_DENOM)],
+            expected_refund: None,
+            expected_orders: vec![
+                (0, 100, decimal256_from_u128(0u128)),
+                (1, 50, decimal256_from_u128(100u128)),
+                (2, 20, decimal256_from_u128(150u128)),
+            ],
+            expected_liquidity: 170,
+            post_fill: 150,
+            expected_claims: vec![(0, 100), (1, 50), (2, 0)],
+            expected_error: None,
+        },
+        AmendLimitTestCase {
+            name: "decrease below filled amount",
+            pre_operations: [place_orders.clone(), vec![fill(70)]].concat(),
+            sender: DEFAULT_SENDER,
+            order_id: 0,
+            new_quantity: Uint128::from(50u128),
+            sent: vec![],
+            expected_refund: None,
+            expected_orders: vec![],
+            expected_liquidity: 0,
+            post_fill: 0,
+            expected_claims: vec![],
+            expected_error: Some(ContractError::AmendFilledOrder {
+                filled: Uint128::from(70u128),
+                new_quantity: Uint128::from(50u128),
+            }),
+        },
+        AmendLimitTestCase {
+            name: "increase with insufficient funds",
+            pre_operations: place_orders.clone(),
+            sender: DEFAULT_SENDER,
+            order_id: 1,
+            new_quantity: Uint128::from(80u128),
+            sent: vec![coin(20, BASE_DENOM)],
+            expected_refund: None,
+            expected_orders: vec![],
+            expected_liquidity: 0,
+            post_fill: 0,
+            expected_claims: vec![],
+            expected_error: Some(ContractError::InsufficientFunds {
+                sent: Uint128::from(20u128),
+                required: Uint128::from(30u128),
+            }),
+        },
+        AmendLimitTestCase {
+            name: "unchanged quantity",
+            pre_operations: place_orders.clone(),
+            sender: DEFAULT_SENDER,
+            order_id: 1,
+            new_quantity: Uint128::from(50u128),
+            sent: vec![],
+            expected_refund: None,
+            expected_orders: vec![],
+            expected_liquidity: 0,
+            post_fill: 0,
+            expected_claims: vec![],
+            expected_error: Some(ContractError::InvalidQuantity {
+                quantity: Uint128::from(50u128),
+            }),
+        },
+        AmendLimitTestCase {
+            name: "sender is not owner",
+            pre_operations: place_orders.clone(),
+            sender: DEFAULT_OWNER,
+            order_id: 0,
+            new_quantity: Uint128::from(40u128),
+            sent: vec![],
+            expected_refund: None,
+            expected_orders: vec![],
+            expected_liquidity: 0,
+            post_fill: 0,
+            expected_claims: vec![],
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for operation in test.pre_operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = amend_limit(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(test.sender, &test.sent),
+            0,
+            test.order_id,
+            test.new_quantity,
+        );
+
+        // -- Post test assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(res, Err(err), "{}", format_test_name(test.name));
+            continue;
+        }
+        let res = res.unwrap();
+
+        let expected_messages: Vec<SubMsg> = test
+            .expected_refund
+            .map(|refund| {
+                SubMsg::reply_on_error(
+                    BankMsg::Send {
+                        to_address: sender.to_string(),
+                        amount: vec![coin(refund.u128(), BASE_DENOM)],
+                    },
+                    REPLY_ID_REFUND,
+                )
+            })
+            .into_iter()
+            .collect();
+        assert_eq!(
+            res.messages,
+            expected_messages,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        let tick_orders: Vec<(u64, u128, Decimal256)> = orders()
+            .prefix(0)
+            .range(
+                deps.as_ref().storage,
+                None,
+                None,
+                cosmwasm_std::Order::Ascending,
+            )
+            .map(|item| {
+                let (_, order) = item.unwrap();
+                (order.order_id, order.quantity.u128(), order.etas)
+            })
+            .collect();
+        assert_eq!(
+            tick_orders,
+            test.expected_orders,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        let liquidity = TICK_STATE
+            .load(deps.as_ref().storage, 0)
+            .unwrap()
+            .get_values(OrderDirection::Ask)
+            .total_amount_of_liquidity;
+        assert_eq!(
+            liquidity,
+            decimal256_from_u128(test.expected_liquidity),
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Fills after amending must respect the original queue order
+        fill(test.post_fill)
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+        for (order_id, expected_claim) in test.expected_claims {
+            let res = claim_order(
+                deps.as_mut().storage,
+                env.contract.address.clone(),
+                sender.clone(),
+                0,
+                order_id,
+                None,
+            );
+            if expected_claim == 0 {
+                assert!(res.is_err(), "{}", format_test_name(test.name));
+                continue;
+            }
+            let (amount, ..) = res.unwrap();
+            assert_eq!(
+                amount,
+                Uint256::from(expected_claim),
+                "{}: order {}",
+                format_test_name(test.name),
+                order_id
+            );
+        }
+    }
+}
+
+struct AmendIncreaseValidationTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    paused: bool,
+    min_order_size: u128,
+    disallow_same_tick_opposing: bool,
+    new_quantity: Uint128,
+    expected_error: ContractError,
+}
+
+#[test]
+fn test_amend_limit_increase_validation() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let place = |order_id: u64, direction: OrderDirection| {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            order_id,
+            direction,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        ))
+    };
+    let test_cases = vec![
+        AmendIncreaseValidationTestCase {
+            name: "increase while paused",
+            pre_operations: vec![place(0, OrderDirection::Ask)],
+            paused: true,
+            min_order_size: 0,
+            disallow_same_tick_opposing: false,
+            new_quantity: Uint128::from(150u128),
+            expected_error: ContractError::Paused,
+        },
+        AmendIncreaseValidationTestCase {
+            name: "added quantity below minimum order size",
+            pre_operations: vec![place(0, OrderDirection::Ask)],
+            paused: false,
+            min_order_size: 50,
+            disallow_same_tick_opposing: false,
+            new_quantity: Uint128::from(120u128),
+            expected_error: ContractError::OrderTooSmall {
+                size: Uint128::from(20u128),
+                minimum: Uint128::from(50u128),
+            },
+        },
+        AmendIncreaseValidationTestCase {
+            name: "in place growth on tick with opposing liquidity",
+            pre_operations: vec![place(0, OrderDirection::Ask), place(1, OrderDirection::Bid)],
+            paused: false,
+            min_order_size: 0,
+            disallow_same_tick_opposing: true,
+            new_quantity: Uint128::from(150u128),
+            expected_error: ContractError::SameTickOpposing { tick_id: 0 },
+        },
+        AmendIncreaseValidationTestCase {
+            name: "new order on tick with opposing liquidity",
+            pre_operations: vec![
+                place(0, OrderDirection::Ask),
+                place(1, OrderDirection::Ask),
+                place(2, OrderDirection::Bid),
+            ],
+            paused: false,
+            min_order_size: 0,
+            disallow_same_tick_opposing: true,
+            new_quantity: Uint128::from(150u128),
+            expected_error: ContractError::SameTickOpposing { tick_id: 0 },
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for operation in test.pre_operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+        PAUSED.save(deps.as_mut().storage, &test.paused).unwrap();
+        set_min_order_size(deps.as_mut().storage, Uint128::from(test.min_order_size)).unwrap();
+        DISALLOW_SAME_TICK_OPPOSING
+            .save(deps.as_mut().storage, &test.disallow_same_tick_opposing)
+            .unwrap();
+        let ask_liquidity_before = TICK_STATE
+            .load(deps.as_ref().storage, 0)
+            .unwrap()
+            .get_values(OrderDirection::Ask)
+            .total_amount_of_liquidity;
+
+        // -- System under test --
+        let res = amend_limit(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(sender.as_str(), &[coin(50, BASE_DENOM)]),
+            0,
+            0,
+            test.new_quantity,
+        );
+
+        // -- Post test assertions --
+        assert_eq!(
+            res,
+            Err(test.expected_error),
+            "{}",
+            format_test_name(test.name)
+        );
+        let ask_liquidity = TICK_STATE
+            .load(deps.as_ref().storage, 0)
+            .unwrap()
+            .get_values(OrderDirection::Ask)
+            .total_amount_of_liquidity;
+        assert_eq!(
+            ask_liquidity,
+            ask_liquidity_before,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct PostOnlyTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    tick_id: i64,
+    order_direction: OrderDirection,
+    post_only: Option<bool>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_place_limit_post_only() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let quantity = Uint128::from(100u128);
+    let resting_ask = OrderOperation::PlaceLimit(LimitOrder::new(
+        10,
+        0,
+        OrderDirection::Ask,
+        sender.clone(),
+        quantity,
+        Decimal256::zero(),
+        None,
+    ));
+    let resting_bid = OrderOperation::PlaceLimit(LimitOrder::new(
+        -10,
+        1,
+        OrderDirection::Bid,
+        sender.clone(),
+        quantity,
+        Decimal256::zero(),
+        None,
+    ));
+    let test_cases = vec![
+        PostOnlyTestCase {
+            name: "bid below best ask rests",
+            pre_operations: vec![resting_ask.clone(), resting_bid.clone()],
+            tick_id: 5,
+            order_direction: OrderDirection::Bid,
+            post_only: Some(true),
+            expected_error: None,
+        },
+        PostOnlyTestCase {
+            name: "bid at best ask crosses",
+            pre_operations: vec![resting_ask.clone(), resting_bid.clone()],
+            tick_id: 10,
+            order_direction: OrderDirection::Bid,
+            post_only: Some(true),
+            expected_error: Some(ContractError::WouldCross {
+                tick_id: 10,
+                best_opposing_tick: 10,
+            }),
+        },
+        PostOnlyTestCase {
+            name: "bid above best ask crosses",
+            pre_operations: vec![resting_ask.clone(), resting_bid.clone()],
+            tick_id: 20,
+            order_direction: OrderDirection::Bid,
+            post_only: Some(true),
+            expected_error: Some(ContractError::WouldCross {
+                tick_id: 20,
+                best_opposing_tick: 10,
+            }),
+        },
+        PostOnlyTestCase {
+            name: "ask above best bid rests",
+            pre_operations: vec![resting_ask.clone(), resting_bid.clone()],
+            tick_id: 0,
+            order_direction: OrderDirection::Ask,
+            post_only: Some(true),
+            expected_error: None,
+        },
+        PostOnlyTestCase {
+            name: "ask at best bid crosses",
+            pre_operations: vec![resting_ask.clone(), resting_bid.clone()],
+            tick_id: -10,
+            order_direction: OrderDirection::Ask,
+            post_only: Some(true),
+            expected_error: Some(ContractError::WouldCross {
+                tick_id: -10,
+                best_opposing_tick: -10,
+            }),
+        },
+        PostOnlyTestCase {
+            name: "ask below best bid crosses",
+            pre_operations: vec![resting_ask.clone(), resting_bid.clone()],
+            tick_id: -20,
+            order_direction: OrderDirection::Ask,
+            post_only: Some(true),
+            expected_error: Some(ContractError::WouldCross {
+                tick_id: -20,
+                best_opposing_tick: -10,
+            }),
+        },
+        PostOnlyTestCase {
+            name: "no opposing liquidity",
+            pre_operations: vec![resting_ask.clone()],
+            tick_id: -20,
+            order_direction: OrderDirection::Ask,
+            post_only: Some(true),
+            expected_error: None,
+        },
+        PostOnlyTestCase {
+            name: "crossing order without post only",
+            pre_operations: vec![resting_ask.clone(), resting_bid.clone()],
+            tick_id: 10,
+            order_direction: OrderDirection::Bid,
+            post_only: None,
+            expected_error: None,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for operation in test.pre_operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+        let tick_state_before = TICK_STATE
+            .may_load(deps.as_ref().storage, test.tick_id)
+            .unwrap();
+
+        // -- System under test --
+        let denom = match test.order_direction {
+            OrderDirection::Ask => BASE_DENOM,
+            OrderDirection::Bid => QUOTE_DENOM,
+        };
+        let res = place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info(sender.as_str(), &[coin(quantity.u128(), denom)]),
+            test.tick_id,
+            test.order_direction,
+            quantity,
+            None,
+            test.post_only,
+            None,
+            None,
+            None,
+            false,
+        );
+
+        // -- Post test assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(res, Err(err), "{}", format_test_name(test.name));
+
+            // Rejected orders must not touch the tick
+            let tick_state_after = TICK_STATE
+                .may_load(deps.as_ref().storage, test.tick_id)
+                .unwrap();
+            assert_eq!(
+                tick_state_before,
+                tick_state_after,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        assert!(res.is_ok(), "{}", format_test_name(test.name));
+        let liquidity = TICK_STATE
+            .load(deps.as_ref().storage, test.tick_id)
+            .unwrap()
+            .get_values(test.order_direction)
+            .total_amount_of_liquidity;
+        assert!(!liquidity.is_zero(), "{}", format_test_name(test.name));
+    }
+}
+#[test]
+fn test_claim_response_data() {
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let owner = Addr::unchecked(DEFAULT_OWNER);
+    let info = mock_info(DEFAULT_SENDER, &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // An ask and a bid on tick 0, both fully filled
+    let operations = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            owner.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            1,
+            OrderDirection::Bid,
+            owner.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(100u128),
+            OrderDirection::Bid,
+            Addr::unchecked(DEFAULT_SENDER),
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(50u128),
+            OrderDirection::Ask,
+            Addr::unchecked(DEFAULT_SENDER),
+        )),
+    ];
+    for operation in operations {
+        operation
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+
+    // A single claim encodes the claimed denom and amount
+    let res = claim_limit(deps.as_mut(), env.clone(), info.clone(), 0, 0, None).unwrap();
+    let data: ClaimData = from_json(res.data.unwrap()).unwrap();
+    assert_eq!(
+        data,
+        ClaimData {
+            tick_id: 0,
+            order_id: 0,
+            owner: owner.clone(),
+            claimed: coin_u256(100u128, QUOTE_DENOM),
+        }
+    );
+
+    // A batch claim only encodes the orders that were claimed
+    let res = batch_claim_limits(
+        deps.as_mut(),
+        info.clone(),
+        env.clone(),
+        vec![(0, 0), (0, 1)],
+        None,
+    )
+    .unwrap();
+    let data: BatchClaimData = from_json(res.data.unwrap()).unwrap();
+    assert_eq!(
+        data,
+        BatchClaimData {
+            claims: vec![ClaimData {
+                tick_id: 0,
+                order_id: 1,
+                owner,
+                claimed: coin_u256(50u128, BASE_DENOM),
+            }],
+        }
+    );
+}
+struct FillOrKillTestCase {
+    name: &'static str,
+    tick_bound: i64,
+    fill_or_kill: bool,
+    expected_remaining: Uint128,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_run_market_order_fill_or_kill() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let test_cases = vec![
+        FillOrKillTestCase {
+            name: "fully fillable within bound",
+            tick_bound: 10,
+            fill_or_kill: true,
+            expected_remaining: Uint128::zero(),
+            expected_error: None,
+        },
+        FillOrKillTestCase {
+            name: "partial fill without fill or kill",
+            tick_bound: 0,
+            fill_or_kill: false,
+            expected_remaining: Uint128::from(50u128),
+            expected_error: None,
+        },
+        FillOrKillTestCase {
+            name: "partial fill is killed",
+            tick_bound: 0,
+            fill_or_kill: true,
+            expected_remaining: Uint128::zero(),
+            expected_error: Some(ContractError::InsufficientLiquidity),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        // 100 ask liquidity on each of ticks 0 and 10
+        for (order_id, tick_id) in [0, 10].into_iter().enumerate() {
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                tick_id,
+                order_id as u64,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(100u128),
+                Decimal256::zero(),
+                None,
+            ))
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+        }
+        let tick_states_before: Vec<TickState> = [0, 10]
+            .iter()
+            .map(|tick_id| TICK_STATE.load(deps.as_ref().storage, *tick_id).unwrap())
+            .collect();
+        let orderbook_before = ORDERBOOK.load(deps.as_ref().storage).unwrap();
+
+        // -- System under test --
+        let mut market_order =
+            MarketOrder::new(Uint128::from(150u128), OrderDirection::Bid, sender.clone());
+        let res = run_market_order(
+            deps.as_mut().storage,
+            env.contract.address.clone(),
+            env.block.time,
+            &mut market_order,
+            test.tick_bound,
+            test.fill_or_kill,
+            None,
+        );
+
+        // -- Post test assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(res, Err(err), "{}", format_test_name(test.name));
+
+            // A killed order must not leave any tick or pointer updates behind
+            let tick_states_after: Vec<TickState> = [0, 10]
+                .iter()
+                .map(|tick_id| TICK_STATE.load(deps.as_ref().storage, *tick_id).unwrap())
+                .collect();
+            assert_eq!(
+                tick_states_before,
+                tick_states_after,
+                "{}",
+                format_test_name(test.name)
+            );
+            assert_eq!(
+                orderbook_before,
+                ORDERBOOK.load(deps.as_ref().storage).unwrap(),
+                "{}",
+                format_test_name(test.name)
+            );
+            assert_eq!(
+                get_directional_liquidity(deps.as_ref().storage, OrderDirection::Ask).unwrap(),
+                decimal256_from_u128(200u128),
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        assert!(res.is_ok(), "{}", format_test_name(test.name));
+        assert_eq!(
+            market_order.quantity,
+            test.expected_remaining,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+struct MarketOrderMinOutputTestCase {
+    name: &'static str,
+    min_output: Option<Uint128>,
+    expected_output: Uint256,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_run_market_order_min_output() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    // A thin book: the order is consumed entirely at the worse price on the first tick
+    let test_cases = vec![
+        MarketOrderMinOutputTestCase {
+            name: "no minimum",
+            min_output: None,
+            expected_output: Uint256::from(50u128),
+            expected_error: None,
+        },
+        MarketOrderMinOutputTestCase {
+            name: "minimum met",
+            min_output: Some(Uint128::from(50u128)),
+            expected_output: Uint256::from(50u128),
+            expected_error: None,
+        },
+        MarketOrderMinOutputTestCase {
+            name: "thin book below minimum",
+            min_output: Some(Uint128::from(51u128)),
+            expected_output: Uint256::zero(),
+            expected_error: Some(ContractError::SwapBelowMin {
+                min: Uint256::from(51u128),
+                received: Uint256::from(50u128),
+                denom: BASE_DENOM.to_string(),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        for (order_id, tick_id) in [LARGE_NEGATIVE_TICK, 0].into_iter().enumerate() {
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                tick_id,
+                order_id as u64,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(50u128),
+                Decimal256::zero(),
+                None,
+            ))
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+        }
+        let tick_state_before = TICK_STATE
+            .load(deps.as_ref().storage, LARGE_NEGATIVE_TICK)
+            .unwrap();
+
+        // -- System under test --
+        let mut market_order =
+            MarketOrder::new(Uint128::from(100u128), OrderDirection::Bid, sender.clone());
+        let res = run_market_order(
+            deps.as_mut().storage,
+            env.contract.address.clone(),
+            env.block.time,
+            &mut market_order,
+            MAX_TICK,
+            false,
+            test.min_output,
+        );
+
+        // -- Post test assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(res, Err(err), "{}", format_test_name(test.name));
+            assert_eq!(
+                tick_state_before,
+                TICK_STATE
+                    .load(deps.as_ref().storage, LARGE_NEGATIVE_TICK)
+                    .unwrap(),
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let (output, _, _, _) = res.unwrap();
+        assert_eq!(
+            output,
+            test.expected_output,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+struct ClaimAllTestCase {
+    name: &'static str,
+    maker_fee: Decimal256,
+    start_after: Option<(i64, u64)>,
+    limit: Option<u64>,
+    expected_claimed_orders: &'static str,
+    expected_next_start_after: Option<&'static str>,
+    // Expected (recipient, amounts, reply id) per merged send
+    expected_sends: Vec<(&'static str, Vec<Coin256>, u64)>,
+    // Expected remaining quantity of each order, `None` if fully claimed
+    expected_remaining: Vec<((i64, u64), Option<u128>)>,
+}
+
+#[test]
+fn test_claim_all() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let operations = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            Some(Decimal256::percent(1)),
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            1,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            2,
+            OrderDirection::Bid,
+            sender.clone(),
+            Uint128::from(40u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            10,
+            3,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )),
+        // Fills order 0 fully and order 1 partially
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(120u128),
+            OrderDirection::Bid,
+            Addr::unchecked("taker"),
+        )),
+        // Fills order 2 fully
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(40u128),
+            OrderDirection::Ask,
+            Addr::unchecked("taker"),
+        )),
+    ];
+    let test_cases = vec![
+        ClaimAllTestCase {
+            name: "merges claims into a single send",
+            maker_fee: Decimal256::zero(),
+            start_after: None,
+            limit: None,
+            expected_claimed_orders: "0:0,0:1,0:2",
+            expected_next_start_after: None,
+            expected_sends: vec![(
+                DEFAULT_SENDER,
+                vec![
+                    coin_u256(40u128, BASE_DENOM),
+                    coin_u256(120u128, QUOTE_DENOM),
+                ],
+                REPLY_ID_CLAIM,
+            )],
+            expected_remaining: vec![
+                ((0, 0), None),
+                ((0, 1), Some(30)),
+                ((0, 2), None),
+                ((10, 3), Some(100)),
+            ],
+        },
+        ClaimAllTestCase {
+            name: "maker fee sent separately",
+            maker_fee: Decimal256::percent(2),
+            start_after: None,
+            limit: None,
+            expected_claimed_orders: "0:0,0:1,0:2",
+            expected_next_start_after: None,
+            expected_sends: vec![
+                (
+                    DEFAULT_SENDER,
+                    vec![
+                        coin_u256(40u128, BASE_DENOM),
+                        coin_u256(118u128, QUOTE_DENOM),
+                    ],
+                    REPLY_ID_CLAIM,
+                ),
+                (
+                    DEFAULT_MAKER_FEE_RECIPIENT,
+                    vec![coin_u256(2u128, QUOTE_DENOM)],
+                    REPLY_ID_MAKER_FEE,
+                ),
+            ],
+            expected_remaining: vec![
+                ((0, 0), None),
+                ((0, 1), Some(30)),
+                ((0, 2), None),
+                ((10, 3), Some(100)),
+            ],
+        },
+        ClaimAllTestCase {
+            name: "bounded by limit",
+            maker_fee: Decimal256::zero(),
+            start_after: None,
+            limit: Some(2),
+            expected_claimed_orders: "0:0,0:1",
+            expected_next_start_after: Some("0:1"),
+            expected_sends: vec![(
+                DEFAULT_SENDER,
+                vec![coin_u256(120u128, QUOTE_DENOM)],
+                REPLY_ID_CLAIM,
+            )],
+            expected_remaining: vec![
+                ((0, 0), None),
+                ((0, 1), Some(30)),
+                ((0, 2), Some(40)),
+                ((10, 3), Some(100)),
+            ],
+        },
+        ClaimAllTestCase {
+            name: "resumes after cursor",
+            maker_fee: Decimal256::zero(),
+            start_after: Some((0, 1)),
+            limit: None,
+            expected_claimed_orders: "0:2",
+            expected_next_start_after: None,
+            expected_sends: vec![(
+                DEFAULT_SENDER,
+                vec![coin_u256(40u128, BASE_DENOM)],
+                REPLY_ID_CLAIM,
+            )],
+            expected_remaining: vec![
+                ((0, 0), Some(100)),
+                ((0, 1), Some(50)),
+                ((0, 2), None),
+                ((10, 3), Some(100)),
+            ],
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        MAKER_FEE
+            .save(deps.as_mut().storage, &test.maker_fee)
+            .unwrap();
+        for operation in operations.clone() {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = claim_all(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            test.start_after,
+            test.limit,
+        )
+        .unwrap();
+
+        // -- Post test assertions --
+        let expected_messages: Vec<SubMsg> = test
+            .expected_sends
+            .into_iter()
+            .map(|(recipient, amount, reply_id)| {
+                SubMsg::reply_on_error(
+                    MsgSend256 {
+                        from_address: env.contract.address.to_string(),
+                        to_address: recipient.to_string(),
+                        amount,
+                    },
+                    reply_id,
+                )
+            })
+            .collect();
+        assert_eq!(
+            res.messages,
+            expected_messages,
+            "{}",
+            format_test_name(test.name)
+        );
+        let claimed_orders = res
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "claimed_orders")
+            .unwrap();
+        assert_eq!(
+            claimed_orders.value,
+            test.expected_claimed_orders,
+            "{}",
+            format_test_name(test.name)
+        );
+        let next_start_after = res
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "next_start_after")
+            .map(|attr| attr.value.as_str());
+        assert_eq!(
+            next_start_after,
+            test.expected_next_start_after,
+            "{}",
+            format_test_name(test.name)
+        );
+        for (key, expected_quantity) in test.expected_remaining {
+            let quantity = orders()
+                .may_load(deps.as_ref().storage, &key)
+                .unwrap()
+                .map(|order| order.quantity.u128());
+            assert_eq!(
+                quantity,
+                expected_quantity,
+                "{}: order {:?}",
+                format_test_name(test.name),
+                key
+            );
+        }
+    }
+}
+struct VoidExpiredOrdersTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    elapsed_seconds: u64,
+    expected_refund: Option<Uint128>,
+    // Quantity left on the expiring order after voiding, `None` if it was removed
+    expected_remaining_quantity: Option<Uint128>,
+    // Tick ETAS after a subsequent 50 unit fill
+    expected_etas_after_fill: u128,
+}
+
+#[test]
+fn test_void_expired_orders() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let expiring_owner = Addr::unchecked(DEFAULT_OWNER);
+    let test_cases = vec![
+        VoidExpiredOrdersTestCase {
+            name: "unfilled expired order is removed",
+            pre_operations: vec![],
+            elapsed_seconds: 200,
+            expected_refund: Some(Uint128::from(100u128)),
+            expected_remaining_quantity: None,
+            expected_etas_after_fill: 150,
+        },
+        VoidExpiredOrdersTestCase {
+            name: "partially filled expired order keeps its fill",
+            pre_operations: vec![OrderOperation::RunMarket(MarketOrder::new(
+                Uint128::from(30u128),
+                OrderDirection::Bid,
+                sender.clone(),
+            ))],
+            elapsed_seconds: 200,
+            expected_refund: Some(Uint128::from(70u128)),
+            expected_remaining_quantity: Some(Uint128::from(30u128)),
+            expected_etas_after_fill: 150,
+        },
+        VoidExpiredOrdersTestCase {
+            name: "unexpired order is untouched",
+            pre_operations: vec![],
+            elapsed_seconds: 50,
+            expected_refund: None,
+            expected_remaining_quantity: Some(Uint128::from(100u128)),
+            expected_etas_after_fill: 50,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let mut env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        // An expiring ask at the front of the tick queue, followed by one without an expiry
+        OrderOperation::PlaceLimit(
+            LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Ask,
+                expiring_owner.clone(),
+                Uint128::from(100u128),
+                Decimal256::zero(),
+                None,
+            )
+            .with_expiry(env.block.time.plus_seconds(100)),
+        )
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            1,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+
+        for operation in test.pre_operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+        let liquidity_before =
+            get_directional_liquidity(deps.as_ref().storage, OrderDirection::Ask).unwrap();
+
+        env.block.time = env.block.time.plus_seconds(test.elapsed_seconds);
+
+        // -- System under test --
+        let market_order =
+            MarketOrder::new(Uint128::from(50u128), OrderDirection::Bid, sender.clone());
+        let refund_msgs = void_expired_orders(
+            deps.as_mut().storage,
+            &market_order,
+            MAX_TICK,
+            env.block.time,
+        )
+        .unwrap();
+
+        // -- Post test assertions --
+        let expected_refund_msgs: Vec<SubMsg> = test
+            .expected_refund
+            .map(|refund| {
+                SubMsg::reply_on_error(
+                    BankMsg::Send {
+                        to_address: expiring_owner.to_string(),
+                        amount: vec![coin(refund.u128(), BASE_DENOM)],
+                    },
+                    REPLY_ID_REFUND,
+                )
+            })
+            .into_iter()
+            .collect();
+        assert_eq!(
+            refund_msgs,
+            expected_refund_msgs,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        let expiring_order = orders().may_load(deps.as_ref().storage, &(0, 0)).unwrap();
+        assert_eq!(
+            expiring_order.map(|order| order.quantity),
+            test.expected_remaining_quantity,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Voided quantity no longer counts towards the tick's liquidity
+        let voided = test.expected_refund.unwrap_or_default();
+        assert_eq!(
+            get_directional_liquidity(deps.as_ref().storage, OrderDirection::Ask).unwrap(),
+            liquidity_before
+                .checked_sub(Decimal256::from_ratio(voided, 1u128))
+                .unwrap(),
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Voided orders are removed from the expiry index
+        assert_eq!(
+            ORDER_EXPIRIES
+                .sub_prefix(0)
+                .keys(deps.as_ref().storage, None, None, Order::Ascending)
+                .count(),
+            usize::from(test.expected_refund.is_none()),
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Fills skip the voided quantity and go to the live order behind it
+        let mut market_order = market_order;
+        run_market_order(
+            deps.as_mut().storage,
+            env.contract.address.clone(),
+            env.block.time,
+            &mut market_order,
+            MAX_TICK,
+            false,
+            None,
+        )
+        .unwrap();
+        let tick_values =
+            get_synced_tick_values(deps.as_ref().storage, 0, OrderDirection::Ask).unwrap();
+        assert_eq!(
+            tick_values.effective_total_amount_swapped,
+            decimal256_from_u128(test.expected_etas_after_fill),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_place_limit_expiry() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+    let info = mock_info(sender.as_str(), &[coin(100u128, BASE_DENOM)]);
+
+    // An expiry at or before the current block time is rejected
+    for expiry in [env.block.time, env.block.time.minus_seconds(1)] {
+        let res = place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            0,
+            OrderDirection::Ask,
+            Uint128::from(100u128),
+            None,
+            None,
+            Some(expiry),
+            None,
+            None,
+            false,
+        );
+        assert_eq!(
+            res,
+            Err(ContractError::InvalidExpiry {
+                expiry,
+                block_time: env.block.time
+            })
+        );
+    }
+
+    // A future expiry is stored on the order and indexed
+    let expiry = env.block.time.plus_seconds(1);
+    place_limit(
+        &mut deps.as_mut(),
+        env.clone(),
+        info,
+        0,
+        OrderDirection::Ask,
+        Uint128::from(100u128),
+        None,
+        None,
+        Some(expiry),
+        None,
+        None,
+        false,
+    )
+    .unwrap();
+    let order = orders().load(deps.as_ref().storage, &(0, 0)).unwrap();
+    assert_eq!(order.expiry, Some(expiry));
+    assert!(ORDER_EXPIRIES.has(deps.as_ref().storage, (0, expiry.nanos(), 0)));
+
+    // Cancelling the order clears it from the index
+    cancel_limit(deps.as_mut(), env, mock_info(sender.as_str(), &[]), 0, 0).unwrap();
+    assert!(!ORDER_EXPIRIES.has(deps.as_ref().storage, (0, expiry.nanos(), 0)));
+}
+
+struct CancelLimitPartialTestCase {
+    name: &'static str,
+    // Amount filled before the cancel
+    filled: u128,
+    amount: u128,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_cancel_limit_partial() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let quantity = 100u128;
+    let test_cases = vec![
+        CancelLimitPartialTestCase {
+            name: "partial cancel of unfilled order",
+            filled: 0,
+            amount: 40,
+            expected_error: None,
+        },
+        CancelLimitPartialTestCase {
+            name: "partial cancel of partially filled order",
+            filled: 20,
+            amount: 40,
+            expected_error: None,
+        },
+        CancelLimitPartialTestCase {
+            name: "cancel entire unfilled order",
+            filled: 0,
+            amount: 100,
+            expected_error: None,
+        },
+        CancelLimitPartialTestCase {
+            name: "cancel unfilled remainder of partially filled order",
+            filled: 20,
+            amount: 80,
+            expected_error: None,
+        },
+        CancelLimitPartialTestCase {
+            name: "amount exceeds unfilled remainder",
+            filled: 20,
+            amount: 90,
+            expected_error: Some(ContractError::CancelExceedsRemaining {
+                amount: Uint128::from(90u128),
+                remaining: Uint128::from(80u128),
+            }),
+        },
+        CancelLimitPartialTestCase {
+            name: "zero amount",
+            filled: 0,
+            amount: 0,
+            expected_error: Some(ContractError::InvalidQuantity {
+                quantity: Uint128::zero(),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        let place_ask = |order_id: u64, quantity: u128| {
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                order_id,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(quantity),
+                Decimal256::zero(),
+                None,
+            ))
+        };
+        let fill = OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(test.filled),
+            OrderDirection::Bid,
+            sender.clone(),
+        ));
+
+        let mut deps = mock_dependencies_custom();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        let mut operations = vec![place_ask(0, quantity)];
+        if test.filled > 0 {
+            operations.push(fill.clone());
+        }
+        for operation in operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = cancel_limit_partial(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            0,
+            0,
+            Uint128::from(test.amount),
+        );
+
+        // -- Post test assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(res, Err(err), "{}", format_test_name(test.name));
+            continue;
+        }
+        let res = res.unwrap();
+
+        // The same amount placed as a separate order at the back of the tick and fully cancelled
+        let mut expected_deps = mock_dependencies_custom();
+        create_orderbook(
+            expected_deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        let kept = quantity - test.amount;
+        let mut operations = vec![];
+        if kept > 0 {
+            operations.push(place_ask(0, kept));
+        }
+        let cancelled_order_id = operations.len() as u64;
+        operations.push(place_ask(cancelled_order_id, test.amount));
+        if test.filled > 0 {
+            operations.push(fill.clone());
+        }
+        for operation in operations {
+            operation
+                .run(expected_deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+        let expected_res = cancel_limit(
+            expected_deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            0,
+            cancelled_order_id,
+        )
+        .unwrap();
+
+        assert_eq!(
+            res.messages,
+            expected_res.messages,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            get_root_node(deps.as_ref().storage, 0, OrderDirection::Ask)
+                .unwrap()
+                .traverse(deps.as_ref().storage)
+                .unwrap(),
+            get_root_node(expected_deps.as_ref().storage, 0, OrderDirection::Ask)
+                .unwrap()
+                .traverse(expected_deps.as_ref().storage)
+                .unwrap(),
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            TICK_STATE.load(deps.as_ref().storage, 0).unwrap(),
+            TICK_STATE.load(expected_deps.as_ref().storage, 0).unwrap(),
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // The remainder of the order stays on the book unless nothing is left
+        let order = orders().may_load(deps.as_ref().storage, &(0, 0)).unwrap();
+        assert_eq!(
+            order.map(|order| order.quantity),
+            (kept > 0).then(|| Uint128::from(kept)),
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Claims on the remainder are unaffected by the cancelled amount
+        if test.filled > 0 {
+            let (claimed, _, _) = claim_order(
+                deps.as_mut().storage,
+                env.contract.address.clone(),
+                sender.clone(),
+                0,
+                0,
+                None,
+            )
+            .unwrap();
+            assert_eq!(
+                claimed,
+                Uint256::from(test.filled),
+                "{}",
+                format_test_name(test.name)
+            );
+        }
+    }
+}
+struct TakerFeeTestCase {
+    name: &'static str,
+    taker_fee: Option<Decimal256>,
+    quantity: u128,
+    expected_output: u128,
+    expected_fee: Option<u128>,
+}
+
+#[test]
+fn test_run_market_order_taker_fee() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let fee_collector = Addr::unchecked("collector");
+    let test_cases = vec![
+        TakerFeeTestCase {
+            name: "no taker fee",
+            taker_fee: None,
+            quantity: 1000,
+            expected_output: 1000,
+            expected_fee: None,
+        },
+        TakerFeeTestCase {
+            name: "zero taker fee",
+            taker_fee: Some(Decimal256::zero()),
+            quantity: 1000,
+            expected_output: 1000,
+            expected_fee: None,
+        },
+        TakerFeeTestCase {
+            name: "0.1% taker fee",
+            taker_fee: Some(Decimal256::permille(1)),
+            quantity: 1000,
+            expected_output: 999,
+            expected_fee: Some(1),
+        },
+        TakerFeeTestCase {
+            name: "fractional fee is rounded up",
+            taker_fee: Some(Decimal256::permille(1)),
+            quantity: 999,
+            expected_output: 998,
+            expected_fee: Some(1),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        if let Some(taker_fee) = test.taker_fee {
+            set_taker_fee(deps.as_mut().storage, taker_fee, fee_collector.clone()).unwrap();
+        }
+
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(1000u128),
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+
+        // -- System under test --
+        let mut market_order = MarketOrder::new(
+            Uint128::from(test.quantity),
+            OrderDirection::Bid,
+            sender.clone(),
+        );
+        let (output, bank_msg, fee_msg, _) = run_market_order(
+            deps.as_mut().storage,
+            env.contract.address.clone(),
+            env.block.time,
+            &mut market_order,
+            MAX_TICK,
+            false,
+            None,
+        )
+        .unwrap();
+
+        // -- Post test assertions --
+        assert_eq!(
+            output,
+            Uint256::from(test.expected_output),
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            bank_msg,
+            MsgSend256 {
+                from_address: env.contract.address.to_string(),
+                to_address: sender.to_string(),
+                amount: vec![coin_u256(test.expected_output, BASE_DENOM)],
+            },
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            fee_msg,
+            test.expected_fee.map(|fee| MsgSend256 {
+                from_address: env.contract.address.to_string(),
+                to_address: fee_collector.to_string(),
+                amount: vec![coin_u256(fee, BASE_DENOM)],
+            }),
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // The fee is taken from the output, so the full fill is removed from the book
+        assert_eq!(
+            get_directional_liquidity(deps.as_ref().storage, OrderDirection::Ask).unwrap(),
+            decimal256_from_u128(1000 - test.quantity),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+#[test]
+fn test_market_order_walks_populated_ticks() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // Ask liquidity only at ticks 0 and 40,000,000, with an emptied tick in between
+    let operations = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            20_000_000,
+            1,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::Cancel((20_000_000, 1)),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            40_000_000,
+            2,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )),
+    ];
+    for operation in operations {
+        operation
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+
+    // -- System under test --
+    let mut market_order = MarketOrder::new(
+        Uint128::from(1_000_000_000_000u128),
+        OrderDirection::Bid,
+        sender.clone(),
+    );
+    let res = simulate_market_order(
+        deps.as_ref().storage,
+        &mut market_order,
+        MAX_TICK,
+        None,
+        env.block.time,
+    )
+    .unwrap();
+
+    // -- Post test assertions --
+    let touched_ticks: Vec<i64> = res
+        .tick_updates
+        .iter()
+        .map(|(tick_id, _)| *tick_id)
+        .collect();
+    assert_eq!(touched_ticks, vec![0, 40_000_000]);
+    assert_eq!(res.output, coin_u256(200u128, BASE_DENOM));
+}
+struct MaxTickMoveTestCase {
+    name: &'static str,
+    max_tick_move: Option<i64>,
+    order: MarketOrder,
+    expected_output: Uint256,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_market_order_max_tick_move() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let test_cases = vec![
+        MaxTickMoveTestCase {
+            name: "BID: small order stays within bounds",
+            max_tick_move: Some(500),
+            order: MarketOrder::new(Uint128::from(50u128), OrderDirection::Bid, sender.clone()),
+            expected_output: Uint256::from(50u128),
+            expected_error: None,
+        },
+        MaxTickMoveTestCase {
+            name: "BID: large order halts before jumping past the limit",
+            max_tick_move: Some(500),
+            order: MarketOrder::new(Uint128::from(150u128), OrderDirection::Bid, sender.clone()),
+            expected_output: Uint256::zero(),
+            expected_error: Some(ContractError::ExcessivePriceImpact {
+                start_tick: 0,
+                tick_id: 1000,
+                max_tick_move: 500,
+            }),
+        },
+        MaxTickMoveTestCase {
+            name: "BID: large order moving exactly the limit",
+            max_tick_move: Some(1000),
+            order: MarketOrder::new(Uint128::from(150u128), OrderDirection::Bid, sender.clone()),
+            expected_output: Uint256::from(150u128),
+            expected_error: None,
+        },
+        MaxTickMoveTestCase {
+            name: "BID: large order without a limit",
+            max_tick_move: None,
+            order: MarketOrder::new(Uint128::from(150u128), OrderDirection::Bid, sender.clone()),
+            expected_output: Uint256::from(150u128),
+            expected_error: None,
+        },
+        MaxTickMoveTestCase {
+            name: "ASK: large order halts before jumping past the limit",
+            max_tick_move: Some(500),
+            order: MarketOrder::new(Uint128::from(150u128), OrderDirection::Ask, sender.clone()),
+            expected_output: Uint256::zero(),
+            expected_error: Some(ContractError::ExcessivePriceImpact {
+                start_tick: -1,
+                tick_id: -1001,
+                max_tick_move: 500,
+            }),
+        },
+        MaxTickMoveTestCase {
+            name: "ASK: small order stays within bounds",
+            max_tick_move: Some(500),
+            order: MarketOrder::new(Uint128::from(50u128), OrderDirection::Ask, sender.clone()),
+            expected_output: Uint256::from(50u128),
+            expected_error: None,
+        },
+    ];
+
+    for test in test_cases {
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        if let Some(max_tick_move) = test.max_tick_move {
+            set_max_tick_move(deps.as_mut().storage, max_tick_move).unwrap();
+        }
+
+        // Sparse book with liquidity 1000 ticks apart on each side
+        for (order_id, (tick_id, direction)) in [
+            (0, OrderDirection::Ask),
+            (1000, OrderDirection::Ask),
+            (-1, OrderDirection::Bid),
+            (-1001, OrderDirection::Bid),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                tick_id,
+                order_id as u64,
+                direction,
+                sender.clone(),
+                Uint128::from(100u128),
+                Decimal256::zero(),
+                None,
+            ))
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+        }
+        let tick_state_before = TICK_STATE
+            .range(deps.as_ref().storage, None, None, Order::Ascending)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let mut order = test.order.clone();
+        let tick_bound = match order.order_direction {
+            OrderDirection::Bid => MAX_TICK,
+            OrderDirection::Ask => MIN_TICK,
+        };
+        let res = run_market_order(
+            deps.as_mut().storage,
+            env.contract.address.clone(),
+            env.block.time,
+            &mut order,
+            tick_bound,
+            false,
+            None,
+        );
+
+        if let Some(err) = test.expected_error {
+            assert_eq!(res.unwrap_err(), err, "{}", format_test_name(test.name));
+
+            // Nothing is filled once the limit is exceeded
+            let tick_state_after = TICK_STATE
+                .range(deps.as_ref().storage, None, None, Order::Ascending)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(
+                tick_state_after,
+                tick_state_before,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let (output, ..) = res.unwrap();
+        assert_eq!(
+            output,
+            test.expected_output,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_run_market_order_fill_events() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // Asks of 10, 20 (cancelled) and 30 on tick 0 and two asks of 20 on a tick priced at 2,
+    // with the first ask already half filled
+    let asks = [
+        (0, 10u128),
+        (0, 20),
+        (0, 30),
+        (LARGE_POSITIVE_TICK, 20),
+        (LARGE_POSITIVE_TICK, 20),
+    ];
+    let mut operations: Vec<OrderOperation> = asks
+        .iter()
+        .enumerate()
+        .map(|(order_id, (tick_id, quantity))| {
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                *tick_id,
+                order_id as u64,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(*quantity),
+                Decimal256::zero(),
+                None,
+            ))
+        })
+        .collect();
+    operations.push(OrderOperation::Cancel((0, 1)));
+    operations.push(OrderOperation::RunMarket(MarketOrder::new(
+        Uint128::from(5u128),
+        OrderDirection::Bid,
+        sender.clone(),
+    )));
+    for operation in operations {
+        operation
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+
+    // -- System under test --
+    let mut market_order =
+        MarketOrder::new(Uint128::from(50u128), OrderDirection::Bid, sender.clone());
+    let (output, _, _, events) = run_market_order(
+        deps.as_mut().storage,
+        env.contract.address.clone(),
+        env.block.time,
+        &mut market_order,
+        MAX_TICK,
+        false,
+        None,
+    )
+    .unwrap();
+
+    // -- Post test assertions --
+    assert_eq!(output, Uint256::from(65u128));
+    let mut expected_events: Vec<Event> = [
+        (0, 0, 5u128),
+        (0, 2, 30),
+        (LARGE_POSITIVE_TICK, 3, 20),
+        (LARGE_POSITIVE_TICK, 4, 10),
+    ]
+    .iter()
+    .map(|(tick_id, order_id, amount_filled)| {
+        Event::new("order_filled").add_attributes(vec![
+            ("tick_id", tick_id.to_string()),
+            ("order_id", order_id.to_string()),
+            ("owner", sender.to_string()),
+            ("amount_filled", amount_filled.to_string()),
+            ("direction", OrderDirection::Ask.to_string()),
+        ])
+    })
+    .collect();
+    // Tick 0 is emptied by the fill
+    expected_events.insert(
+        2,
+        Event::new("tick_deactivated").add_attributes(vec![
+            ("tick_id", "0".to_string()),
+            ("direction", OrderDirection::Ask.to_string()),
+        ]),
+    );
+    assert_eq!(events, expected_events);
+}
+struct MinOrderSizeTestCase {
+    name: &'static str,
+    min_order_size: u128,
+    quantity: u128,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_place_limit_min_order_size() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let test_cases = vec![
+        MinOrderSizeTestCase {
+            name: "below minimum",
+            min_order_size: 100,
+            quantity: 99,
+            expected_error: Some(ContractError::OrderTooSmall {
+                size: Uint128::from(99u128),
+                minimum: Uint128::from(100u128),
+            }),
+        },
+        MinOrderSizeTestCase {
+            name: "at minimum",
+            min_order_size: 100,
+            quantity: 100,
+            expected_error: None,
+        },
+        MinOrderSizeTestCase {
+            name: "above minimum",
+            min_order_size: 100,
+            quantity: 101,
+            expected_error: None,
+        },
+        MinOrderSizeTestCase {
+            name: "zero minimum accepts any positive size",
+            min_order_size: 0,
+            quantity: 1,
+            expected_error: None,
+        },
+        MinOrderSizeTestCase {
+            name: "zero quantity is rejected before the minimum",
+            min_order_size: 100,
+            quantity: 0,
+            expected_error: Some(ContractError::InvalidQuantity {
+                quantity: Uint128::zero(),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        set_min_order_size(deps.as_mut().storage, Uint128::from(test.min_order_size)).unwrap();
+        let info = mock_info(sender.as_str(), &[coin(test.quantity, BASE_DENOM)]);
+
+        // -- System under test --
+        let res = place_limit(
+            &mut deps.as_mut(),
+            env,
+            info,
+            0,
+            OrderDirection::Ask,
+            Uint128::from(test.quantity),
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+        );
+
+        // -- Post test assertions --
+        match test.expected_error {
+            Some(expected_error) => assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            ),
+            None => assert!(res.is_ok(), "{}", format_test_name(test.name)),
+        }
+    }
+}
+
+struct TickSpacingTestCase {
+    name: &'static str,
+    tick_spacing: i64,
+    tick_id: i64,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_place_limit_tick_spacing() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let test_cases = vec![
+        TickSpacingTestCase {
+            name: "on grid",
+            tick_spacing: 100,
+            tick_id: 300,
+            expected_error: None,
+        },
+        TickSpacingTestCase {
+            name: "zero tick is on grid",
+            tick_spacing: 100,
+            tick_id: 0,
+            expected_error: None,
+        },
+        TickSpacingTestCase {
+            name: "negative tick on grid",
+            tick_spacing: 100,
+            tick_id: -300,
+            expected_error: None,
+        },
+        TickSpacingTestCase {
+            name: "off grid",
+            tick_spacing: 100,
+            tick_id: 350,
+            expected_error: Some(ContractError::InvalidTickId { tick_id: 350 }),
+        },
+        TickSpacingTestCase {
+            name: "negative tick off grid",
+            tick_spacing: 100,
+            tick_id: -1,
+            expected_error: Some(ContractError::InvalidTickId { tick_id: -1 }),
+        },
+        TickSpacingTestCase {
+            name: "spacing of one accepts every tick",
+            tick_spacing: 1,
+            tick_id: -351,
+            expected_error: None,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        set_tick_spacing(deps.as_mut().storage, test.tick_spacing).unwrap();
+        let info = mock_info(sender.as_str(), &[coin(10u128, BASE_DENOM)]);
+
+        // -- System under test --
+        let res = place_limit(
+            &mut deps.as_mut(),
+            env,
+            info,
+            test.tick_id,
+            OrderDirection::Ask,
+            Uint128::from(10u128),
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+        );
+
+        // -- Post test assertions --
+        match test.expected_error {
+            Some(expected_error) => assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            ),
+            None => assert!(res.is_ok(), "{}", format_test_name(test.name)),
+        }
+    }
+}
+#[test]
+fn test_place_limit_max_orders_per_tick() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+    set_max_orders_per_tick(deps.as_mut().storage, 2).unwrap();
+
+    let place = |tick_id: i64, direction: OrderDirection| {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            0,
+            direction,
+            sender.clone(),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        ))
+    };
+
+    // Fill the ask side of tick 0 to its limit
+    for _ in 0..2 {
+        place(0, OrderDirection::Ask)
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+    assert_eq!(
+        get_tick_order_count(deps.as_ref().storage, 0, OrderDirection::Ask).unwrap(),
+        2
+    );
+
+    // -- System under test --
+    let res = place(0, OrderDirection::Ask).run(deps.as_mut(), env.clone(), info.clone());
+    assert_eq!(res, Err(ContractError::TickFull { tick_id: 0 }));
+
+    // The limit applies per tick and direction
+    place(1, OrderDirection::Ask)
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+    place(0, OrderDirection::Bid)
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+
+    // Cancelling an order frees a slot
+    OrderOperation::Cancel((0, 1))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+    assert_eq!(
+        get_tick_order_count(deps.as_ref().storage, 0, OrderDirection::Ask).unwrap(),
+        1
+    );
+    place(0, OrderDirection::Ask)
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+
+    // Claiming a fully filled order frees a slot
+    let res = place(0, OrderDirection::Ask).run(deps.as_mut(), env.clone(), info.clone());
+    assert_eq!(res, Err(ContractError::TickFull { tick_id: 0 }));
+    OrderOperation::RunMarket(MarketOrder::new(
+        Uint128::from(10u128),
+        OrderDirection::Bid,
+        Addr::unchecked("buyer"),
+    ))
+    .run(deps.as_mut(), env.clone(), info.clone())
+    .unwrap();
+    OrderOperation::Claim((0, 0))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+    place(0, OrderDirection::Ask)
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+    assert_eq!(
+        get_tick_order_count(deps.as_ref().storage, 0, OrderDirection::Ask).unwrap(),
+        2
+    );
+}
+
+#[test]
+fn test_place_limit_max_claim_bounty() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // The cap may not exceed the global 1% guardrail
+    assert_eq!(
+        set_max_claim_bounty(deps.as_mut().storage, Decimal256::percent(2)),
+        Err(ContractError::InvalidClaimBounty {
+            claim_bounty: Some(Decimal256::percent(2))
+        })
+    );
+    set_max_claim_bounty(deps.as_mut().storage, Decimal256::permille(5)).unwrap();
+
+    let place = |claim_bounty: Decimal256| {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            Some(claim_bounty),
+        ))
+    };
+
+    // -- System under test --
+    // A bounty within the global guardrail but above the book's cap is rejected
+    let res = place(Decimal256::permille(8)).run(deps.as_mut(), env.clone(), info.clone());
+    assert_eq!(
+        res,
+        Err(ContractError::InvalidClaimBounty {
+            claim_bounty: Some(Decimal256::permille(8))
+        })
+    );
+
+    // Bounties up to and including the cap are accepted
+    for claim_bounty in [Decimal256::permille(4), Decimal256::permille(5)] {
+        place(claim_bounty)
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+    assert_eq!(
+        get_tick_order_count(deps.as_ref().storage, 0, OrderDirection::Ask).unwrap(),
+        2
+    );
+}
+
+#[test]
+fn test_place_limit_immediate_or_cancel() {
+    let maker = Addr::unchecked(DEFAULT_OWNER);
+    let taker = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(maker.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // Rest 50 base at a price of 1 and 50 base further out
+    for tick_id in [0, LARGE_POSITIVE_TICK] {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            0,
+            OrderDirection::Ask,
+            maker.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+    }
+
+    let place_ioc = |deps: &mut DepsMut, tick_id: i64| {
+        place_limit(
+            deps,
+            env.clone(),
+            mock_info(taker.as_str(), &[coin(100, QUOTE_DENOM)]),
+            tick_id,
+            OrderDirection::Bid,
+            Uint128::from(100u128),
+            None,
+            None,
+            None,
+            Some(TimeInForce::ImmediateOrCancel),
+            None,
+            false,
+        )
+        .unwrap()
+    };
+    let refund_msg = |amount: u128| {
+        SubMsg::reply_on_error(
+            BankMsg::Send {
+                to_address: taker.to_string(),
+                amount: vec![coin(amount, QUOTE_DENOM)],
+            },
+            REPLY_ID_REFUND,
+        )
+    };
+
+    // -- System under test --
+    // A non-crossing order is refunded in full without resting on the book
+    let res = place_ioc(&mut deps.as_mut(), -10);
+    assert_eq!(res.messages, vec![refund_msg(100)]);
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "refund_quantity" && attr.value == "100"));
+    assert!(
+        get_directional_liquidity(deps.as_ref().storage, OrderDirection::Bid)
+            .unwrap()
+            .is_zero()
+    );
+
+    // A crossing order fills the liquidity within its limit tick and refunds the rest
+    let res = place_ioc(&mut deps.as_mut(), 0);
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: env.contract.address.to_string(),
+                    to_address: taker.to_string(),
+                    amount: vec![coin_u256(50u128, BASE_DENOM)],
+                },
+                REPLY_ID_IMMEDIATE_FILL,
+            ),
+            refund_msg(50),
+        ]
+    );
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "output_quantity" && attr.value == "50"));
+
+    // -- Post test assertions --
+    // Only the ask beyond the limit tick remains and no bid was inserted into a sumtree
+    assert_eq!(
+        get_directional_liquidity(deps.as_ref().storage, OrderDirection::Ask).unwrap(),
+        Decimal256::from_ratio(50u128, 1u128)
+    );
+    assert!(
+        get_directional_liquidity(deps.as_ref().storage, OrderDirection::Bid)
+            .unwrap()
+            .is_zero()
+    );
+    for tick_id in [-10, 0] {
+        assert!(TREE
+            .may_load(
+                deps.as_ref().storage,
+                &(tick_id, &OrderDirection::Bid.to_string())
+            )
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            get_tick_order_count(deps.as_ref().storage, tick_id, OrderDirection::Bid).unwrap(),
+            0
+        );
+    }
+
+    // An order on the outermost tick fills what the book holds and refunds the rest
+    let res = place_ioc(&mut deps.as_mut(), MAX_TICK);
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: env.contract.address.to_string(),
+                    to_address: taker.to_string(),
+                    amount: vec![coin_u256(50u128, BASE_DENOM)],
+                },
+                REPLY_ID_IMMEDIATE_FILL,
+            ),
+            refund_msg(75),
+        ]
+    );
+    assert!(
+        get_directional_liquidity(deps.as_ref().storage, OrderDirection::Ask)
+            .unwrap()
+            .is_zero()
+    );
+
+    // Options that only apply to resting orders are rejected
+    let (expiry, display_quantity, claim_bounty) = (
+        Some(env.block.time.plus_seconds(60)),
+        Some(Uint128::from(10u128)),
+        Some(Decimal256::permille(1)),
+    );
+    for (option, expiry, display_quantity, claim_bounty) in [
+        ("expiry", expiry, None, None),
+        ("display_quantity", None, display_quantity, None),
+        ("claim_bounty", None, None, claim_bounty),
+    ] {
+        let res = place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info(taker.as_str(), &[coin(100, QUOTE_DENOM)]),
+            0,
+            OrderDirection::Bid,
+            Uint128::from(100u128),
+            claim_bounty,
+            None,
+            expiry,
+            Some(TimeInForce::ImmediateOrCancel),
+            display_quantity,
+            false,
+        );
+        assert_eq!(
+            res,
+            Err(ContractError::InvalidImmediateOrCancel {
+                option: option.to_string()
+            })
+        );
+    }
+}
+
+#[test]
+fn test_place_limit_iceberg() {
+    let maker = Addr::unchecked(DEFAULT_OWNER);
+    let taker = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(taker.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    let place_ask = |deps: &mut DepsMut, quantity: u128, display_quantity: Option<u128>| {
+        place_limit(
+            deps,
+            env.clone(),
+            mock_info(maker.as_str(), &[coin(quantity, BASE_DENOM)]),
+            0,
+            OrderDirection::Ask,
+            Uint128::from(quantity),
+            None,
+            None,
+            None,
+            None,
+            display_quantity.map(Uint128::from),
+            false,
+        )
+    };
+    let displayed_depth = |deps: &DepsMut| {
+        ticks_in_range(deps.as_ref(), 0, 0, OrderDirection::Ask, None, None).unwrap()
+    };
+    let ask_values = |deps: &DepsMut| {
+        TICK_STATE
+            .load(deps.storage, 0)
+            .unwrap()
+            .get_values(OrderDirection::Ask)
+    };
+
+    // The display quantity must be positive and keep part of the order in reserve
+    for display_quantity in [0, 100] {
+        assert_eq!(
+            place_ask(&mut deps.as_mut(), 100, Some(display_quantity)),
+            Err(ContractError::InvalidDisplayQuantity {
+                display_quantity: Uint128::from(display_quantity),
+                quantity: Uint128::from(100u128),
+            })
+        );
+    }
+
+    // An iceberg order showing 30 of 100, followed by a regular order of 20
+    place_ask(&mut deps.as_mut(), 100, Some(30)).unwrap();
+    place_ask(&mut deps.as_mut(), 20, None).unwrap();
+
+    // Only the displayed slice counts towards the tick's depth
+    assert_eq!(
+        displayed_depth(&deps.as_mut()),
+        vec![(0, decimal256_from_u128(50u128))]
+    );
+    assert_eq!(
+        ask_values(&deps.as_mut()).total_amount_of_liquidity,
+        decimal256_from_u128(120u128)
+    );
+
+    // -- System under test --
+    // Filling more than the displayed slice reveals the next slice from the reserve
+    OrderOperation::RunMarket(MarketOrder::new(
+        Uint128::from(40u128),
+        OrderDirection::Bid,
+        taker.clone(),
+    ))
+    .run(deps.as_mut(), env.clone(), info.clone())
+    .unwrap();
+    assert_eq!(
+        displayed_depth(&deps.as_mut()),
+        vec![(0, decimal256_from_u128(50u128))]
+    );
+    assert_eq!(
+        ask_values(&deps.as_mut()).hidden_liquidity,
+        decimal256_from_u128(30u128)
+    );
+
+    // The rest of the reserve is still matchable ahead of the regular order
+    let mut market_order =
+        MarketOrder::new(Uint128::from(70u128), OrderDirection::Bid, taker.clone());
+    let (output, ..) = run_market_order(
+        deps.as_mut().storage,
+        env.contract.address.clone(),
+        env.block.time,
+        &mut market_order,
+        MAX_TICK,
+        false,
+        None,
+    )
+    .unwrap();
+    assert_eq!(output, Uint256::from(70u128));
+
+    // -- Post test assertions --
+    assert_eq!(
+        get_amount_filled(
+            deps.as_ref().storage,
+            &orders().load(deps.as_ref().storage, &(0, 0)).unwrap()
+        )
+        .unwrap(),
+        Uint128::from(100u128)
+    );
+    assert_eq!(
+        displayed_depth(&deps.as_mut()),
+        vec![(0, decimal256_from_u128(10u128))]
+    );
+    assert!(ask_values(&deps.as_mut()).hidden_liquidity.is_zero());
+}
+
+#[test]
+fn test_same_tick_fill_order() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // Two identical orders on the same tick
+    for _ in 0..2 {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+    }
+
+    // The later order is queued behind the earlier one rather than sharing its ETAS
+    let first = orders().load(deps.as_ref().storage, &(0, 0)).unwrap();
+    let second = orders().load(deps.as_ref().storage, &(0, 1)).unwrap();
+    assert_eq!(first.etas, Decimal256::zero());
+    assert_eq!(second.etas, decimal256_from_u128(10u128));
+
+    let market_bid = |quantity: u128| {
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(quantity),
+            OrderDirection::Bid,
+            sender.clone(),
+        ))
+    };
+    let claim = |deps: DepsMut, order_id: u64| {
+        claim_order(
+            deps.storage,
+            env.contract.address.clone(),
+            sender.clone(),
+            0,
+            order_id,
+            None,
+        )
+        .map(|(amount, ..)| amount)
+    };
+
+    // -- System under test --
+    // A partial fill only reaches the lower order id
+    market_bid(5)
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+    assert_eq!(
+        claim(deps.as_mut(), 1),
+        Err(ContractError::NotYetReached {
+            tick_id: 0,
+            order_id: 1
+        })
+    );
+    assert_eq!(claim(deps.as_mut(), 0), Ok(Uint256::from(5u128)));
+
+    // The higher order id only fills once the lower one is exhausted
+    market_bid(10)
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+
+    // -- Post test assertions --
+    assert_eq!(claim(deps.as_mut(), 0), Ok(Uint256::from(5u128)));
+    assert_eq!(claim(deps.as_mut(), 1), Ok(Uint256::from(5u128)));
+    assert_eq!(
+        orders()
+            .load(deps.as_ref().storage, &(0, 1))
+            .unwrap()
+            .quantity,
+        Uint128::from(5u128)
+    );
+}
+
+#[test]
+fn test_cancel_limit_donate() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // Three orders of 10 queued on the same tick
+    for _ in 0..3 {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+    }
+
+    // -- System under test --
+    // Only the owner may donate an order
+    assert_eq!(
+        cancel_limit_donate(deps.as_mut(), mock_info("other", &[]), 0, 1),
+        Err(ContractError::Unauthorized {})
+    );
+    let res = cancel_limit_donate(deps.as_mut(), info.clone(), 0, 1).unwrap();
+
+    // -- Post test assertions --
+    // Nothing is refunded and the donation is appended to the back of the tick without creating an
+    // order for it
+    assert!(res.messages.is_empty());
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "donated_quantity" && attr.value == "10"));
+    assert!(orders()
+        .may_load(deps.as_ref().storage, &(0, 1))
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        orders()
+            .prefix(0)
+            .keys(deps.as_ref().storage, None, None, Order::Ascending)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![0, 2]
+    );
+    let tick_values = TICK_STATE
+        .load(deps.as_ref().storage, 0)
+        .unwrap()
+        .get_values(OrderDirection::Ask);
+    assert_eq!(
+        tick_values.cumulative_total_value,
+        decimal256_from_u128(40u128)
+    );
+    assert_eq!(
+        tick_values.total_amount_of_liquidity,
+        decimal256_from_u128(30u128)
+    );
+    assert_eq!(
+        get_directional_liquidity(deps.as_ref().storage, OrderDirection::Ask).unwrap(),
+        decimal256_from_u128(30u128)
+    );
+
+    // An order placed after the donation queues behind it
+    OrderOperation::PlaceLimit(LimitOrder::new(
+        0,
+        3,
+        OrderDirection::Ask,
+        sender.clone(),
+        Uint128::from(10u128),
+        Decimal256::zero(),
+        None,
+    ))
+    .run(deps.as_mut(), env.clone(), info.clone())
+    .unwrap();
+
+    // A fill skips the cancelled order and reaches the donation only after the remaining orders
+    let mut market_order =
+        MarketOrder::new(Uint128::from(25u128), OrderDirection::Bid, sender.clone());
+    let (output, ..) = run_market_order(
+        deps.as_mut().storage,
+        env.contract.address.clone(),
+        env.block.time,
+        &mut market_order,
+        MAX_TICK,
+        false,
+        None,
+    )
+    .unwrap();
+    assert_eq!(output, Uint256::from(25u128));
+
+    // The orders around the donated one claim their full fills, while the donation absorbs the
+    // rest before the order behind it
+    for order_id in [0, 2] {
+        let (claimed, ..) = claim_order(
+            deps.as_mut().storage,
+            env.contract.address.clone(),
+            sender.clone(),
+            0,
+            order_id,
+            None,
+        )
+        .unwrap();
+        assert_eq!(claimed, Uint256::from(10u128));
+    }
+    let res = claim_order(
+        deps.as_mut().storage,
+        env.contract.address.clone(),
+        sender.clone(),
+        0,
+        3,
+        None,
+    );
+    assert_eq!(
+        res.unwrap_err(),
+        ContractError::NotYetReached {
+            tick_id: 0,
+            order_id: 3
+        }
+    );
+
+    // Filling the rest of the donation lets the order behind it fill
+    let mut market_order =
+        MarketOrder::new(Uint128::from(10u128), OrderDirection::Bid, sender.clone());
+    run_market_order(
+        deps.as_mut().storage,
+        env.contract.address.clone(),
+        env.block.time,
+        &mut market_order,
+        MAX_TICK,
+        false,
+        None,
+    )
+    .unwrap();
+    let (claimed, ..) = claim_order(
+        deps.as_mut().storage,
+        env.contract.address.clone(),
+        sender.clone(),
+        0,
+        3,
+        None,
+    )
+    .unwrap();
+    assert_eq!(claimed, Uint256::from(5u128));
+    assert_eq!(
+        TICK_STATE
+            .load(deps.as_ref().storage, 0)
+            .unwrap()
+            .get_values(OrderDirection::Ask)
+            .total_amount_of_liquidity,
+        decimal256_from_u128(5u128)
+    );
+}
+
+struct PruneTickTestCase {
+    name: &'static str,
+    operations: Vec<OrderOperation>,
+    tick_id: i64,
+    direction: OrderDirection,
+    expected_tick_state: Option<TickState>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_prune_tick() {
+    let valid_tick_id = 0;
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let test_cases = vec![
+        PruneTickTestCase {
+            name: "fully filled and claimed tick",
+            operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    valid_tick_id,
+                    0,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(10u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                    Addr::unchecked("buyer"),
+                )),
+                OrderOperation::Claim((valid_tick_id, 0)),
+            ],
+            tick_id: valid_tick_id,
+            direction: OrderDirection::Ask,
+            expected_tick_state: None,
+            expected_error: None,
+        },
+        PruneTickTestCase {
+            name: "fully cancelled tick with a sumtree",
+            operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    valid_tick_id,
+                    0,
+                    OrderDirection::Bid,
+                    sender.clone(),
+                    Uint128::from(10u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    valid_tick_id,
+                    1,
+                    OrderDirection::Bid,
+                    sender.clone(),
+                    Uint128::from(10u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::Cancel((valid_tick_id, 0)),
+                OrderOperation::Cancel((valid_tick_id, 1)),
+            ],
+            tick_id: valid_tick_id,
+            direction: OrderDirection::Bid,
+            expected_tick_state: None,
+            expected_error: None,
+        },
+        PruneTickTestCase {
+            name: "direction without orders leaves the other direction intact",
+            operations: vec![OrderOperation::PlaceLimit(LimitOrder::new(
+                valid_tick_id,
+                0,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(10u128),
+                Decimal256::zero(),
+                None,
+            ))],
+            tick_id: valid_tick_id,
+            direction: OrderDirection::Bid,
+            expected_tick_state: Some(TickState {
+                ask_values: TickValues {
+                    total_amount_of_liquidity: Decimal256::from_ratio(10u128, 1u128),
+                    cumulative_total_value: Decimal256::from_ratio(10u128, 1u128),
+                    ..TickValues::default()
+                },
+                bid_values: TickValues::default(),
+            }),
+            expected_error: None,
+        },
+        PruneTickTestCase {
+            name: "unclaimed partially filled order",
+            operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    valid_tick_id,
+                    0,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(10u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(5u128),
+                    OrderDirection::Bid,
+                    Addr::unchecked("buyer"),
+                )),
+            ],
+            tick_id: valid_tick_id,
+            direction: OrderDirection::Ask,
+            expected_tick_state: None,
+            expected_error: Some(ContractError::TickNotPrunable {
+                tick_id: valid_tick_id,
+                reason: "tick has remaining liquidity".to_string(),
+            }),
+        },
+        PruneTickTestCase {
+            name: "unclaimed fully filled order",
+            operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    valid_tick_id,
+                    0,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(10u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                    Addr::unchecked("buyer"),
+                )),
+            ],
+            tick_id: valid_tick_id,
+            direction: OrderDirection::Ask,
+            expected_tick_state: None,
+            expected_error: Some(ContractError::TickNotPrunable {
+                tick_id: valid_tick_id,
+                reason: "order 0 is still resting on the tick".to_string(),
+            }),
+        },
+        PruneTickTestCase {
+            name: "tick without state",
+            operations: vec![],
+            tick_id: valid_tick_id,
+            direction: OrderDirection::Ask,
+            expected_tick_state: None,
+            expected_error: Some(ContractError::InvalidTickId {
+                tick_id: valid_tick_id,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for operation in test.operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // Anyone may prune a tick
+        let res = prune_tick(
+            deps.as_mut(),
+            mock_info("keeper", &[]),
+            test.tick_id,
+            test.direction,
+        );
+
+        if let Some(err) = test.expected_error {
+            assert_eq!(res, Err(err), "{}", format_test_name(test.name));
+            continue;
+        }
+        res.unwrap();
+
+        let tick_state = TICK_STATE
+            .may_load(deps.as_ref().storage, test.tick_id)
+            .unwrap();
+        assert_eq!(
+            tick_state,
+            test.expected_tick_state,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // The sumtree of the pruned direction is fully removed
+        assert!(
+            TREE.may_load(
+                deps.as_ref().storage,
+                &(test.tick_id, &test.direction.to_string())
+            )
+            .unwrap()
+            .is_none(),
+            "{}",
+            format_test_name(test.name)
+        );
+        let remaining_nodes = NODES
+            .prefix(test.tick_id)
+            .range(deps.as_ref().storage, None, None, Order::Ascending)
+            .filter(|item| item.as_ref().unwrap().1.direction == test.direction)
+            .count();
+        assert_eq!(remaining_nodes, 0, "{}", format_test_name(test.name));
+    }
+}
+
+#[test]
+fn test_consolidate_orders() {
+    let valid_tick_id = 0;
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // Three orders of the sender on one tick, with another owner's order between them
+    let operations = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            valid_tick_id,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            valid_tick_id,
+            1,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            valid_tick_id,
+            2,
+            OrderDirection::Ask,
+            Addr::unchecked("other"),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            valid_tick_id,
+            3,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        )),
+        // Fills the first order and half of the second
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(15u128),
+            OrderDirection::Bid,
+            Addr::unchecked("buyer"),
+        )),
+    ];
+    for operation in operations {
+        operation
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+
+    let mut claimable_before = Uint128::zero();
+    let mut remaining_before = Uint128::zero();
+    for order_id in [0, 1, 3] {
+        let order = orders()
+            .load(deps.as_ref().storage, &(valid_tick_id, order_id))
+            .unwrap();
+        let (filled, _) = get_unclaimed_fill(deps.as_ref().storage, &order).unwrap();
+        remaining_before += order.quantity - filled;
+        claimable_before += claimable_amount(deps.as_ref(), valid_tick_id, order_id)
+            .unwrap()
+            .amount;
+    }
+    assert_eq!(claimable_before, Uint128::from(15u128));
+    assert_eq!(remaining_before, Uint128::from(15u128));
+
+    // -- System under test --
+    let res = consolidate_orders(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        valid_tick_id,
+        OrderDirection::Ask,
+    )
+    .unwrap();
+
+    // -- Post test assertions --
+
+    // Filled portions are claimed before merging
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: "cosmos2contract".to_string(),
+                    to_address: sender.to_string(),
+                    amount: vec![coin_u256(Uint256::from(10u128), QUOTE_DENOM)],
+                },
+                REPLY_ID_CLAIM,
+            ),
+            SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: "cosmos2contract".to_string(),
+                    to_address: sender.to_string(),
+                    amount: vec![coin_u256(Uint256::from(5u128), QUOTE_DENOM)],
+                },
+                REPLY_ID_CLAIM,
+            ),
+        ]
+    );
+
+    // The remainders are merged into a single order at the back of the queue
+    let sender_orders = get_orders_by_owner(
+        deps.as_ref().storage,
+        FilterOwnerOrders::ByTick(valid_tick_id, sender.clone()),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    let mut expected_order = LimitOrder::new(
+        valid_tick_id,
+        4,
+        OrderDirection::Ask,
+        sender.clone(),
+        remaining_before,
+        decimal256_from_u128(40u128),
+        None,
+    );
+    expected_order.placed_quantity = expected_order.quantity;
+    assert_eq!(sender_orders, vec![expected_order]);
+    assert_eq!(
+        claimable_amount(deps.as_ref(), valid_tick_id, 4)
+            .unwrap()
+            .amount,
+        Uint128::zero()
+    );
+
+    // Tick liquidity is unchanged by the merge
+    let tick_values = TICK_STATE
+        .load(deps.as_ref().storage, valid_tick_id)
+        .unwrap()
+        .get_values(OrderDirection::Ask);
+    assert_eq!(
+        tick_values.total_amount_of_liquidity,
+        decimal256_from_u128(25u128)
+    );
+
+    // Filling the rest of the tick lets the merged order claim exactly its quantity
+    OrderOperation::RunMarket(MarketOrder::new(
+        Uint128::from(25u128),
+        OrderDirection::Bid,
+        Addr::unchecked("buyer"),
+    ))
+    .run(deps.as_mut(), env.clone(), info.clone())
+    .unwrap();
+    assert_eq!(
+        claimable_amount(deps.as_ref(), valid_tick_id, 2)
+            .unwrap()
+            .amount,
+        Uint128::from(10u128)
+    );
+    assert_eq!(
+        claimable_amount(deps.as_ref(), valid_tick_id, 4)
+            .unwrap()
+            .amount,
+        Uint128::from(15u128)
+    );
+}
+
+struct ConsolidateOrdersErrorTestCase {
+    name: &'static str,
+    operations: Vec<OrderOperation>,
+    expected_error: ContractError,
+}
+
+#[test]
+fn test_consolidate_orders_errors() {
+    let valid_tick_id = 0;
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let test_cases = vec![
+        ConsolidateOrdersErrorTestCase {
+            name: "no orders",
+            operations: vec![],
+            expected_error: ContractError::CannotConsolidate {
+                tick_id: valid_tick_id,
+                reason: "no orders to merge".to_string(),
+            },
+        },
+        ConsolidateOrdersErrorTestCase {
+            name: "single unfilled order",
+            operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    valid_tick_id,
+                    0,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(10u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    valid_tick_id,
+                    1,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(10u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(10u128),
+                    OrderDirection::Bid,
+                    Addr::unchecked("buyer"),
+                )),
+            ],
+            expected_error: ContractError::CannotConsolidate {
+                tick_id: valid_tick_id,
+                reason: "fewer than two unfilled orders to merge".to_string(),
+            },
+        },
+        ConsolidateOrdersErrorTestCase {
+            name: "differing claim bounties",
+            operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    valid_tick_id,
+                    0,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(10u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    valid_tick_id,
+                    1,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(10u128),
+                    Decimal256::zero(),
+                    Some(Decimal256::percent(1)),
+                )),
+            ],
+            expected_error: ContractError::CannotConsolidate {
+                tick_id: valid_tick_id,
+                reason: "orders have differing claim bounties or expiries".to_string(),
+            },
+        },
+    ];
+
+    for test in test_cases {
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for operation in test.operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        let res = consolidate_orders(deps.as_mut(), env, info, valid_tick_id, OrderDirection::Ask);
+        assert_eq!(
+            res.unwrap_err(),
+            test.expected_error,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+#[test]
+fn test_tick_transition_events() {
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    let tick_id = 0;
+    let transition_events = |events: &[Event]| -> Vec<String> {
+        events
+            .iter()
+            .filter(|event| event.ty == "tick_activated" || event.ty == "tick_deactivated")
+            .map(|event| {
+                assert_eq!(
+                    event.attributes,
+                    vec![
+                        cosmwasm_std::Attribute::new("tick_id", tick_id.to_string()),
+                        cosmwasm_std::Attribute::new("direction", OrderDirection::Ask.to_string()),
+                    ]
+                );
+                event.ty.clone()
+            })
+            .collect()
+    };
+    let place_ask = |deps: &mut cosmwasm_std::OwnedDeps<_, _, _, _>, quantity: u128| {
+        place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info(sender.as_str(), &[coin(quantity, BASE_DENOM)]),
+            tick_id,
+            OrderDirection::Ask,
+            Uint128::from(quantity),
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap()
+    };
+
+    // Placing on an empty tick activates it, further orders keep it active
+    let res = place_ask(&mut deps, 10);
+    assert_eq!(transition_events(&res.events), vec!["tick_activated"]);
+    let res = place_ask(&mut deps, 10);
+    assert!(transition_events(&res.events).is_empty());
+
+    // A fill that leaves liquidity on the tick does not deactivate it
+    let mut market_order =
+        MarketOrder::new(Uint128::from(5u128), OrderDirection::Bid, sender.clone());
+    let (_, _, _, events) = run_market_order(
+        deps.as_mut().storage,
+        env.contract.address.clone(),
+        env.block.time,
+        &mut market_order,
+        MAX_TICK,
+        false,
+        None,
+    )
+    .unwrap();
+    assert!(transition_events(&events).is_empty());
+
+    // Filling the rest of the tick deactivates it once
+    let mut market_order =
+        MarketOrder::new(Uint128::from(15u128), OrderDirection::Bid, sender.clone());
+    let (_, _, _, events) = run_market_order(
+        deps.as_mut().storage,
+        env.contract.address.clone(),
+        env.block.time,
+        &mut market_order,
+        MAX_TICK,
+        false,
+        None,
+    )
+    .unwrap();
+    assert_eq!(transition_events(&events), vec!["tick_deactivated"]);
+
+    // Placing again re-activates the tick, and cancelling its only order deactivates it
+    let res = place_ask(&mut deps, 10);
+    assert_eq!(transition_events(&res.events), vec!["tick_activated"]);
+    let order_id = res
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "order_id")
+        .unwrap()
+        .value
+        .parse::<u64>()
+        .unwrap();
+    let res = cancel_limit(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(sender.as_str(), &[]),
+        tick_id,
+        order_id,
+    )
+    .unwrap();
+    assert_eq!(transition_events(&res.events), vec!["tick_deactivated"]);
+}
+#[test]
+fn test_transfer_order() {
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let owner = Addr::unchecked(DEFAULT_SENDER);
+    let new_owner = Addr::unchecked("newowner");
+    let tick_id = 0;
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // An ask of 100, half filled before the transfer
+    let operations = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            0,
+            OrderDirection::Ask,
+            owner.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(50u128),
+            OrderDirection::Bid,
+            owner.clone(),
+        )),
+    ];
+    for operation in operations {
+        operation
+            .run(deps.as_mut(), env.clone(), mock_info(owner.as_str(), &[]))
+            .unwrap();
+    }
+
+    // Only the owner may transfer the order
+    let err = transfer_order(
+        deps.as_mut(),
+        mock_info(new_owner.as_str(), &[]),
+        tick_id,
+        0,
+        new_owner.to_string(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // The new owner must be a valid address
+    let err = transfer_order(
+        deps.as_mut(),
+        mock_info(owner.as_str(), &[]),
+        tick_id,
+        0,
+        "".to_string(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Std(_)));
+
+    // -- System under test --
+    transfer_order(
+        deps.as_mut(),
+        mock_info(owner.as_str(), &[]),
+        tick_id,
+        0,
+        new_owner.to_string(),
+    )
+    .unwrap();
+
+    // -- Post Test Assertions --
+    let owner_orders = get_orders_by_owner(
+        &deps.storage,
+        FilterOwnerOrders::all(owner.clone()),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    assert!(owner_orders.is_empty());
+    let new_owner_orders = get_orders_by_owner(
+        &deps.storage,
+        FilterOwnerOrders::by_tick(tick_id, new_owner.clone()),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(new_owner_orders.len(), 1);
+    assert_eq!(new_owner_orders[0].owner, new_owner);
+
+    // The fill from before the transfer is paid to the new owner
+    let (amount, bank_msgs, _) = claim_order(
+        deps.as_mut().storage,
+        env.contract.address.clone(),
+        owner.clone(),
+        tick_id,
+        0,
+        None,
+    )
+    .unwrap();
+    assert_eq!(amount, Uint256::from(50u128));
+    assert_eq!(
+        bank_msgs[0],
+        SubMsg::reply_on_error(
+            MsgSend256 {
+                from_address: env.contract.address.to_string(),
+                to_address: new_owner.to_string(),
+                amount: vec![coin_u256(50u128, QUOTE_DENOM)],
+            },
+            REPLY_ID_CLAIM,
+        )
+    );
+}
+struct CancelOrdersInRangeTestCase {
+    name: &'static str,
+    extra_operations: Vec<OrderOperation>,
+    min_tick: i64,
+    max_tick: i64,
+    limit: Option<u64>,
+    expected_cancelled: Vec<(i64, u64)>,
+    expected_refund: u128,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_cancel_orders_in_range() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let other = Addr::unchecked("other");
+    // Sender asks on ticks -100, 0, 100 and 200, another owner's ask on tick 0 and a sender bid on
+    // tick -50
+    let base_operations = [
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            -100,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            1,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(20u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            2,
+            OrderDirection::Ask,
+            other.clone(),
+            Uint128::from(25u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            100,
+            3,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(30u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            200,
+            4,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(40u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            -50,
+            5,
+            OrderDirection::Bid,
+            sender.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        )),
+    ];
+    let test_cases = vec![
+        CancelOrdersInRangeTestCase {
+            name: "cancels owned orders in range",
+            extra_operations: vec![],
+            min_tick: -100,
+            max_tick: 100,
+            limit: None,
+            expected_cancelled: vec![(-100, 0), (0, 1), (100, 3)],
+            expected_refund: 60,
+            expected_error: None,
+        },
+        CancelOrdersInRangeTestCase {
+            name: "limit caps cancelled orders",
+            extra_operations: vec![],
+            min_tick: -100,
+            max_tick: 100,
+            limit: Some(2),
+            expected_cancelled: vec![(-100, 0), (0, 1)],
+            expected_refund: 30,
+            expected_error: None,
+        },
+        CancelOrdersInRangeTestCase {
+            name: "partially filled order is skipped",
+            extra_operations: vec![OrderOperation::RunMarket(MarketOrder::new(
+                Uint128::from(5u128),
+                OrderDirection::Bid,
+                other.clone(),
+            ))],
+            min_tick: -100,
+            max_tick: 100,
+            limit: None,
+            expected_cancelled: vec![(0, 1), (100, 3)],
+            expected_refund: 50,
+            expected_error: None,
+        },
+        CancelOrdersInRangeTestCase {
+            name: "no orders in range",
+            extra_operations: vec![],
+            min_tick: 101,
+            max_tick: 199,
+            limit: None,
+            expected_cancelled: vec![],
+            expected_refund: 0,
+            expected_error: None,
+        },
+        CancelOrdersInRangeTestCase {
+            name: "inverted range",
+            extra_operations: vec![],
+            min_tick: 100,
+            max_tick: -100,
+            limit: None,
+            expected_cancelled: vec![],
+            expected_refund: 0,
+            expected_error: Some(ContractError::InvalidTickBounds {
+                min_tick: 100,
+                max_tick: -100,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        for operation in base_operations.iter().chain(test.extra_operations.iter()) {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = cancel_orders_in_range(
+            deps.as_mut(),
+            info.clone(),
+            test.min_tick,
+            test.max_tick,
+            OrderDirection::Ask,
+            test.limit,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                res.unwrap_err(),
+                expected_error,
+                "{}",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+        let res = res.unwrap();
+
+        // Only the expected orders were removed
+        for key in [(-100, 0), (0, 1), (0, 2), (100, 3), (200, 4), (-50, 5)] {
+            assert_eq!(
+                orders().has(&deps.storage, &key),
+                !test.expected_cancelled.contains(&key),
+                "{}: unexpected state for order {key:?}",
+                format_test_name(test.name)
+            );
+        }
+
+        let expected_messages = if test.expected_refund == 0 {
+            vec![]
+        } else {
+            vec![SubMsg::reply_on_error(
+                BankMsg::Send {
+                    to_address: sender.to_string(),
+                    amount: vec![coin(test.expected_refund, BASE_DENOM)],
+                },
+                REPLY_ID_REFUND,
+            )]
+        };
+        assert_eq!(
+            res.messages,
+            expected_messages,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Each cancellation is recorded in its tick's sumtree
+        for (tick_id, _) in &test.expected_cancelled {
+            let root = get_root_node(&deps.storage, *tick_id, OrderDirection::Ask).unwrap();
+            assert!(
+                !root.get_value().is_zero(),
+                "{}",
+                format_test_name(test.name)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_order_ids_not_reused() {
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    let place = |deps: &mut DepsMut, tick_id: i64| -> u64 {
+        let res = place_limit(
+            deps,
+            env.clone(),
+            mock_info(DEFAULT_SENDER, &[coin(10u128, BASE_DENOM)]),
+            tick_id,
+            OrderDirection::Ask,
+            Uint128::from(10u128),
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+        res.attributes
+            .iter()
+            .find(|attr| attr.key == "order_id")
+            .unwrap()
+            .value
+            .parse()
+            .unwrap()
+    };
+
+    // Place and cancel the first order on the tick
+    let first_id = place(&mut deps.as_mut(), 0);
+    assert_eq!(first_id, 0);
+    cancel_limit(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(DEFAULT_SENDER, &[]),
+        0,
+        first_id,
+    )
+    .unwrap();
+
+    // Re-placing on the same tick allocates a fresh, higher id
+    let second_id = place(&mut deps.as_mut(), 0);
+    assert_eq!(second_id, 1);
+
+    // Ids on other ticks continue from the same counter and never collide
+    let other_tick_id = place(&mut deps.as_mut(), 1);
+    assert_eq!(other_tick_id, 2);
+
+    // Pruning an emptied tick does not reset the counter
+    cancel_limit(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(DEFAULT_SENDER, &[]),
+        0,
+        second_id,
+    )
+    .unwrap();
+    prune_tick(
+        deps.as_mut(),
+        mock_info("keeper", &[]),
+        0,
+        OrderDirection::Ask,
+    )
+    .unwrap();
+    let third_id = place(&mut deps.as_mut(), 0);
+    assert_eq!(third_id, 3);
+
+    // Cancelled ids are never written back to storage
+    for cancelled_id in [first_id, second_id] {
+        assert!(orders()
+            .may_load(deps.as_ref().storage, &(0, cancelled_id))
+            .unwrap()
+            .is_none());
+    }
+    assert_eq!(ORDER_ID.load(deps.as_ref().storage).unwrap(), 4);
+}
+
+struct RollOrderTestCase {
+    name: &'static str,
+    sender: &'static str,
+    fill_quantity: Uint128,
+    new_direction: OrderDirection,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_roll_order() {
+    let valid_tick_id = 0;
+    let new_tick_id = -1;
+    let test_cases = vec![
+        RollOrderTestCase {
+            name: "fully filled ask rolled into a bid",
+            sender: DEFAULT_SENDER,
+            fill_quantity: Uint128::from(10u128),
+            new_direction: OrderDirection::Bid,
+            expected_error: None,
+        },
+        RollOrderTestCase {
+            name: "partially filled ask rolls only the filled amount",
+            sender: DEFAULT_SENDER,
+            fill_quantity: Uint128::from(4u128),
+            new_direction: OrderDirection::Bid,
+            expected_error: None,
+        },
+        RollOrderTestCase {
+            name: "claimed denom cannot fund an ask",
+            sender: DEFAULT_SENDER,
+            fill_quantity: Uint128::from(10u128),
+            new_direction: OrderDirection::Ask,
+            expected_error: Some(ContractError::PaymentError(PaymentError::MissingDenom(
+                BASE_DENOM.to_string(),
+            ))),
+        },
+        RollOrderTestCase {
+            name: "unfilled order",
+            sender: DEFAULT_SENDER,
+            fill_quantity: Uint128::zero(),
+            new_direction: OrderDirection::Bid,
+            expected_error: Some(ContractError::NotYetReached {
+                tick_id: 0,
+                order_id: 0,
+            }),
+        },
+        RollOrderTestCase {
+            name: "sender is not the owner",
+            sender: "not_the_owner",
+            fill_quantity: Uint128::from(10u128),
+            new_direction: OrderDirection::Bid,
+            expected_error: Some(ContractError::Unauthorized {}),
+        },
+    ];
+
+    for test in test_cases {
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        let mut operations = vec![OrderOperation::PlaceLimit(LimitOrder::new(
+            valid_tick_id,
+            0,
+            OrderDirection::Ask,
+            Addr::unchecked(DEFAULT_SENDER),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        ))];
+        if !test.fill_quantity.is_zero() {
+            operations.push(OrderOperation::RunMarket(MarketOrder::new(
+                test.fill_quantity,
+                OrderDirection::Bid,
+                Addr::unchecked("buyer"),
+            )));
+        }
+        for operation in operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = roll_order(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(test.sender, &[]),
+            valid_tick_id,
+            0,
+            new_tick_id,
+            test.new_direction,
+        );
+
+        // -- Post Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(res, Err(err), "{}", format_test_name(test.name));
+            continue;
+        }
+        let res = res.unwrap();
+
+        let new_order_id: u64 = res
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "new_order_id")
+            .unwrap()
+            .value
+            .parse()
+            .unwrap();
+        let new_order = orders()
+            .load(deps.as_ref().storage, &(new_tick_id, new_order_id))
+            .unwrap();
+        assert_eq!(
+            new_order,
+            LimitOrder::new(
+                new_tick_id,
+                new_order_id,
+                OrderDirection::Bid,
+                Addr::unchecked(DEFAULT_SENDER),
+                test.fill_quantity,
+                Decimal256::zero(),
+                None,
+            ),
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // The proceeds stay in the contract, so no claim is sent to the owner
+        assert!(res.messages.is_empty(), "{}", format_test_name(test.name));
+        assert_eq!(
+            res.events[0].ty,
+            "limitClaimed",
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Any unfilled remainder of the original order stays on the book
+        let remaining = Uint128::from(10u128) - test.fill_quantity;
+        let original = orders()
+            .may_load(deps.as_ref().storage, &(valid_tick_id, 0))
+            .unwrap();
+        assert_eq!(
+            original.map(|order| order.quantity),
+            (!remaining.is_zero()).then_some(remaining),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct BoundaryTickTestCase {
+    name: &'static str,
+    tick_id: i64,
+    order_direction: OrderDirection,
+    quantity: u128,
+    market_quantity: u128,
+    // Expected (market order output, claimed output), `None` if the market order is too small to fill
+    expected_amounts: Option<(u128, u128)>,
+}
+
+#[test]
+fn test_orders_at_tick_bounds() {
+    let max_price = Uint128::try_from(tick_to_price(MAX_TICK).unwrap().to_uint_floor())
+        .unwrap()
+        .u128();
+    let min_price_inverse = 1_000_000_000_000u128;
+    let test_cases = vec![
+        BoundaryTickTestCase {
+            name: "ask at MIN_TICK",
+            tick_id: MIN_TICK,
+            order_direction: OrderDirection::Ask,
+            quantity: 1,
+            market_quantity: min_price_inverse,
+            expected_amounts: Some((1, min_price_inverse)),
+        },
+        BoundaryTickTestCase {
+            name: "bid at MIN_TICK",
+            tick_id: MIN_TICK,
+            order_direction: OrderDirection::Bid,
+            quantity: min_price_inverse,
+            market_quantity: 1,
+            expected_amounts: Some((min_price_inverse, 1)),
+        },
+        BoundaryTickTestCase {
+            name: "ask at MAX_TICK",
+            tick_id: MAX_TICK,
+            order_direction: OrderDirection::Ask,
+            quantity: max_price,
+            market_quantity: 1,
+            expected_amounts: Some((max_price, 1)),
+        },
+        BoundaryTickTestCase {
+            name: "bid at MAX_TICK",
+            tick_id: MAX_TICK,
+            order_direction: OrderDirection::Bid,
+            quantity: 1,
+            market_quantity: max_price,
+            expected_amounts: Some((1, max_price)),
+        },
+        BoundaryTickTestCase {
+            name: "market order too small to fill at MIN_TICK",
+            tick_id: MIN_TICK,
+            order_direction: OrderDirection::Ask,
+            quantity: 10,
+            market_quantity: min_price_inverse - 1,
+            expected_amounts: None,
+        },
+        BoundaryTickTestCase {
+            name: "market order too small to fill at MAX_TICK",
+            tick_id: MAX_TICK,
+            order_direction: OrderDirection::Bid,
+            quantity: 10,
+            market_quantity: max_price - 1,
+            expected_amounts: None,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        let order_denom = match test.order_direction {
+            OrderDirection::Ask => BASE_DENOM,
+            OrderDirection::Bid => QUOTE_DENOM,
+        };
+
+        // Place one order to be filled and claimed, and another to be cancelled
+        for _ in 0..2 {
+            place_limit(
+                &mut deps.as_mut(),
+                env.clone(),
+                mock_info("maker", &[coin(test.quantity, order_denom)]),
+                test.tick_id,
+                test.order_direction,
+                Uint128::from(test.quantity),
+                None,
+                None,
+                None,
+                None,
+                None,
+                false,
+            )
+            .unwrap();
+        }
+
+        // -- System under test --
+        let tick_bound = match test.order_direction {
+            OrderDirection::Ask => MAX_TICK,
+            OrderDirection::Bid => MIN_TICK,
+        };
+        let mut market_order = MarketOrder::new(
+            Uint128::from(test.market_quantity),
+            test.order_direction.opposite(),
+            Addr::unchecked("taker"),
+        );
+        let res = run_market_order(
+            deps.as_mut().storage,
+            env.contract.address.clone(),
+            env.block.time,
+            &mut market_order,
+            tick_bound,
+            false,
+            None,
+        );
+
+        // -- Post test assertions --
+        match test.expected_amounts {
+            Some((expected_output, expected_claimed)) => {
+                let (output, ..) = res.unwrap();
+                assert_eq!(
+                    output,
+                    Uint256::from(expected_output),
+                    "{}",
+                    format_test_name(test.name)
+                );
+
+                let (claimed, ..) = claim_order(
+                    deps.as_mut().storage,
+                    env.contract.address.clone(),
+                    Addr::unchecked("maker"),
+                    test.tick_id,
+                    0,
+                    None,
+                )
+                .unwrap();
+                assert_eq!(
+                    claimed,
+                    Uint256::from(expected_claimed),
+                    "{}",
+                    format_test_name(test.name)
+                );
+            }
+            None => assert_eq!(
+                res.unwrap_err(),
+                ContractError::ZeroOutput,
+                "{}",
+                format_test_name(test.name)
+            ),
+        }
+
+        // The untouched order is refunded in full
+        let res = cancel_limit(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("maker", &[]),
+            test.tick_id,
+            1,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: "maker".to_string(),
+                amount: vec![coin(test.quantity, order_denom)],
+            }
+            .into(),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+#[test]
+fn test_market_order_stops_at_zero_output() {
+    let min_price_inverse = 1_000_000_000_000u128;
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+    // One unit of base at each of the two lowest ticks
+    for tick_id in [MIN_TICK, MIN_TICK + 1] {
+        place_limit(
+            &mut deps.as_mut(),
+            env.clone(),
+            mock_info("maker", &[coin(1, BASE_DENOM)]),
+            tick_id,
+            OrderDirection::Ask,
+            Uint128::one(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+    }
+
+    let mut run_bid = |quantity: u128| {
+        let mut market_order = MarketOrder::new(
+            Uint128::from(quantity),
+            OrderDirection::Bid,
+            Addr::unchecked("taker"),
+        );
+        run_market_order(
+            deps.as_mut().storage,
+            env.contract.address.clone(),
+            env.block.time,
+            &mut market_order,
+            MAX_TICK,
+            false,
+            None,
+        )
+        .map(|(output, ..)| (output, market_order.quantity))
+    };
+
+    // -- System under test --
+    // An input too small to buy a single unit is rejected rather than consumed
+    assert_eq!(
+        run_bid(min_price_inverse - 1),
+        Err(ContractError::ZeroOutput)
+    );
+
+    // A fill that has produced output stops at the zero output boundary on the next tick and leaves
+    // the dust unconsumed
+    assert_eq!(
+        run_bid(min_price_inverse * 3 / 2),
+        Ok((Uint256::one(), Uint128::from(min_price_inverse / 2)))
+    );
+
+    // -- Post test assertions --
+    assert_eq!(
+        get_directional_liquidity(deps.as_ref().storage, OrderDirection::Ask).unwrap(),
+        Decimal256::one()
+    );
+}
@@ -13,6 +13,8 @@ struct CreateOrderbookTestCase {
     name: &'static str,
     quote_denom: String,
     base_denom: String,
+    // (quote denom, base denom) of an orderbook created beforehand
+    existing_book: Option<(&'static str, &'static str)>,
     expected_error: Option<ContractError>,
 }
 
@@ -23,12 +25,14 @@ fn test_create_orderbook() {
             name: "valid_orderbook",
             quote_denom: QUOTE_DENOM.to_string(),
             base_denom: BASE_DENOM.to_string(),
+            existing_book: None,
             expected_error: None,
         },
         CreateOrderbookTestCase {
             name: "invalid quote denom",
             quote_denom: "notadenom".to_string(),
             base_denom: BASE_DENOM.to_string(),
+            existing_book: None,
             expected_error: Some(ContractError::InvalidDenom {
                 denom: "notadenom".to_string(),
             }),
@@ -37,6 +41,7 @@ fn test_create_orderbook() {
             name: "invalid base denom",
             quote_denom: QUOTE_DENOM.to_string(),
             base_denom: "notadenom".to_string(),
+            existing_book: None,
             expected_error: Some(ContractError::InvalidDenom {
                 denom: "notadenom".to_string(),
             }),
@@ -45,21 +50,82 @@ fn test_create_orderbook() {
             name: "empty denom",
             quote_denom: QUOTE_DENOM.to_string(),
             base_denom: "".to_string(),
-            expected_error: Some(ContractError::InvalidDenom {
-                denom: "".to_string(),
+            existing_book: None,
+            expected_error: Some(ContractError::InvalidPair {
+                token_in_denom: QUOTE_DENOM.to_string(),
+                token_out_denom: "".to_string(),
+            }),
+        },
+        CreateOrderbookTestCase {
+            name: "empty quote denom",
+            quote_denom: "".to_string(),
+            base_denom: BASE_DENOM.to_string(),
+            existing_book: None,
+            expected_error: Some(ContractError::InvalidPair {
+                token_in_denom: "".to_string(),
+                token_out_denom: BASE_DENOM.to_string(),
+            }),
+        },
+        CreateOrderbookTestCase {
+            name: "malformed denom",
+            quote_denom: "1quote!".to_string(),
+            base_denom: BASE_DENOM.to_string(),
+            existing_book: None,
+            expected_error: Some(ContractError::InvalidPair {
+                token_in_denom: "1quote!".to_string(),
+                token_out_denom: BASE_DENOM.to_string(),
+            }),
+        },
+        CreateOrderbookTestCase {
+            name: "denom too short",
+            quote_denom: QUOTE_DENOM.to_string(),
+            base_denom: "ab".to_string(),
+            existing_book: None,
+            expected_error: Some(ContractError::InvalidPair {
+                token_in_denom: QUOTE_DENOM.to_string(),
+                token_out_denom: "ab".to_string(),
             }),
         },
         CreateOrderbookTestCase {
             name: "duplicate denoms",
             quote_denom: QUOTE_DENOM.to_string(),
             base_denom: QUOTE_DENOM.to_string(),
+            existing_book: None,
             expected_error: Some(ContractError::DuplicateDenoms {}),
         },
+        CreateOrderbookTestCase {
+            name: "reversed pair of existing book",
+            quote_denom: BASE_DENOM.to_string(),
+            base_denom: QUOTE_DENOM.to_string(),
+            existing_book: Some((QUOTE_DENOM, BASE_DENOM)),
+            expected_error: Some(ContractError::BookAlreadyExists {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+            }),
+        },
+        CreateOrderbookTestCase {
+            name: "same pair as existing book",
+            quote_denom: QUOTE_DENOM.to_string(),
+            base_denom: BASE_DENOM.to_string(),
+            existing_book: Some((QUOTE_DENOM, BASE_DENOM)),
+            expected_error: Some(ContractError::BookAlreadyExists {
+                quote_denom: QUOTE_DENOM.to_string(),
+                base_denom: BASE_DENOM.to_string(),
+            }),
+        },
     ];
 
     for test in test_cases {
         // -- Test Setup --
         let mut deps = mock_dependencies_custom();
+        if let Some((quote_denom, base_denom)) = test.existing_book {
+            create_orderbook(
+                deps.as_mut(),
+                quote_denom.to_string(),
+                base_denom.to_string(),
+            )
+            .unwrap();
+        }
 
         // -- System under test --
         let res = create_orderbook(
@@ -0,0 +1,356 @@
+use cosmwasm_std::{
+    testing::{mock_env, mock_info},
+    Addr, Decimal256, Order, Reply, ReplyOn, SubMsg, SubMsgResponse, SubMsgResult, Uint128,
+    Uint256,
+};
+
+use super::{
+    mock_querier::mock_dependencies_custom,
+    test_constants::{BASE_DENOM, DEFAULT_SENDER, QUOTE_DENOM},
+    test_utils::{format_test_name, OrderOperation},
+};
+use crate::{
+    contract::{execute, reply},
+    msg::ExecuteMsg,
+    orderbook::create_orderbook,
+    state::{FAILED_PAYOUTS, PENDING_PAYOUTS},
+    types::{
+        coin_u256, Coin256, LimitOrder, MarketOrder, MsgSend256, OrderDirection, REPLY_ID_CLAIM,
+        REPLY_ID_CLAIM_BOUNTY, REPLY_ID_REFUND,
+    },
+    ContractError,
+};
+
+const KEEPER: &str = "keeper";
+
+struct PayoutReplyTestCase {
+    name: &'static str,
+    operations: Vec<OrderOperation>,
+    sender: &'static str,
+    msg: ExecuteMsg,
+    expected_payout_ids: Vec<u64>,
+    // The expected reply id of each payout in execution order, and whether its send succeeds
+    replies: Vec<(u64, bool)>,
+    expected_failed_payouts: Vec<(&'static str, Vec<Coin256>)>,
+}
+
+fn filled_ask_with_bounty() -> Vec<OrderOperation> {
+    vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            Addr::unchecked(DEFAULT_SENDER),
+            Uint128::from(1000u128),
+            Decimal256::zero(),
+            Some(Decimal256::percent(1)),
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(1000u128),
+            OrderDirection::Bid,
+            Addr::unchecked("buyer"),
+        )),
+    ]
+}
+
+#[test]
+fn test_payout_reply() {
+    let claim_msg = ExecuteMsg::ClaimLimit {
+        tick_id: 0,
+        order_id: 0,
+        min_claim: None,
+        to_credit: None,
+    };
+    let test_cases = vec![
+        PayoutReplyTestCase {
+            name: "all payouts succeed",
+            operations: filled_ask_with_bounty(),
+            sender: KEEPER,
+            msg: claim_msg.clone(),
+            expected_payout_ids: vec![REPLY_ID_CLAIM, REPLY_ID_CLAIM_BOUNTY],
+            replies: vec![(REPLY_ID_CLAIM, true), (REPLY_ID_CLAIM_BOUNTY, true)],
+            expected_failed_payouts: vec![],
+        },
+        PayoutReplyTestCase {
+            name: "failed claim is credited to the order owner",
+            operations: filled_ask_with_bounty(),
+            sender: KEEPER,
+            msg: claim_msg.clone(),
+            expected_payout_ids: vec![REPLY_ID_CLAIM, REPLY_ID_CLAIM_BOUNTY],
+            replies: vec![(REPLY_ID_CLAIM, false), (REPLY_ID_CLAIM_BOUNTY, true)],
+            expected_failed_payouts: vec![(DEFAULT_SENDER, vec![coin_u256(990u128, QUOTE_DENOM)])],
+        },
+        PayoutReplyTestCase {
+            name: "failed bounty is re-credited to the order owner",
+            operations: filled_ask_with_bounty(),
+            sender: KEEPER,
+            msg: claim_msg.clone(),
+            expected_payout_ids: vec![REPLY_ID_CLAIM, REPLY_ID_CLAIM_BOUNTY],
+            replies: vec![
+                (REPLY_ID_CLAIM, true),
+                (REPLY_ID_CLAIM_BOUNTY, false),
+                // Re-credit of the bounty to the order owner
+                (REPLY_ID_CLAIM, true),
+            ],
+            expected_failed_payouts: vec![],
+        },
+        PayoutReplyTestCase {
+            name: "failed bounty re-credit is credited to the order owner",
+            operations: filled_ask_with_bounty(),
+            sender: KEEPER,
+            msg: claim_msg,
+            expected_payout_ids: vec![REPLY_ID_CLAIM, REPLY_ID_CLAIM_BOUNTY],
+            replies: vec![
+                (REPLY_ID_CLAIM, false),
+                (REPLY_ID_CLAIM_BOUNTY, false),
+                (REPLY_ID_CLAIM, false),
+            ],
+            expected_failed_payouts: vec![(DEFAULT_SENDER, vec![coin_u256(1000u128, QUOTE_DENOM)])],
+        },
+        PayoutReplyTestCase {
+            name: "failed refund is credited to the order owner",
+            operations: vec![OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Ask,
+                Addr::unchecked(DEFAULT_SENDER),
+                Uint128::from(100u128),
+                Decimal256::zero(),
+                None,
+            ))],
+            sender: DEFAULT_SENDER,
+            msg: ExecuteMsg::CancelLimit {
+                tick_id: 0,
+                order_id: 0,
+                swap_refund: None,
+                to_credit: None,
+            },
+            expected_payout_ids: vec![REPLY_ID_REFUND],
+            replies: vec![(REPLY_ID_REFUND, false)],
+            expected_failed_payouts: vec![(DEFAULT_SENDER, vec![coin_u256(100u128, BASE_DENOM)])],
+        },
+    ];
+
+    for test in test_cases {
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for operation in test.operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(test.sender, &[]),
+            test.msg,
+        )
+        .unwrap();
+
+        // Every recoverable payout replies regardless of its result
+        let payout_ids: Vec<u64> = res
+            .messages
+            .iter()
+            .filter(|sub_msg| sub_msg.reply_on == ReplyOn::Always)
+            .map(|sub_msg| sub_msg.id)
+            .collect();
+        assert_eq!(
+            payout_ids,
+            test.expected_payout_ids,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // Simulate the chain replying to each payout in execution order
+        for (id, succeeds) in test.replies {
+            let result = if succeeds {
+                SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: None,
+                })
+            } else {
+                SubMsgResult::Err("failed to send".to_string())
+            };
+            reply(deps.as_mut(), env.clone(), Reply { id, result }).unwrap();
+        }
+
+        assert!(
+            PENDING_PAYOUTS.may_load(&deps.storage).unwrap().is_none(),
+            "{}",
+            format_test_name(test.name)
+        );
+        let failed_payouts: Vec<(Addr, Vec<Coin256>)> = FAILED_PAYOUTS
+            .range(&deps.storage, None, None, Order::Ascending)
+            .map(|item| item.unwrap())
+            .collect();
+        let expected_failed_payouts: Vec<(Addr, Vec<Coin256>)> = test
+            .expected_failed_payouts
+            .into_iter()
+            .map(|(recipient, amount)| (Addr::unchecked(recipient), amount))
+            .collect();
+        assert_eq!(
+            failed_payouts,
+            expected_failed_payouts,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_bounty_reply_recredits_order_owner() {
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(DEFAULT_SENDER, &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+    for operation in filled_ask_with_bounty() {
+        operation
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(KEEPER, &[]),
+        ExecuteMsg::ClaimLimit {
+            tick_id: 0,
+            order_id: 0,
+            min_claim: None,
+            to_credit: None,
+        },
+    )
+    .unwrap();
+
+    let success = SubMsgResult::Ok(SubMsgResponse {
+        events: vec![],
+        data: None,
+    });
+    reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: REPLY_ID_CLAIM,
+            result: success,
+        },
+    )
+    .unwrap();
+    let res = reply(
+        deps.as_mut(),
+        env.clone(),
+        Reply {
+            id: REPLY_ID_CLAIM_BOUNTY,
+            result: SubMsgResult::Err("failed to send".to_string()),
+        },
+    )
+    .unwrap();
+
+    let mut expected_msg = SubMsg::reply_on_error(
+        MsgSend256 {
+            from_address: env.contract.address.to_string(),
+            to_address: DEFAULT_SENDER.to_string(),
+            amount: vec![coin_u256(10u128, QUOTE_DENOM)],
+        },
+        REPLY_ID_CLAIM,
+    );
+    expected_msg.reply_on = ReplyOn::Always;
+    assert_eq!(res.messages, vec![expected_msg]);
+
+    // A reply that does not match the pending payout is rejected
+    let err = reply(
+        deps.as_mut(),
+        env,
+        Reply {
+            id: REPLY_ID_REFUND,
+            result: SubMsgResult::Err("failed to send".to_string()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ReplyError {
+            id: REPLY_ID_REFUND,
+            error: "Reply does not match the next pending payout".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_withdraw_failed_payout() {
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let owed = vec![
+        coin_u256(90u128, QUOTE_DENOM),
+        coin_u256(Uint256::from(5u128), BASE_DENOM),
+    ];
+    FAILED_PAYOUTS
+        .save(
+            deps.as_mut().storage,
+            &Addr::unchecked(DEFAULT_SENDER),
+            &owed,
+        )
+        .unwrap();
+
+    // Only the recipient's credit can be withdrawn
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(KEEPER, &[]),
+        ExecuteMsg::WithdrawFailedPayout {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NoFailedPayout {
+            recipient: KEEPER.to_string(),
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(DEFAULT_SENDER, &[]),
+        ExecuteMsg::WithdrawFailedPayout {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(MsgSend256 {
+            from_address: env.contract.address.to_string(),
+            to_address: DEFAULT_SENDER.to_string(),
+            amount: owed,
+        })]
+    );
+    assert!(FAILED_PAYOUTS
+        .may_load(&deps.storage, &Addr::unchecked(DEFAULT_SENDER))
+        .unwrap()
+        .is_none());
+
+    // The credit can only be withdrawn once
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info(DEFAULT_SENDER, &[]),
+        ExecuteMsg::WithdrawFailedPayout {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NoFailedPayout {
+            recipient: DEFAULT_SENDER.to_string(),
+        }
+    );
+}
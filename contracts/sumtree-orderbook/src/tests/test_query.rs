@@ -1,16 +1,33 @@
+use std::str::FromStr;
+
 use cosmwasm_std::{
-    coin,
+    coin, from_json,
     testing::{mock_env, mock_info},
-    Addr, Coin, Decimal, Decimal256, Uint128,
+    Addr, BankMsg, Coin, CosmosMsg, Decimal, Decimal256, Int256, Order, SignedDecimal256, Uint128,
+    Uint256,
 };
 
 use crate::{
-    constants::{EXPECTED_SWAP_FEE, MAX_TICK, MIN_TICK},
-    orderbook::create_orderbook,
+    constants::{
+        max_spot_price, DEFAULT_MAKER_FEE_RECIPIENT, EXPECTED_SWAP_FEE, MAX_TICK, MIN_TICK,
+    },
+    msg::{
+        BookLiquidityResponse, ClaimableOrder, ClaimableResponse, CompactionCandidate,
+        DenomSolvency, FillPriceResponse, MarketStatsResponse, OrderAvgPriceResponse,
+        QueuePositionResponse, SpreadResponse, SumtreeInfoResponse, SwapExactAmountInResponseData,
+        TickSwapProgressResponse,
+    },
+    order::{cancel_limit, claim_order},
+    orderbook::{create_orderbook, set_taker_fee},
     query,
-    state::IS_ACTIVE,
+    state::{orders, IS_ACTIVE, MAKER_FEE, MARKET_STATS, TICK_STATE},
+    sudo::dispatch_swap_exact_amount_in,
+    sumtree::node::NodeType,
     tests::mock_querier::mock_dependencies_custom,
-    types::{coin_u256, Coin256, LimitOrder, MarketOrder, OrderDirection, TickState, TickValues},
+    types::{
+        coin_u256, Coin256, LimitOrder, MarketOrder, MarketOrderStopReason, MsgSend256,
+        OrderDirection, OrderStatus, TickState, TickValues,
+    },
     ContractError,
 };
 
@@ -591,6 +608,7 @@ fn test_calc_out_amount_given_in() {
 
         let res = query::calc_out_amount_given_in(
             deps.as_ref(),
+            env.clone(),
             test.token_in.clone(),
             test.token_out_denom.to_string(),
             test.swap_fee,
@@ -781,6 +799,7 @@ fn test_all_ticks() {
                     effective_total_amount_swapped: Decimal256::zero(),
                     cumulative_realized_cancels: Decimal256::zero(),
                     last_tick_sync_etas: Decimal256::zero(),
+                    hidden_liquidity: Decimal256::zero(),
                 },
                 bid_values: TickValues::default(),
             }],
@@ -817,6 +836,7 @@ fn test_all_ticks() {
                     effective_total_amount_swapped: Decimal256::zero(),
                     cumulative_realized_cancels: Decimal256::zero(),
                     last_tick_sync_etas: Decimal256::zero(),
+                    hidden_liquidity: Decimal256::zero(),
                 },
                 bid_values: TickValues {
                     total_amount_of_liquidity: Decimal256::one(),
@@ -824,6 +844,7 @@ fn test_all_ticks() {
                     effective_total_amount_swapped: Decimal256::zero(),
                     cumulative_realized_cancels: Decimal256::zero(),
                     last_tick_sync_etas: Decimal256::zero(),
+                    hidden_liquidity: Decimal256::zero(),
                 },
             }],
             start_after: None,
@@ -855,6 +876,7 @@ fn test_all_ticks() {
                         effective_total_amount_swapped: Decimal256::zero(),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                     bid_values: TickValues {
                         total_amount_of_liquidity: decimal256_from_u128(1000u128),
@@ -862,6 +884,7 @@ fn test_all_ticks() {
                         effective_total_amount_swapped: Decimal256::zero(),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 })
                 .collect(),
@@ -894,6 +917,7 @@ fn test_all_ticks() {
                         effective_total_amount_swapped: Decimal256::zero(),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                     bid_values: TickValues {
                         total_amount_of_liquidity: decimal256_from_u128(1000u128),
@@ -901,6 +925,7 @@ fn test_all_ticks() {
                         effective_total_amount_swapped: Decimal256::zero(),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 })
                 .collect(),
@@ -935,6 +960,7 @@ fn test_all_ticks() {
                         effective_total_amount_swapped: Decimal256::zero(),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                     bid_values: TickValues {
                         total_amount_of_liquidity: decimal256_from_u128(1000u128),
@@ -942,6 +968,7 @@ fn test_all_ticks() {
                         effective_total_amount_swapped: Decimal256::zero(),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 })
                 .collect(),
@@ -976,6 +1003,7 @@ fn test_all_ticks() {
                         effective_total_amount_swapped: Decimal256::zero(),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                     bid_values: TickValues {
                         total_amount_of_liquidity: decimal256_from_u128(1000u128),
@@ -983,6 +1011,7 @@ fn test_all_ticks() {
                         effective_total_amount_swapped: Decimal256::zero(),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 })
                 .collect(),
@@ -1017,6 +1046,7 @@ fn test_all_ticks() {
                         effective_total_amount_swapped: Decimal256::zero(),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                     bid_values: TickValues {
                         total_amount_of_liquidity: decimal256_from_u128(1000u128),
@@ -1024,6 +1054,7 @@ fn test_all_ticks() {
                         effective_total_amount_swapped: Decimal256::zero(),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 })
                 .collect(),
@@ -1049,6 +1080,7 @@ fn test_all_ticks() {
                         effective_total_amount_swapped: Decimal256::zero(),
                         cumulative_realized_cancels: Decimal256::zero(),
                         last_tick_sync_etas: Decimal256::zero(),
+                        hidden_liquidity: Decimal256::zero(),
                     },
                 })
                 .collect(),
@@ -1072,6 +1104,7 @@ fn test_all_ticks() {
                     effective_total_amount_swapped: Decimal256::zero(),
                     cumulative_realized_cancels: Decimal256::zero(),
                     last_tick_sync_etas: Decimal256::zero(),
+                    hidden_liquidity: Decimal256::zero(),
                 },
             }],
             start_after: Some(11),
@@ -1172,6 +1205,8 @@ struct OrdersByOwnerTestCase {
     start_from: Option<(i64, u64)>,
     end_at: Option<(i64, u64)>,
     limit: Option<u64>,
+    direction: Option<OrderDirection>,
+    only_claimable: bool,
     expected_error: Option<ContractError>,
 }
 
@@ -1189,6 +1224,8 @@ fn test_orders_by_owner() {
             start_from: None,
             end_at: None,
             limit: None,
+            direction: None,
+            only_claimable: false,
             expected_error: None,
         },
         OrdersByOwnerTestCase {
@@ -1215,6 +1252,8 @@ fn test_orders_by_owner() {
             start_from: None,
             end_at: None,
             limit: None,
+            direction: None,
+            only_claimable: false,
             expected_error: None,
         },
         OrdersByOwnerTestCase {
@@ -1252,6 +1291,8 @@ fn test_orders_by_owner() {
             start_from: None,
             end_at: None,
             limit: Some(1),
+            direction: None,
+            only_claimable: false,
             expected_error: None,
         },
         OrdersByOwnerTestCase {
@@ -1289,6 +1330,8 @@ fn test_orders_by_owner() {
             start_from: Some((0, 0)),
             end_at: None,
             limit: None,
+            direction: None,
+            only_claimable: false,
             expected_error: None,
         },
         OrdersByOwnerTestCase {
@@ -1326,6 +1369,8 @@ fn test_orders_by_owner() {
             start_from: None,
             end_at: Some((0, 0)),
             limit: None,
+            direction: None,
+            only_claimable: false,
             expected_error: None,
         },
         OrdersByOwnerTestCase {
@@ -1402,6 +1447,219 @@ fn test_orders_by_owner() {
             start_from: None,
             end_at: None,
             limit: None,
+            direction: None,
+            only_claimable: false,
+            expected_error: None,
+        },
+        OrdersByOwnerTestCase {
+            name: "direction filter",
+            pre_operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    0,
+                    OrderDirection::Ask,
+                    Addr::unchecked("sender"),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    -1,
+                    1,
+                    OrderDirection::Bid,
+                    Addr::unchecked("sender"),
+                    Uint128::from(150u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    1,
+                    2,
+                    OrderDirection::Ask,
+                    Addr::unchecked("sender"),
+                    Uint128::from(150u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+            ],
+            expected_output: vec![LimitOrder::new(
+                -1,
+                1,
+                OrderDirection::Bid,
+                Addr::unchecked("sender"),
+                Uint128::from(150u128),
+                Decimal256::zero(),
+                None,
+            )],
+            owner: Addr::unchecked("sender"),
+            start_from: None,
+            end_at: None,
+            limit: None,
+            direction: Some(OrderDirection::Bid),
+            only_claimable: false,
+            expected_error: None,
+        },
+        OrdersByOwnerTestCase {
+            name: "only claimable after partial fills",
+            pre_operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    0,
+                    OrderDirection::Ask,
+                    Addr::unchecked("sender"),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    1,
+                    OrderDirection::Ask,
+                    Addr::unchecked("sender"),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    -1,
+                    2,
+                    OrderDirection::Bid,
+                    Addr::unchecked("sender"),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                // Fully fills the first ask and partially fills the second
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(75u128),
+                    OrderDirection::Bid,
+                    Addr::unchecked("buyer"),
+                )),
+            ],
+            expected_output: vec![
+                LimitOrder::new(
+                    0,
+                    0,
+                    OrderDirection::Ask,
+                    Addr::unchecked("sender"),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                ),
+                LimitOrder::new(
+                    0,
+                    1,
+                    OrderDirection::Ask,
+                    Addr::unchecked("sender"),
+                    Uint128::from(50u128),
+                    decimal256_from_u128(50u128),
+                    None,
+                ),
+            ],
+            owner: Addr::unchecked("sender"),
+            start_from: None,
+            end_at: None,
+            limit: None,
+            direction: None,
+            only_claimable: true,
+            expected_error: None,
+        },
+        OrdersByOwnerTestCase {
+            name: "only claimable paginates past filtered orders",
+            pre_operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    0,
+                    OrderDirection::Ask,
+                    Addr::unchecked("sender"),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    1,
+                    OrderDirection::Ask,
+                    Addr::unchecked("sender"),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    -1,
+                    2,
+                    OrderDirection::Bid,
+                    Addr::unchecked("sender"),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(75u128),
+                    OrderDirection::Bid,
+                    Addr::unchecked("buyer"),
+                )),
+            ],
+            // The unfilled bid on tick -1 sorts first and is skipped without using up the limit
+            expected_output: vec![LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Ask,
+                Addr::unchecked("sender"),
+                Uint128::from(50u128),
+                Decimal256::zero(),
+                None,
+            )],
+            owner: Addr::unchecked("sender"),
+            start_from: None,
+            end_at: None,
+            limit: Some(1),
+            direction: None,
+            only_claimable: true,
+            expected_error: None,
+        },
+        OrdersByOwnerTestCase {
+            name: "only claimable resumes from cursor",
+            pre_operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    0,
+                    OrderDirection::Ask,
+                    Addr::unchecked("sender"),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    1,
+                    OrderDirection::Ask,
+                    Addr::unchecked("sender"),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(75u128),
+                    OrderDirection::Bid,
+                    Addr::unchecked("buyer"),
+                )),
+            ],
+            expected_output: vec![LimitOrder::new(
+                0,
+                1,
+                OrderDirection::Ask,
+                Addr::unchecked("sender"),
+                Uint128::from(50u128),
+                decimal256_from_u128(50u128),
+                None,
+            )],
+            owner: Addr::unchecked("sender"),
+            start_from: Some((0, 0)),
+            end_at: None,
+            limit: Some(1),
+            direction: Some(OrderDirection::Ask),
+            only_claimable: true,
             expected_error: None,
         },
     ];
@@ -1432,6 +1690,8 @@ fn test_orders_by_owner() {
             test.start_from,
             test.end_at,
             test.limit,
+            test.direction,
+            test.only_claimable,
         );
 
         if let Some(err) = test.expected_error {
@@ -1458,3 +1718,3736 @@ fn test_orders_by_owner() {
         );
     }
 }
+
+struct OrderAvgPriceTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    tick_id: i64,
+    order_id: u64,
+    expected_output: OrderAvgPriceResponse,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_order_avg_price() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let test_cases = vec![
+        OrderAvgPriceTestCase {
+            name: "unclaimed order",
+            pre_operations: vec![OrderOperation::PlaceLimit(LimitOrder::new(
+                LARGE_POSITIVE_TICK,
+                0,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(10u128),
+                Decimal256::zero(),
+                None,
+            ))],
+            tick_id: LARGE_POSITIVE_TICK,
+            order_id: 0,
+            expected_output: OrderAvgPriceResponse {
+                avg_price: None,
+                claimed_quantity: Uint128::zero(),
+                claimed_output: Uint256::zero(),
+            },
+            expected_error: None,
+        },
+        OrderAvgPriceTestCase {
+            name: "ASK: multiple partial claims",
+            pre_operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    LARGE_POSITIVE_TICK,
+                    0,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(20u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(3u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+                OrderOperation::Claim((LARGE_POSITIVE_TICK, 0)),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(2u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+                OrderOperation::Claim((LARGE_POSITIVE_TICK, 0)),
+            ],
+            tick_id: LARGE_POSITIVE_TICK,
+            order_id: 0,
+            // 10 base filled for 5 quote across two claims
+            expected_output: OrderAvgPriceResponse {
+                avg_price: Some(Decimal256::percent(200)),
+                claimed_quantity: Uint128::from(10u128),
+                claimed_output: Uint256::from(5u128),
+            },
+            expected_error: None,
+        },
+        OrderAvgPriceTestCase {
+            name: "BID: partial claim",
+            pre_operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    LARGE_NEGATIVE_TICK,
+                    0,
+                    OrderDirection::Bid,
+                    sender.clone(),
+                    Uint128::from(100u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(25u128),
+                    OrderDirection::Ask,
+                    sender.clone(),
+                )),
+                OrderOperation::Claim((LARGE_NEGATIVE_TICK, 0)),
+            ],
+            tick_id: LARGE_NEGATIVE_TICK,
+            order_id: 0,
+            // 50 quote filled for 25 base
+            expected_output: OrderAvgPriceResponse {
+                avg_price: Some(Decimal256::percent(50)),
+                claimed_quantity: Uint128::from(50u128),
+                claimed_output: Uint256::from(25u128),
+            },
+            expected_error: None,
+        },
+        OrderAvgPriceTestCase {
+            name: "order not found",
+            pre_operations: vec![],
+            tick_id: 0,
+            order_id: 0,
+            expected_output: OrderAvgPriceResponse {
+                avg_price: None,
+                claimed_quantity: Uint128::zero(),
+                claimed_output: Uint256::zero(),
+            },
+            expected_error: Some(ContractError::OrderNotFound {
+                tick_id: 0,
+                order_id: 0,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for operation in test.pre_operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = query::order_avg_price(deps.as_ref(), test.tick_id, test.order_id);
+
+        // -- Post Test Assertions --
+        if let Some(err) = test.expected_error {
+            assert_eq!(res.unwrap_err(), err, "{}", format_test_name(test.name));
+            continue;
+        }
+
+        assert_eq!(
+            res.unwrap(),
+            test.expected_output,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct SolvencyCheckTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    // Mocked bank balances held by the contract
+    balances: Vec<Coin>,
+    // Expected (liabilities, delta) for base and quote respectively
+    expected_base: (u128, i128),
+    expected_quote: (u128, i128),
+}
+
+#[test]
+fn test_solvency_check() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let test_cases = vec![
+        SolvencyCheckTestCase {
+            name: "resting orders only",
+            pre_operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    0,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(100u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    LARGE_NEGATIVE_TICK,
+                    1,
+                    OrderDirection::Bid,
+                    sender.clone(),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+            ],
+            balances: vec![coin(100u128, BASE_DENOM), coin(50u128, QUOTE_DENOM)],
+            expected_base: (100, 0),
+            expected_quote: (50, 0),
+        },
+        SolvencyCheckTestCase {
+            name: "filled and unclaimed orders",
+            pre_operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    0,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(100u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    LARGE_NEGATIVE_TICK,
+                    1,
+                    OrderDirection::Bid,
+                    sender.clone(),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(30u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            // 30 base paid out to the market order, 30 quote received
+            balances: vec![coin(70u128, BASE_DENOM), coin(80u128, QUOTE_DENOM)],
+            expected_base: (70, 0),
+            expected_quote: (80, 0),
+        },
+        SolvencyCheckTestCase {
+            name: "unclaimed fill behind unsynced cancellation",
+            pre_operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    0,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    1,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::Cancel((0, 0)),
+                OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(30u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                )),
+            ],
+            // 50 base refunded, 30 base paid out, 30 quote received
+            balances: vec![coin(20u128, BASE_DENOM), coin(30u128, QUOTE_DENOM)],
+            expected_base: (20, 0),
+            expected_quote: (30, 0),
+        },
+        SolvencyCheckTestCase {
+            name: "surplus dust",
+            pre_operations: vec![OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(100u128),
+                Decimal256::zero(),
+                None,
+            ))],
+            balances: vec![coin(105u128, BASE_DENOM), coin(1u128, QUOTE_DENOM)],
+            expected_base: (100, 5),
+            expected_quote: (0, 1),
+        },
+        SolvencyCheckTestCase {
+            name: "deficit",
+            pre_operations: vec![OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(100u128),
+                Decimal256::zero(),
+                None,
+            ))],
+            balances: vec![coin(90u128, BASE_DENOM)],
+            expected_base: (100, -10),
+            expected_quote: (0, 0),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for operation in test.pre_operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        deps.querier
+            .base
+            .update_balance(env.contract.address.clone(), test.balances.clone());
+
+        // -- System under test --
+        let res = query::solvency_check(deps.as_ref(), env).unwrap();
+
+        // -- Post Test Assertions --
+        let balance_of = |denom: &str| {
+            test.balances
+                .iter()
+                .find(|c| c.denom == denom)
+                .map_or(Uint128::zero(), |c| c.amount)
+        };
+        let expected = vec![
+            DenomSolvency {
+                denom: BASE_DENOM.to_string(),
+                balance: Uint256::from_uint128(balance_of(BASE_DENOM)),
+                liabilities: Uint256::from(test.expected_base.0),
+                delta: Int256::from(test.expected_base.1),
+            },
+            DenomSolvency {
+                denom: QUOTE_DENOM.to_string(),
+                balance: Uint256::from_uint128(balance_of(QUOTE_DENOM)),
+                liabilities: Uint256::from(test.expected_quote.0),
+                delta: Int256::from(test.expected_quote.1),
+            },
+        ];
+        assert_eq!(res.denoms, expected, "{}", format_test_name(test.name));
+    }
+}
+
+struct TopClaimableTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    limit: Option<u32>,
+    // Expected (order id, claimable quantity) in returned order, all orders are placed on tick 0
+    expected_orders: Vec<(u64, u128)>,
+}
+
+#[test]
+fn test_top_claimable() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    // Asks of varying size on tick 0 and a resting bid that never gets filled
+    let place_orders = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(30u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            1,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            2,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(60u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            LARGE_NEGATIVE_TICK,
+            3,
+            OrderDirection::Bid,
+            sender.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        )),
+    ];
+    // Fills the first two asks fully and the third partially
+    let market_order = OrderOperation::RunMarket(MarketOrder::new(
+        Uint128::from(150u128),
+        OrderDirection::Bid,
+        sender.clone(),
+    ));
+
+    let test_cases = vec![
+        TopClaimableTestCase {
+            name: "no fills",
+            pre_operations: place_orders.clone(),
+            limit: None,
+            expected_orders: vec![],
+        },
+        TopClaimableTestCase {
+            name: "sorted by claimable amount",
+            pre_operations: [place_orders.clone(), vec![market_order.clone()]].concat(),
+            limit: None,
+            expected_orders: vec![(1, 100), (0, 30), (2, 20)],
+        },
+        TopClaimableTestCase {
+            name: "limited",
+            pre_operations: [place_orders.clone(), vec![market_order.clone()]].concat(),
+            limit: Some(2),
+            expected_orders: vec![(1, 100), (0, 30)],
+        },
+        TopClaimableTestCase {
+            name: "partially claimed order",
+            pre_operations: [
+                place_orders.clone(),
+                vec![market_order.clone(), OrderOperation::Claim((0, 1))],
+            ]
+            .concat(),
+            limit: None,
+            expected_orders: vec![(0, 30), (2, 20)],
+        },
+        TopClaimableTestCase {
+            name: "zero limit",
+            pre_operations: [place_orders.clone(), vec![market_order.clone()]].concat(),
+            limit: Some(0),
+            expected_orders: vec![],
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for operation in test.pre_operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = query::top_claimable(deps.as_ref(), test.limit).unwrap();
+
+        // -- Post Test Assertions --
+        // Tick 0 has a price of 1, so claimable output is equal to claimable quantity
+        let expected: Vec<ClaimableOrder> = test
+            .expected_orders
+            .iter()
+            .map(|(order_id, quantity)| ClaimableOrder {
+                tick_id: 0,
+                order_id: *order_id,
+                owner: sender.clone(),
+                claimable_quantity: Uint128::from(*quantity),
+                claimable_output: Uint256::from(*quantity),
+            })
+            .collect();
+        assert_eq!(res.orders, expected, "{}", format_test_name(test.name));
+    }
+}
+
+struct TickQueueTestCase {
+    name: &'static str,
+    direction: OrderDirection,
+    start_after: Option<u64>,
+    limit: Option<u64>,
+    // Expected (order id, etas) in returned order
+    expected_orders: Vec<(u64, u128)>,
+}
+
+#[test]
+fn test_tick_queue() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let tick_id = 0;
+    // Asks of 10, 20 and 30 with a bid of 50 placed in between
+    let pre_operations = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            1,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(20u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            2,
+            OrderDirection::Bid,
+            sender.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            3,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(30u128),
+            Decimal256::zero(),
+            None,
+        )),
+    ];
+
+    let test_cases = vec![
+        TickQueueTestCase {
+            name: "ask queue",
+            direction: OrderDirection::Ask,
+            start_after: None,
+            limit: None,
+            expected_orders: vec![(0, 0), (1, 10), (3, 30)],
+        },
+        TickQueueTestCase {
+            name: "bid queue",
+            direction: OrderDirection::Bid,
+            start_after: None,
+            limit: None,
+            expected_orders: vec![(2, 0)],
+        },
+        TickQueueTestCase {
+            name: "paginated",
+            direction: OrderDirection::Ask,
+            start_after: Some(0),
+            limit: Some(1),
+            expected_orders: vec![(1, 10)],
+        },
+        TickQueueTestCase {
+            name: "paginated past opposing order",
+            direction: OrderDirection::Ask,
+            start_after: Some(1),
+            limit: Some(1),
+            expected_orders: vec![(3, 30)],
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for operation in pre_operations.clone() {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = query::tick_queue(
+            deps.as_ref(),
+            tick_id,
+            test.direction,
+            test.start_after,
+            test.limit,
+        )
+        .unwrap();
+
+        // -- Post Test Assertions --
+        let orders: Vec<(u64, Decimal256)> = res.iter().map(|o| (o.order_id, o.etas)).collect();
+        let expected: Vec<(u64, Decimal256)> = test
+            .expected_orders
+            .iter()
+            .map(|(order_id, etas)| (*order_id, decimal256_from_u128(*etas)))
+            .collect();
+        assert_eq!(orders, expected, "{}", format_test_name(test.name));
+        assert!(
+            res.windows(2).all(|pair| pair[0].etas < pair[1].etas),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_tick_queue_pagination_after_cancel() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // Five asks of 10 on one tick, with the second one cancelled
+    let mut operations: Vec<OrderOperation> = (0..5)
+        .map(|order_id| {
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                order_id,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(10u128),
+                Decimal256::zero(),
+                None,
+            ))
+        })
+        .collect();
+    operations.push(OrderOperation::Cancel((0, 1)));
+    for operation in operations {
+        operation
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+
+    // -- System under test --
+    let first_page =
+        query::tick_queue(deps.as_ref(), 0, OrderDirection::Ask, None, Some(2)).unwrap();
+    let second_page = query::tick_queue(
+        deps.as_ref(),
+        0,
+        OrderDirection::Ask,
+        first_page.last().map(|order| order.order_id),
+        Some(2),
+    )
+    .unwrap();
+    let last_page = query::tick_queue(
+        deps.as_ref(),
+        0,
+        OrderDirection::Ask,
+        second_page.last().map(|order| order.order_id),
+        Some(2),
+    )
+    .unwrap();
+
+    // -- Post Test Assertions --
+    let order_ids =
+        |orders: &[LimitOrder]| -> Vec<u64> { orders.iter().map(|order| order.order_id).collect() };
+    assert_eq!(order_ids(&first_page), vec![0, 2]);
+    assert_eq!(order_ids(&second_page), vec![3, 4]);
+    assert!(last_page.is_empty());
+
+    // Oversized limits are capped rather than rejected
+    let all_orders =
+        query::tick_queue(deps.as_ref(), 0, OrderDirection::Ask, None, Some(1000)).unwrap();
+    assert_eq!(order_ids(&all_orders), vec![0, 2, 3, 4]);
+}
+
+struct CompactionCandidatesTestCase {
+    name: &'static str,
+    min_fragments: u64,
+    start_after: Option<i64>,
+    limit: Option<u64>,
+    // Expected (tick id, root weight) of returned candidates
+    expected_candidates: Vec<(i64, u64)>,
+    expected_next_start_after: Option<i64>,
+}
+
+#[test]
+fn test_compaction_candidates() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let heavy_tick = 0;
+    let light_tick = 1;
+    let heavy_cancels = 8;
+
+    // Fragment the heavy tick with many cancellations and the light tick with one
+    let mut pre_operations = vec![
+        OrderOperation::PlaceLimitMulti((
+            vec![heavy_tick],
+            heavy_cancels,
+            Uint128::from(10u128),
+            OrderDirection::Ask,
+        )),
+        OrderOperation::PlaceLimitMulti((
+            vec![light_tick],
+            1,
+            Uint128::from(10u128),
+            OrderDirection::Ask,
+        )),
+    ];
+    for order_id in 0..heavy_cancels as u64 {
+        pre_operations.push(OrderOperation::Cancel((heavy_tick, order_id)));
+    }
+    pre_operations.push(OrderOperation::Cancel((light_tick, heavy_cancels as u64)));
+
+    let test_cases = vec![
+        CompactionCandidatesTestCase {
+            name: "only heavily fragmented tick returned",
+            min_fragments: 2,
+            start_after: None,
+            limit: None,
+            expected_candidates: vec![(heavy_tick, 4)],
+            expected_next_start_after: None,
+        },
+        CompactionCandidatesTestCase {
+            name: "all fragmented ticks returned",
+            min_fragments: 0,
+            start_after: None,
+            limit: None,
+            expected_candidates: vec![(heavy_tick, 4), (light_tick, 2)],
+            expected_next_start_after: None,
+        },
+        CompactionCandidatesTestCase {
+            name: "threshold above all ticks",
+            min_fragments: 4,
+            start_after: None,
+            limit: None,
+            expected_candidates: vec![],
+            expected_next_start_after: None,
+        },
+        CompactionCandidatesTestCase {
+            name: "scan limited returns cursor",
+            min_fragments: 0,
+            start_after: None,
+            limit: Some(1),
+            expected_candidates: vec![(heavy_tick, 4)],
+            expected_next_start_after: Some(heavy_tick),
+        },
+        CompactionCandidatesTestCase {
+            name: "resume from cursor",
+            min_fragments: 0,
+            start_after: Some(heavy_tick),
+            limit: Some(1),
+            expected_candidates: vec![(light_tick, 2)],
+            expected_next_start_after: Some(light_tick),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for operation in pre_operations.clone() {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = query::compaction_candidates(
+            deps.as_ref(),
+            test.min_fragments,
+            test.start_after,
+            test.limit,
+        )
+        .unwrap();
+
+        // -- Post Test Assertions --
+        let expected: Vec<CompactionCandidate> = test
+            .expected_candidates
+            .iter()
+            .map(|(tick_id, weight)| CompactionCandidate {
+                tick_id: *tick_id,
+                direction: OrderDirection::Ask,
+                weight: *weight,
+            })
+            .collect();
+        assert_eq!(res.candidates, expected, "{}", format_test_name(test.name));
+        assert_eq!(
+            res.next_start_after,
+            test.expected_next_start_after,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct SimulateSwapTestCase {
+    name: &'static str,
+    token_in: Coin,
+    target_tick: Option<i64>,
+    max_ticks_crossed: Option<u32>,
+    expected_stopped_reason: MarketOrderStopReason,
+    // Expected (output, remaining input), only asserted for orders stopping on the first tick
+    expected_amounts: Option<(u128, u128)>,
+}
+
+#[test]
+fn test_simulate_swap_exact_amount_in() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    // Asks of 10 on ticks 0 and 1
+    let pre_operations = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            1,
+            1,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        )),
+    ];
+
+    let test_cases = vec![
+        SimulateSwapTestCase {
+            name: "filled",
+            token_in: coin(10u128, QUOTE_DENOM),
+            target_tick: None,
+            max_ticks_crossed: None,
+            expected_stopped_reason: MarketOrderStopReason::Filled,
+            expected_amounts: Some((10, 0)),
+        },
+        SimulateSwapTestCase {
+            name: "out of liquidity",
+            token_in: coin(1000u128, QUOTE_DENOM),
+            target_tick: None,
+            max_ticks_crossed: None,
+            expected_stopped_reason: MarketOrderStopReason::OutOfLiquidity,
+            expected_amounts: None,
+        },
+        SimulateSwapTestCase {
+            name: "max ticks reached",
+            token_in: coin(15u128, QUOTE_DENOM),
+            target_tick: None,
+            max_ticks_crossed: Some(1),
+            expected_stopped_reason: MarketOrderStopReason::MaxTicksReached,
+            expected_amounts: Some((10, 5)),
+        },
+        SimulateSwapTestCase {
+            name: "max ticks not reached",
+            token_in: coin(15u128, QUOTE_DENOM),
+            target_tick: None,
+            max_ticks_crossed: Some(2),
+            expected_stopped_reason: MarketOrderStopReason::Filled,
+            expected_amounts: None,
+        },
+        SimulateSwapTestCase {
+            name: "price bound",
+            token_in: coin(15u128, QUOTE_DENOM),
+            target_tick: Some(0),
+            max_ticks_crossed: None,
+            expected_stopped_reason: MarketOrderStopReason::PriceBound,
+            expected_amounts: Some((10, 5)),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for operation in pre_operations.clone() {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = query::simulate_swap_exact_amount_in(
+            deps.as_ref(),
+            env.clone(),
+            test.token_in,
+            BASE_DENOM.to_string(),
+            test.target_tick,
+            test.max_ticks_crossed,
+        )
+        .unwrap();
+
+        // -- Post Test Assertions --
+        assert_eq!(
+            res.stopped_reason,
+            test.expected_stopped_reason,
+            "{}",
+            format_test_name(test.name)
+        );
+        if let Some((output, remaining)) = test.expected_amounts {
+            assert_eq!(
+                res.token_out,
+                coin_u256(output, BASE_DENOM),
+                "{}",
+                format_test_name(test.name)
+            );
+            assert_eq!(
+                res.token_in_remaining,
+                Uint128::from(remaining),
+                "{}",
+                format_test_name(test.name)
+            );
+        }
+    }
+}
+
+struct SimulateClaimAllTestCase {
+    name: &'static str,
+    claimer: Option<&'static str>,
+    start_after: Option<(i64, u64)>,
+    max_orders: Option<u64>,
+    expected_orders: Vec<(i64, u64)>,
+    // Expected (recipient, amounts) per message
+    expected_messages: Vec<(&'static str, Vec<Coin256>)>,
+    expected_next_start_after: Option<(i64, u64)>,
+}
+
+#[test]
+fn test_simulate_claim_all() {
+    let owner = Addr::unchecked(DEFAULT_SENDER);
+    let keeper = "keeper";
+    // Two asks (one with a 1% bounty) and a bid on tick 0, all fully filled
+    let pre_operations = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            owner.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            Some(Decimal256::percent(1)),
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            1,
+            OrderDirection::Ask,
+            owner.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            2,
+            OrderDirection::Bid,
+            owner.clone(),
+            Uint128::from(40u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(150u128),
+            OrderDirection::Bid,
+            Addr::unchecked(keeper),
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(40u128),
+            OrderDirection::Ask,
+            Addr::unchecked(keeper),
+        )),
+    ];
+    // With a 2% maker fee:
+    // * Order 0 yields 100 quote, split into a bounty of 1, a fee of 2 and 97 for the owner
+    // * Order 1 yields 50 quote, split into a fee of 1 and 49 for the owner
+    // * Order 2 yields 40 base, the fee rounds down to zero
+    let test_cases = vec![
+        SimulateClaimAllTestCase {
+            name: "all orders claimed by keeper",
+            claimer: Some(keeper),
+            start_after: None,
+            max_orders: None,
+            expected_orders: vec![(0, 0), (0, 1), (0, 2)],
+            expected_messages: vec![
+                (keeper, vec![coin_u256(1u128, QUOTE_DENOM)]),
+                (
+                    DEFAULT_MAKER_FEE_RECIPIENT,
+                    vec![coin_u256(3u128, QUOTE_DENOM)],
+                ),
+                (
+                    DEFAULT_SENDER,
+                    vec![
+                        coin_u256(40u128, BASE_DENOM),
+                        coin_u256(146u128, QUOTE_DENOM),
+                    ],
+                ),
+            ],
+            expected_next_start_after: None,
+        },
+        SimulateClaimAllTestCase {
+            name: "owner claims own bounty",
+            claimer: None,
+            start_after: None,
+            max_orders: None,
+            expected_orders: vec![(0, 0), (0, 1), (0, 2)],
+            expected_messages: vec![
+                (
+                    DEFAULT_MAKER_FEE_RECIPIENT,
+                    vec![coin_u256(3u128, QUOTE_DENOM)],
+                ),
+                (
+                    DEFAULT_SENDER,
+                    vec![
+                        coin_u256(40u128, BASE_DENOM),
+                        coin_u256(147u128, QUOTE_DENOM),
+                    ],
+                ),
+            ],
+            expected_next_start_after: None,
+        },
+        SimulateClaimAllTestCase {
+            name: "bounded by max orders",
+            claimer: Some(keeper),
+            start_after: None,
+            max_orders: Some(2),
+            expected_orders: vec![(0, 0), (0, 1)],
+            expected_messages: vec![
+                (keeper, vec![coin_u256(1u128, QUOTE_DENOM)]),
+                (
+                    DEFAULT_MAKER_FEE_RECIPIENT,
+                    vec![coin_u256(3u128, QUOTE_DENOM)],
+                ),
+                (DEFAULT_SENDER, vec![coin_u256(146u128, QUOTE_DENOM)]),
+            ],
+            expected_next_start_after: Some((0, 1)),
+        },
+        SimulateClaimAllTestCase {
+            name: "resumed from cursor",
+            claimer: Some(keeper),
+            start_after: Some((0, 1)),
+            max_orders: Some(2),
+            expected_orders: vec![(0, 2)],
+            expected_messages: vec![(DEFAULT_SENDER, vec![coin_u256(40u128, BASE_DENOM)])],
+            expected_next_start_after: None,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(owner.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        MAKER_FEE
+            .save(deps.as_mut().storage, &Decimal256::percent(2))
+            .unwrap();
+
+        for operation in pre_operations.clone() {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = query::simulate_claim_all(
+            deps.as_ref(),
+            env.clone(),
+            owner.clone(),
+            test.claimer.map(Addr::unchecked),
+            test.start_after,
+            test.max_orders,
+        )
+        .unwrap();
+
+        // -- Post Test Assertions --
+        let expected_messages: Vec<MsgSend256> = test
+            .expected_messages
+            .iter()
+            .map(|(recipient, amount)| MsgSend256 {
+                from_address: env.contract.address.to_string(),
+                to_address: recipient.to_string(),
+                amount: amount.clone(),
+            })
+            .collect();
+        assert_eq!(
+            res.orders,
+            test.expected_orders,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            res.messages,
+            expected_messages,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            res.next_start_after,
+            test.expected_next_start_after,
+            "{}",
+            format_test_name(test.name)
+        );
+
+        // The simulated proceeds match the sum of the owner's share of each individual claim
+        let claimer = Addr::unchecked(test.claimer.unwrap_or(owner.as_str()));
+        let mut claimed: Vec<Coin256> = vec![];
+        for (tick_id, order_id) in res.orders {
+            let (amount, _, order) = claim_order(
+                deps.as_mut().storage,
+                env.contract.address.clone(),
+                claimer.clone(),
+                tick_id,
+                order_id,
+                None,
+            )
+            .unwrap();
+            let denom = if order.order_direction == OrderDirection::Ask {
+                QUOTE_DENOM
+            } else {
+                BASE_DENOM
+            };
+            match claimed.iter_mut().find(|coin| coin.denom == denom) {
+                Some(coin) => coin.amount += amount,
+                None => claimed.push(coin_u256(amount, denom)),
+            }
+        }
+        // Bounties on the owner's own orders are included in the claimed amount
+        if claimer == owner {
+            let bounty = res.messages.iter().find(|msg| msg.to_address == keeper);
+            assert!(bounty.is_none(), "{}", format_test_name(test.name));
+        }
+        claimed.sort_by(|a, b| a.denom.cmp(&b.denom));
+        assert_eq!(res.proceeds, claimed, "{}", format_test_name(test.name));
+    }
+}
+
+struct TickSwapProgressTestCase {
+    name: &'static str,
+    fill: u128,
+    direction: OrderDirection,
+    expected: TickSwapProgressResponse,
+}
+
+#[test]
+fn test_tick_swap_progress() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let test_cases = vec![
+        TickSwapProgressTestCase {
+            name: "unfilled tick",
+            fill: 0,
+            direction: OrderDirection::Ask,
+            expected: TickSwapProgressResponse {
+                effective_total_amount_swapped: Decimal256::zero(),
+                cumulative_total_value: decimal256_from_u128(100u128),
+                fill_ratio: Some(Decimal256::zero()),
+            },
+        },
+        TickSwapProgressTestCase {
+            name: "half filled tick",
+            fill: 50,
+            direction: OrderDirection::Ask,
+            expected: TickSwapProgressResponse {
+                effective_total_amount_swapped: decimal256_from_u128(50u128),
+                cumulative_total_value: decimal256_from_u128(100u128),
+                fill_ratio: Some(Decimal256::percent(50)),
+            },
+        },
+        TickSwapProgressTestCase {
+            name: "fully filled tick",
+            fill: 100,
+            direction: OrderDirection::Ask,
+            expected: TickSwapProgressResponse {
+                effective_total_amount_swapped: decimal256_from_u128(100u128),
+                cumulative_total_value: decimal256_from_u128(100u128),
+                fill_ratio: Some(Decimal256::one()),
+            },
+        },
+        TickSwapProgressTestCase {
+            name: "direction without orders",
+            fill: 0,
+            direction: OrderDirection::Bid,
+            expected: TickSwapProgressResponse {
+                effective_total_amount_swapped: Decimal256::zero(),
+                cumulative_total_value: Decimal256::zero(),
+                fill_ratio: None,
+            },
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        let mut operations = vec![OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        ))];
+        if test.fill > 0 {
+            operations.push(OrderOperation::RunMarket(MarketOrder::new(
+                Uint128::from(test.fill),
+                OrderDirection::Bid,
+                sender.clone(),
+            )));
+        }
+        for operation in operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = query::tick_swap_progress(deps.as_ref(), 0, test.direction).unwrap();
+
+        // -- Post Test Assertions --
+        assert_eq!(res, test.expected, "{}", format_test_name(test.name));
+    }
+
+    // Ticks that never held an order have no progress
+    let deps = mock_dependencies_custom();
+    let res = query::tick_swap_progress(deps.as_ref(), 1, OrderDirection::Ask).unwrap();
+    assert_eq!(
+        res,
+        TickSwapProgressResponse {
+            effective_total_amount_swapped: Decimal256::zero(),
+            cumulative_total_value: Decimal256::zero(),
+            fill_ratio: None,
+        }
+    );
+}
+
+struct TicksInRangeTestCase {
+    name: &'static str,
+    min_tick: i64,
+    max_tick: i64,
+    direction: OrderDirection,
+    start_after: Option<i64>,
+    limit: Option<u64>,
+    expected_ticks: Vec<(i64, u128)>,
+}
+
+#[test]
+fn test_ticks_in_range() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let limit_order = |tick_id: i64, order_id: u64, direction: OrderDirection, quantity: u128| {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            order_id,
+            direction,
+            sender.clone(),
+            Uint128::from(quantity),
+            Decimal256::zero(),
+            None,
+        ))
+    };
+    let operations = vec![
+        limit_order(10, 0, OrderDirection::Ask, 100),
+        limit_order(20, 1, OrderDirection::Ask, 200),
+        limit_order(30, 2, OrderDirection::Ask, 300),
+        limit_order(40, 3, OrderDirection::Ask, 400),
+        limit_order(-10, 4, OrderDirection::Bid, 50),
+        limit_order(15, 5, OrderDirection::Bid, 60),
+        // Tick 25 is left without liquidity after the cancel
+        limit_order(25, 6, OrderDirection::Ask, 500),
+        OrderOperation::Cancel((25, 6)),
+    ];
+    let test_cases = vec![
+        TicksInRangeTestCase {
+            name: "all asks",
+            min_tick: MIN_TICK,
+            max_tick: MAX_TICK,
+            direction: OrderDirection::Ask,
+            start_after: None,
+            limit: None,
+            expected_ticks: vec![(10, 100), (20, 200), (30, 300), (40, 400)],
+        },
+        TicksInRangeTestCase {
+            name: "all bids",
+            min_tick: MIN_TICK,
+            max_tick: MAX_TICK,
+            direction: OrderDirection::Bid,
+            start_after: None,
+            limit: None,
+            expected_ticks: vec![(-10, 50), (15, 60)],
+        },
+        TicksInRangeTestCase {
+            name: "asks within bounds",
+            min_tick: 15,
+            max_tick: 30,
+            direction: OrderDirection::Ask,
+            start_after: None,
+            limit: None,
+            expected_ticks: vec![(20, 200), (30, 300)],
+        },
+        TicksInRangeTestCase {
+            name: "limited",
+            min_tick: MIN_TICK,
+            max_tick: MAX_TICK,
+            direction: OrderDirection::Ask,
+            start_after: None,
+            limit: Some(2),
+            expected_ticks: vec![(10, 100), (20, 200)],
+        },
+        TicksInRangeTestCase {
+            name: "paginated",
+            min_tick: MIN_TICK,
+            max_tick: MAX_TICK,
+            direction: OrderDirection::Ask,
+            start_after: Some(20),
+            limit: Some(1),
+            expected_ticks: vec![(30, 300)],
+        },
+        TicksInRangeTestCase {
+            name: "cursor below range",
+            min_tick: 15,
+            max_tick: MAX_TICK,
+            direction: OrderDirection::Ask,
+            start_after: Some(10),
+            limit: None,
+            expected_ticks: vec![(20, 200), (30, 300), (40, 400)],
+        },
+        TicksInRangeTestCase {
+            name: "empty range",
+            min_tick: 41,
+            max_tick: 100,
+            direction: OrderDirection::Ask,
+            start_after: None,
+            limit: None,
+            expected_ticks: vec![],
+        },
+    ];
+
+    // -- Test Setup --
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+    for operation in operations {
+        operation
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+
+    for test in test_cases {
+        // -- System under test --
+        let res = query::ticks_in_range(
+            deps.as_ref(),
+            test.min_tick,
+            test.max_tick,
+            test.direction,
+            test.start_after,
+            test.limit,
+        )
+        .unwrap();
+
+        // -- Post Test Assertions --
+        let expected: Vec<(i64, Decimal256)> = test
+            .expected_ticks
+            .into_iter()
+            .map(|(tick_id, liquidity)| (tick_id, decimal256_from_u128(liquidity)))
+            .collect();
+        assert_eq!(res, expected, "{}", format_test_name(test.name));
+    }
+}
+
+struct ClaimableAmountTestCase {
+    name: &'static str,
+    operations: Vec<OrderOperation>,
+    maker_fee: Decimal256,
+    tick_id: i64,
+    order_id: u64,
+    expected: ClaimableResponse,
+}
+
+#[test]
+fn test_claimable_amount() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let limit_order = |tick_id: i64, order_id: u64, quantity: u128, bounty: Option<Decimal256>| {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            order_id,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(quantity),
+            Decimal256::zero(),
+            bounty,
+        ))
+    };
+    let market_bid = |quantity: u128| {
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(quantity),
+            OrderDirection::Bid,
+            sender.clone(),
+        ))
+    };
+    let quote = |amount: u128| ClaimableResponse {
+        amount: Uint128::from(amount),
+        denom: QUOTE_DENOM.to_string(),
+    };
+    let test_cases = vec![
+        ClaimableAmountTestCase {
+            name: "unfilled order",
+            operations: vec![limit_order(0, 0, 100, None)],
+            maker_fee: Decimal256::zero(),
+            tick_id: 0,
+            order_id: 0,
+            expected: quote(0),
+        },
+        ClaimableAmountTestCase {
+            name: "partially filled order",
+            operations: vec![limit_order(0, 0, 100, None), market_bid(40)],
+            maker_fee: Decimal256::zero(),
+            tick_id: 0,
+            order_id: 0,
+            expected: quote(40),
+        },
+        ClaimableAmountTestCase {
+            name: "fully filled order",
+            operations: vec![limit_order(0, 0, 100, None), market_bid(100)],
+            maker_fee: Decimal256::zero(),
+            tick_id: 0,
+            order_id: 0,
+            expected: quote(100),
+        },
+        ClaimableAmountTestCase {
+            name: "cancellation ahead in queue",
+            operations: vec![
+                limit_order(0, 0, 50, None),
+                limit_order(0, 1, 100, None),
+                OrderOperation::Cancel((0, 0)),
+                market_bid(60),
+            ],
+            maker_fee: Decimal256::zero(),
+            tick_id: 0,
+            order_id: 1,
+            expected: quote(60),
+        },
+        ClaimableAmountTestCase {
+            name: "bounty and maker fee deducted",
+            operations: vec![
+                limit_order(0, 0, 100, Some(Decimal256::percent(1))),
+                market_bid(100),
+            ],
+            maker_fee: Decimal256::percent(2),
+            tick_id: 0,
+            order_id: 0,
+            expected: quote(97),
+        },
+        ClaimableAmountTestCase {
+            name: "non-unit price",
+            operations: vec![
+                limit_order(LARGE_NEGATIVE_TICK, 0, 100, None),
+                market_bid(50),
+            ],
+            maker_fee: Decimal256::zero(),
+            tick_id: LARGE_NEGATIVE_TICK,
+            order_id: 0,
+            expected: quote(50),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        MAKER_FEE
+            .save(deps.as_mut().storage, &test.maker_fee)
+            .unwrap();
+        for operation in test.operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = query::claimable_amount(deps.as_ref(), test.tick_id, test.order_id).unwrap();
+
+        // -- Post Test Assertions --
+        assert_eq!(res, test.expected, "{}", format_test_name(test.name));
+
+        // The query matches what claiming actually pays the owner
+        let claim = claim_order(
+            deps.as_mut().storage,
+            env.contract.address.clone(),
+            Addr::unchecked("claimer"),
+            test.tick_id,
+            test.order_id,
+            None,
+        );
+        if res.amount.is_zero() {
+            assert!(claim.is_err(), "{}", format_test_name(test.name));
+        } else {
+            let (amount, ..) = claim.unwrap();
+            assert_eq!(
+                amount,
+                Uint256::from_uint128(res.amount),
+                "{}",
+                format_test_name(test.name)
+            );
+        }
+    }
+
+    // Missing orders error rather than returning zero
+    let mut deps = mock_dependencies_custom();
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+    let res = query::claimable_amount(deps.as_ref(), 0, 0);
+    assert_eq!(
+        res,
+        Err(ContractError::OrderNotFound {
+            tick_id: 0,
+            order_id: 0
+        })
+    );
+}
+
+#[test]
+fn test_total_claimable() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let other = Addr::unchecked("other");
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // The sender holds a bid at a price of 0.5 and two asks at a price of 1, ahead of another owner's ask
+    let operations = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            LARGE_NEGATIVE_TICK,
+            0,
+            OrderDirection::Bid,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            1,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            2,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            3,
+            OrderDirection::Ask,
+            other.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        )),
+        // Fills all of the first ask and 30 of the second
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(130u128),
+            OrderDirection::Bid,
+            other.clone(),
+        )),
+        // Fills 40 quote of the bid, paying out 20 base
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(20u128),
+            OrderDirection::Ask,
+            other.clone(),
+        )),
+    ];
+    for operation in operations {
+        operation
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+
+    // -- System under test --
+    let res = query::total_claimable(deps.as_ref(), sender.clone(), None).unwrap();
+
+    // -- Post Test Assertions --
+    assert_eq!(res, vec![coin(20, BASE_DENOM), coin(130, QUOTE_DENOM)]);
+
+    // Scanning only the first order leaves out the asks
+    let res = query::total_claimable(deps.as_ref(), sender.clone(), Some(1)).unwrap();
+    assert_eq!(res, vec![coin(20, BASE_DENOM)]);
+
+    // An owner with nothing filled has nothing to claim
+    let res = query::total_claimable(deps.as_ref(), other, None).unwrap();
+    assert_eq!(res, Vec::<Coin>::new());
+}
+
+struct SpreadTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    expected: SpreadResponse,
+}
+
+#[test]
+fn test_spread() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let low_price = Decimal256::percent(50);
+    let high_price = Decimal256::percent(200);
+    let test_cases = vec![
+        SpreadTestCase {
+            name: "empty book",
+            pre_operations: vec![],
+            expected: SpreadResponse {
+                best_bid_tick: None,
+                best_ask_tick: None,
+                best_bid_price: None,
+                best_ask_price: None,
+                mid_price: None,
+            },
+        },
+        SpreadTestCase {
+            name: "one sided book",
+            pre_operations: vec![OrderOperation::PlaceLimit(LimitOrder::new(
+                LARGE_POSITIVE_TICK,
+                0,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(100u128),
+                Decimal256::zero(),
+                None,
+            ))],
+            expected: SpreadResponse {
+                best_bid_tick: None,
+                best_ask_tick: Some(LARGE_POSITIVE_TICK),
+                best_bid_price: None,
+                best_ask_price: Some(high_price),
+                mid_price: None,
+            },
+        },
+        SpreadTestCase {
+            name: "two sided book",
+            pre_operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    LARGE_NEGATIVE_TICK,
+                    0,
+                    OrderDirection::Bid,
+                    sender.clone(),
+                    Uint128::from(100u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    LARGE_POSITIVE_TICK,
+                    1,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(100u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+            ],
+            expected: SpreadResponse {
+                best_bid_tick: Some(LARGE_NEGATIVE_TICK),
+                best_ask_tick: Some(LARGE_POSITIVE_TICK),
+                best_bid_price: Some(low_price),
+                best_ask_price: Some(high_price),
+                mid_price: Some(Decimal256::percent(125)),
+            },
+        },
+        SpreadTestCase {
+            name: "stale pointer is skipped",
+            pre_operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    LARGE_NEGATIVE_TICK,
+                    0,
+                    OrderDirection::Bid,
+                    sender.clone(),
+                    Uint128::from(100u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    1,
+                    OrderDirection::Bid,
+                    sender.clone(),
+                    Uint128::from(100u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                // Cancelling leaves the bid pointer on the now empty tick
+                OrderOperation::Cancel((0, 1)),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    LARGE_POSITIVE_TICK,
+                    2,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(100u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+            ],
+            expected: SpreadResponse {
+                best_bid_tick: Some(LARGE_NEGATIVE_TICK),
+                best_ask_tick: Some(LARGE_POSITIVE_TICK),
+                best_bid_price: Some(low_price),
+                best_ask_price: Some(high_price),
+                mid_price: Some(Decimal256::percent(125)),
+            },
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for operation in test.pre_operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = query::spread(deps.as_ref()).unwrap();
+
+        // -- Post Test Assertions --
+        assert_eq!(res, test.expected, "{}", format_test_name(test.name));
+    }
+}
+#[test]
+fn test_order_fill_price() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // Orders that do not exist are reported as not found
+    let res = query::order_fill_price(deps.as_ref(), LARGE_NEGATIVE_TICK, 0);
+    assert_eq!(
+        res,
+        Err(ContractError::OrderNotFound {
+            tick_id: LARGE_NEGATIVE_TICK,
+            order_id: 0
+        })
+    );
+
+    // An ask of 100 at a price of 0.5, filled by 10 and claimed, then filled by a further 20
+    let operations = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            LARGE_NEGATIVE_TICK,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(20u128),
+            OrderDirection::Bid,
+            sender.clone(),
+        )),
+        OrderOperation::Claim((LARGE_NEGATIVE_TICK, 0)),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(40u128),
+            OrderDirection::Bid,
+            sender.clone(),
+        )),
+    ];
+    for operation in operations {
+        operation
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+
+    // -- System under test --
+    let res = query::order_fill_price(deps.as_ref(), LARGE_NEGATIVE_TICK, 0).unwrap();
+
+    // -- Post Test Assertions --
+    assert_eq!(
+        res,
+        FillPriceResponse {
+            filled_quantity: Uint128::from(30u128),
+            average_price: Decimal256::percent(50),
+            remaining_quantity: Uint128::from(70u128),
+        }
+    );
+}
+struct CancelledPrefixSumTestCase {
+    name: &'static str,
+    tick_id: i64,
+    target_etas: u128,
+    expected_sum: u128,
+}
+
+#[test]
+fn test_cancelled_prefix_sum() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    // Asks of 10, 20, 30 and 40 on tick 0, cancelling the 20 at ETAS 10 and the 40 at ETAS 60
+    let mut pre_operations: Vec<OrderOperation> = [10u128, 20, 30, 40]
+        .iter()
+        .enumerate()
+        .map(|(order_id, quantity)| {
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                order_id as u64,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(*quantity),
+                Decimal256::zero(),
+                None,
+            ))
+        })
+        .collect();
+    pre_operations.push(OrderOperation::Cancel((0, 1)));
+    pre_operations.push(OrderOperation::Cancel((0, 3)));
+
+    let test_cases = vec![
+        CancelledPrefixSumTestCase {
+            name: "below first cancellation",
+            tick_id: 0,
+            target_etas: 5,
+            expected_sum: 0,
+        },
+        CancelledPrefixSumTestCase {
+            name: "at start of first cancellation",
+            tick_id: 0,
+            target_etas: 10,
+            expected_sum: 20,
+        },
+        CancelledPrefixSumTestCase {
+            name: "between cancellations",
+            tick_id: 0,
+            target_etas: 45,
+            expected_sum: 20,
+        },
+        CancelledPrefixSumTestCase {
+            name: "at start of second cancellation",
+            tick_id: 0,
+            target_etas: 60,
+            expected_sum: 60,
+        },
+        CancelledPrefixSumTestCase {
+            name: "beyond all cancellations",
+            tick_id: 0,
+            target_etas: 200,
+            expected_sum: 60,
+        },
+        CancelledPrefixSumTestCase {
+            name: "tick without sumtree",
+            tick_id: 1,
+            target_etas: 200,
+            expected_sum: 0,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for operation in pre_operations.clone() {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res = query::cancelled_prefix_sum(
+            deps.as_ref(),
+            test.tick_id,
+            OrderDirection::Ask,
+            decimal256_from_u128(test.target_etas),
+        )
+        .unwrap();
+
+        // -- Post Test Assertions --
+        assert_eq!(
+            res,
+            decimal256_from_u128(test.expected_sum),
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct BookLiquidityTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    expected_bid_liquidity: u128,
+    expected_ask_liquidity: u128,
+}
+
+#[test]
+fn test_book_liquidity() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let place_ops = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            LARGE_NEGATIVE_TICK,
+            0,
+            OrderDirection::Bid,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            1,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            LARGE_POSITIVE_TICK,
+            2,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(25u128),
+            Decimal256::zero(),
+            None,
+        )),
+    ];
+    let test_cases: Vec<BookLiquidityTestCase> = vec![
+        BookLiquidityTestCase {
+            name: "empty orderbook",
+            pre_operations: vec![],
+            expected_bid_liquidity: 0,
+            expected_ask_liquidity: 0,
+        },
+        BookLiquidityTestCase {
+            name: "places on both sides",
+            pre_operations: place_ops.clone(),
+            expected_bid_liquidity: 100,
+            expected_ask_liquidity: 75,
+        },
+        BookLiquidityTestCase {
+            name: "places then cancel",
+            pre_operations: [place_ops.clone(), vec![OrderOperation::Cancel((0, 1))]].concat(),
+            expected_bid_liquidity: 100,
+            expected_ask_liquidity: 25,
+        },
+        BookLiquidityTestCase {
+            name: "places then market order",
+            pre_operations: [
+                place_ops.clone(),
+                vec![OrderOperation::RunMarket(MarketOrder::new(
+                    Uint128::from(30u128),
+                    OrderDirection::Bid,
+                    sender.clone(),
+                ))],
+            ]
+            .concat(),
+            expected_bid_liquidity: 100,
+            expected_ask_liquidity: 45,
+        },
+        BookLiquidityTestCase {
+            name: "places, cancel and market orders on both sides",
+            pre_operations: [
+                place_ops,
+                vec![
+                    OrderOperation::Cancel((LARGE_POSITIVE_TICK, 2)),
+                    OrderOperation::RunMarket(MarketOrder::new(
+                        Uint128::from(50u128),
+                        OrderDirection::Bid,
+                        sender.clone(),
+                    )),
+                    // Fills 20 of the bid at a price of 0.5
+                    OrderOperation::RunMarket(MarketOrder::new(
+                        Uint128::from(10u128),
+                        OrderDirection::Ask,
+                        sender.clone(),
+                    )),
+                ],
+            ]
+            .concat(),
+            expected_bid_liquidity: 80,
+            expected_ask_liquidity: 0,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let response = query::book_liquidity(deps.as_ref()).unwrap();
+
+        // -- Post test assertions --
+        assert_eq!(
+            response,
+            BookLiquidityResponse {
+                total_bid_liquidity: decimal256_from_u128(test.expected_bid_liquidity),
+                total_ask_liquidity: decimal256_from_u128(test.expected_ask_liquidity),
+            },
+            "{}: unexpected book liquidity",
+            format_test_name(test.name)
+        );
+
+        // The running totals must match a recomputation over every tick
+        let (recomputed_bid, recomputed_ask) = TICK_STATE
+            .range(&deps.storage, None, None, Order::Ascending)
+            .map(|item| item.unwrap().1)
+            .fold(
+                (Decimal256::zero(), Decimal256::zero()),
+                |(bid, ask), tick_state| {
+                    (
+                        bid + tick_state
+                            .get_values(OrderDirection::Bid)
+                            .total_amount_of_liquidity,
+                        ask + tick_state
+                            .get_values(OrderDirection::Ask)
+                            .total_amount_of_liquidity,
+                    )
+                },
+            );
+        assert_eq!(
+            (response.total_bid_liquidity, response.total_ask_liquidity),
+            (recomputed_bid, recomputed_ask),
+            "{}: running totals diverged from tick state",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct BookImbalanceTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    expected_imbalance: SignedDecimal256,
+}
+
+#[test]
+fn test_book_imbalance() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let place = |tick_id: i64, direction: OrderDirection, quantity: u128| {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            0,
+            direction,
+            sender.clone(),
+            Uint128::from(quantity),
+            Decimal256::zero(),
+            None,
+        ))
+    };
+    let test_cases: Vec<BookImbalanceTestCase> = vec![
+        BookImbalanceTestCase {
+            name: "empty orderbook",
+            pre_operations: vec![],
+            expected_imbalance: SignedDecimal256::zero(),
+        },
+        BookImbalanceTestCase {
+            name: "balanced book",
+            pre_operations: vec![
+                place(LARGE_NEGATIVE_TICK, OrderDirection::Bid, 50),
+                place(0, OrderDirection::Ask, 50),
+            ],
+            expected_imbalance: SignedDecimal256::zero(),
+        },
+        BookImbalanceTestCase {
+            name: "bid heavy book",
+            pre_operations: vec![
+                place(LARGE_NEGATIVE_TICK, OrderDirection::Bid, 150),
+                place(0, OrderDirection::Ask, 50),
+            ],
+            expected_imbalance: SignedDecimal256::percent(50),
+        },
+        BookImbalanceTestCase {
+            // 100 base at a price of 2 is 50 quote of notional
+            name: "ask heavy book weighted by price",
+            pre_operations: vec![
+                place(LARGE_NEGATIVE_TICK, OrderDirection::Bid, 30),
+                place(LARGE_POSITIVE_TICK, OrderDirection::Ask, 100),
+            ],
+            expected_imbalance: SignedDecimal256::percent(-25),
+        },
+        BookImbalanceTestCase {
+            name: "only asks",
+            pre_operations: vec![place(0, OrderDirection::Ask, 10)],
+            expected_imbalance: SignedDecimal256::negative_one(),
+        },
+        BookImbalanceTestCase {
+            name: "only bids",
+            pre_operations: vec![place(0, OrderDirection::Bid, 10)],
+            expected_imbalance: SignedDecimal256::one(),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let imbalance = query::book_imbalance(deps.as_ref()).unwrap();
+
+        // -- Post test assertions --
+        assert_eq!(
+            imbalance,
+            test.expected_imbalance,
+            "{}: unexpected book imbalance",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct TickPriceTestCase {
+    name: &'static str,
+    tick_id: i64,
+    expected_price: Option<Decimal256>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_tick_price() {
+    let test_cases: Vec<TickPriceTestCase> = vec![
+        TickPriceTestCase {
+            name: "price one",
+            tick_id: 0,
+            expected_price: Some(Decimal256::one()),
+            expected_error: None,
+        },
+        TickPriceTestCase {
+            name: "large positive tick",
+            tick_id: 40_000_000,
+            expected_price: Some(Decimal256::from_str("50000").unwrap()),
+            expected_error: None,
+        },
+        TickPriceTestCase {
+            name: "negative tick",
+            tick_id: -17_765_433,
+            expected_price: Some(Decimal256::from_str("0.01234567").unwrap()),
+            expected_error: None,
+        },
+        TickPriceTestCase {
+            name: "min tick",
+            tick_id: MIN_TICK,
+            expected_price: Some(Decimal256::from_str("0.000000000001").unwrap()),
+            expected_error: None,
+        },
+        TickPriceTestCase {
+            name: "max tick",
+            tick_id: MAX_TICK,
+            expected_price: Some(max_spot_price()),
+            expected_error: None,
+        },
+        TickPriceTestCase {
+            name: "below min tick",
+            tick_id: MIN_TICK - 1,
+            expected_price: None,
+            expected_error: Some(ContractError::InvalidTickId {
+                tick_id: MIN_TICK - 1,
+            }),
+        },
+        TickPriceTestCase {
+            name: "above max tick",
+            tick_id: MAX_TICK + 1,
+            expected_price: None,
+            expected_error: Some(ContractError::InvalidTickId {
+                tick_id: MAX_TICK + 1,
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- System under test --
+        let result = query::tick_price(test.tick_id);
+
+        // -- Post test assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                result.unwrap_err(),
+                expected_error,
+                "{}: did not receive expected error",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        assert_eq!(
+            result.unwrap(),
+            test.expected_price.unwrap(),
+            "{}: unexpected tick price",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_sumtree_nodes() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // Ticks without cancellations have no sumtree
+    assert!(
+        query::sumtree_nodes(deps.as_ref(), 0, OrderDirection::Ask, None)
+            .unwrap()
+            .is_empty()
+    );
+
+    // Cancelling the first and last of three orders inserts leaves at ETAS 0 and 20
+    let mut operations: Vec<OrderOperation> = (0..3)
+        .map(|order_id| {
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                order_id,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(10u128),
+                Decimal256::zero(),
+                None,
+            ))
+        })
+        .collect();
+    operations.extend([
+        OrderOperation::Cancel((0, 0)),
+        OrderOperation::Cancel((0, 2)),
+    ]);
+    for op in operations {
+        op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+    }
+
+    // -- System under test --
+    let nodes = query::sumtree_nodes(deps.as_ref(), 0, OrderDirection::Ask, None).unwrap();
+
+    // -- Post test assertions --
+    let node_types: Vec<NodeType> = nodes.iter().map(|node| node.node_type.clone()).collect();
+    let expected_root = NodeType::Internal {
+        accumulator: decimal256_from_u128(20u128),
+        range: (decimal256_from_u128(0u128), decimal256_from_u128(30u128)),
+        weight: 2,
+    };
+    assert_eq!(
+        node_types,
+        vec![
+            expected_root,
+            NodeType::leaf(decimal256_from_u128(0u128), decimal256_from_u128(10u128)),
+            NodeType::leaf(decimal256_from_u128(20u128), decimal256_from_u128(10u128)),
+        ]
+    );
+    assert_eq!(nodes[0].left, Some(nodes[1].key));
+    assert_eq!(nodes[0].right, Some(nodes[2].key));
+    assert_eq!(nodes[1].parent, Some(nodes[0].key));
+    assert_eq!(nodes[2].parent, Some(nodes[0].key));
+
+    // Output is capped at the requested number of nodes
+    let capped = query::sumtree_nodes(deps.as_ref(), 0, OrderDirection::Ask, Some(2)).unwrap();
+    assert_eq!(capped, nodes[..2].to_vec());
+}
+
+struct SumtreeInfoTestCase {
+    name: &'static str,
+    // Order IDs to cancel out of ten resting asks of 10 at tick 0
+    cancelled_orders: Vec<u64>,
+    expected_response: SumtreeInfoResponse,
+}
+
+#[test]
+fn test_sumtree_info() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let test_cases = vec![
+        SumtreeInfoTestCase {
+            name: "no sumtree",
+            cancelled_orders: vec![],
+            expected_response: SumtreeInfoResponse {
+                node_count: 0,
+                height: 0,
+                root_value: Decimal256::zero(),
+            },
+        },
+        SumtreeInfoTestCase {
+            name: "single leaf",
+            cancelled_orders: vec![0],
+            expected_response: SumtreeInfoResponse {
+                node_count: 2,
+                height: 2,
+                root_value: decimal256_from_u128(10u128),
+            },
+        },
+        SumtreeInfoTestCase {
+            name: "two leaves under the root",
+            cancelled_orders: vec![0, 2],
+            expected_response: SumtreeInfoResponse {
+                node_count: 3,
+                height: 2,
+                root_value: decimal256_from_u128(20u128),
+            },
+        },
+        SumtreeInfoTestCase {
+            name: "three leaves",
+            cancelled_orders: vec![0, 2, 4],
+            expected_response: SumtreeInfoResponse {
+                node_count: 5,
+                height: 3,
+                root_value: decimal256_from_u128(30u128),
+            },
+        },
+        SumtreeInfoTestCase {
+            name: "five leaves",
+            cancelled_orders: vec![0, 2, 4, 6, 8],
+            expected_response: SumtreeInfoResponse {
+                node_count: 9,
+                height: 4,
+                root_value: decimal256_from_u128(50u128),
+            },
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        // Cancelling non-adjacent orders keeps every cancelled range in its own leaf
+        let mut operations: Vec<OrderOperation> = (0..10)
+            .map(|order_id| {
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    order_id,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(10u128),
+                    Decimal256::zero(),
+                    None,
+                ))
+            })
+            .collect();
+        operations.extend(
+            test.cancelled_orders
+                .iter()
+                .map(|order_id| OrderOperation::Cancel((0, *order_id))),
+        );
+        for op in operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let response = query::sumtree_info(deps.as_ref(), 0, OrderDirection::Ask).unwrap();
+
+        // -- Post test assertions --
+        assert_eq!(
+            response,
+            test.expected_response,
+            "{}: unexpected sumtree info",
+            format_test_name(test.name)
+        );
+
+        // Every cancelled range is stored in its own leaf
+        let leaf_count = query::sumtree_nodes(deps.as_ref(), 0, OrderDirection::Ask, None)
+            .unwrap()
+            .iter()
+            .filter(|node| matches!(node.node_type, NodeType::Leaf { .. }))
+            .count();
+        assert_eq!(
+            leaf_count,
+            test.cancelled_orders.len(),
+            "{}: unexpected number of leaves",
+            format_test_name(test.name)
+        );
+    }
+}
+struct EstimateSwapExactAmountInTestCase {
+    name: &'static str,
+    pre_operations: Vec<OrderOperation>,
+    token_in: Coin,
+    token_out_denom: &'static str,
+    expected_final_tick: Option<i64>,
+}
+
+#[test]
+fn test_estimate_swap_exact_amount_in() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let test_cases = vec![
+        EstimateSwapExactAmountInTestCase {
+            name: "BID: single tick fill",
+            pre_operations: vec![OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(100u128),
+                Decimal256::zero(),
+                None,
+            ))],
+            token_in: coin(50u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM,
+            expected_final_tick: Some(0),
+        },
+        EstimateSwapExactAmountInTestCase {
+            name: "BID: multi tick fill",
+            pre_operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    0,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    LARGE_POSITIVE_TICK,
+                    1,
+                    OrderDirection::Ask,
+                    sender.clone(),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+            ],
+            token_in: coin(60u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM,
+            expected_final_tick: Some(LARGE_POSITIVE_TICK),
+        },
+        EstimateSwapExactAmountInTestCase {
+            name: "ASK: multi tick fill",
+            pre_operations: vec![
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    0,
+                    0,
+                    OrderDirection::Bid,
+                    sender.clone(),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+                OrderOperation::PlaceLimit(LimitOrder::new(
+                    LARGE_NEGATIVE_TICK,
+                    1,
+                    OrderDirection::Bid,
+                    sender.clone(),
+                    Uint128::from(50u128),
+                    Decimal256::zero(),
+                    None,
+                )),
+            ],
+            token_in: coin(60u128, BASE_DENOM),
+            token_out_denom: QUOTE_DENOM,
+            expected_final_tick: Some(LARGE_NEGATIVE_TICK),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for op in test.pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let estimate = query::estimate_swap_exact_amount_in(
+            deps.as_ref(),
+            env.clone(),
+            test.token_in.clone(),
+            test.token_out_denom.to_string(),
+            EXPECTED_SWAP_FEE,
+        )
+        .unwrap();
+
+        // -- Post test assertions --
+        assert_eq!(
+            estimate.final_tick,
+            test.expected_final_tick,
+            "{}: unexpected final tick",
+            format_test_name(test.name)
+        );
+
+        // The estimate matches the output of executing the same swap
+        let response = dispatch_swap_exact_amount_in(
+            deps.as_mut(),
+            env.clone(),
+            sender.to_string(),
+            test.token_in,
+            test.token_out_denom.to_string(),
+            Uint128::zero(),
+            EXPECTED_SWAP_FEE,
+            None,
+            false,
+            false,
+            None,
+        )
+        .unwrap();
+        let data: SwapExactAmountInResponseData = from_json(response.data.unwrap()).unwrap();
+        assert_eq!(
+            estimate.token_out,
+            coin_u256(data.token_out_amount, test.token_out_denom),
+            "{}: estimate does not match executed swap",
+            format_test_name(test.name)
+        );
+        assert!(
+            !estimate.token_out.amount.is_zero(),
+            "{}: swap produced no output",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct EstimateSwapExactAmountOutTestCase {
+    name: &'static str,
+    token_out: u128,
+    taker_fee: Option<Decimal256>,
+    expected_token_out: u128,
+    expected_sufficient_liquidity: bool,
+}
+
+#[test]
+fn test_estimate_swap_exact_amount_out() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let test_cases = vec![
+        EstimateSwapExactAmountOutTestCase {
+            name: "single tick fill",
+            token_out: 50,
+            taker_fee: None,
+            expected_token_out: 50,
+            expected_sufficient_liquidity: true,
+        },
+        EstimateSwapExactAmountOutTestCase {
+            name: "multi tick fill",
+            token_out: 150,
+            taker_fee: None,
+            expected_token_out: 150,
+            expected_sufficient_liquidity: true,
+        },
+        EstimateSwapExactAmountOutTestCase {
+            name: "multi tick fill covering taker fee",
+            token_out: 150,
+            taker_fee: Some(Decimal256::percent(1)),
+            expected_token_out: 150,
+            expected_sufficient_liquidity: true,
+        },
+        EstimateSwapExactAmountOutTestCase {
+            name: "insufficient liquidity",
+            token_out: 250,
+            taker_fee: None,
+            expected_token_out: 200,
+            expected_sufficient_liquidity: false,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        if let Some(taker_fee) = test.taker_fee {
+            set_taker_fee(
+                deps.as_mut().storage,
+                taker_fee,
+                Addr::unchecked("collector"),
+            )
+            .unwrap();
+        }
+
+        for (order_id, tick_id) in [0, LARGE_POSITIVE_TICK].into_iter().enumerate() {
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                tick_id,
+                order_id as u64,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(100u128),
+                Decimal256::zero(),
+                None,
+            ))
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+        }
+
+        // -- System under test --
+        let estimate = query::estimate_swap_exact_amount_out(
+            deps.as_ref(),
+            env.clone(),
+            coin(test.token_out, BASE_DENOM),
+            QUOTE_DENOM.to_string(),
+            EXPECTED_SWAP_FEE,
+        )
+        .unwrap();
+
+        // -- Post test assertions --
+        assert_eq!(
+            estimate.token_out,
+            coin_u256(test.expected_token_out, BASE_DENOM),
+            "{}: unexpected output",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            estimate.sufficient_liquidity,
+            test.expected_sufficient_liquidity,
+            "{}: unexpected liquidity flag",
+            format_test_name(test.name)
+        );
+
+        // Swapping in the estimated input yields at least the estimated output
+        let response = dispatch_swap_exact_amount_in(
+            deps.as_mut(),
+            env.clone(),
+            sender.to_string(),
+            coin(
+                Uint128::try_from(estimate.token_in.amount).unwrap().u128(),
+                QUOTE_DENOM,
+            ),
+            BASE_DENOM.to_string(),
+            Uint128::try_from(estimate.token_out.amount).unwrap(),
+            EXPECTED_SWAP_FEE,
+            None,
+            false,
+            false,
+            None,
+        )
+        .unwrap();
+        let data: SwapExactAmountInResponseData = from_json(response.data.unwrap()).unwrap();
+        assert!(
+            data.token_out_amount >= estimate.token_out.amount,
+            "{}: estimated input produced {} but expected at least {}",
+            format_test_name(test.name),
+            data.token_out_amount,
+            estimate.token_out.amount
+        );
+    }
+}
+
+struct MarketStatsTestCase {
+    name: &'static str,
+    // (seconds after the initial block time, market order)
+    trades: Vec<(u64, MarketOrder)>,
+    query_offset: u64,
+    expected_last_price: Option<Decimal256>,
+    expected_last_trade_offset: Option<u64>,
+    expected_volume: u128,
+    expected_buckets: usize,
+}
+
+#[test]
+fn test_market_stats() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let hour = 3_600u64;
+    let bid = |quantity: u128| {
+        MarketOrder::new(Uint128::from(quantity), OrderDirection::Bid, sender.clone())
+    };
+    let ask = |quantity: u128| {
+        MarketOrder::new(Uint128::from(quantity), OrderDirection::Ask, sender.clone())
+    };
+    let test_cases: Vec<MarketStatsTestCase> = vec![
+        MarketStatsTestCase {
+            name: "no trades",
+            trades: vec![],
+            query_offset: 0,
+            expected_last_price: None,
+            expected_last_trade_offset: None,
+            expected_volume: 0,
+            expected_buckets: 0,
+        },
+        MarketStatsTestCase {
+            name: "single bid",
+            trades: vec![(0, bid(100))],
+            query_offset: 0,
+            expected_last_price: Some(Decimal256::one()),
+            expected_last_trade_offset: Some(0),
+            expected_volume: 100,
+            expected_buckets: 1,
+        },
+        MarketStatsTestCase {
+            name: "bid then ask in the same bucket",
+            // The ask of 10 fills 20 quote of the bid at a price of 0.5
+            trades: vec![(0, bid(100)), (60, ask(10))],
+            query_offset: 60,
+            expected_last_price: Some(Decimal256::percent(50)),
+            expected_last_trade_offset: Some(60),
+            expected_volume: 120,
+            expected_buckets: 1,
+        },
+        MarketStatsTestCase {
+            name: "trades across buckets",
+            trades: vec![(0, bid(100)), (hour, bid(50)), (2 * hour, ask(10))],
+            query_offset: 2 * hour,
+            expected_last_price: Some(Decimal256::percent(50)),
+            expected_last_trade_offset: Some(2 * hour),
+            expected_volume: 170,
+            expected_buckets: 3,
+        },
+        MarketStatsTestCase {
+            name: "volume leaves the window without new trades",
+            trades: vec![(0, bid(100)), (23 * hour, bid(50))],
+            query_offset: 25 * hour,
+            expected_last_price: Some(Decimal256::one()),
+            expected_last_trade_offset: Some(23 * hour),
+            expected_volume: 50,
+            expected_buckets: 2,
+        },
+        MarketStatsTestCase {
+            name: "stale buckets dropped on update",
+            trades: vec![(0, bid(100)), (hour, bid(50)), (25 * hour + 60, ask(10))],
+            query_offset: 25 * hour + 60,
+            expected_last_price: Some(Decimal256::percent(50)),
+            expected_last_trade_offset: Some(25 * hour + 60),
+            expected_volume: 20,
+            expected_buckets: 1,
+        },
+        MarketStatsTestCase {
+            name: "all volume outside the window",
+            trades: vec![(0, bid(100)), (60, ask(10))],
+            query_offset: 48 * hour,
+            expected_last_price: Some(Decimal256::percent(50)),
+            expected_last_trade_offset: Some(60),
+            expected_volume: 0,
+            expected_buckets: 1,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        // Resting liquidity at a price of 1 for bids and 0.5 for asks
+        let place_ops = vec![
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                LARGE_NEGATIVE_TICK,
+                0,
+                OrderDirection::Bid,
+                sender.clone(),
+                Uint128::from(1000u128),
+                Decimal256::zero(),
+                None,
+            )),
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                1,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(1000u128),
+                Decimal256::zero(),
+                None,
+            )),
+        ];
+        for op in place_ops {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        for (offset, order) in test.trades {
+            let mut trade_env = env.clone();
+            trade_env.block.time = env.block.time.plus_seconds(offset);
+            OrderOperation::RunMarket(order)
+                .run(deps.as_mut(), trade_env, info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let mut query_env = env.clone();
+        query_env.block.time = env.block.time.plus_seconds(test.query_offset);
+        let response = query::market_stats(deps.as_ref(), query_env).unwrap();
+
+        // -- Post test assertions --
+        assert_eq!(
+            response,
+            MarketStatsResponse {
+                last_price: test.expected_last_price,
+                last_trade_time: test
+                    .expected_last_trade_offset
+                    .map(|offset| env.block.time.plus_seconds(offset)),
+                volume_24h: Uint256::from(test.expected_volume),
+            },
+            "{}: unexpected market stats",
+            format_test_name(test.name)
+        );
+
+        let stored_buckets = MARKET_STATS
+            .may_load(&deps.storage)
+            .unwrap()
+            .map_or(0, |stats| stats.volume_buckets.len());
+        assert_eq!(
+            stored_buckets,
+            test.expected_buckets,
+            "{}: unexpected number of stored volume buckets",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_queue_position() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // Orders that do not exist are reported as not found
+    let res = query::queue_position(deps.as_ref(), 0, 0);
+    assert_eq!(
+        res,
+        Err(ContractError::OrderNotFound {
+            tick_id: 0,
+            order_id: 0
+        })
+    );
+
+    // Asks of 10, 20 (cancelled), 30 and 40 on one tick, with the first ask half filled, plus a
+    // bid on the same tick that is never ahead of an ask
+    let mut operations: Vec<OrderOperation> = [10u128, 20, 30, 40]
+        .iter()
+        .enumerate()
+        .map(|(order_id, quantity)| {
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                order_id as u64,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(*quantity),
+                Decimal256::zero(),
+                None,
+            ))
+        })
+        .collect();
+    operations.extend([
+        OrderOperation::Cancel((0, 1)),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(5u128),
+            OrderDirection::Bid,
+            sender.clone(),
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            -1,
+            4,
+            OrderDirection::Bid,
+            sender.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        )),
+    ]);
+    for operation in operations {
+        operation
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+
+    // -- System under test --
+    let front = query::queue_position(deps.as_ref(), 0, 0).unwrap();
+    let middle = query::queue_position(deps.as_ref(), 0, 2).unwrap();
+    let back = query::queue_position(deps.as_ref(), 0, 3).unwrap();
+
+    // -- Post Test Assertions --
+    let total_at_tick = Decimal256::from_ratio(75u128, 1u128);
+    assert_eq!(
+        front,
+        QueuePositionResponse {
+            quantity_ahead: Decimal256::zero(),
+            total_at_tick,
+        }
+    );
+    // Only the unfilled half of the first ask is ahead, the cancelled ask is skipped
+    assert_eq!(
+        middle,
+        QueuePositionResponse {
+            quantity_ahead: Decimal256::from_ratio(5u128, 1u128),
+            total_at_tick,
+        }
+    );
+    assert_eq!(
+        back,
+        QueuePositionResponse {
+            quantity_ahead: Decimal256::from_ratio(35u128, 1u128),
+            total_at_tick,
+        }
+    );
+}
+
+#[test]
+fn test_tick_values() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // Untouched ticks return defaults rather than erroring
+    assert_eq!(
+        query::tick_values(deps.as_ref(), 0, OrderDirection::Ask).unwrap(),
+        TickValues::default()
+    );
+
+    // Asks of 10, 20 (cancelled) and 30 on tick 0 partially filled, and a bid on tick -1
+    let operations = vec![
+        OrderOperation::PlaceLimitMulti((vec![0], 3, Uint128::from(10u128), OrderDirection::Ask)),
+        OrderOperation::Cancel((0, 1)),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(15u128),
+            OrderDirection::Bid,
+            sender.clone(),
+        )),
+        OrderOperation::Claim((0, 0)),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            -1,
+            3,
+            OrderDirection::Bid,
+            sender.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        )),
+    ];
+    for operation in operations {
+        operation
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+
+    // -- System under test --
+    for (tick_id, direction) in [
+        (0, OrderDirection::Ask),
+        (0, OrderDirection::Bid),
+        (-1, OrderDirection::Bid),
+    ] {
+        let res = query::tick_values(deps.as_ref(), tick_id, direction).unwrap();
+
+        // -- Post Test Assertions --
+        assert_eq!(
+            res,
+            TICK_STATE
+                .load(deps.as_ref().storage, tick_id)
+                .unwrap()
+                .get_values(direction),
+            "unexpected values for tick {tick_id} {direction}"
+        );
+    }
+
+    // The claim synced the realized cancellation into the ask values
+    let ask_values = query::tick_values(deps.as_ref(), 0, OrderDirection::Ask).unwrap();
+    assert_eq!(
+        ask_values.cumulative_realized_cancels,
+        Decimal256::from_ratio(10u128, 1u128)
+    );
+    assert_eq!(
+        ask_values.cumulative_total_value,
+        Decimal256::from_ratio(30u128, 1u128)
+    );
+}
+
+struct SimulatePriceImpactTestCase {
+    name: &'static str,
+    direction: OrderDirection,
+    amount: Uint128,
+    taker_fee: Option<Decimal256>,
+    expected_average_price: Option<Decimal256>,
+    expected_worst_price: Option<Decimal256>,
+    expected_output: Coin256,
+    expected_fully_filled: bool,
+}
+
+#[test]
+fn test_simulate_price_impact() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    // Asks of 10 at a price of 1 and 20 at a price of 2
+    let pre_operations = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            LARGE_POSITIVE_TICK,
+            1,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(20u128),
+            Decimal256::zero(),
+            None,
+        )),
+    ];
+
+    let test_cases = vec![
+        SimulatePriceImpactTestCase {
+            name: "filled within the first tick",
+            direction: OrderDirection::Bid,
+            amount: Uint128::from(5u128),
+            taker_fee: None,
+            expected_average_price: Some(Decimal256::one()),
+            expected_worst_price: Some(Decimal256::one()),
+            expected_output: coin_u256(5u128, BASE_DENOM),
+            expected_fully_filled: true,
+        },
+        SimulatePriceImpactTestCase {
+            name: "filled across both ticks",
+            direction: OrderDirection::Bid,
+            amount: Uint128::from(20u128),
+            taker_fee: None,
+            expected_average_price: Some(Decimal256::from_str("1.5").unwrap()),
+            expected_worst_price: Some(Decimal256::from_ratio(2u128, 1u128)),
+            expected_output: coin_u256(30u128, BASE_DENOM),
+            expected_fully_filled: true,
+        },
+        SimulatePriceImpactTestCase {
+            name: "liquidity exhausted",
+            direction: OrderDirection::Bid,
+            amount: Uint128::from(100u128),
+            taker_fee: None,
+            expected_average_price: Some(Decimal256::from_str("1.5").unwrap()),
+            expected_worst_price: Some(Decimal256::from_ratio(2u128, 1u128)),
+            expected_output: coin_u256(30u128, BASE_DENOM),
+            expected_fully_filled: false,
+        },
+        SimulatePriceImpactTestCase {
+            name: "average price net of taker fee",
+            direction: OrderDirection::Bid,
+            amount: Uint128::from(20u128),
+            taker_fee: Some(Decimal256::percent(5)),
+            expected_average_price: Some(Decimal256::from_str("1.4").unwrap()),
+            expected_worst_price: Some(Decimal256::from_ratio(2u128, 1u128)),
+            expected_output: coin_u256(28u128, BASE_DENOM),
+            expected_fully_filled: true,
+        },
+        SimulatePriceImpactTestCase {
+            name: "no opposing liquidity",
+            direction: OrderDirection::Ask,
+            amount: Uint128::from(10u128),
+            taker_fee: None,
+            expected_average_price: None,
+            expected_worst_price: None,
+            expected_output: coin_u256(0u128, QUOTE_DENOM),
+            expected_fully_filled: false,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        if let Some(taker_fee) = test.taker_fee {
+            set_taker_fee(
+                deps.as_mut().storage,
+                taker_fee,
+                Addr::unchecked("fee_collector"),
+            )
+            .unwrap();
+        }
+
+        for operation in pre_operations.clone() {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let res =
+            query::simulate_price_impact(deps.as_ref(), env.clone(), test.direction, test.amount)
+                .unwrap();
+
+        // -- Post Test Assertions --
+        assert_eq!(
+            res.average_price,
+            test.expected_average_price,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            res.worst_price,
+            test.expected_worst_price,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            res.output,
+            test.expected_output,
+            "{}",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            res.fully_filled,
+            test.expected_fully_filled,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
+
+struct OwnerTicksTestCase {
+    name: &'static str,
+    owner: &'static str,
+    direction: Option<OrderDirection>,
+    start_after: Option<i64>,
+    limit: Option<u64>,
+    expected_ticks: Vec<(i64, OrderDirection)>,
+}
+
+#[test]
+fn test_owner_ticks() {
+    let owner = "owner";
+    let other = "other";
+    // (tick_id, direction, owner), placed in this order
+    let placements = [
+        (20, OrderDirection::Ask, owner),
+        (0, OrderDirection::Bid, owner),
+        (5, OrderDirection::Ask, owner),
+        (7, OrderDirection::Bid, other),
+        (-10, OrderDirection::Bid, owner),
+        (0, OrderDirection::Ask, owner),
+        (5, OrderDirection::Ask, owner),
+    ];
+
+    let test_cases = vec![
+        OwnerTicksTestCase {
+            name: "all ticks",
+            owner,
+            direction: None,
+            start_after: None,
+            limit: None,
+            expected_ticks: vec![
+                (-10, OrderDirection::Bid),
+                (0, OrderDirection::Bid),
+                (0, OrderDirection::Ask),
+                (5, OrderDirection::Ask),
+                (20, OrderDirection::Ask),
+            ],
+        },
+        OwnerTicksTestCase {
+            name: "asks only",
+            owner,
+            direction: Some(OrderDirection::Ask),
+            start_after: None,
+            limit: None,
+            expected_ticks: vec![
+                (0, OrderDirection::Ask),
+                (5, OrderDirection::Ask),
+                (20, OrderDirection::Ask),
+            ],
+        },
+        OwnerTicksTestCase {
+            name: "limit counts ticks rather than directions",
+            owner,
+            direction: None,
+            start_after: None,
+            limit: Some(2),
+            expected_ticks: vec![
+                (-10, OrderDirection::Bid),
+                (0, OrderDirection::Bid),
+                (0, OrderDirection::Ask),
+            ],
+        },
+        OwnerTicksTestCase {
+            name: "start after a tick",
+            owner,
+            direction: None,
+            start_after: Some(0),
+            limit: Some(1),
+            expected_ticks: vec![(5, OrderDirection::Ask)],
+        },
+        OwnerTicksTestCase {
+            name: "other owner",
+            owner: other,
+            direction: None,
+            start_after: None,
+            limit: None,
+            expected_ticks: vec![(7, OrderDirection::Bid)],
+        },
+        OwnerTicksTestCase {
+            name: "owner without orders",
+            owner: "nobody",
+            direction: None,
+            start_after: None,
+            limit: None,
+            expected_ticks: vec![],
+        },
+    ];
+
+    // -- Test Setup --
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+    for (order_id, (tick_id, direction, owner)) in placements.into_iter().enumerate() {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            order_id as u64,
+            direction,
+            Addr::unchecked(owner),
+            Uint128::from(10u128),
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), mock_info(owner, &[]))
+        .unwrap();
+    }
+
+    for test in test_cases {
+        // -- System under test --
+        let res = query::owner_ticks(
+            deps.as_ref(),
+            Addr::unchecked(test.owner),
+            test.direction,
+            test.start_after,
+            test.limit,
+        )
+        .unwrap();
+
+        // -- Post Test Assertions --
+        assert_eq!(res, test.expected_ticks, "{}", format_test_name(test.name));
+    }
+}
+
+struct OrderStatusTestCase {
+    name: &'static str,
+    operations: Vec<OrderOperation>,
+    expected_status: OrderStatus,
+    expect_order_stored: bool,
+}
+
+#[test]
+fn test_order_status() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let tick_id = 0;
+    let place = OrderOperation::PlaceLimit(LimitOrder::new(
+        tick_id,
+        0,
+        OrderDirection::Ask,
+        sender.clone(),
+        Uint128::from(100u128),
+        Decimal256::zero(),
+        None,
+    ));
+    let fill = |quantity: u128| {
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(quantity),
+            OrderDirection::Bid,
+            sender.clone(),
+        ))
+    };
+    let test_cases = vec![
+        OrderStatusTestCase {
+            name: "order never placed",
+            operations: vec![],
+            expected_status: OrderStatus::NotFound,
+            expect_order_stored: false,
+        },
+        OrderStatusTestCase {
+            name: "resting order",
+            operations: vec![place.clone()],
+            expected_status: OrderStatus::Open,
+            expect_order_stored: true,
+        },
+        OrderStatusTestCase {
+            name: "partially filled",
+            operations: vec![place.clone(), fill(40)],
+            expected_status: OrderStatus::PartiallyFilled,
+            expect_order_stored: true,
+        },
+        OrderStatusTestCase {
+            name: "partially filled and claimed",
+            operations: vec![place.clone(), fill(40), OrderOperation::Claim((tick_id, 0))],
+            expected_status: OrderStatus::PartiallyFilled,
+            expect_order_stored: true,
+        },
+        OrderStatusTestCase {
+            name: "fully filled, unclaimed",
+            operations: vec![place.clone(), fill(100)],
+            expected_status: OrderStatus::FullyFilled,
+            expect_order_stored: true,
+        },
+        OrderStatusTestCase {
+            name: "fully filled and claimed",
+            operations: vec![
+                place.clone(),
+                fill(40),
+                OrderOperation::Claim((tick_id, 0)),
+                fill(60),
+                OrderOperation::Claim((tick_id, 0)),
+            ],
+            expected_status: OrderStatus::FullyFilled,
+            expect_order_stored: false,
+        },
+        OrderStatusTestCase {
+            name: "cancelled",
+            operations: vec![place.clone(), OrderOperation::Cancel((tick_id, 0))],
+            expected_status: OrderStatus::Cancelled,
+            expect_order_stored: false,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        for operation in test.operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let status = query::order_status(deps.as_ref(), tick_id, 0).unwrap();
+
+        // -- Post Test Assertions --
+        assert_eq!(
+            status,
+            test.expected_status,
+            "{}: unexpected order status",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            orders().has(&deps.storage, &(tick_id, 0)),
+            test.expect_order_stored,
+            "{}: unexpected order record",
+            format_test_name(test.name)
+        );
+    }
+}
+#[test]
+fn test_fill_preview() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // Asks of 10, 5 (cancelled) and 10 at a price of 1, then 20 and 20 at a price of 2. The first
+    // ask is partially filled for 4 so only 6 of it remains
+    let mut operations = vec![];
+    for (tick_id, order_id, quantity) in [
+        (0, 0, 10u128),
+        (0, 1, 5),
+        (0, 2, 10),
+        (LARGE_POSITIVE_TICK, 3, 20),
+        (LARGE_POSITIVE_TICK, 4, 20),
+    ] {
+        operations.push(OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            order_id,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(quantity),
+            Decimal256::zero(),
+            None,
+        )));
+    }
+    operations.push(OrderOperation::Cancel((0, 1)));
+    operations.push(OrderOperation::RunMarket(MarketOrder::new(
+        Uint128::from(4u128),
+        OrderDirection::Bid,
+        sender.clone(),
+    )));
+    for operation in operations {
+        operation
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+
+    // -- System under test --
+    // 16 of the 20 quote fills the rest of the first tick, and the last 4 buys 8 at a price of 2
+    let partial = query::fill_preview(
+        deps.as_ref(),
+        env.clone(),
+        OrderDirection::Bid,
+        Uint128::from(20u128),
+    )
+    .unwrap();
+    let exhausted = query::fill_preview(
+        deps.as_ref(),
+        env.clone(),
+        OrderDirection::Bid,
+        Uint128::from(1000u128),
+    )
+    .unwrap();
+    let no_liquidity = query::fill_preview(
+        deps.as_ref(),
+        env.clone(),
+        OrderDirection::Ask,
+        Uint128::from(10u128),
+    )
+    .unwrap();
+
+    // -- Post Test Assertions --
+    assert_eq!(
+        partial,
+        vec![
+            (0, 0, Uint128::from(6u128)),
+            (0, 2, Uint128::from(10u128)),
+            (LARGE_POSITIVE_TICK, 3, Uint128::from(8u128)),
+        ]
+    );
+    assert_eq!(
+        exhausted,
+        vec![
+            (0, 0, Uint128::from(6u128)),
+            (0, 2, Uint128::from(10u128)),
+            (LARGE_POSITIVE_TICK, 3, Uint128::from(20u128)),
+            (LARGE_POSITIVE_TICK, 4, Uint128::from(20u128)),
+        ]
+    );
+    assert!(no_liquidity.is_empty());
+}
+
+#[test]
+fn test_queries_skip_expired_orders() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let mut env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // An expiring ask at a price of 1, behind which sits a live ask at a price of 2
+    OrderOperation::PlaceLimit(
+        LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )
+        .with_expiry(env.block.time.plus_seconds(100)),
+    )
+    .run(deps.as_mut(), env.clone(), info.clone())
+    .unwrap();
+    OrderOperation::PlaceLimit(LimitOrder::new(
+        LARGE_POSITIVE_TICK,
+        1,
+        OrderDirection::Ask,
+        sender.clone(),
+        Uint128::from(100u128),
+        Decimal256::zero(),
+        None,
+    ))
+    .run(deps.as_mut(), env.clone(), info.clone())
+    .unwrap();
+
+    env.block.time = env.block.time.plus_seconds(200);
+
+    // -- System under test --
+    let calc_out = query::calc_out_amount_given_in(
+        deps.as_ref(),
+        env.clone(),
+        coin(25u128, QUOTE_DENOM),
+        BASE_DENOM.to_string(),
+        EXPECTED_SWAP_FEE,
+    )
+    .unwrap();
+    let estimate_in = query::estimate_swap_exact_amount_in(
+        deps.as_ref(),
+        env.clone(),
+        coin(25u128, QUOTE_DENOM),
+        BASE_DENOM.to_string(),
+        EXPECTED_SWAP_FEE,
+    )
+    .unwrap();
+    let estimate_out = query::estimate_swap_exact_amount_out(
+        deps.as_ref(),
+        env.clone(),
+        coin(150u128, BASE_DENOM),
+        QUOTE_DENOM.to_string(),
+        EXPECTED_SWAP_FEE,
+    )
+    .unwrap();
+    let preview = query::fill_preview(
+        deps.as_ref(),
+        env.clone(),
+        OrderDirection::Bid,
+        Uint128::from(1000u128),
+    )
+    .unwrap();
+
+    // -- Post Test Assertions --
+    // Only the live ask is counted, so 25 quote buys 50 base at a price of 2
+    assert_eq!(calc_out.token_out, coin_u256(50u128, BASE_DENOM).into());
+    assert_eq!(estimate_in.token_out, coin_u256(50u128, BASE_DENOM));
+    assert_eq!(estimate_in.final_tick, Some(LARGE_POSITIVE_TICK));
+    assert_eq!(estimate_out.token_out, coin_u256(100u128, BASE_DENOM));
+    assert!(!estimate_out.sufficient_liquidity);
+    assert_eq!(
+        preview,
+        vec![(LARGE_POSITIVE_TICK, 1, Uint128::from(100u128))]
+    );
+
+    // The estimate matches the output of executing the same swap, which voids the expired ask
+    let response = dispatch_swap_exact_amount_in(
+        deps.as_mut(),
+        env.clone(),
+        sender.to_string(),
+        coin(25u128, QUOTE_DENOM),
+        BASE_DENOM.to_string(),
+        Uint128::zero(),
+        EXPECTED_SWAP_FEE,
+        None,
+        false,
+        false,
+        None,
+    )
+    .unwrap();
+    let data: SwapExactAmountInResponseData = from_json(response.data.unwrap()).unwrap();
+    assert_eq!(
+        estimate_in.token_out,
+        coin_u256(data.token_out_amount, BASE_DENOM)
+    );
+}
+
+#[test]
+fn test_cancel_refund_preview() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // An ask of 100 partially filled for 30
+    let operations = vec![
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        )),
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(30u128),
+            OrderDirection::Bid,
+            Addr::unchecked("buyer"),
+        )),
+    ];
+    for operation in operations {
+        operation
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+    }
+
+    // -- System under test --
+    // While the fill is unclaimed the preview fails exactly as the cancel would
+    let before_claim = query::cancel_refund_preview(deps.as_ref(), 0, 0).unwrap_err();
+    let cancel_err = cancel_limit(deps.as_mut(), env.clone(), info.clone(), 0, 0).unwrap_err();
+    OrderOperation::Claim((0, 0))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+    let after_claim = query::cancel_refund_preview(deps.as_ref(), 0, 0).unwrap();
+
+    // -- Post Test Assertions --
+    assert_eq!(before_claim, ContractError::CancelFilledOrder);
+    assert_eq!(cancel_err, before_claim);
+    assert_eq!(after_claim, coin(70, BASE_DENOM));
+
+    // Once claimed, the preview matches the refund of an actual cancel
+    let res = cancel_limit(deps.as_mut(), env, info, 0, 0).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: sender.to_string(),
+            amount: vec![after_claim],
+        })
+    );
+
+    let err = query::cancel_refund_preview(deps.as_ref(), 0, 0).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::OrderNotFound {
+            tick_id: 0,
+            order_id: 0
+        }
+    );
+}
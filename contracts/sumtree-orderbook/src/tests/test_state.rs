@@ -1,7 +1,9 @@
+use crate::constants::{MAX_TICK, MIN_TICK};
 use crate::state::*;
+use crate::tests::test_utils::format_test_name;
 use crate::types::{FilterOwnerOrders, LimitOrder, OrderDirection, TickState};
 use cosmwasm_std::testing::MockStorage;
-use cosmwasm_std::{Addr, Decimal256, Order, Uint128};
+use cosmwasm_std::{Addr, Decimal256, Order, StdResult, Uint128};
 
 #[test]
 fn test_order_id_works() {
@@ -139,7 +141,8 @@ fn test_get_orders_by_owner_all_larger_than_default_page_size() {
     )
     .unwrap();
 
-    assert_eq!(owner_orders.len(), 150);
+    // Page sizes above the maximum are capped
+    assert_eq!(owner_orders.len(), MAX_PAGE_SIZE as usize);
     owner_orders.iter().for_each(|order| {
         assert_eq!(order.owner, Addr::unchecked(owner));
     });
@@ -253,3 +256,196 @@ fn test_get_orders_by_owner_with_pagination() {
         (order_amount as f64 / page_size as f64).ceil() as u64
     );
 }
+
+#[test]
+fn test_get_orders_by_owner_capped_pages() {
+    let mut storage = MockStorage::new();
+    let order_amount = 350;
+    let ticks = [-5, 0, 7];
+    let owner = Addr::unchecked("owner1");
+
+    // Orders of the owner spread across ticks and interleaved with another owner's
+    for i in 0..order_amount {
+        let order_id = new_order_id(&mut storage).unwrap();
+        let tick = ticks[i % 3];
+        let current_owner = if i % 4 == 0 {
+            Addr::unchecked("owner2")
+        } else {
+            owner.clone()
+        };
+        let order = LimitOrder::new(
+            tick,
+            order_id,
+            OrderDirection::Bid,
+            current_owner,
+            Uint128::one(),
+            Decimal256::zero(),
+            None,
+        );
+        orders()
+            .save(&mut storage, &(tick, order_id), &order)
+            .unwrap();
+    }
+    let mut expected_keys: Vec<(i64, u64)> = (0..order_amount)
+        .filter(|i| i % 4 != 0)
+        .map(|i| (ticks[i % 3], i as u64))
+        .collect();
+    expected_keys.sort();
+
+    // -- System under test --
+    // Oversized pages are capped
+    let owner_orders = get_orders_by_owner(
+        &storage,
+        FilterOwnerOrders::All(owner.clone()),
+        None,
+        None,
+        Some(10_000),
+    )
+    .unwrap();
+    assert_eq!(owner_orders.len(), MAX_PAGE_SIZE as usize);
+
+    // Paging with the last key of each page visits every order once, in ascending key order
+    let mut fetched_keys = vec![];
+    let mut start_after = None;
+    loop {
+        let owner_orders = get_orders_by_owner(
+            &storage,
+            FilterOwnerOrders::All(owner.clone()),
+            start_after,
+            None,
+            Some(MAX_PAGE_SIZE),
+        )
+        .unwrap();
+        assert!(owner_orders.len() <= MAX_PAGE_SIZE as usize);
+        let Some(last) = owner_orders.last() else {
+            break;
+        };
+        start_after = Some((last.tick_id, last.order_id));
+        fetched_keys.extend(
+            owner_orders
+                .iter()
+                .map(|order| (order.tick_id, order.order_id)),
+        );
+    }
+
+    // -- Post test assertions --
+    assert_eq!(fetched_keys, expected_keys);
+}
+
+struct NextPopulatedTickTestCase {
+    name: &'static str,
+    direction: OrderDirection,
+    from_tick: i64,
+    tick_bound: i64,
+    expected_tick: Option<i64>,
+}
+
+#[test]
+fn test_next_populated_tick() {
+    let test_cases = vec![
+        NextPopulatedTickTestCase {
+            name: "ask from populated tick",
+            direction: OrderDirection::Ask,
+            from_tick: 0,
+            tick_bound: MAX_TICK,
+            expected_tick: Some(0),
+        },
+        NextPopulatedTickTestCase {
+            name: "ask skips empty ticks",
+            direction: OrderDirection::Ask,
+            from_tick: 1,
+            tick_bound: MAX_TICK,
+            expected_tick: Some(40_000_000),
+        },
+        NextPopulatedTickTestCase {
+            name: "ask skips ticks with only bid liquidity",
+            direction: OrderDirection::Ask,
+            from_tick: -10,
+            tick_bound: MAX_TICK,
+            expected_tick: Some(0),
+        },
+        NextPopulatedTickTestCase {
+            name: "ask beyond tick bound",
+            direction: OrderDirection::Ask,
+            from_tick: 1,
+            tick_bound: 39_999_999,
+            expected_tick: None,
+        },
+        NextPopulatedTickTestCase {
+            name: "ask at tick bound",
+            direction: OrderDirection::Ask,
+            from_tick: 1,
+            tick_bound: 40_000_000,
+            expected_tick: Some(40_000_000),
+        },
+        NextPopulatedTickTestCase {
+            name: "bid walks descending",
+            direction: OrderDirection::Bid,
+            from_tick: MAX_TICK,
+            tick_bound: MIN_TICK,
+            expected_tick: Some(-10),
+        },
+        NextPopulatedTickTestCase {
+            name: "bid beyond tick bound",
+            direction: OrderDirection::Bid,
+            from_tick: MAX_TICK,
+            tick_bound: -9,
+            expected_tick: None,
+        },
+        NextPopulatedTickTestCase {
+            name: "from tick past tick bound",
+            direction: OrderDirection::Ask,
+            from_tick: 40_000_001,
+            tick_bound: 40_000_000,
+            expected_tick: None,
+        },
+    ];
+
+    // Asks at ticks 0 and 40,000,000, a bid at tick -10, a tick with no remaining liquidity and a
+    // tick drained after holding asks
+    let mut storage = MockStorage::new();
+    let with_liquidity = |direction: OrderDirection| {
+        let mut tick_state = TickState::default();
+        let mut tick_values = tick_state.get_values(direction);
+        tick_values.total_amount_of_liquidity = Decimal256::one();
+        tick_state.set_values(direction, tick_values);
+        tick_state
+    };
+    for (tick_id, tick_state) in [
+        (-10, with_liquidity(OrderDirection::Bid)),
+        (0, with_liquidity(OrderDirection::Ask)),
+        (20, TickState::default()),
+        (30, with_liquidity(OrderDirection::Ask)),
+        (30, TickState::default()),
+        (40_000_000, with_liquidity(OrderDirection::Ask)),
+    ] {
+        save_tick_state(&mut storage, tick_id, &tick_state).unwrap();
+    }
+
+    // Only ticks holding liquidity are indexed, so drained ticks are never visited
+    assert_eq!(
+        POPULATED_TICKS
+            .keys(&storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap(),
+        vec![
+            ("ask".to_string(), 0),
+            ("ask".to_string(), 40_000_000),
+            ("bid".to_string(), -10),
+        ]
+    );
+
+    for test in test_cases {
+        // -- System under test --
+        let res =
+            next_populated_tick(&storage, test.direction, test.from_tick, test.tick_bound).unwrap();
+
+        // -- Post test assertions --
+        assert_eq!(
+            res.map(|(tick_id, _)| tick_id),
+            test.expected_tick,
+            "{}",
+            format_test_name(test.name)
+        );
+    }
+}
@@ -1,30 +1,33 @@
 use cosmwasm_std::{
     coin,
     testing::{mock_env, mock_info},
-    to_json_binary, Addr, BankMsg, Coin, Decimal, Decimal256, Empty, StdError, SubMsg, Uint128,
-    Uint256,
+    to_json_binary, Addr, BankMsg, Coin, Decimal, Decimal256, Empty, Order, StdError, SubMsg,
+    Uint128, Uint256,
 };
 
 use crate::{
     auth::ADMIN,
     constants::EXPECTED_SWAP_FEE,
     contract::execute,
-    msg::{AuthExecuteMsg, ExecuteMsg, SudoMsg, SwapExactAmountInResponseData},
-    orderbook::create_orderbook,
-    state::IS_ACTIVE,
+    msg::{AuthExecuteMsg, ExecuteMsg, PlaceLimitInput, SudoMsg, SwapExactAmountInResponseData},
+    order::claim_order,
+    orderbook::{create_orderbook, register_denom_alias, set_taker_fee},
+    query,
+    state::{orders, IS_ACTIVE, REFERRER_VOLUME, TICK_STATE},
     sudo::{
         dispatch_swap_exact_amount_in, ensure_is_active, set_active, sudo, validate_output_amount,
     },
+    sumtree::tree::get_root_node,
     tests::{mock_querier::mock_dependencies_custom, test_constants::QUOTE_DENOM},
     types::{
-        coin_u256, Coin256, LimitOrder, MsgSend256, OrderDirection, REPLY_ID_REFUND,
+        coin_u256, Coin256, LimitOrder, MarketOrder, MsgSend256, OrderDirection, REPLY_ID_REFUND,
         REPLY_ID_SUDO_SWAP_EXACT_IN,
     },
     ContractError,
 };
 
 use super::{
-    test_constants::{BASE_DENOM, DEFAULT_SENDER},
+    test_constants::{BASE_DENOM, DEFAULT_SENDER, LARGE_POSITIVE_TICK},
     test_utils::{format_test_name, OrderOperation},
 };
 
@@ -56,12 +59,10 @@ fn test_validate_output_amount() {
             min_out_amount: Uint256::zero(),
             output: coin_u256(50u128, in_denom),
             input: coin_u256(101u128, out_denom),
-            expected_error: Some(ContractError::InvalidSwap {
-                error: format!(
-                    "Exceeded max swap amount: expected {} received {}",
-                    Uint128::from(100u128),
-                    Uint128::from(101u128)
-                ),
+            expected_error: Some(ContractError::SwapAboveMax {
+                max: Uint256::from(100u128),
+                received: Uint256::from(101u128),
+                denom: out_denom.to_string(),
             }),
         },
         ValidateOutputAmountTestCase {
@@ -70,12 +71,10 @@ fn test_validate_output_amount() {
             min_out_amount: Uint256::from(50u128),
             input: coin_u256(50u128, in_denom),
             output: coin_u256(41u128, out_denom),
-            expected_error: Some(ContractError::InvalidSwap {
-                error: format!(
-                    "Did not meet minimum swap amount: expected {} received {}",
-                    Uint128::from(50u128),
-                    Uint128::from(41u128)
-                ),
+            expected_error: Some(ContractError::SwapBelowMin {
+                min: Uint256::from(50u128),
+                received: Uint256::from(41u128),
+                denom: out_denom.to_string(),
             }),
         },
         ValidateOutputAmountTestCase {
@@ -170,7 +169,10 @@ fn test_swap_exact_amount_in() {
             expected_output: coin_u256(100u128, BASE_DENOM),
             expected_num_msgs: 1,
             expected_refund_msg: None,
-            expected_error: Some(ContractError::InsufficientLiquidity),
+            expected_error: Some(ContractError::InsufficientLiquidityForSwap {
+                requested: Uint128::from(100u128),
+                available: Uint128::from(10u128),
+            }),
         },
         SwapExactAmountInTestCase {
             name: "BID: zero liquidity in orderbook",
@@ -183,7 +185,10 @@ fn test_swap_exact_amount_in() {
             expected_output: coin_u256(100u128, BASE_DENOM),
             expected_num_msgs: 1,
             expected_refund_msg: None,
-            expected_error: Some(ContractError::InsufficientLiquidity),
+            expected_error: Some(ContractError::InsufficientLiquidityForSwap {
+                requested: Uint128::from(100u128),
+                available: Uint128::from(0u128),
+            }),
         },
         SwapExactAmountInTestCase {
             name: "ASK: valid basic swap",
@@ -225,7 +230,10 @@ fn test_swap_exact_amount_in() {
             expected_output: coin_u256(100u128, QUOTE_DENOM),
             expected_num_msgs: 1,
             expected_refund_msg: None,
-            expected_error: Some(ContractError::InsufficientLiquidity),
+            expected_error: Some(ContractError::InsufficientLiquidityForSwap {
+                requested: Uint128::from(100u128),
+                available: Uint128::from(10u128),
+            }),
         },
         SwapExactAmountInTestCase {
             name: "ASK: zero liquidity in orderbook",
@@ -238,7 +246,10 @@ fn test_swap_exact_amount_in() {
             expected_output: coin_u256(100u128, QUOTE_DENOM),
             expected_num_msgs: 1,
             expected_refund_msg: None,
-            expected_error: Some(ContractError::InsufficientLiquidity),
+            expected_error: Some(ContractError::InsufficientLiquidityForSwap {
+                requested: Uint128::from(100u128),
+                available: Uint128::from(0u128),
+            }),
         },
         SwapExactAmountInTestCase {
             name: "invalid in denom",
@@ -283,8 +294,25 @@ fn test_swap_exact_amount_in() {
             expected_output: coin_u256(100u128, QUOTE_DENOM),
             expected_num_msgs: 1,
             expected_refund_msg: None,
-            expected_error: Some(ContractError::InvalidSwap {
-                error: "Input and output denoms cannot be the same".to_string(),
+            expected_error: Some(ContractError::SwapWrongDenom {
+                expected: QUOTE_DENOM.to_string(),
+                received: BASE_DENOM.to_string(),
+            }),
+        },
+        SwapExactAmountInTestCase {
+            name: "duplicate denom outside the orderbook",
+            pre_operations: vec![],
+            token_in: coin(100u128, "notadenom"),
+            token_out_denom: "notadenom",
+            token_out_min_amount: Uint128::from(100u128),
+            swap_fee: EXPECTED_SWAP_FEE,
+            target_tick: None,
+            expected_output: coin_u256(100u128, QUOTE_DENOM),
+            expected_num_msgs: 1,
+            expected_refund_msg: None,
+            expected_error: Some(ContractError::InvalidPair {
+                token_in_denom: "notadenom".to_string(),
+                token_out_denom: "notadenom".to_string(),
             }),
         },
         SwapExactAmountInTestCase {
@@ -298,11 +326,9 @@ fn test_swap_exact_amount_in() {
             expected_output: coin_u256(100u128, QUOTE_DENOM),
             expected_num_msgs: 1,
             expected_refund_msg: None,
-            expected_error: Some(ContractError::InvalidSwap {
-                error: format!(
-                    "Provided swap fee does not match: expected {EXPECTED_SWAP_FEE} received {}",
-                    Decimal::one()
-                ),
+            expected_error: Some(ContractError::SwapFeeMismatch {
+                expected: EXPECTED_SWAP_FEE,
+                received: Decimal::one(),
             }),
         },
         SwapExactAmountInTestCase {
@@ -376,6 +402,9 @@ fn test_swap_exact_amount_in() {
             test.token_out_min_amount,
             test.swap_fee,
             test.target_tick,
+            false,
+            false,
+            None,
         );
 
         // -- Post test assertions --
@@ -417,10 +446,10 @@ fn test_swap_exact_amount_in() {
             format_test_name(test.name)
         );
 
-        if test.expected_refund_msg.is_some() {
+        if let Some(expected_refund_msg) = test.expected_refund_msg {
             let refund_msg = &response.messages[1];
             assert_eq!(
-                &test.expected_refund_msg.unwrap(),
+                &expected_refund_msg,
                 refund_msg,
                 "{}: did not receive expected refund message",
                 format_test_name(test.name)
@@ -436,6 +465,672 @@ fn test_swap_exact_amount_in() {
     }
 }
 
+struct SelfTradePreventionTestCase {
+    name: &'static str,
+    stp: bool,
+    token_in: Coin,
+    expected_output: Coin256,
+    expected_refund_msgs: Vec<SubMsg>,
+    expect_own_order_removed: bool,
+}
+
+#[test]
+fn test_swap_exact_amount_in_self_trade_prevention() {
+    let valid_tick_id = 0;
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let stranger = Addr::unchecked("stranger");
+    let test_cases: Vec<SelfTradePreventionTestCase> = vec![
+        SelfTradePreventionTestCase {
+            name: "STP disabled: fills own resting order",
+            stp: false,
+            token_in: coin(100u128, QUOTE_DENOM),
+            expected_output: coin_u256(100u128, BASE_DENOM),
+            expected_refund_msgs: vec![],
+            expect_own_order_removed: false,
+        },
+        SelfTradePreventionTestCase {
+            name: "STP enabled: cancels own resting order and fills stranger",
+            stp: true,
+            // Only the stranger's liquidity remains available to the sender
+            token_in: coin(50u128, QUOTE_DENOM),
+            expected_output: coin_u256(50u128, BASE_DENOM),
+            // Cancelled resting order refunded to the sender
+            expected_refund_msgs: vec![SubMsg::reply_on_error(
+                BankMsg::Send {
+                    to_address: sender.to_string(),
+                    amount: vec![coin(50u128, BASE_DENOM)],
+                },
+                REPLY_ID_REFUND,
+            )],
+            expect_own_order_removed: true,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        // The sender's own order rests ahead of a stranger's on the same tick
+        let pre_operations = vec![
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                valid_tick_id,
+                0,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(50u128),
+                Decimal256::zero(),
+                None,
+            )),
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                valid_tick_id,
+                1,
+                OrderDirection::Ask,
+                stranger.clone(),
+                Uint128::from(50u128),
+                Decimal256::zero(),
+                None,
+            )),
+        ];
+        for op in pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        // -- System under test --
+        let response = dispatch_swap_exact_amount_in(
+            deps.as_mut(),
+            env.clone(),
+            sender.to_string(),
+            test.token_in,
+            BASE_DENOM.to_string(),
+            Uint128::zero(),
+            EXPECTED_SWAP_FEE,
+            None,
+            false,
+            test.stp,
+            None,
+        )
+        .unwrap();
+
+        // -- Post test assertions --
+        let expected_msg = SubMsg::reply_on_error(
+            MsgSend256 {
+                from_address: env.contract.address.to_string(),
+                to_address: sender.to_string(),
+                amount: vec![test.expected_output.clone()],
+            },
+            REPLY_ID_SUDO_SWAP_EXACT_IN,
+        );
+        assert_eq!(
+            response.messages[0],
+            expected_msg,
+            "{}: did not receive expected output message",
+            format_test_name(test.name)
+        );
+        assert_eq!(
+            response.messages[1..],
+            test.expected_refund_msgs,
+            "{}: did not receive expected refund messages",
+            format_test_name(test.name)
+        );
+
+        // The own order is either removed or fully filled, never left resting
+        let own_order = orders()
+            .may_load(&deps.storage, &(valid_tick_id, 0))
+            .unwrap();
+        assert_eq!(
+            own_order.is_none(),
+            test.expect_own_order_removed,
+            "{}: own order removal did not match",
+            format_test_name(test.name)
+        );
+
+        // The stranger's order is fully filled in both modes, leaving the tick empty
+        let tick_values = TICK_STATE
+            .load(&deps.storage, valid_tick_id)
+            .unwrap()
+            .get_values(OrderDirection::Ask);
+        assert!(
+            tick_values.total_amount_of_liquidity.is_zero(),
+            "{}: tick liquidity not drained",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_swap_exact_amount_in_self_trade_prevention_with_expiry() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let stranger = Addr::unchecked("stranger");
+    let mut deps = mock_dependencies_custom();
+    let mut env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // The first tick only holds a stranger's expiring order and the sender's own order, so the
+    // swap has to clear both before it can reach the stranger's live order on the next tick
+    OrderOperation::PlaceLimit(
+        LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            stranger.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        )
+        .with_expiry(env.block.time.plus_seconds(100)),
+    )
+    .run(deps.as_mut(), env.clone(), info.clone())
+    .unwrap();
+    for (tick_id, order_id, owner) in [(0, 1, &sender), (LARGE_POSITIVE_TICK, 2, &stranger)] {
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            tick_id,
+            order_id,
+            OrderDirection::Ask,
+            owner.clone(),
+            Uint128::from(50u128),
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+    }
+
+    env.block.time = env.block.time.plus_seconds(200);
+
+    // -- System under test --
+    let response = dispatch_swap_exact_amount_in(
+        deps.as_mut(),
+        env.clone(),
+        sender.to_string(),
+        coin(25u128, QUOTE_DENOM),
+        BASE_DENOM.to_string(),
+        Uint128::zero(),
+        EXPECTED_SWAP_FEE,
+        None,
+        false,
+        true,
+        None,
+    )
+    .unwrap();
+
+    // -- Post test assertions --
+    // 25 quote buys the stranger's 50 base at a price of 2, and both orders on the first tick
+    // are refunded to their owners
+    let refund = |owner: &Addr| {
+        SubMsg::reply_on_error(
+            BankMsg::Send {
+                to_address: owner.to_string(),
+                amount: vec![coin(50u128, BASE_DENOM)],
+            },
+            REPLY_ID_REFUND,
+        )
+    };
+    assert_eq!(
+        response.messages,
+        vec![
+            SubMsg::reply_on_error(
+                MsgSend256 {
+                    from_address: env.contract.address.to_string(),
+                    to_address: sender.to_string(),
+                    amount: vec![coin_u256(50u128, BASE_DENOM)],
+                },
+                REPLY_ID_SUDO_SWAP_EXACT_IN,
+            ),
+            refund(&stranger),
+            refund(&sender),
+        ]
+    );
+    for tick_id in [0, LARGE_POSITIVE_TICK] {
+        let tick_values = TICK_STATE
+            .load(&deps.storage, tick_id)
+            .unwrap()
+            .get_values(OrderDirection::Ask);
+        assert!(tick_values.total_amount_of_liquidity.is_zero());
+    }
+}
+
+struct DenomAliasSwapTestCase {
+    name: &'static str,
+    token_in: Coin,
+    token_out_denom: &'static str,
+    expected_output: Coin256,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_swap_exact_amount_in_denom_alias() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let quote_alias = "ibc/quote";
+    let base_alias = "ibc/base";
+    let test_cases = vec![
+        DenomAliasSwapTestCase {
+            name: "canonical denoms",
+            token_in: coin(100u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM,
+            expected_output: coin_u256(100u128, BASE_DENOM),
+            expected_error: None,
+        },
+        DenomAliasSwapTestCase {
+            name: "aliased token in",
+            token_in: coin(100u128, quote_alias),
+            token_out_denom: BASE_DENOM,
+            expected_output: coin_u256(0u128, BASE_DENOM),
+            expected_error: Some(ContractError::AliasedDenom {
+                alias: quote_alias.to_string(),
+                canonical: QUOTE_DENOM.to_string(),
+            }),
+        },
+        DenomAliasSwapTestCase {
+            name: "aliased token out",
+            token_in: coin(100u128, QUOTE_DENOM),
+            token_out_denom: base_alias,
+            expected_output: coin_u256(0u128, BASE_DENOM),
+            expected_error: Some(ContractError::AliasedDenom {
+                alias: base_alias.to_string(),
+                canonical: BASE_DENOM.to_string(),
+            }),
+        },
+        DenomAliasSwapTestCase {
+            name: "aliased token in and out",
+            token_in: coin(100u128, quote_alias),
+            token_out_denom: base_alias,
+            expected_output: coin_u256(0u128, BASE_DENOM),
+            expected_error: Some(ContractError::AliasedDenom {
+                alias: quote_alias.to_string(),
+                canonical: QUOTE_DENOM.to_string(),
+            }),
+        },
+        DenomAliasSwapTestCase {
+            name: "unregistered alias",
+            token_in: coin(100u128, "ibc/unregistered"),
+            token_out_denom: BASE_DENOM,
+            expected_output: coin_u256(0u128, BASE_DENOM),
+            expected_error: Some(ContractError::InvalidPair {
+                token_in_denom: "ibc/unregistered".to_string(),
+                token_out_denom: BASE_DENOM.to_string(),
+            }),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        register_denom_alias(
+            deps.as_mut().storage,
+            quote_alias.to_string(),
+            QUOTE_DENOM.to_string(),
+        )
+        .unwrap();
+        register_denom_alias(
+            deps.as_mut().storage,
+            base_alias.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), info)
+        .unwrap();
+
+        // Aliases still resolve when estimating the swap
+        let estimate = query::estimate_swap_exact_amount_in(
+            deps.as_ref(),
+            env.clone(),
+            coin(100u128, quote_alias),
+            base_alias.to_string(),
+            EXPECTED_SWAP_FEE,
+        )
+        .unwrap();
+        assert_eq!(
+            estimate.token_out,
+            coin_u256(100u128, BASE_DENOM),
+            "{}: estimate did not resolve aliases",
+            format_test_name(test.name)
+        );
+
+        // -- System under test --
+        let response = dispatch_swap_exact_amount_in(
+            deps.as_mut(),
+            env.clone(),
+            sender.to_string(),
+            test.token_in,
+            test.token_out_denom.to_string(),
+            Uint128::from(100u128),
+            EXPECTED_SWAP_FEE,
+            None,
+            false,
+            false,
+            None,
+        );
+
+        // -- Post test assertions --
+        if let Some(expected_error) = test.expected_error {
+            assert_eq!(
+                response.unwrap_err(),
+                expected_error,
+                "{}: did not receive expected error",
+                format_test_name(test.name)
+            );
+            continue;
+        }
+
+        let expected_msg = SubMsg::reply_on_error(
+            MsgSend256 {
+                from_address: env.contract.address.to_string(),
+                to_address: sender.to_string(),
+                amount: vec![test.expected_output],
+            },
+            REPLY_ID_SUDO_SWAP_EXACT_IN,
+        );
+        assert_eq!(
+            response.unwrap().messages,
+            vec![expected_msg],
+            "{}: did not receive expected output message",
+            format_test_name(test.name)
+        );
+    }
+}
+
+#[test]
+fn test_aliased_funds_rejected() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let base_alias = "ibc/base";
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+    register_denom_alias(
+        deps.as_mut().storage,
+        base_alias.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    // Every path that receives or pays out funds rejects the alias, as it would otherwise be
+    // booked as the canonical denom
+    let expected_error = ContractError::AliasedDenom {
+        alias: base_alias.to_string(),
+        canonical: BASE_DENOM.to_string(),
+    };
+    let aliased_funds = [coin(100u128, base_alias)];
+    for (name, funds, msg) in [
+        (
+            "place limit",
+            &aliased_funds[..],
+            ExecuteMsg::PlaceLimit {
+                tick_id: 0,
+                order_direction: OrderDirection::Ask,
+                quantity: Uint128::from(100u128),
+                claim_bounty: None,
+                post_only: None,
+                expiry: None,
+                time_in_force: None,
+                display_quantity: None,
+                use_credit: None,
+            },
+        ),
+        (
+            "place limit batch",
+            &aliased_funds[..],
+            ExecuteMsg::PlaceLimitBatch {
+                orders: vec![PlaceLimitInput {
+                    tick_id: 0,
+                    order_direction: OrderDirection::Ask,
+                    quantity: Uint128::from(100u128),
+                    claim_bounty: None,
+                }],
+            },
+        ),
+        ("deposit", &aliased_funds[..], ExecuteMsg::Deposit {}),
+        (
+            "withdraw",
+            &[],
+            ExecuteMsg::Withdraw {
+                denom: base_alias.to_string(),
+                amount: Uint128::from(100u128),
+            },
+        ),
+    ] {
+        // -- System under test --
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(sender.as_str(), funds),
+            msg,
+        );
+
+        // -- Post test assertions --
+        assert_eq!(
+            res.unwrap_err(),
+            expected_error,
+            "{}: did not receive expected error",
+            format_test_name(name)
+        );
+    }
+}
+
+struct ReferrerSwapTestCase {
+    name: &'static str,
+    resting_direction: OrderDirection,
+    token_in: Coin,
+    token_out_denom: &'static str,
+    taker_fee: Option<Decimal256>,
+    referrer: Option<&'static str>,
+    num_swaps: u32,
+    expected_volume_per_swap: u128,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_swap_exact_amount_in_referrer() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let referrer = "referrer";
+    let test_cases = vec![
+        ReferrerSwapTestCase {
+            name: "no referrer",
+            resting_direction: OrderDirection::Ask,
+            token_in: coin(50u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM,
+            taker_fee: None,
+            referrer: None,
+            num_swaps: 1,
+            expected_volume_per_swap: 0,
+            expected_error: None,
+        },
+        ReferrerSwapTestCase {
+            name: "bid with referrer",
+            resting_direction: OrderDirection::Ask,
+            token_in: coin(50u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM,
+            taker_fee: None,
+            referrer: Some(referrer),
+            num_swaps: 1,
+            expected_volume_per_swap: 50,
+            expected_error: None,
+        },
+        ReferrerSwapTestCase {
+            // Volume is measured in quote before the taker fee is taken from the output
+            name: "ask with referrer and taker fee",
+            resting_direction: OrderDirection::Bid,
+            token_in: coin(50u128, BASE_DENOM),
+            token_out_denom: QUOTE_DENOM,
+            taker_fee: Some(Decimal256::percent(2)),
+            referrer: Some(referrer),
+            num_swaps: 1,
+            expected_volume_per_swap: 50,
+            expected_error: None,
+        },
+        ReferrerSwapTestCase {
+            name: "repeated swaps accumulate",
+            resting_direction: OrderDirection::Ask,
+            token_in: coin(50u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM,
+            taker_fee: None,
+            referrer: Some(referrer),
+            num_swaps: 2,
+            expected_volume_per_swap: 50,
+            expected_error: None,
+        },
+        ReferrerSwapTestCase {
+            name: "invalid referrer",
+            resting_direction: OrderDirection::Ask,
+            token_in: coin(50u128, QUOTE_DENOM),
+            token_out_denom: BASE_DENOM,
+            taker_fee: None,
+            referrer: Some("Referrer"),
+            num_swaps: 1,
+            expected_volume_per_swap: 0,
+            expected_error: Some(ContractError::Std(StdError::generic_err(
+                "Invalid input: address not normalized",
+            ))),
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        if let Some(taker_fee) = test.taker_fee {
+            set_taker_fee(
+                deps.as_mut().storage,
+                taker_fee,
+                Addr::unchecked("fee_collector"),
+            )
+            .unwrap();
+        }
+
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            test.resting_direction,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), info)
+        .unwrap();
+
+        for swap in 1..=test.num_swaps {
+            // -- System under test --
+            let response = sudo(
+                deps.as_mut(),
+                env.clone(),
+                SudoMsg::SwapExactAmountIn {
+                    sender: sender.to_string(),
+                    token_in: test.token_in.clone(),
+                    token_out_denom: test.token_out_denom.to_string(),
+                    token_out_min_amount: Uint128::one(),
+                    swap_fee: EXPECTED_SWAP_FEE,
+                    referrer: test.referrer.map(str::to_string),
+                },
+            );
+
+            // -- Post test assertions --
+            if let Some(expected_error) = &test.expected_error {
+                assert_eq!(
+                    &response.unwrap_err(),
+                    expected_error,
+                    "{}: did not receive expected error",
+                    format_test_name(test.name)
+                );
+                break;
+            }
+
+            let attributes = response.unwrap().attributes;
+            let attribute = |key: &str| {
+                attributes
+                    .iter()
+                    .find(|attr| attr.key == key)
+                    .map(|attr| attr.value.clone())
+            };
+            let expected_total = test.expected_volume_per_swap * swap as u128;
+            if test.referrer.is_some() {
+                assert_eq!(
+                    (
+                        attribute("referrer"),
+                        attribute("referred_volume"),
+                        attribute("referrer_total_volume")
+                    ),
+                    (
+                        Some(referrer.to_string()),
+                        Some(test.expected_volume_per_swap.to_string()),
+                        Some(expected_total.to_string())
+                    ),
+                    "{}: unexpected referrer attributes",
+                    format_test_name(test.name)
+                );
+            } else {
+                assert_eq!(
+                    attribute("referrer"),
+                    None,
+                    "{}: unexpected referrer attribute",
+                    format_test_name(test.name)
+                );
+            }
+        }
+
+        let recorded: Vec<(Addr, Uint256)> = REFERRER_VOLUME
+            .range(&deps.storage, None, None, Order::Ascending)
+            .map(|item| item.unwrap())
+            .collect();
+        let expected_recorded = match test.referrer {
+            Some(_) if test.expected_error.is_none() => vec![(
+                Addr::unchecked(referrer),
+                Uint256::from(test.expected_volume_per_swap * test.num_swaps as u128),
+            )],
+            _ => vec![],
+        };
+        assert_eq!(
+            recorded,
+            expected_recorded,
+            "{}: unexpected referred volume",
+            format_test_name(test.name)
+        );
+    }
+}
+
 #[test]
 fn test_sudo_transfer_admin() {
     let mut deps = mock_dependencies_custom();
@@ -568,6 +1263,11 @@ fn test_set_active_execute() {
                 order_direction: OrderDirection::Ask,
                 quantity: Uint128::from(100u128),
                 claim_bounty: None,
+                post_only: None,
+                expiry: None,
+                time_in_force: None,
+                display_quantity: None,
+                use_credit: None,
             },
             active_status: Some(true),
             expected_error: None,
@@ -579,6 +1279,11 @@ fn test_set_active_execute() {
                 order_direction: OrderDirection::Ask,
                 quantity: Uint128::from(100u128),
                 claim_bounty: None,
+                post_only: None,
+                expiry: None,
+                time_in_force: None,
+                display_quantity: None,
+                use_credit: None,
             },
             active_status: None,
             expected_error: None,
@@ -590,6 +1295,11 @@ fn test_set_active_execute() {
                 order_direction: OrderDirection::Ask,
                 quantity: Uint128::from(100u128),
                 claim_bounty: None,
+                post_only: None,
+                expiry: None,
+                time_in_force: None,
+                display_quantity: None,
+                use_credit: None,
             },
             active_status: Some(false),
             expected_error: Some(ContractError::Inactive),
@@ -691,6 +1401,7 @@ fn test_set_active_sudo() {
                 token_out_denom: BASE_DENOM.to_string(),
                 token_out_min_amount: Uint128::from(100u128),
                 swap_fee: Decimal::zero(),
+                referrer: None,
             },
             active_status: Some(true),
             expected_error: None,
@@ -712,6 +1423,7 @@ fn test_set_active_sudo() {
                 token_out_denom: BASE_DENOM.to_string(),
                 token_out_min_amount: Uint128::from(100u128),
                 swap_fee: Decimal::zero(),
+                referrer: None,
             },
             active_status: Some(false),
             expected_error: Some(ContractError::Inactive),
@@ -764,3 +1476,348 @@ fn test_set_active_sudo() {
         );
     }
 }
+
+struct PausedExecuteTestCase {
+    name: &'static str,
+    msg: ExecuteMsg,
+    funds: Vec<Coin>,
+    paused_states: Vec<bool>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_paused_execute() {
+    let place_msg = ExecuteMsg::PlaceLimit {
+        tick_id: 0,
+        order_direction: OrderDirection::Ask,
+        quantity: Uint128::from(100u128),
+        claim_bounty: None,
+        post_only: None,
+        expiry: None,
+        time_in_force: None,
+        display_quantity: None,
+        use_credit: None,
+    };
+    let test_cases = vec![
+        PausedExecuteTestCase {
+            name: "paused: place limit rejected",
+            msg: place_msg.clone(),
+            funds: vec![coin(100u128, BASE_DENOM)],
+            paused_states: vec![true],
+            expected_error: Some(ContractError::Paused),
+        },
+        PausedExecuteTestCase {
+            name: "paused: cancel limit allowed",
+            msg: ExecuteMsg::CancelLimit {
+                tick_id: 0,
+                order_id: 1,
+                swap_refund: None,
+                to_credit: None,
+            },
+            funds: vec![],
+            paused_states: vec![true],
+            expected_error: None,
+        },
+        PausedExecuteTestCase {
+            name: "paused: claim limit allowed",
+            msg: ExecuteMsg::ClaimLimit {
+                tick_id: 0,
+                order_id: 0,
+                min_claim: None,
+                to_credit: None,
+            },
+            funds: vec![],
+            paused_states: vec![true],
+            expected_error: None,
+        },
+        PausedExecuteTestCase {
+            name: "unpaused: place limit allowed",
+            msg: place_msg.clone(),
+            funds: vec![coin(100u128, BASE_DENOM)],
+            paused_states: vec![true, false],
+            expected_error: None,
+        },
+        PausedExecuteTestCase {
+            name: "never paused: place limit allowed",
+            msg: place_msg,
+            funds: vec![coin(100u128, BASE_DENOM)],
+            paused_states: vec![],
+            expected_error: None,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(DEFAULT_SENDER, &[coin(100u128, BASE_DENOM)]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        ADMIN
+            .save(deps.as_mut().storage, &Addr::unchecked(DEFAULT_SENDER))
+            .unwrap();
+
+        // A filled order to claim and an unfilled order to cancel
+        let pre_operations = vec![
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                0,
+                OrderDirection::Ask,
+                Addr::unchecked(DEFAULT_SENDER),
+                Uint128::from(100u128),
+                Decimal256::zero(),
+                None,
+            )),
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                0,
+                1,
+                OrderDirection::Ask,
+                Addr::unchecked(DEFAULT_SENDER),
+                Uint128::from(100u128),
+                Decimal256::zero(),
+                None,
+            )),
+            OrderOperation::RunMarket(MarketOrder::new(
+                Uint128::from(100u128),
+                OrderDirection::Bid,
+                Addr::unchecked("buyer"),
+            )),
+        ];
+        for op in pre_operations {
+            op.run(deps.as_mut(), env.clone(), info.clone()).unwrap();
+        }
+
+        for paused in test.paused_states {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(DEFAULT_SENDER, &[]),
+                ExecuteMsg::Auth(AuthExecuteMsg::SetPaused { paused }),
+            )
+            .unwrap();
+        }
+
+        // -- System under test --
+        let resp = execute(
+            deps.as_mut(),
+            env,
+            mock_info(DEFAULT_SENDER, &test.funds),
+            test.msg,
+        );
+        if let Some(expected_err) = test.expected_error {
+            assert_eq!(
+                resp.unwrap_err(),
+                expected_err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            continue;
+        }
+
+        assert!(
+            resp.is_ok(),
+            "{}: execute message unexpectedly failed; {}",
+            test.name,
+            resp.unwrap_err()
+        );
+    }
+}
+
+struct PausedSudoTestCase {
+    name: &'static str,
+    paused_states: Vec<bool>,
+    expected_error: Option<ContractError>,
+}
+
+#[test]
+fn test_paused_sudo() {
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let test_cases = vec![
+        PausedSudoTestCase {
+            name: "paused: swap rejected",
+            paused_states: vec![true],
+            expected_error: Some(ContractError::Paused),
+        },
+        PausedSudoTestCase {
+            name: "unpaused: swap allowed",
+            paused_states: vec![true, false],
+            expected_error: None,
+        },
+    ];
+
+    for test in test_cases {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+        ADMIN.save(deps.as_mut().storage, &sender).unwrap();
+
+        OrderOperation::PlaceLimit(LimitOrder::new(
+            0,
+            0,
+            OrderDirection::Ask,
+            sender.clone(),
+            Uint128::from(100u128),
+            Decimal256::zero(),
+            None,
+        ))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+
+        for paused in test.paused_states {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                ExecuteMsg::Auth(AuthExecuteMsg::SetPaused { paused }),
+            )
+            .unwrap();
+        }
+
+        // -- System under test --
+        let resp = sudo(
+            deps.as_mut(),
+            env,
+            SudoMsg::SwapExactAmountIn {
+                sender: sender.to_string(),
+                token_in: coin(100u128, QUOTE_DENOM),
+                token_out_denom: BASE_DENOM.to_string(),
+                token_out_min_amount: Uint128::from(100u128),
+                swap_fee: EXPECTED_SWAP_FEE,
+                referrer: None,
+            },
+        );
+        if let Some(expected_err) = test.expected_error {
+            assert_eq!(
+                resp.unwrap_err(),
+                expected_err,
+                "{}: did not receive expected error",
+                test.name
+            );
+            continue;
+        }
+
+        assert!(
+            resp.is_ok(),
+            "{}: sudo message unexpectedly failed; {}",
+            test.name,
+            resp.unwrap_err()
+        );
+    }
+}
+
+#[test]
+fn test_sumtree_safe_mode() {
+    let valid_tick_id = 0;
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+
+    // Cancelling every other order inserts sumtree nodes with ascending ETAS,
+    // which forces rotations when safe mode is disabled.
+    let cancelled_orders = [0, 2, 4, 6, 8];
+    let remaining_orders = [1, 3, 5, 7, 9];
+
+    let mut results = vec![];
+    for safe_mode in [false, true] {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        if safe_mode {
+            let resp = sudo(
+                deps.as_mut(),
+                env.clone(),
+                SudoMsg::SetSumtreeSafeMode { enabled: true },
+            )
+            .unwrap();
+            assert!(
+                resp.attributes.iter().any(|attr| attr.key == "warning"),
+                "safe mode toggle must be loudly attributed"
+            );
+        }
+
+        let mut operations = vec![OrderOperation::PlaceLimitMulti((
+            vec![valid_tick_id],
+            10,
+            Uint128::from(10u128),
+            OrderDirection::Ask,
+        ))];
+        for order_id in cancelled_orders {
+            operations.push(OrderOperation::Cancel((valid_tick_id, order_id)));
+        }
+        operations.push(OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(35u128),
+            OrderDirection::Bid,
+            sender.clone(),
+        )));
+
+        for operation in operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+
+        // -- System under test --
+        let claims: Vec<Option<Uint256>> = remaining_orders
+            .iter()
+            .map(|order_id| {
+                claim_order(
+                    deps.as_mut().storage,
+                    env.contract.address.clone(),
+                    sender.clone(),
+                    valid_tick_id,
+                    *order_id,
+                    None,
+                )
+                .ok()
+                .map(|(amount, ..)| amount)
+            })
+            .collect();
+
+        let root =
+            get_root_node(deps.as_ref().storage, valid_tick_id, OrderDirection::Ask).unwrap();
+        results.push((claims, root.get_weight()));
+    }
+
+    // -- Post Test Assertions --
+    let (balanced_claims, balanced_weight) = &results[0];
+    let (safe_mode_claims, safe_mode_weight) = &results[1];
+    assert_eq!(
+        balanced_claims,
+        &vec![
+            Some(Uint256::from(10u128)),
+            Some(Uint256::from(10u128)),
+            Some(Uint256::from(10u128)),
+            Some(Uint256::from(5u128)),
+            None,
+        ],
+        "unexpected claims in balanced mode"
+    );
+    assert_eq!(
+        balanced_claims, safe_mode_claims,
+        "claims in safe mode did not match balanced mode"
+    );
+    assert!(
+        safe_mode_weight > balanced_weight,
+        "rotations were not skipped in safe mode"
+    );
+}
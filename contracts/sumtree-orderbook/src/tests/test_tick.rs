@@ -1,10 +1,20 @@
+use crate::msg::SudoMsg;
+use crate::order::{cancel_limit, claim_order};
+use crate::orderbook::create_orderbook;
+use crate::query::realized_cancels;
 use crate::state::TICK_STATE;
+use crate::sudo::sudo;
 use crate::sumtree::node::NodeType;
 use crate::sumtree::test::test_tree::insert_and_refetch;
-use crate::tick::sync_tick;
-use crate::types::{OrderDirection, TickState, TickValues};
-use cosmwasm_std::testing::mock_dependencies;
-use cosmwasm_std::{Decimal256, Storage};
+use crate::sumtree::tree::{get_root_node_or_default, TREE};
+use crate::tests::mock_querier::mock_dependencies_custom;
+use crate::tests::test_constants::{BASE_DENOM, DEFAULT_SENDER, QUOTE_DENOM};
+use crate::tests::test_utils::OrderOperation;
+use crate::tick::{subtract_liquidity, sync_tick};
+use crate::types::{LimitOrder, MarketOrder, OrderDirection, TickState, TickValues};
+use crate::ContractError;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{Addr, Decimal256, Storage, Uint128, Uint256};
 
 struct SyncTickTestCase {
     name: &'static str,
@@ -424,6 +434,109 @@ fn test_sync_tick() {
 // build_tick_values builds a `TickValues` that simulates the given total liquidity and unrealized cancels.
 // This helper allows us to test tick level functionality without leaning on higher level abstractions like
 // place_limit and cancel_limit.
+#[test]
+fn test_recompute_realized_cancels() {
+    let tick_id = 0;
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+
+    for repair in [false, true] {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        // Three asks of 10, the first of which is cancelled. Filling 10 and claiming the second
+        // order syncs the tick, realizing the cancellation.
+        let operations = vec![
+            OrderOperation::PlaceLimitMulti((
+                vec![tick_id],
+                3,
+                Uint128::from(10u128),
+                OrderDirection::Ask,
+            )),
+            OrderOperation::Cancel((tick_id, 0)),
+            OrderOperation::RunMarket(MarketOrder::new(
+                Uint128::from(10u128),
+                OrderDirection::Bid,
+                sender.clone(),
+            )),
+            OrderOperation::Claim((tick_id, 1)),
+        ];
+        for operation in operations {
+            operation
+                .run(deps.as_mut(), env.clone(), info.clone())
+                .unwrap();
+        }
+        let res = realized_cancels(deps.as_ref(), tick_id, OrderDirection::Ask).unwrap();
+        assert_eq!(res.stored, Decimal256::from_ratio(10u128, 1u128));
+        assert!(res.in_sync);
+
+        // Corrupt the stored cumulative realized cancels
+        let mut tick_state = TICK_STATE.load(&deps.storage, tick_id).unwrap();
+        let mut ask_values = tick_state.get_values(OrderDirection::Ask);
+        ask_values.cumulative_realized_cancels = Decimal256::zero();
+        tick_state.set_values(OrderDirection::Ask, ask_values);
+        TICK_STATE
+            .save(deps.as_mut().storage, tick_id, &tick_state)
+            .unwrap();
+
+        let res = realized_cancels(deps.as_ref(), tick_id, OrderDirection::Ask).unwrap();
+        assert_eq!(res.stored, Decimal256::zero());
+        assert_eq!(res.recomputed, Decimal256::from_ratio(10u128, 1u128));
+        assert!(!res.in_sync);
+
+        // -- System under test --
+        if repair {
+            sudo(
+                deps.as_mut(),
+                env.clone(),
+                SudoMsg::RecomputeRealizedCancels {
+                    tick_id,
+                    direction: OrderDirection::Ask,
+                },
+            )
+            .unwrap();
+            assert!(
+                realized_cancels(deps.as_ref(), tick_id, OrderDirection::Ask)
+                    .unwrap()
+                    .in_sync
+            );
+        }
+
+        // Fill the last order and claim it, which syncs the tick again
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(10u128),
+            OrderDirection::Bid,
+            sender.clone(),
+        ))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+        let res = claim_order(
+            deps.as_mut().storage,
+            env.contract.address.clone(),
+            sender.clone(),
+            tick_id,
+            2,
+            None,
+        );
+
+        // -- Post Test Assertions --
+        if repair {
+            let (amount, ..) = res.unwrap();
+            assert_eq!(amount, Uint256::from(10u128));
+        } else {
+            // The desynced value causes the cancellation to be realized a second time
+            assert_eq!(res.unwrap_err(), ContractError::InvalidTickSync);
+        }
+    }
+}
+
 fn build_tick_values(total_liquidity: u128, unrealized_cancels: u128) -> TickValues {
     // We set initial cumulative tick value to zero
     let cumulative_realized_cancels = Decimal256::zero();
@@ -445,9 +558,153 @@ fn build_tick_values(total_liquidity: u128, unrealized_cancels: u128) -> TickVal
         total_amount_of_liquidity,
         cumulative_realized_cancels,
         last_tick_sync_etas,
+        hidden_liquidity: Decimal256::zero(),
     }
 }
 
+#[test]
+fn test_fill_tick_without_sumtree() {
+    let tick_id = 0;
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+
+    for dangling_root in [false, true] {
+        // -- Test Setup --
+        let mut deps = mock_dependencies_custom();
+        let env = mock_env();
+        let info = mock_info(sender.as_str(), &[]);
+        create_orderbook(
+            deps.as_mut(),
+            QUOTE_DENOM.to_string(),
+            BASE_DENOM.to_string(),
+        )
+        .unwrap();
+
+        // Two asks of 10 and 20 with no cancellations, so the tick never gets a sumtree
+        for quantity in [10u128, 20u128] {
+            OrderOperation::PlaceLimit(LimitOrder::new(
+                tick_id,
+                0,
+                OrderDirection::Ask,
+                sender.clone(),
+                Uint128::from(quantity),
+                Decimal256::zero(),
+                None,
+            ))
+            .run(deps.as_mut(), env.clone(), info.clone())
+            .unwrap();
+        }
+        assert!(TREE
+            .may_load(&deps.storage, &(tick_id, "ask"))
+            .unwrap()
+            .is_none());
+
+        // Point the tick at a root node that was never written
+        if dangling_root {
+            TREE.save(deps.as_mut().storage, &(tick_id, "ask"), &7)
+                .unwrap();
+            let root =
+                get_root_node_or_default(&deps.storage, tick_id, OrderDirection::Ask).unwrap();
+            assert_eq!(root.get_value(), Decimal256::zero());
+        }
+
+        // -- System under test --
+        OrderOperation::RunMarket(MarketOrder::new(
+            Uint128::from(15u128),
+            OrderDirection::Bid,
+            sender.clone(),
+        ))
+        .run(deps.as_mut(), env.clone(), info.clone())
+        .unwrap();
+
+        // -- Post Test Assertions --
+        let mut claimed = vec![];
+        for order_id in [0, 1] {
+            let (amount, ..) = claim_order(
+                deps.as_mut().storage,
+                env.contract.address.clone(),
+                sender.clone(),
+                tick_id,
+                order_id,
+                None,
+            )
+            .unwrap();
+            claimed.push(amount);
+        }
+        assert_eq!(claimed, vec![Uint256::from(10u128), Uint256::from(5u128)]);
+
+        // Syncing the tick must not leave a pointer to a root node that does not exist
+        let tree_root = TREE.may_load(&deps.storage, &(tick_id, "ask")).unwrap();
+        if dangling_root {
+            assert_eq!(tree_root, Some(7));
+        } else {
+            assert!(tree_root.is_none());
+        }
+    }
+}
+#[test]
+fn test_subtract_liquidity() {
+    let tick_id = -5;
+    let mut values = TickValues {
+        total_amount_of_liquidity: Decimal256::from_ratio(30u128, 1u128),
+        ..TickValues::default()
+    };
+
+    // A decrement within the tick's liquidity is applied as is
+    subtract_liquidity(&mut values, tick_id, Decimal256::from_ratio(10u128, 1u128)).unwrap();
+    assert_eq!(
+        values.total_amount_of_liquidity,
+        Decimal256::from_ratio(20u128, 1u128)
+    );
+
+    // Draining the tick exactly leaves it empty
+    subtract_liquidity(&mut values, tick_id, Decimal256::from_ratio(20u128, 1u128)).unwrap();
+    assert!(values.total_amount_of_liquidity.is_zero());
+
+    // Removing more than the tick holds errors and leaves the values untouched
+    let err = subtract_liquidity(&mut values, tick_id, Decimal256::one()).unwrap_err();
+    assert_eq!(err, ContractError::LiquidityUnderflow { tick_id });
+    assert!(values.total_amount_of_liquidity.is_zero());
+}
+
+#[test]
+fn test_cancel_liquidity_underflow() {
+    let tick_id = 0;
+    let sender = Addr::unchecked(DEFAULT_SENDER);
+    let mut deps = mock_dependencies_custom();
+    let env = mock_env();
+    let info = mock_info(sender.as_str(), &[]);
+    create_orderbook(
+        deps.as_mut(),
+        QUOTE_DENOM.to_string(),
+        BASE_DENOM.to_string(),
+    )
+    .unwrap();
+
+    OrderOperation::PlaceLimit(LimitOrder::new(
+        tick_id,
+        0,
+        OrderDirection::Ask,
+        sender.clone(),
+        Uint128::from(10u128),
+        Decimal256::zero(),
+        None,
+    ))
+    .run(deps.as_mut(), env.clone(), info.clone())
+    .unwrap();
+
+    // Corrupt the tick so it holds less liquidity than the order resting on it
+    let mut tick_state = TICK_STATE.load(&deps.storage, tick_id).unwrap();
+    let mut ask_values = tick_state.get_values(OrderDirection::Ask);
+    ask_values.total_amount_of_liquidity = Decimal256::from_ratio(5u128, 1u128);
+    tick_state.set_values(OrderDirection::Ask, ask_values);
+    TICK_STATE
+        .save(deps.as_mut().storage, tick_id, &tick_state)
+        .unwrap();
+
+    let err = cancel_limit(deps.as_mut(), env, info, tick_id, 0).unwrap_err();
+    assert_eq!(err, ContractError::LiquidityUnderflow { tick_id });
+}
+
 // increment_tick_etas increments the ETAS of a tick by the given amounts for both bid and ask orders.
 fn increment_tick_etas(
     storage: &mut dyn Storage,
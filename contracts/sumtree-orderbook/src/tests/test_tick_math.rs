@@ -1,8 +1,10 @@
 use crate::constants::*;
 use crate::error::ContractError;
 use crate::tick_math::{
-    divide_by_price, multiply_by_price, pow_ten, tick_to_price, RoundingDirection,
+    amount_out_rounded, divide_by_price, multiply_by_price, pow_ten, price_to_tick, tick_to_price,
+    RoundingDirection,
 };
+use crate::types::OrderDirection;
 use cosmwasm_std::{Decimal256, OverflowError, OverflowOperation, Uint128, Uint256};
 use std::str::FromStr;
 
@@ -166,6 +168,155 @@ fn test_tick_to_price_error_cases() {
     }
 }
 
+struct PriceToTickTestCase {
+    name: &'static str,
+    price: Decimal256,
+    expected_tick_round_down: i64,
+    expected_tick_round_up: i64,
+}
+
+#[test]
+fn test_price_to_tick() {
+    let test_cases = vec![
+        PriceToTickTestCase {
+            name: "price one",
+            price: Decimal256::one(),
+            expected_tick_round_down: 0,
+            expected_tick_round_up: 0,
+        },
+        PriceToTickTestCase {
+            name: "exact large price",
+            price: Decimal256::from_str("50000").unwrap(),
+            expected_tick_round_down: 40000000,
+            expected_tick_round_up: 40000000,
+        },
+        PriceToTickTestCase {
+            name: "large price between ticks",
+            price: Decimal256::from_str("50000.005").unwrap(),
+            expected_tick_round_down: 40000000,
+            expected_tick_round_up: 40000001,
+        },
+        PriceToTickTestCase {
+            name: "exact sub-1 price",
+            price: Decimal256::from_str("0.01234567").unwrap(),
+            expected_tick_round_down: -17765433,
+            expected_tick_round_up: -17765433,
+        },
+        PriceToTickTestCase {
+            name: "sub-1 price between ticks",
+            price: Decimal256::from_str("0.012345675").unwrap(),
+            expected_tick_round_down: -17765433,
+            expected_tick_round_up: -17765432,
+        },
+        PriceToTickTestCase {
+            name: "price just below one",
+            price: Decimal256::from_str("0.9998").unwrap(),
+            expected_tick_round_down: -2000,
+            expected_tick_round_up: -2000,
+        },
+        PriceToTickTestCase {
+            name: "price at geometric boundary",
+            price: Decimal256::from_str("10").unwrap(),
+            expected_tick_round_down: 9000000,
+            expected_tick_round_up: 9000000,
+        },
+        PriceToTickTestCase {
+            name: "price just below geometric boundary",
+            price: Decimal256::from_str("9.9999995").unwrap(),
+            expected_tick_round_down: 8999999,
+            expected_tick_round_up: 9000000,
+        },
+        PriceToTickTestCase {
+            name: "min price",
+            price: min_spot_price(),
+            expected_tick_round_down: MIN_TICK,
+            expected_tick_round_up: MIN_TICK,
+        },
+        PriceToTickTestCase {
+            name: "below min price clamps",
+            price: Decimal256::zero(),
+            expected_tick_round_down: MIN_TICK,
+            expected_tick_round_up: MIN_TICK,
+        },
+        PriceToTickTestCase {
+            name: "max price",
+            price: max_spot_price(),
+            expected_tick_round_down: MAX_TICK,
+            expected_tick_round_up: MAX_TICK,
+        },
+        PriceToTickTestCase {
+            name: "above max price clamps",
+            price: max_spot_price().checked_add(Decimal256::one()).unwrap(),
+            expected_tick_round_down: MAX_TICK,
+            expected_tick_round_up: MAX_TICK,
+        },
+    ];
+
+    for test in test_cases {
+        assert_eq!(
+            price_to_tick(test.price, RoundingDirection::Down).unwrap(),
+            test.expected_tick_round_down,
+            "{}: unexpected tick when rounding down",
+            test.name
+        );
+        assert_eq!(
+            price_to_tick(test.price, RoundingDirection::Up).unwrap(),
+            test.expected_tick_round_up,
+            "{}: unexpected tick when rounding up",
+            test.name
+        );
+    }
+}
+
+#[test]
+fn test_price_to_tick_round_trip() {
+    // Sample ticks across the full range, plus each geometric boundary and its neighbours
+    let mut ticks: Vec<i64> = (MIN_TICK..MAX_TICK).step_by(1_234_567).collect();
+    for exponent in (MIN_TICK / GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS)
+        ..=(MAX_TICK / GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS)
+    {
+        let boundary = exponent * GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS;
+        ticks.extend([boundary - 1, boundary, boundary + 1]);
+    }
+    ticks.push(MAX_TICK - 1);
+
+    for tick in ticks
+        .into_iter()
+        .filter(|tick| *tick >= MIN_TICK && *tick < MAX_TICK)
+    {
+        let price = tick_to_price(tick).unwrap();
+        let next_price = tick_to_price(tick + 1).unwrap();
+
+        // A tick's own price maps back to that tick in either rounding direction
+        assert_eq!(
+            price_to_tick(price, RoundingDirection::Down).unwrap(),
+            tick,
+            "tick {tick}: price did not round trip when rounding down"
+        );
+        assert_eq!(
+            price_to_tick(price, RoundingDirection::Up).unwrap(),
+            tick,
+            "tick {tick}: price did not round trip when rounding up"
+        );
+
+        // A price strictly between two ticks rounds to the tick on either side of it
+        let mid_price = price
+            .checked_add(next_price)
+            .unwrap()
+            .checked_div(Decimal256::from_ratio(2u128, 1u128))
+            .unwrap();
+        if mid_price == price {
+            continue;
+        }
+        let tick_down = price_to_tick(mid_price, RoundingDirection::Down).unwrap();
+        let tick_up = price_to_tick(mid_price, RoundingDirection::Up).unwrap();
+        assert_eq!(tick_down, tick, "tick {tick}: mid price rounded down");
+        assert_eq!(tick_up, tick + 1, "tick {tick}: mid price rounded up");
+        assert!(tick_to_price(tick_down).unwrap() <= mid_price);
+        assert!(tick_to_price(tick_up).unwrap() >= mid_price);
+    }
+}
+
 #[test]
 fn test_pow_ten() {
     struct PowTenTestCase {
@@ -324,3 +475,86 @@ fn test_divide_by_price() {
         }
     }
 }
+
+struct AmountOutRoundedTestCase {
+    name: &'static str,
+    tick_id: i64,
+    input: Uint128,
+    direction: OrderDirection,
+    expected_output: Uint256,
+}
+
+#[test]
+fn test_amount_out_rounded() {
+    let test_cases = vec![
+        AmountOutRoundedTestCase {
+            name: "BID: MIN_TICK truncates just below a whole unit",
+            tick_id: MIN_TICK,
+            // 1_999_999_999_999 * 10^-12 = 1.999999999999, which would round to 2
+            input: Uint128::from(1_999_999_999_999u128),
+            direction: OrderDirection::Bid,
+            expected_output: Uint256::from(1u128),
+        },
+        AmountOutRoundedTestCase {
+            name: "BID: MIN_TICK truncates dust to zero",
+            tick_id: MIN_TICK,
+            input: Uint128::one(),
+            direction: OrderDirection::Bid,
+            expected_output: Uint256::zero(),
+        },
+        AmountOutRoundedTestCase {
+            name: "ASK: MIN_TICK exact division",
+            tick_id: MIN_TICK,
+            input: Uint128::from(7u128),
+            direction: OrderDirection::Ask,
+            expected_output: Uint256::from(7_000_000_000_000u128),
+        },
+        AmountOutRoundedTestCase {
+            name: "ASK: tick above MIN_TICK truncates just below a whole unit",
+            tick_id: MIN_TICK + 1,
+            // 1 / 0.000000000001000001 = 999999000000.999999..., which would round up
+            input: Uint128::one(),
+            direction: OrderDirection::Ask,
+            expected_output: Uint256::from(999_999_000_000u128),
+        },
+        AmountOutRoundedTestCase {
+            name: "ASK: MAX_TICK truncates just below a whole unit to zero",
+            tick_id: MAX_TICK,
+            // (P - 1) / P = 0.999..., which would round to 1
+            input: Uint128::from(340_282_299_999_999_999_999u128),
+            direction: OrderDirection::Ask,
+            expected_output: Uint256::zero(),
+        },
+        AmountOutRoundedTestCase {
+            name: "ASK: MAX_TICK truncates a half unit",
+            tick_id: MAX_TICK,
+            // 1.5P / P = 1.5, which would round to 2
+            input: Uint128::from(510_423_450_000_000_000_000u128),
+            direction: OrderDirection::Ask,
+            expected_output: Uint256::from(1u128),
+        },
+        AmountOutRoundedTestCase {
+            name: "BID: MAX_TICK exact multiplication",
+            tick_id: MAX_TICK,
+            input: Uint128::from(3u128),
+            direction: OrderDirection::Bid,
+            expected_output: Uint256::from(1_020_846_900_000_000_000_000u128),
+        },
+    ];
+
+    for test in test_cases {
+        let price = tick_to_price(test.tick_id).unwrap();
+        let output = amount_out_rounded(test.input, price, test.direction).unwrap();
+        assert_eq!(output, test.expected_output, "{}", test.name);
+
+        // The output never exceeds the exact value, and is less than one unit below it
+        let input = Decimal256::from_ratio(test.input, 1u128);
+        let exact = match test.direction {
+            OrderDirection::Bid => input.checked_mul(price).unwrap(),
+            OrderDirection::Ask => input.checked_div(price).unwrap(),
+        };
+        let output = Decimal256::from_ratio(output, 1u128);
+        assert!(output <= exact, "{}", test.name);
+        assert!(exact - output < Decimal256::one(), "{}", test.name);
+    }
+}
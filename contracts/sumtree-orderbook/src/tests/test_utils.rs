@@ -1,5 +1,5 @@
 use cosmwasm_std::{
-    coin, testing::mock_info, Addr, Decimal256, DepsMut, Env, MessageInfo, Uint128,
+    coin, testing::mock_info, Addr, Decimal256, DepsMut, Env, MessageInfo, Uint128, Uint256,
 };
 
 use crate::{
@@ -33,8 +33,16 @@ impl OrderOperation {
                     OrderDirection::Bid => MAX_TICK,
                     OrderDirection::Ask => MIN_TICK,
                 };
-                run_market_order(deps.storage, env.contract.address, &mut order, tick_bound)
-                    .unwrap();
+                run_market_order(
+                    deps.storage,
+                    env.contract.address,
+                    env.block.time,
+                    &mut order,
+                    tick_bound,
+                    false,
+                    None,
+                )
+                .unwrap();
                 Ok(())
             }
             OrderOperation::PlaceLimitMulti((
@@ -69,16 +77,22 @@ impl OrderOperation {
                     limit_order.order_direction,
                     limit_order.quantity,
                     limit_order.claim_bounty,
+                    None,
+                    limit_order.expiry,
+                    None,
+                    None,
+                    false,
                 )?;
                 Ok(())
             }
             OrderOperation::Claim((tick_id, order_id)) => {
                 claim_order(
                     deps.storage,
-                    info.sender.clone(),
                     env.contract.address,
+                    info.sender.clone(),
                     tick_id,
                     order_id,
+                    None,
                 )
                 .unwrap();
                 Ok(())
@@ -114,6 +128,10 @@ pub(crate) fn generate_limit_orders(
                 owner: Addr::unchecked(DEFAULT_OWNER),
                 quantity: quantity_per_order,
                 placed_quantity: quantity_per_order,
+                claimed_quantity: Uint128::zero(),
+                claimed_output: Uint256::zero(),
+                expiry: None,
+                display_quantity: None,
                 // We set these values to zero since they will be unused anyway
                 order_id: 0,
                 etas: Decimal256::zero(),
@@ -151,6 +169,11 @@ pub(crate) fn place_multiple_limit_orders(
             order.order_direction,
             order.quantity,
             order.claim_bounty,
+            None,
+            None,
+            None,
+            None,
+            false,
         )?;
     }
     Ok(())
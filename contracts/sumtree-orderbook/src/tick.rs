@@ -1,11 +1,28 @@
 use crate::{
-    error::ContractError,
-    state::TICK_STATE,
-    sumtree::tree::{get_or_init_root_node, get_prefix_sum},
-    types::OrderDirection,
+    error::{ContractError, ContractResult},
+    state::{save_tick_state, TICK_STATE},
+    sumtree::tree::{get_prefix_sum, get_root_node_or_default},
+    types::{OrderDirection, TickValues},
 };
 use cosmwasm_std::{ensure, Decimal256, Storage};
 
+/// Removes `amount` from the liquidity of one direction of a tick.
+///
+/// Liquidity only leaves a tick through fills and cancellations of the orders resting on it, so
+/// removing more than the tick holds means its state is inconsistent. This is reported as a
+/// `LiquidityUnderflow` on the tick rather than a bare overflow error.
+pub(crate) fn subtract_liquidity(
+    values: &mut TickValues,
+    tick_id: i64,
+    amount: Decimal256,
+) -> ContractResult<()> {
+    values.total_amount_of_liquidity = values
+        .total_amount_of_liquidity
+        .checked_sub(amount)
+        .map_err(|_| ContractError::LiquidityUnderflow { tick_id })?;
+    Ok(())
+}
+
 /// Syncs the tick state, ensuring that its ETAS reflects cancellations that have occurred
 /// up until the `current_tick_etas`
 pub fn sync_tick(
@@ -50,8 +67,9 @@ pub fn sync_tick(
         // Get previous cumulative realized cancels to compare against for ETAS updates.
         let old_cumulative_realized_cancels = tick_value.cumulative_realized_cancels;
 
-        // Fetch sumtree for tick by order direction. If none exists, initialize one.
-        let tree = get_or_init_root_node(storage, tick_id, direction)?;
+        // Fetch sumtree for tick by order direction. Ticks without cancellations have no sumtree,
+        // in which case nothing is realized.
+        let tree = get_root_node_or_default(storage, tick_id, direction)?;
 
         // Assuming `calculate_prefix_sum` is a function that calculates the prefix sum at the given ETAS.
         // This function needs to be implemented based on your sumtree structure and logic.
@@ -85,7 +103,79 @@ pub fn sync_tick(
     // Write updated tick values to state
     tick_state.set_values(OrderDirection::Bid, bid_values);
     tick_state.set_values(OrderDirection::Ask, ask_values);
-    TICK_STATE.save(storage, tick_id, &tick_state)?;
+    save_tick_state(storage, tick_id, &tick_state)?;
 
     Ok(())
 }
+
+/// Returns the tick values for the given direction as they would be after a call to `sync_tick`,
+/// without writing anything to state.
+///
+/// This allows queries to reason about up-to-date ETAS values, e.g. when determining how much of an
+/// order is currently claimable.
+pub(crate) fn get_synced_tick_values(
+    storage: &dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<TickValues> {
+    let mut tick_values = TICK_STATE.load(storage, tick_id)?.get_values(direction);
+    let target_etas = tick_values.effective_total_amount_swapped;
+
+    // Mirror the early exit in `sync_tick` for ticks that are already up to date
+    if tick_values.last_tick_sync_etas == target_etas {
+        return Ok(tick_values);
+    }
+
+    // If no sumtree exists for this tick there are no cancellations to realize
+    let tree = get_root_node_or_default(storage, tick_id, direction)?;
+    let new_cumulative_realized_cancels = get_prefix_sum(storage, tree, target_etas)?;
+    let realized_since_last_sync =
+        new_cumulative_realized_cancels.checked_sub(tick_values.cumulative_realized_cancels)?;
+
+    tick_values.effective_total_amount_swapped = tick_values
+        .effective_total_amount_swapped
+        .checked_add(realized_since_last_sync)?;
+    tick_values.cumulative_realized_cancels = new_cumulative_realized_cancels;
+
+    Ok(tick_values)
+}
+
+/// Re-derives the cumulative realized cancels of one direction of a tick from its sumtree, as the
+/// prefix sum of cancellations at the tick's current ETAS, without writing anything to state.
+///
+/// For a tick that is fully synced this matches the stored value, so a mismatch indicates that the
+/// stored value has desynced from the sumtree.
+pub(crate) fn get_recomputed_realized_cancels(
+    storage: &dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<Decimal256> {
+    let tick_values = TICK_STATE
+        .may_load(storage, tick_id)?
+        .ok_or(ContractError::InvalidTickId { tick_id })?
+        .get_values(direction);
+
+    // If no sumtree exists for this tick there are no cancellations to realize
+    let tree = get_root_node_or_default(storage, tick_id, direction)?;
+    get_prefix_sum(storage, tree, tick_values.effective_total_amount_swapped)
+}
+
+/// Overwrites the stored cumulative realized cancels of one direction of a tick with the value
+/// re-derived from its sumtree, returning the corrected tick values.
+///
+/// ETAS is left untouched, so subsequent syncs only realize cancellations starting beyond it.
+pub fn recompute_realized_cancels(
+    storage: &mut dyn Storage,
+    tick_id: i64,
+    direction: OrderDirection,
+) -> ContractResult<TickValues> {
+    let cumulative_realized_cancels = get_recomputed_realized_cancels(storage, tick_id, direction)?;
+
+    let mut tick_state = TICK_STATE.load(storage, tick_id)?;
+    let mut tick_values = tick_state.get_values(direction);
+    tick_values.cumulative_realized_cancels = cumulative_realized_cancels;
+    tick_state.set_values(direction, tick_values.clone());
+    save_tick_state(storage, tick_id, &tick_state)?;
+
+    Ok(tick_values)
+}
@@ -64,6 +64,46 @@ pub fn tick_to_price(tick_index: i64) -> ContractResult<Decimal256> {
     Ok(price)
 }
 
+// price_to_tick converts a price to the tick index whose price is nearest to it in the given rounding direction.
+// Rounding down returns the highest tick with a price at or below the given price, rounding up returns the lowest
+// tick with a price at or above it.
+// Prices outside of the range covered by MIN_TICK and MAX_TICK are clamped to those ticks.
+pub fn price_to_tick(
+    price: Decimal256,
+    rounding_direction: RoundingDirection,
+) -> ContractResult<i64> {
+    if price <= tick_to_price(MIN_TICK)? {
+        return Ok(MIN_TICK);
+    }
+    if price >= tick_to_price(MAX_TICK)? {
+        return Ok(MAX_TICK);
+    }
+
+    // Find the geometric exponent k such that 10^k <= price < 10^(k+1). Each such range spans
+    // GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS ticks, starting at tick k * GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS.
+    let mut geometric_exponent = (MIN_TICK / GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS) as i32;
+    while pow_ten(geometric_exponent + 1)? <= price {
+        geometric_exponent += 1;
+    }
+    let geometric_component = pow_ten(geometric_exponent)?;
+
+    // Within the range, prices are spaced linearly by 10^(k + EXPONENT_AT_PRICE_ONE), which matches the additive
+    // increment tick_to_price uses for both positive and negative ticks.
+    let additive_increment_in_ticks = pow_ten(geometric_exponent + EXPONENT_AT_PRICE_ONE)?;
+    let num_additive_ticks = rounding_direction.round(
+        price
+            .checked_sub(geometric_component)?
+            .checked_div(additive_increment_in_ticks)?,
+    );
+    // There are at most GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS additive ticks, so this cast cannot truncate.
+    let num_additive_ticks = Uint128::try_from(num_additive_ticks)?.u128() as i64;
+
+    let tick_index = (geometric_exponent as i64) * GEOMETRIC_EXPONENT_INCREMENT_DISTANCE_IN_TICKS
+        + num_additive_ticks;
+
+    Ok(tick_index.clamp(MIN_TICK, MAX_TICK))
+}
+
 // Takes an exponent and returns 10^exponent. Supports negative exponents.
 pub fn pow_ten(expo: i32) -> ContractResult<Decimal256> {
     let target_expo = Uint256::from(10u8).checked_pow(expo.unsigned_abs())?;
@@ -135,6 +175,18 @@ pub fn divide_by_price(
     Ok(amount_to_send)
 }
 
+/// Calculates the amount paid out for an input amount of an order in the given direction at the given price.
+///
+/// Payouts are always truncated so that any rounding error is kept by the orderbook rather than overpaid.
+/// Every output paid to takers and makers is calculated through this function.
+pub fn amount_out_rounded(
+    input: Uint128,
+    price: Decimal256,
+    direction: OrderDirection,
+) -> ContractResult<Uint256> {
+    amount_to_value(direction, input, price, RoundingDirection::Down)
+}
+
 /// Converts a tick amount to it's value given a price and order direction
 pub fn amount_to_value(
     order: OrderDirection,
@@ -0,0 +1,60 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal256, Timestamp, Uint256};
+
+use crate::constants::{VOLUME_BUCKET_SECONDS, VOLUME_WINDOW_SECONDS};
+use crate::error::ContractResult;
+
+#[cw_serde]
+#[derive(Default)]
+pub struct MarketStats {
+    // Price of the tick the most recent market order finished on
+    pub last_price: Decimal256,
+    pub last_trade_time: Timestamp,
+    // Traded quote volume per bucket; (bucket start in seconds, volume), oldest first
+    pub volume_buckets: Vec<(u64, Uint256)>,
+}
+
+impl MarketStats {
+    /// Records a trade at `now`, dropping any volume buckets that have left the rolling window.
+    pub fn record_trade(
+        &mut self,
+        now: Timestamp,
+        price: Decimal256,
+        volume: Uint256,
+    ) -> ContractResult<()> {
+        self.prune_volume(now);
+        self.last_price = price;
+        self.last_trade_time = now;
+
+        let bucket_start = now.seconds() - now.seconds() % VOLUME_BUCKET_SECONDS;
+        match self.volume_buckets.last_mut() {
+            Some((start, bucket_volume)) if *start == bucket_start => {
+                *bucket_volume = bucket_volume.checked_add(volume)?;
+            }
+            _ => self.volume_buckets.push((bucket_start, volume)),
+        }
+
+        Ok(())
+    }
+
+    /// Drops volume buckets that started before the rolling window ending at `now`.
+    pub fn prune_volume(&mut self, now: Timestamp) {
+        let window_start = now.seconds().saturating_sub(VOLUME_WINDOW_SECONDS);
+        self.volume_buckets
+            .retain(|(bucket_start, _)| *bucket_start >= window_start);
+    }
+
+    /// Total traded volume within the rolling window ending at `now`.
+    pub fn volume(&self, now: Timestamp) -> ContractResult<Uint256> {
+        let window_start = now.seconds().saturating_sub(VOLUME_WINDOW_SECONDS);
+        let mut total = Uint256::zero();
+        for (_, volume) in self
+            .volume_buckets
+            .iter()
+            .filter(|(bucket_start, _)| *bucket_start >= window_start)
+        {
+            total = total.checked_add(*volume)?;
+        }
+        Ok(total)
+    }
+}
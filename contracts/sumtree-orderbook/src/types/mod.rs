@@ -1,11 +1,15 @@
 mod coin;
+mod market_stats;
 mod order;
 mod orderbook;
+mod payout;
 mod reply_id;
 mod tick;
 
 pub use self::coin::{coin_u256, Coin256, MsgSend256};
+pub use self::market_stats::*;
 pub use self::order::*;
 pub use self::orderbook::*;
+pub use self::payout::*;
 pub use self::reply_id::*;
 pub use self::tick::*;
@@ -2,7 +2,9 @@ use core::fmt;
 use std::fmt::Display;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal256, Uint128};
+use cosmwasm_std::{Addr, Decimal256, Timestamp, Uint128, Uint256};
+
+use crate::error::ContractResult;
 
 #[cw_serde]
 #[derive(Copy)]
@@ -45,8 +47,21 @@ pub struct LimitOrder {
     pub quantity: Uint128,
     pub etas: Decimal256,
     pub claim_bounty: Option<Decimal256>,
-    // Immutable quantity of the order when placed
+    // Quantity of the order when placed, adjusted by any amendments or expiry
     pub placed_quantity: Uint128,
+    // Cumulative quantity of the order that has been filled and claimed
+    // Orders stored before claims were tracked deserialize as zero and are backfilled on migration
+    #[serde(default)]
+    pub claimed_quantity: Uint128,
+    // Cumulative output realized by claims, prior to bounty and maker fee deductions
+    #[serde(default)]
+    pub claimed_output: Uint256,
+    // Time after which the unfilled quantity is voided and refunded, if set
+    #[serde(default)]
+    pub expiry: Option<Timestamp>,
+    // Quantity shown in tick depth while the rest is held in reserve, showing all of it if unset
+    #[serde(default)]
+    pub display_quantity: Option<Uint128>,
 }
 
 impl LimitOrder {
@@ -69,14 +84,59 @@ impl LimitOrder {
             etas,
             claim_bounty,
             placed_quantity: quantity,
+            claimed_quantity: Uint128::zero(),
+            claimed_output: Uint256::zero(),
+            expiry: None,
+            display_quantity: None,
         }
     }
 
+    /// Returns the average price realized across all claims on this order, expressed in the
+    /// same terms as `tick_to_price` (base per quote).
+    ///
+    /// Returns `None` if nothing has been claimed yet.
+    pub fn average_fill_price(&self) -> ContractResult<Option<Decimal256>> {
+        if self.claimed_quantity.is_zero() || self.claimed_output.is_zero() {
+            return Ok(None);
+        }
+
+        let claimed_quantity = Uint256::from_uint128(self.claimed_quantity);
+        let price = match self.order_direction {
+            // Asks fill base for quote, so the price is input over output
+            OrderDirection::Ask => {
+                Decimal256::checked_from_ratio(claimed_quantity, self.claimed_output)?
+            }
+            // Bids fill quote for base, so the price is output over input
+            OrderDirection::Bid => {
+                Decimal256::checked_from_ratio(self.claimed_output, claimed_quantity)?
+            }
+        };
+
+        Ok(Some(price))
+    }
+
     #[cfg(test)]
     pub(crate) fn with_placed_quantity(mut self, quantity: impl Into<Uint128>) -> Self {
         self.placed_quantity = quantity.into();
         self
     }
+
+    #[cfg(test)]
+    pub(crate) fn with_claimed(
+        mut self,
+        quantity: impl Into<Uint128>,
+        output: impl Into<Uint256>,
+    ) -> Self {
+        self.claimed_quantity = quantity.into();
+        self.claimed_output = output.into();
+        self
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_expiry(mut self, expiry: Timestamp) -> Self {
+        self.expiry = Some(expiry);
+        self
+    }
 }
 
 #[cw_serde]
@@ -106,6 +166,47 @@ impl From<LimitOrder> for MarketOrder {
     }
 }
 
+/// How long a limit order may rest on the book
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum TimeInForce {
+    /// The order rests on its tick until it is filled, cancelled or expires
+    #[default]
+    GoodTillCancelled,
+    /// The order fills against crossing liquidity on placement and any remainder is refunded
+    ImmediateOrCancel,
+}
+
+/// Describes why a market order stopped filling against the orderbook
+#[cw_serde]
+#[derive(Copy)]
+pub enum MarketOrderStopReason {
+    /// The order was filled, excluding any unfillable dust
+    Filled,
+    /// The orderbook ran out of liquidity before the order was filled
+    OutOfLiquidity,
+    /// The maximum number of ticks to cross was reached before the order was filled
+    MaxTicksReached,
+    /// The order's tick bound was reached before the order was filled
+    PriceBound,
+}
+
+/// Lifecycle status of a limit order
+#[cw_serde]
+#[derive(Copy)]
+pub enum OrderStatus {
+    /// The order rests on its tick and nothing has been filled
+    Open,
+    /// Part of the order has been filled and part is still resting
+    PartiallyFilled,
+    /// All of the order has been filled, whether or not it has been claimed
+    FullyFilled,
+    /// The order was cancelled or voided before it was fully filled
+    Cancelled,
+    /// No order with this id was ever placed on the tick
+    NotFound,
+}
+
 /// Defines the different way an owners orders can be filtered, all enums filter by owner with each getting more finite
 #[derive(Clone)]
 pub enum FilterOwnerOrders {
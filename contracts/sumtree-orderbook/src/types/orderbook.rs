@@ -1,4 +1,5 @@
 use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal256, Uint128, Uint256};
 
 use crate::{error::ContractResult, ContractError};
 
@@ -13,6 +14,28 @@ pub struct Orderbook {
     pub current_tick: i64,
     pub next_bid_tick: i64,
     pub next_ask_tick: i64,
+
+    // Share of market order output taken as a protocol fee
+    #[serde(default)]
+    pub taker_fee: Decimal256,
+    // Recipient of taker fees, no fee is taken while unset
+    #[serde(default)]
+    pub fee_collector: Option<Addr>,
+    // Smallest limit order quantity accepted, zero disables the check
+    #[serde(default)]
+    pub min_order_size: Uint128,
+    // Furthest a single market order may move the tick pointer from where it started, unbounded if unset
+    #[serde(default)]
+    pub max_tick_move: Option<i64>,
+    // Limit orders may only be placed on ticks that are a multiple of the spacing, every tick if unset
+    #[serde(default)]
+    pub tick_spacing: Option<i64>,
+    // Most orders that may rest in one direction of a tick at once, unbounded if unset
+    #[serde(default)]
+    pub max_orders_per_tick: Option<u32>,
+    // Highest claim bounty a limit order may offer, below the global 1% guardrail; only the guardrail applies if unset
+    #[serde(default)]
+    pub max_claim_bounty: Option<Decimal256>,
 }
 
 impl Orderbook {
@@ -29,7 +52,28 @@ impl Orderbook {
             current_tick,
             next_bid_tick,
             next_ask_tick,
+            taker_fee: Decimal256::zero(),
+            fee_collector: None,
+            min_order_size: Uint128::zero(),
+            max_tick_move: None,
+            tick_spacing: None,
+            max_orders_per_tick: None,
+            max_claim_bounty: None,
+        }
+    }
+
+    /// Calculates the taker fee owed on a market order's gross output.
+    ///
+    /// Rounds up so that the user output is truncated rather than the fee. Returns zero if no fee
+    /// collector is set.
+    pub fn get_taker_fee(&self, output: Uint256) -> ContractResult<Uint256> {
+        if self.fee_collector.is_none() {
+            return Ok(Uint256::zero());
         }
+
+        Ok(Decimal256::checked_from_ratio(output, Uint256::one())?
+            .checked_mul(self.taker_fee)?
+            .to_uint_ceil())
     }
 
     /// Get the expected denomination for a given order direction.
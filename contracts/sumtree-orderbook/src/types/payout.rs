@@ -0,0 +1,15 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+
+use super::Coin256;
+
+/// A refund, claim, bounty or immediate fill payout dispatched in the current transaction that has not yet been
+/// replied to.
+#[cw_serde]
+pub struct PendingPayout {
+    pub reply_id: u64,
+    pub recipient: Addr,
+    pub amount: Vec<Coin256>,
+    /// For bounties, the owner of the claimed order the bounty is returned to if it cannot be paid
+    pub order_owner: Option<Addr>,
+}
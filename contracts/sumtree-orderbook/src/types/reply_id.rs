@@ -3,3 +3,5 @@ pub const REPLY_ID_CLAIM: u64 = 2;
 pub const REPLY_ID_CLAIM_BOUNTY: u64 = 3;
 pub const REPLY_ID_MAKER_FEE: u64 = 4;
 pub const REPLY_ID_SUDO_SWAP_EXACT_IN: u64 = 5;
+pub const REPLY_ID_TAKER_FEE: u64 = 6;
+pub const REPLY_ID_IMMEDIATE_FILL: u64 = 7;
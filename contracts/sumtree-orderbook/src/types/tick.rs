@@ -30,6 +30,20 @@ pub struct TickValues {
     /// It is used to skip tick syncs if ETAS has not changed since the previous
     /// sync.
     pub last_tick_sync_etas: Decimal256,
+
+    /// Portion of TAL held in reserve by iceberg orders
+    /// - Still matchable, but left out of the liquidity reported by tick depth queries.
+    /// - Recomputed from the tick's iceberg orders whenever they are filled or reduced.
+    #[serde(default)]
+    pub hidden_liquidity: Decimal256,
+}
+
+impl TickValues {
+    /// Returns the liquidity shown in tick depth queries, excluding any hidden reserve
+    pub fn displayed_liquidity(&self) -> Decimal256 {
+        self.total_amount_of_liquidity
+            .saturating_sub(self.hidden_liquidity)
+    }
 }
 
 impl Default for TickValues {
@@ -40,6 +54,7 @@ impl Default for TickValues {
             effective_total_amount_swapped: Decimal256::zero(),
             cumulative_realized_cancels: Decimal256::zero(),
             last_tick_sync_etas: Decimal256::zero(),
+            hidden_liquidity: Decimal256::zero(),
         }
     }
 }
@@ -65,6 +80,15 @@ impl TickState {
         }
     }
 
+    /// Returns the tick as shown in depth queries, with hidden reserves left out of TAL
+    pub fn displayed(mut self) -> Self {
+        for values in [&mut self.ask_values, &mut self.bid_values] {
+            values.total_amount_of_liquidity = values.displayed_liquidity();
+            values.hidden_liquidity = Decimal256::zero();
+        }
+        self
+    }
+
     pub fn set_values(&mut self, direction: OrderDirection, values: TickValues) {
         if direction == OrderDirection::Ask {
             self.ask_values = values;